@@ -4,9 +4,10 @@ use miette::{miette, Context, IntoDiagnostic};
 use pixi_consts::consts;
 use rattler_conda_types::{
     version_spec::{EqualityOperator, LogicalOperator, RangeOperator},
-    ChannelConfig, NamedChannelOrUrl, Version, VersionBumpType, VersionSpec,
+    ChannelConfig, NamedChannelOrUrl, Platform, Version, VersionBumpType, VersionSpec,
 };
 use rattler_repodata_gateway::{Gateway, SourceConfig};
+use rattler_solve::ChannelPriority;
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{de::IntoDeserializer, Deserialize, Serialize};
 use std::{
@@ -18,6 +19,9 @@ use std::{
 use url::Url;
 
 const EXPERIMENTAL: &str = "experimental";
+const THEME: &str = "theme";
+const CACHE_DIRS: &str = "cache-dirs";
+const CONTAINER_RUNNER: &str = "container-runner";
 
 pub fn default_channel_config() -> ChannelConfig {
     ChannelConfig::default_with_root_dir(
@@ -214,7 +218,8 @@ pub struct RepodataChannelConfig {
     #[serde(alias = "disable_zstd")] // BREAK: remove to stop supporting snake_case alias
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_zstd: Option<bool>,
-    /// Disable the use of sharded repodata
+    /// Disable the use of sharded repodata. Channels that don't provide
+    /// sharded repodata fall back to the full `repodata.json`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_sharded: Option<bool>,
 }
@@ -243,8 +248,7 @@ impl From<RepodataChannelConfig> for SourceConfig {
             jlap_enabled: !value.disable_jlap.unwrap_or(false),
             zstd_enabled: !value.disable_zstd.unwrap_or(false),
             bz2_enabled: !value.disable_bzip2.unwrap_or(false),
-            // TODO: Change sharded repodata default to true, when enough testing has been done.
-            sharded_enabled: !value.disable_sharded.unwrap_or(true),
+            sharded_enabled: !value.disable_sharded.unwrap_or(false),
             cache_action: Default::default(),
         }
     }
@@ -257,6 +261,39 @@ pub enum KeyringProvider {
     Subprocess,
 }
 
+/// How the files of PyPI packages are linked from the cache into an
+/// environment.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    /// Clone (reflink) the files, falls back to copying if the file system
+    /// doesn't support it.
+    Clone,
+    /// Copy the files.
+    Copy,
+    /// Hard link the files, falls back to copying if the cache is on a
+    /// different file system.
+    Hardlink,
+    /// Symbolically link the files.
+    Symlink,
+}
+
+impl FromStr for LinkMode {
+    type Err = miette::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clone" => Ok(LinkMode::Clone),
+            "copy" => Ok(LinkMode::Copy),
+            "hardlink" => Ok(LinkMode::Hardlink),
+            "symlink" => Ok(LinkMode::Symlink),
+            _ => Err(miette!(
+                "invalid link mode '{s}', expected one of 'clone', 'copy', 'hardlink' or 'symlink'"
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct PyPIConfig {
@@ -276,6 +313,10 @@ pub struct PyPIConfig {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub allow_insecure_host: Vec<String>,
+    /// How the files of packages are linked into an environment.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_mode: Option<LinkMode>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -308,6 +349,67 @@ impl Default for DetachedEnvironments {
     }
 }
 
+/// Whether the post-link scripts of packages are executed when the packages
+/// are installed. In the configuration this is either `"insecure"` or `false`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RunPostLinkScripts {
+    /// Run the post-link scripts. The scripts can execute arbitrary code and
+    /// are not sandboxed, hence the name.
+    Insecure,
+    /// Never run the post-link scripts.
+    #[default]
+    False,
+}
+
+impl RunPostLinkScripts {
+    /// Returns true if the post-link scripts should be executed.
+    pub fn enabled(self) -> bool {
+        matches!(self, RunPostLinkScripts::Insecure)
+    }
+}
+
+impl FromStr for RunPostLinkScripts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "insecure" => Ok(RunPostLinkScripts::Insecure),
+            "false" => Ok(RunPostLinkScripts::False),
+            _ => Err(format!(
+                "invalid value '{s}' for run-post-link-scripts, expected \"insecure\" or false"
+            )),
+        }
+    }
+}
+
+impl Serialize for RunPostLinkScripts {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            RunPostLinkScripts::Insecure => serializer.serialize_str("insecure"),
+            RunPostLinkScripts::False => serializer.serialize_bool(false),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RunPostLinkScripts {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bool(bool),
+            String(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Bool(false) => Ok(RunPostLinkScripts::False),
+            Raw::Bool(true) => Err(serde::de::Error::custom(
+                "post-link scripts can execute arbitrary code, use \"insecure\" to run them",
+            )),
+            Raw::String(s) => RunPostLinkScripts::from_str(&s).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct ExperimentalConfig {
@@ -335,6 +437,179 @@ impl ExperimentalConfig {
     }
 }
 
+/// The accent color that pixi uses to highlight its output.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccentColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl From<AccentColor> for console::Color {
+    fn from(value: AccentColor) -> Self {
+        match value {
+            AccentColor::Red => console::Color::Red,
+            AccentColor::Green => console::Color::Green,
+            AccentColor::Yellow => console::Color::Yellow,
+            AccentColor::Blue => console::Color::Blue,
+            AccentColor::Magenta => console::Color::Magenta,
+            AccentColor::Cyan => console::Color::Cyan,
+            AccentColor::White => console::Color::White,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ThemeConfig {
+    /// The color used for spinners, progress bars and highlights in reports,
+    /// when not set each element uses its default color.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<AccentColor>,
+
+    /// Whether emoji and other unicode symbols are used to draw progress bars
+    /// and reports.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+}
+
+impl ThemeConfig {
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            accent_color: other.accent_color.or(self.accent_color),
+            emoji: other.emoji.or(self.emoji),
+        }
+    }
+
+    /// Retrieve the accent color, `None` when the default colors are used.
+    pub fn accent_color(&self) -> Option<AccentColor> {
+        self.accent_color
+    }
+
+    /// Retrieve whether emoji are enabled (defaults to true).
+    pub fn emoji(&self) -> bool {
+        self.emoji.unwrap_or(true)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.accent_color.is_none() && self.emoji.is_none()
+    }
+}
+
+/// The locations of the individual caches, every cache that is not set is
+/// stored in a subdirectory of the pixi cache directory.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CacheDirsConfig {
+    /// The directory of the conda package cache.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packages: Option<PathBuf>,
+
+    /// The directory of the repodata cache.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repodata: Option<PathBuf>,
+
+    /// The directory of the PyPI (uv) cache.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pypi: Option<PathBuf>,
+
+    /// The directory of the task caches, every project uses a subdirectory.
+    /// When not set the task cache is stored in the `.pixi` directory of the
+    /// project.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<PathBuf>,
+}
+
+impl CacheDirsConfig {
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            packages: other.packages.or(self.packages),
+            repodata: other.repodata.or(self.repodata),
+            pypi: other.pypi.or(self.pypi),
+            tasks: other.tasks.or(self.tasks),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.packages.is_none()
+            && self.repodata.is_none()
+            && self.pypi.is_none()
+            && self.tasks.is_none()
+    }
+}
+
+/// The engine that runs the containers of the container runner.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerEngine {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerEngine {
+    /// The name of the executable of the engine.
+    pub fn executable(&self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "docker",
+            ContainerEngine::Podman => "podman",
+        }
+    }
+}
+
+/// Configures running tasks for a platform that is not the current platform
+/// in a container, e.g. `pixi run --platform linux-64 test` on macOS.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ContainerRunnerConfig {
+    /// The engine that runs the containers, defaults to docker.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<ContainerEngine>,
+
+    /// The image to run the tasks of a platform in. The image has to contain
+    /// pixi. Only platforms with an image can be used with the runner.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub images: HashMap<Platform, String>,
+}
+
+impl ContainerRunnerConfig {
+    pub fn merge(self, other: Self) -> Self {
+        let mut images = self.images;
+        images.extend(other.images);
+        Self {
+            engine: other.engine.or(self.engine),
+            images,
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.engine.is_none() && self.images.is_empty()
+    }
+
+    /// Retrieve the engine that runs the containers (defaults to docker).
+    pub fn engine(&self) -> ContainerEngine {
+        self.engine.unwrap_or_default()
+    }
+
+    /// Retrieve the image to run the tasks of the given platform in.
+    pub fn image(&self, platform: Platform) -> Option<&str> {
+        self.images.get(&platform).map(String::as_str)
+    }
+}
+
 // Making the default values part of pixi_config to allow for printing the default settings in the future.
 /// The default maximum number of concurrent solves that can be run at once.
 /// Defaulting to the number of CPUs available.
@@ -413,6 +688,7 @@ impl PyPIConfig {
                 .into_iter()
                 .chain(other.allow_insecure_host)
                 .collect(),
+            link_mode: other.link_mode.or(self.link_mode),
         }
     }
 
@@ -433,11 +709,12 @@ impl PyPIConfig {
         self.index_url.is_none()
             && self.extra_index_urls.is_empty()
             && self.keyring_provider.is_none()
+            && self.link_mode.is_none()
     }
 }
 
 /// The strategy for that will be used for pinning a version of a package.
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Copy, clap::ValueEnum)]
 #[serde(rename_all = "kebab-case")]
 pub enum PinningStrategy {
     /// Default semver strategy e.g. "1.2.3" becomes ">=1.2.3, <2" but "0.1.0"
@@ -620,6 +897,60 @@ pub struct Config {
     #[serde(default)]
     #[serde(skip_serializing_if = "ConcurrencyConfig::is_default")]
     pub concurrency: ConcurrencyConfig,
+
+    /// The colors and symbols used in the output of pixi.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "ThemeConfig::is_default")]
+    pub theme: ThemeConfig,
+
+    /// A directory, for instance on a network drive, that is used to share
+    /// packages built from immutable source dependencies between machines.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_build_cache: Option<PathBuf>,
+
+    /// Whether the executables of the project environment take precedence over
+    /// the executables exposed by `pixi global` in `pixi run` and `pixi shell`.
+    /// When not set, the project executables are used and a warning is shown
+    /// for every executable that is provided by both.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefer_project_executables: Option<bool>,
+
+    /// The channel priority used when solving environments that do not set
+    /// `channel-priority` in the manifest.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_priority: Option<ChannelPriority>,
+
+    /// The maximum number of times a failed HTTP request is retried, with an
+    /// exponential backoff between the attempts.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// Install the environments of projects into a store in the cache
+    /// directory, shared by all projects, and link them into the projects.
+    /// Environments with the same locked packages are only installed once.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_environments: Option<bool>,
+
+    /// Whether the post-link scripts of packages are executed when they are
+    /// installed, either `"insecure"` or `false` (the default).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_post_link_scripts: Option<RunPostLinkScripts>,
+
+    /// The locations of the individual caches.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "CacheDirsConfig::is_default")]
+    pub cache_dirs: CacheDirsConfig,
+
+    /// The container runner for tasks of other platforms.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "ContainerRunnerConfig::is_default")]
+    pub container_runner: ContainerRunnerConfig,
 }
 
 impl Default for Config {
@@ -639,6 +970,15 @@ impl Default for Config {
             force_activate: None,
             experimental: ExperimentalConfig::default(),
             concurrency: ConcurrencyConfig::default(),
+            theme: ThemeConfig::default(),
+            shared_build_cache: None,
+            prefer_project_executables: None,
+            channel_priority: None,
+            max_retries: None,
+            shared_environments: None,
+            run_post_link_scripts: None,
+            cache_dirs: CacheDirsConfig::default(),
+            container_runner: ContainerRunnerConfig::default(),
         }
     }
 }
@@ -707,9 +1047,6 @@ impl Config {
         // Use conda-forge as the default channel
         config.default_channels = vec![NamedChannelOrUrl::Name("conda-forge".into())];
 
-        // Enable sharded repodata by default.
-        config.repodata_config.default.disable_sharded = Some(false);
-
         config
     }
 
@@ -895,6 +1232,12 @@ impl Config {
             "mirrors",
             "detached-environments",
             "pinning-strategy",
+            "shared-build-cache",
+            "prefer-project-executables",
+            "channel-priority",
+            "max-retries",
+            "shared-environments",
+            "run-post-link-scripts",
             "max-concurrent-solves",
             "repodata-config",
             "repodata-config.disable-jlap",
@@ -905,7 +1248,19 @@ impl Config {
             "pypi-config.index-url",
             "pypi-config.extra-index-urls",
             "pypi-config.keyring-provider",
+            "pypi-config.link-mode",
             "experimental.use-environment-activation-cache",
+            "theme",
+            "theme.accent-color",
+            "theme.emoji",
+            "cache-dirs",
+            "cache-dirs.packages",
+            "cache-dirs.repodata",
+            "cache-dirs.pypi",
+            "cache-dirs.tasks",
+            "container-runner",
+            "container-runner.engine",
+            "container-runner.images",
         ]
     }
 
@@ -939,6 +1294,17 @@ impl Config {
             experimental: self.experimental.merge(other.experimental),
             // Make other take precedence over self to allow for setting the value through the CLI
             concurrency: self.concurrency.merge(other.concurrency),
+            theme: self.theme.merge(other.theme),
+            shared_build_cache: other.shared_build_cache.or(self.shared_build_cache),
+            prefer_project_executables: other
+                .prefer_project_executables
+                .or(self.prefer_project_executables),
+            channel_priority: other.channel_priority.or(self.channel_priority),
+            max_retries: other.max_retries.or(self.max_retries),
+            shared_environments: other.shared_environments.or(self.shared_environments),
+            run_post_link_scripts: other.run_post_link_scripts.or(self.run_post_link_scripts),
+            cache_dirs: self.cache_dirs.merge(other.cache_dirs),
+            container_runner: self.container_runner.merge(other.container_runner),
         }
     }
 
@@ -1000,6 +1366,81 @@ impl Config {
         self.force_activate.unwrap_or(false)
     }
 
+    /// Retrieve the theme that is used to style the output.
+    pub fn theme(&self) -> &ThemeConfig {
+        &self.theme
+    }
+
+    /// Retrieve the value for the shared_build_cache field.
+    pub fn shared_build_cache(&self) -> Option<&PathBuf> {
+        self.shared_build_cache.as_ref()
+    }
+
+    /// Retrieve the value for the prefer_project_executables field, `None`
+    /// when the user did not choose.
+    pub fn prefer_project_executables(&self) -> Option<bool> {
+        self.prefer_project_executables
+    }
+
+    /// Retrieve the value for the channel_priority field, `None` when the
+    /// default of the solver should be used.
+    pub fn channel_priority(&self) -> Option<ChannelPriority> {
+        self.channel_priority
+    }
+
+    /// Retrieve the maximum number of retries of a failed HTTP request
+    /// (defaults to 3).
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(3)
+    }
+
+    /// Retrieve whether environments are installed into the shared
+    /// environment store (defaults to false).
+    pub fn shared_environments(&self) -> bool {
+        self.shared_environments.unwrap_or(false)
+    }
+
+    /// Retrieve whether the post-link scripts of packages are executed
+    /// (defaults to false).
+    pub fn run_post_link_scripts(&self) -> RunPostLinkScripts {
+        self.run_post_link_scripts.unwrap_or_default()
+    }
+
+    /// Retrieve the directory of the conda package cache.
+    pub fn package_cache_dir(&self) -> miette::Result<PathBuf> {
+        match &self.cache_dirs.packages {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(get_cache_dir()?.join(consts::CONDA_PACKAGE_CACHE_DIR)),
+        }
+    }
+
+    /// Retrieve the directory of the repodata cache.
+    pub fn repodata_cache_dir(&self) -> miette::Result<PathBuf> {
+        match &self.cache_dirs.repodata {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(get_cache_dir()?.join(consts::CONDA_REPODATA_CACHE_DIR)),
+        }
+    }
+
+    /// Retrieve the directory of the PyPI (uv) cache.
+    pub fn pypi_cache_dir(&self) -> miette::Result<PathBuf> {
+        match &self.cache_dirs.pypi {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(get_cache_dir()?.join(consts::PYPI_CACHE_DIR)),
+        }
+    }
+
+    /// Retrieve the directory that contains the task caches of all projects,
+    /// `None` when the task cache is stored in the project.
+    pub fn task_cache_dir(&self) -> Option<&Path> {
+        self.cache_dirs.tasks.as_deref()
+    }
+
+    /// Retrieve the container runner for tasks of other platforms.
+    pub fn container_runner(&self) -> &ContainerRunnerConfig {
+        &self.container_runner
+    }
+
     pub fn experimental_activation_cache_usage(&self) -> bool {
         self.experimental.use_environment_activation_cache()
     }
@@ -1042,9 +1483,41 @@ impl Config {
             "authentication-override-file" => {
                 self.authentication_override_file = value.map(PathBuf::from);
             }
+            "shared-build-cache" => {
+                self.shared_build_cache = value.map(PathBuf::from);
+            }
+            "prefer-project-executables" => {
+                self.prefer_project_executables =
+                    value.map(|v| v.parse()).transpose().into_diagnostic()?;
+            }
+            "channel-priority" => {
+                self.channel_priority = value
+                    .map(|v| {
+                        ChannelPriority::deserialize(
+                            IntoDeserializer::<serde::de::value::Error>::into_deserializer(
+                                v.as_str(),
+                            ),
+                        )
+                    })
+                    .transpose()
+                    .into_diagnostic()?;
+            }
             "tls-no-verify" => {
                 self.tls_no_verify = value.map(|v| v.parse()).transpose().into_diagnostic()?;
             }
+            "max-retries" => {
+                self.max_retries = value.map(|v| v.parse()).transpose().into_diagnostic()?;
+            }
+            "shared-environments" => {
+                self.shared_environments =
+                    value.map(|v| v.parse()).transpose().into_diagnostic()?;
+            }
+            "run-post-link-scripts" => {
+                self.run_post_link_scripts = value
+                    .map(|v| RunPostLinkScripts::from_str(&v))
+                    .transpose()
+                    .map_err(|e| miette!(e))?;
+            }
             "mirrors" => {
                 self.mirrors = value
                     .map(|v| serde_json::de::from_str(&v))
@@ -1134,6 +1607,10 @@ impl Config {
                             })
                             .transpose()?;
                     }
+                    "link-mode" => {
+                        self.pypi_config.link_mode =
+                            value.map(|v| LinkMode::from_str(&v)).transpose()?;
+                    }
                     _ => return Err(err),
                 }
             }
@@ -1160,6 +1637,94 @@ impl Config {
                     _ => return Err(err),
                 }
             }
+            key if key.starts_with(THEME) => {
+                if key == THEME {
+                    if let Some(value) = value {
+                        self.theme = serde_json::de::from_str(&value).into_diagnostic()?;
+                    } else {
+                        self.theme = ThemeConfig::default();
+                    }
+                    return Ok(());
+                } else if !key.starts_with(format!("{THEME}.").as_str()) {
+                    return Err(err);
+                }
+
+                let subkey = key.strip_prefix(format!("{THEME}.").as_str()).unwrap();
+                match subkey {
+                    "accent-color" => {
+                        self.theme.accent_color = value
+                            .map(|v| {
+                                AccentColor::deserialize(
+                                    IntoDeserializer::<serde::de::value::Error>::into_deserializer(
+                                        v.as_str(),
+                                    ),
+                                )
+                            })
+                            .transpose()
+                            .into_diagnostic()?;
+                    }
+                    "emoji" => {
+                        self.theme.emoji =
+                            value.map(|v| v.parse()).transpose().into_diagnostic()?;
+                    }
+                    _ => return Err(err),
+                }
+            }
+            key if key.starts_with(CACHE_DIRS) => {
+                if key == CACHE_DIRS {
+                    if let Some(value) = value {
+                        self.cache_dirs = serde_json::de::from_str(&value).into_diagnostic()?;
+                    } else {
+                        self.cache_dirs = CacheDirsConfig::default();
+                    }
+                    return Ok(());
+                } else if !key.starts_with(format!("{CACHE_DIRS}.").as_str()) {
+                    return Err(err);
+                }
+
+                let subkey = key.strip_prefix(format!("{CACHE_DIRS}.").as_str()).unwrap();
+                let value = value.map(PathBuf::from);
+                match subkey {
+                    "packages" => self.cache_dirs.packages = value,
+                    "repodata" => self.cache_dirs.repodata = value,
+                    "pypi" => self.cache_dirs.pypi = value,
+                    "tasks" => self.cache_dirs.tasks = value,
+                    _ => return Err(err),
+                }
+            }
+            key if key.starts_with(CONTAINER_RUNNER) => {
+                if key == CONTAINER_RUNNER {
+                    if let Some(value) = value {
+                        self.container_runner =
+                            serde_json::de::from_str(&value).into_diagnostic()?;
+                    } else {
+                        self.container_runner = ContainerRunnerConfig::default();
+                    }
+                    return Ok(());
+                } else if !key.starts_with(format!("{CONTAINER_RUNNER}.").as_str()) {
+                    return Err(err);
+                }
+
+                let subkey = key
+                    .strip_prefix(format!("{CONTAINER_RUNNER}.").as_str())
+                    .unwrap();
+                match subkey {
+                    "engine" => {
+                        self.container_runner.engine = value
+                            .map(|v| serde_json::from_value(serde_json::Value::String(v)))
+                            .transpose()
+                            .into_diagnostic()?;
+                    }
+                    "images" => {
+                        self.container_runner.images = value
+                            .map(|v| serde_json::de::from_str(&v))
+                            .transpose()
+                            .into_diagnostic()?
+                            .unwrap_or_default();
+                    }
+                    _ => return Err(err),
+                }
+            }
             key if key.starts_with("concurrency") => {
                 if key == "concurrency" {
                     if let Some(value) = value {
@@ -1216,15 +1781,17 @@ impl Config {
     /// Constructs a [`Gateway`] using a [`ClientWithMiddleware`]
     pub fn gateway(&self, client: ClientWithMiddleware) -> Gateway {
         // Determine the cache directory and fall back to sane defaults otherwise.
-        let cache_dir = get_cache_dir().unwrap_or_else(|e| {
+        let cache_dir = self.repodata_cache_dir().unwrap_or_else(|e| {
             tracing::error!("failed to determine repodata cache directory: {e}");
-            std::env::current_dir().unwrap_or_else(|_| PathBuf::from("./"))
+            std::env::current_dir()
+                .unwrap_or_else(|_| PathBuf::from("./"))
+                .join(consts::CONDA_REPODATA_CACHE_DIR)
         });
 
         // Construct the gateway
         Gateway::builder()
             .with_client(client)
-            .with_cache_dir(cache_dir.join(consts::CONDA_REPODATA_CACHE_DIR))
+            .with_cache_dir(cache_dir)
             .with_channel_config(self.into())
             .with_max_concurrent_requests(self.max_concurrent_downloads())
             .finish()
@@ -1309,6 +1876,26 @@ UNUSED = "unused"
         assert_eq!(config.pinning_strategy, Some(expected));
     }
 
+    #[rstest]
+    #[case("\"insecure\"", RunPostLinkScripts::Insecure)]
+    #[case("false", RunPostLinkScripts::False)]
+    fn test_config_parse_run_post_link_scripts(
+        #[case] input: &str,
+        #[case] expected: RunPostLinkScripts,
+    ) {
+        let toml = format!("run-post-link-scripts = {}", input);
+        let (config, _) = Config::from_toml(&toml).unwrap();
+        assert_eq!(config.run_post_link_scripts, Some(expected));
+
+        let serialized = toml_edit::ser::to_string(&config).unwrap();
+        assert!(serialized.contains(&toml));
+    }
+
+    #[test]
+    fn test_config_parse_run_post_link_scripts_true() {
+        assert!(Config::from_toml("run-post-link-scripts = true").is_err());
+    }
+
     #[test]
     fn test_config_from_cli() {
         let cli = ConfigCli {
@@ -1411,6 +1998,26 @@ UNUSED = "unused"
             },
             loaded_from: Vec::from([PathBuf::from_str("test").unwrap()]),
             force_activate: Some(true),
+            theme: ThemeConfig {
+                accent_color: Some(AccentColor::Magenta),
+                emoji: Some(false),
+            },
+            shared_build_cache: Some(PathBuf::from("/path/to/build-cache")),
+            prefer_project_executables: Some(true),
+            channel_priority: Some(ChannelPriority::Disabled),
+            max_retries: Some(5),
+            shared_environments: Some(true),
+            run_post_link_scripts: Some(RunPostLinkScripts::Insecure),
+            cache_dirs: CacheDirsConfig {
+                packages: Some(PathBuf::from("/shared/pkgs")),
+                repodata: Some(PathBuf::from("/shared/repodata")),
+                pypi: Some(PathBuf::from("/shared/uv")),
+                tasks: Some(PathBuf::from("/local/tasks")),
+            },
+            container_runner: ContainerRunnerConfig {
+                engine: Some(ContainerEngine::Podman),
+                images: HashMap::from([(Platform::Linux64, "ghcr.io/prefix-dev/pixi".to_string())]),
+            },
             pypi_config: PyPIConfig {
                 allow_insecure_host: Vec::from(["test".to_string()]),
                 extra_index_urls: Vec::from([
@@ -1418,6 +2025,7 @@ UNUSED = "unused"
                 ]),
                 index_url: Some(Url::parse("https://conda.anaconda.org/conda-forge").unwrap()),
                 keyring_provider: Some(KeyringProvider::Subprocess),
+                link_mode: Some(LinkMode::Copy),
             },
             repodata_config: RepodataConfig {
                 default: RepodataChannelConfig {
@@ -1608,6 +2216,105 @@ UNUSED = "unused"
             Some(PathBuf::from("/path/to/envs"))
         );
 
+        config
+            .set("theme.accent-color", Some("cyan".to_string()))
+            .unwrap();
+        assert_eq!(config.theme().accent_color(), Some(AccentColor::Cyan));
+        assert!(config
+            .set("theme.accent-color", Some("purple".to_string()))
+            .is_err());
+        config
+            .set("theme.emoji", Some("false".to_string()))
+            .unwrap();
+        assert!(!config.theme().emoji());
+        config.set("theme", None).unwrap();
+        assert!(config.theme().emoji());
+
+        config
+            .set(
+                "shared-build-cache",
+                Some("/path/to/build-cache".to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.shared_build_cache(),
+            Some(&PathBuf::from("/path/to/build-cache"))
+        );
+
+        config
+            .set("prefer-project-executables", Some("false".to_string()))
+            .unwrap();
+        assert_eq!(config.prefer_project_executables(), Some(false));
+
+        config
+            .set("channel-priority", Some("disabled".to_string()))
+            .unwrap();
+        assert_eq!(config.channel_priority(), Some(ChannelPriority::Disabled));
+        assert!(config
+            .set("channel-priority", Some("lowest".to_string()))
+            .is_err());
+
+        assert_eq!(config.max_retries(), 3);
+        config.set("max-retries", Some("10".to_string())).unwrap();
+        assert_eq!(config.max_retries(), 10);
+        assert!(config.set("max-retries", Some("-1".to_string())).is_err());
+
+        assert!(!config.shared_environments());
+        config
+            .set("shared-environments", Some("true".to_string()))
+            .unwrap();
+        assert!(config.shared_environments());
+
+        assert_eq!(config.run_post_link_scripts(), RunPostLinkScripts::False);
+        config
+            .set("run-post-link-scripts", Some("insecure".to_string()))
+            .unwrap();
+        assert!(config.run_post_link_scripts().enabled());
+        assert!(config
+            .set("run-post-link-scripts", Some("true".to_string()))
+            .is_err());
+
+        config
+            .set("cache-dirs.packages", Some("/shared/pkgs".to_string()))
+            .unwrap();
+        assert_eq!(
+            config.package_cache_dir().unwrap(),
+            PathBuf::from("/shared/pkgs")
+        );
+        config
+            .set("cache-dirs.tasks", Some("/local/tasks".to_string()))
+            .unwrap();
+        assert_eq!(config.task_cache_dir(), Some(Path::new("/local/tasks")));
+        assert!(config
+            .set("cache-dirs.unknown", Some("/tmp".to_string()))
+            .is_err());
+        config.set("cache-dirs", None).unwrap();
+        assert_eq!(config.task_cache_dir(), None);
+
+        config
+            .set("container-runner.engine", Some("podman".to_string()))
+            .unwrap();
+        assert_eq!(config.container_runner().engine(), ContainerEngine::Podman);
+        config
+            .set(
+                "container-runner.images",
+                Some(r#"{"linux-64": "ghcr.io/prefix-dev/pixi"}"#.to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.container_runner().image(Platform::Linux64),
+            Some("ghcr.io/prefix-dev/pixi")
+        );
+        assert_eq!(
+            config.container_runner().image(Platform::LinuxAarch64),
+            None
+        );
+        assert!(config
+            .set("container-runner.engine", Some("lxc".to_string()))
+            .is_err());
+        config.set("container-runner", None).unwrap();
+        assert_eq!(config.container_runner().engine(), ContainerEngine::Docker);
+
         config
             .set("mirrors", Some(r#"{"https://conda.anaconda.org/conda-forge": ["https://prefix.dev/conda-forge"]}"#.to_string()))
             .unwrap();
@@ -1654,6 +2361,14 @@ UNUSED = "unused"
             Some(KeyringProvider::Subprocess)
         );
 
+        config
+            .set("pypi-config.link-mode", Some("hardlink".to_string()))
+            .unwrap();
+        assert_eq!(config.pypi_config().link_mode, Some(LinkMode::Hardlink));
+        config
+            .set("pypi-config.link-mode", Some("reflink".to_string()))
+            .unwrap_err();
+
         config.set("change-ps1", None).unwrap();
         assert_eq!(config.change_ps1, None);
 
@@ -11,12 +11,14 @@ pub const PROJECT_MANIFEST: &str = "pixi.toml";
 pub const PYPROJECT_MANIFEST: &str = "pyproject.toml";
 pub const PROJECT_LOCK_FILE: &str = "pixi.lock";
 pub const CONFIG_FILE: &str = "config.toml";
+pub const HISTORY_FILE: &str = "history.jsonl";
 pub const PIXI_DIR: &str = ".pixi";
 pub const PIXI_VERSION: &str = match option_env!("PIXI_VERSION") {
     Some(v) => v,
     None => "0.39.2",
 };
 pub const PREFIX_FILE_NAME: &str = "pixi_env_prefix";
+pub const DETACHED_PROJECT_MARKER: &str = "pixi_project_manifest";
 pub const ENVIRONMENTS_DIR: &str = "envs";
 pub const SOLVE_GROUP_ENVIRONMENTS_DIR: &str = "solve-group-envs";
 pub const PYPI_DEPENDENCIES: &str = "pypi-dependencies";
@@ -30,7 +32,9 @@ pub const CONDA_REPODATA_CACHE_DIR: &str = rattler_cache::REPODATA_CACHE_DIR;
 pub const CONDA_META_DIR: &str = "conda-meta";
 pub const PYPI_CACHE_DIR: &str = "uv-cache";
 pub const CONDA_PYPI_MAPPING_CACHE_DIR: &str = "conda-pypi-mapping";
+pub const GIT_CACHE_DIR: &str = "git-cache-v0";
 pub const CACHED_ENVS_DIR: &str = "cached-envs-v0";
+pub const SHARED_ENVS_DIR: &str = "shared-envs-v0";
 // TODO: CACHED_BUILD_ENVS_DIR was deprecated in favor of CACHED_BUILD_ENVS_DIR. This constant will be removed in a future release.
 pub const _CACHED_BUILD_ENVS_DIR: &str = "cached-build-envs-v0";
 pub const CACHED_BUILD_TOOL_ENVS_DIR: &str = "cached-build-tool-envs-v0";
@@ -43,6 +47,9 @@ pub const ONE_TIME_MESSAGES_DIR: &str = "one-time-messages";
 pub const DEFAULT_CHANNELS: &[&str] = &["conda-forge"];
 
 pub const ENVIRONMENT_FILE_NAME: &str = "pixi";
+pub const INSTALL_JOURNAL_FILE_NAME: &str = "pixi_install_journal";
+pub const INSTALL_BACKUP_DIR_NAME: &str = "pixi-install-backup";
+pub const REPAIR_BACKUP_DIR_NAME: &str = "pixi-repair-backup";
 
 lazy_static! {
     pub static ref TASK_STYLE: Style = Style::new().blue();
@@ -58,7 +65,7 @@ pub struct CondaEmoji;
 
 impl Display for CondaEmoji {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if console::Term::stderr().features().colors_supported() {
+        if console::colors_enabled_stderr() {
             write!(f, "{}", console::style("C").bold().green())
         } else {
             write!(f, "(conda)")
@@ -70,7 +77,7 @@ pub struct PypiEmoji;
 
 impl Display for PypiEmoji {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if console::Term::stderr().features().colors_supported() {
+        if console::colors_enabled_stderr() {
             write!(f, "{}", console::style("P").bold().blue())
         } else {
             write!(f, "(pypi)")
@@ -1,10 +1,44 @@
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 #[derive(Default, Clone, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Activation {
     pub scripts: Option<Vec<String>>,
     /// Environment variables to set before running the scripts.
+    ///
+    /// A list of values, e.g. a list of paths, is joined with the path
+    /// separator of the current platform.
+    #[serde(default, deserialize_with = "deserialize_env")]
     pub env: Option<IndexMap<String, String>>,
 }
+
+/// The value of an activation environment variable.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EnvValue {
+    Value(String),
+    List(Vec<String>),
+}
+
+impl EnvValue {
+    fn into_value(self) -> String {
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        match self {
+            EnvValue::Value(value) => value,
+            EnvValue::List(values) => values.join(separator),
+        }
+    }
+}
+
+fn deserialize_env<'de, D>(deserializer: D) -> Result<Option<IndexMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let env = Option::<IndexMap<String, EnvValue>>::deserialize(deserializer)?;
+    Ok(env.map(|env| {
+        env.into_iter()
+            .map(|(key, value)| (key, value.into_value()))
+            .collect()
+    }))
+}
@@ -18,7 +18,7 @@ use crate::{
     pypi::{pypi_options::PypiOptions, PyPiPackageName},
     target::Targets,
     utils::PixiSpanned,
-    PyPiRequirement, SpecType, SystemRequirements, WorkspaceTarget,
+    PyPiRequirement, SolveStrategy, SpecType, SystemRequirements, WorkspaceTarget,
 };
 
 /// The name of a feature. This is either a string or default for the default
@@ -136,6 +136,10 @@ pub struct Feature {
     /// it will be seen as unset and overwritten by a set one.
     pub channel_priority: Option<ChannelPriority>,
 
+    /// The strategy the solver uses to select the versions of the packages,
+    /// if not set the highest versions are selected.
+    pub solve_strategy: Option<SolveStrategy>,
+
     /// Additional system requirements
     pub system_requirements: SystemRequirements,
 
@@ -154,6 +158,7 @@ impl Feature {
             platforms: None,
             channels: None,
             channel_priority: None,
+            solve_strategy: None,
             system_requirements: SystemRequirements::default(),
             pypi_options: None,
 
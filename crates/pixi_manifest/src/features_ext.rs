@@ -8,7 +8,8 @@ use rattler_solve::ChannelPriority;
 
 use crate::{
     has_features_iter::HasFeaturesIter, pypi::pypi_options::PypiOptions, CondaDependencies,
-    HasManifestRef, PrioritizedChannel, PyPiDependencies, SpecType, SystemRequirements,
+    HasManifestRef, PrioritizedChannel, PyPiDependencies, SolveStrategy, SpecType,
+    SystemRequirements,
 };
 
 /// ChannelPriorityCombination error, thrown when multiple channel priorities
@@ -17,6 +18,12 @@ use crate::{
 #[error("Multiple channel priorities are not allowed in a single environment")]
 pub struct ChannelPriorityCombinationError;
 
+/// SolveStrategyCombination error, thrown when multiple solve strategies are
+/// set
+#[derive(Debug, thiserror::Error)]
+#[error("Multiple solve strategies are not allowed in a single environment")]
+pub struct SolveStrategyCombinationError;
+
 /// A trait that implement various methods for collections that combine
 /// attributes of Features It is implemented by Environment, GroupedEnvironment
 /// and SolveGroup. Remove some of the boilerplate of combining features and its
@@ -72,7 +79,7 @@ pub trait FeaturesExt<'source>: HasManifestRef<'source> + HasFeaturesIter<'sourc
         let mut channel_priority = None;
         for feature in self.features() {
             if let Some(priority) = feature.channel_priority {
-                if channel_priority == Some(priority) {
+                if channel_priority.is_some_and(|current| current != priority) {
                     return Err(ChannelPriorityCombinationError);
                 }
                 channel_priority = Some(priority);
@@ -81,6 +88,21 @@ pub trait FeaturesExt<'source>: HasManifestRef<'source> + HasFeaturesIter<'sourc
         Ok(channel_priority)
     }
 
+    /// Returns the solve strategy, error on multiple values, return None if
+    /// no value is set.
+    fn solve_strategy(&self) -> Result<Option<SolveStrategy>, SolveStrategyCombinationError> {
+        let mut solve_strategy = None;
+        for feature in self.features() {
+            if let Some(strategy) = feature.solve_strategy {
+                if solve_strategy.is_some_and(|current| current != strategy) {
+                    return Err(SolveStrategyCombinationError);
+                }
+                solve_strategy = Some(strategy);
+            }
+        }
+        Ok(solve_strategy)
+    }
+
     /// Returns the platforms that this collection is compatible with.
     ///
     /// Which platforms a collection support depends on which platforms the
@@ -9,12 +9,14 @@ mod feature;
 mod features_ext;
 mod has_features_iter;
 mod has_manifest_ref;
+mod lint;
 mod manifests;
 mod package;
 mod preview;
 pub mod pypi;
 pub mod pyproject;
 mod solve_group;
+mod solve_strategy;
 mod spec_type;
 mod system_requirements;
 mod target;
@@ -35,11 +37,13 @@ pub use features_ext::FeaturesExt;
 pub use has_features_iter::HasFeaturesIter;
 pub use has_manifest_ref::HasManifestRef;
 use itertools::Itertools;
+pub use lint::{Lint, LintKind};
 pub use manifests::{Manifest, ManifestKind, ManifestSource, PackageManifest, WorkspaceManifest};
 use miette::Diagnostic;
 pub use preview::{KnownPreviewFeature, Preview, PreviewFeature};
 pub use pypi::pypi_requirement::PyPiRequirement;
 use rattler_conda_types::Platform;
+pub use solve_strategy::SolveStrategy;
 pub use spec_type::SpecType;
 pub use system_requirements::{LibCSystemRequirement, SystemRequirements};
 pub use target::{TargetSelector, Targets, WorkspaceTarget};
@@ -0,0 +1,497 @@
+use std::{collections::HashSet, fmt::Display};
+
+use itertools::Itertools;
+use miette::{Diagnostic, SourceSpan};
+use pixi_spec::PixiSpec;
+use rattler_conda_types::{ChannelConfig, Platform, VersionSpec};
+use thiserror::Error;
+use toml_edit::{ImDocument, Item};
+use url::Url;
+
+use crate::{Environment, Feature, FeatureName, Manifest, PrioritizedChannel, SpecType};
+
+/// The kind of issue found by [`Manifest::lint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// A target for platforms that no environment with the feature supports.
+    UnusedTarget,
+    /// A package that is both a run dependency and a host or build dependency.
+    DuplicateDependency,
+    /// A conda dependency without any version constraint.
+    UnconstrainedDependency,
+    /// A channel that none of the locked packages comes from.
+    UnusedChannel,
+    /// A task that depends on a task that is not defined.
+    MissingTaskDependency,
+}
+
+impl LintKind {
+    /// The name of the lint, as shown to the user.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::UnusedTarget => "unused-target",
+            LintKind::DuplicateDependency => "duplicate-dependency",
+            LintKind::UnconstrainedDependency => "unconstrained-dependency",
+            LintKind::UnusedChannel => "unused-channel",
+            LintKind::MissingTaskDependency => "missing-task-dependency",
+        }
+    }
+}
+
+impl Display for LintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An issue found in the manifest. The span points into the contents of the
+/// manifest, so the issue can be rendered like a parse error.
+#[derive(Debug, Clone, Error, Diagnostic)]
+#[error("{message}")]
+#[diagnostic(severity(Warning))]
+pub struct Lint {
+    pub kind: LintKind,
+    pub message: String,
+    #[label("{label}")]
+    pub span: Option<SourceSpan>,
+    pub label: String,
+    #[help]
+    pub help: Option<String>,
+}
+
+impl Manifest {
+    /// Checks the manifest for issues that are not errors, but likely
+    /// mistakes.
+    ///
+    /// If the urls of the locked conda packages are given, the channels that
+    /// none of them come from are reported as well.
+    pub fn lint(
+        &self,
+        locked_package_urls: Option<&[Url]>,
+        channel_config: &ChannelConfig,
+    ) -> Vec<Lint> {
+        let linter = Linter::new(self);
+        let mut lints = Vec::new();
+        for feature in self.workspace.features.values() {
+            linter.unused_targets(feature, &mut lints);
+            linter.dependencies(feature, &mut lints);
+            linter.task_dependencies(feature, &mut lints);
+        }
+        if let Some(urls) = locked_package_urls {
+            linter.unused_channels(urls, channel_config, &mut lints);
+        }
+        lints
+    }
+}
+
+/// Looks up the spans of the lints in the original contents of the manifest.
+struct Linter<'a> {
+    manifest: &'a Manifest,
+    document: Option<ImDocument<&'a str>>,
+    prefix: &'static [&'static str],
+}
+
+impl<'a> Linter<'a> {
+    fn new(manifest: &'a Manifest) -> Self {
+        Self {
+            manifest,
+            document: manifest
+                .contents
+                .as_deref()
+                .and_then(|contents| ImDocument::parse(contents).ok()),
+            prefix: if manifest.source.is_pyproject_toml() {
+                &["tool", "pixi"]
+            } else {
+                &[]
+            },
+        }
+    }
+
+    /// Returns the item at the path of keys, relative to the pixi
+    /// configuration in the manifest.
+    fn item(&self, path: &[&str]) -> Option<&Item> {
+        let mut item = self.document.as_ref()?.as_item();
+        for key in self.prefix.iter().chain(path) {
+            item = item.as_table_like()?.get(key)?;
+        }
+        Some(item)
+    }
+
+    fn span(&self, path: &[&str]) -> Option<SourceSpan> {
+        self.item(path)?.span().map(SourceSpan::from)
+    }
+
+    /// Returns the path of the table of the feature and target.
+    fn table_path(feature: &Feature, target: Option<String>) -> Vec<String> {
+        let mut path = Vec::new();
+        if let FeatureName::Named(name) = &feature.name {
+            path.extend(["feature".to_string(), name.clone()]);
+        }
+        if let Some(target) = target {
+            path.extend(["target".to_string(), target]);
+        }
+        path
+    }
+
+    /// The platforms of the environment, which are the platforms all of its
+    /// features support.
+    fn environment_platforms(&self, environment: &Environment) -> HashSet<Platform> {
+        let workspace = &self.manifest.workspace;
+        environment
+            .features
+            .iter()
+            .filter_map(|name| workspace.features.get(&FeatureName::Named(name.clone())))
+            .chain((!environment.no_default_feature).then(|| workspace.default_feature()))
+            .map(|feature| {
+                feature
+                    .platforms
+                    .as_ref()
+                    .unwrap_or(&workspace.workspace.platforms)
+                    .value
+                    .iter()
+                    .copied()
+                    .collect::<HashSet<_>>()
+            })
+            .reduce(|platforms, feature_platforms| {
+                platforms
+                    .intersection(&feature_platforms)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Targets of a feature that no environment with the feature can use,
+    /// because the platforms are removed by another feature of the
+    /// environment.
+    fn unused_targets(&self, feature: &Feature, lints: &mut Vec<Lint>) {
+        let platforms: HashSet<Platform> = self
+            .manifest
+            .workspace
+            .environments
+            .iter()
+            .filter(|environment| match &feature.name {
+                FeatureName::Default => !environment.no_default_feature,
+                FeatureName::Named(name) => environment.features.contains(name),
+            })
+            .flat_map(|environment| self.environment_platforms(environment))
+            .collect();
+        // A feature that is not used by any environment is not linted.
+        if platforms.is_empty() {
+            return;
+        }
+
+        for selector in feature.targets.user_defined_selectors() {
+            if platforms.iter().any(|platform| selector.matches(*platform)) {
+                continue;
+            }
+            lints.push(Lint {
+                kind: LintKind::UnusedTarget,
+                message: format!(
+                    "the target '{}' of {} is not used by any environment",
+                    selector,
+                    feature_description(feature)
+                ),
+                span: feature.targets.source_loc(selector).map(SourceSpan::from),
+                label: "unused target".to_string(),
+                help: Some(format!(
+                    "the environments with this feature only support {}",
+                    platforms.iter().map(Platform::as_str).sorted().join(", ")
+                )),
+            });
+        }
+    }
+
+    /// Packages that are a run dependency and a host or build dependency at
+    /// the same time, and dependencies without a version constraint.
+    fn dependencies(&self, feature: &Feature, lints: &mut Vec<Lint>) {
+        for (target, selector) in feature.targets.iter() {
+            let table = Self::table_path(feature, selector.map(ToString::to_string));
+            let span = |spec_type: SpecType, name: &str| {
+                let path = table
+                    .iter()
+                    .map(String::as_str)
+                    .chain([spec_type.name(), name])
+                    .collect_vec();
+                self.span(&path)
+            };
+
+            let run_dependencies = target.dependencies.get(&SpecType::Run);
+            for spec_type in [SpecType::Host, SpecType::Build] {
+                let Some(dependencies) = target.dependencies.get(&spec_type) else {
+                    continue;
+                };
+                for name in dependencies.keys() {
+                    if !run_dependencies.is_some_and(|run| run.contains_key(name)) {
+                        continue;
+                    }
+                    lints.push(Lint {
+                        kind: LintKind::DuplicateDependency,
+                        message: format!(
+                            "'{}' is in both `{}` and `{}`",
+                            name.as_source(),
+                            SpecType::Run.name(),
+                            spec_type.name()
+                        ),
+                        span: span(spec_type, name.as_source()),
+                        label: "also a run dependency".to_string(),
+                        help: Some(format!(
+                            "a run dependency is also available at build time, remove it from `{}` unless the constraints differ",
+                            spec_type.name()
+                        )),
+                    });
+                }
+            }
+
+            for (spec_type, dependencies) in target
+                .dependencies
+                .iter()
+                .sorted_by_key(|(spec_type, _)| spec_type.name())
+            {
+                for (name, spec) in dependencies {
+                    if !is_unconstrained(spec) {
+                        continue;
+                    }
+                    lints.push(Lint {
+                        kind: LintKind::UnconstrainedDependency,
+                        message: format!(
+                            "'{}' does not have a version constraint",
+                            name.as_source()
+                        ),
+                        span: span(*spec_type, name.as_source()),
+                        label: "any version".to_string(),
+                        help: Some(
+                            "an update of the lock file can pick any new major version, add a constraint like `>=1.2,<2`"
+                                .to_string(),
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Tasks that depend on a task that is not defined in any feature or
+    /// target.
+    fn task_dependencies(&self, feature: &Feature, lints: &mut Vec<Lint>) {
+        let defined: HashSet<_> = self
+            .manifest
+            .workspace
+            .features
+            .values()
+            .flat_map(|feature| feature.targets.iter())
+            .flat_map(|(target, _)| target.tasks.keys())
+            .collect();
+
+        for (target, selector) in feature.targets.iter() {
+            let table = Self::table_path(feature, selector.map(ToString::to_string));
+            for (name, task) in target.tasks.iter().sorted_by_key(|(name, _)| *name) {
+                for dependency in task.depends_on() {
+                    if defined.contains(dependency) {
+                        continue;
+                    }
+                    let task_path = table
+                        .iter()
+                        .map(String::as_str)
+                        .chain(["tasks", name.as_str()])
+                        .collect_vec();
+                    let span = ["depends-on", "depends_on"]
+                        .into_iter()
+                        .find_map(|key| {
+                            let mut path = task_path.clone();
+                            path.push(key);
+                            self.span(&path)
+                        })
+                        .or_else(|| self.span(&task_path));
+                    lints.push(Lint {
+                        kind: LintKind::MissingTaskDependency,
+                        message: format!(
+                            "the task '{}' depends on '{}', which is not defined",
+                            name.as_str(),
+                            dependency.as_str()
+                        ),
+                        span,
+                        label: format!("'{}' is not a task", dependency.as_str()),
+                        help: Some("define the task or remove it from `depends-on`".to_string()),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Channels that none of the locked packages come from.
+    fn unused_channels(
+        &self,
+        locked_package_urls: &[Url],
+        channel_config: &ChannelConfig,
+        lints: &mut Vec<Lint>,
+    ) {
+        let workspace = &self.manifest.workspace;
+        let features = workspace.features.values().filter_map(|feature| {
+            let channels = match &feature.name {
+                FeatureName::Default => &workspace.workspace.channels,
+                FeatureName::Named(_) => feature.channels.as_ref()?,
+            };
+            Some((feature, channels))
+        });
+
+        for (feature, channels) in features {
+            for (index, PrioritizedChannel { channel, .. }) in channels.iter().enumerate() {
+                let Ok(base_url) = channel.clone().into_base_url(channel_config) else {
+                    continue;
+                };
+                if locked_package_urls
+                    .iter()
+                    .any(|url| url.as_str().starts_with(base_url.as_str()))
+                {
+                    continue;
+                }
+                let span = match &feature.name {
+                    FeatureName::Default => ["project", "workspace"]
+                        .into_iter()
+                        .find_map(|table| self.channel_span(&[table, "channels"], index)),
+                    FeatureName::Named(name) => {
+                        self.channel_span(&["feature", name.as_str(), "channels"], index)
+                    }
+                };
+                lints.push(Lint {
+                    kind: LintKind::UnusedChannel,
+                    message: format!(
+                        "none of the locked packages come from the channel '{}'",
+                        channel
+                    ),
+                    span,
+                    label: "unused channel".to_string(),
+                    help: Some(
+                        "remove the channel with `pixi project channel remove`, unless it is only needed on a platform that is not locked yet"
+                            .to_string(),
+                    ),
+                });
+            }
+        }
+    }
+
+    fn channel_span(&self, path: &[&str], index: usize) -> Option<SourceSpan> {
+        self.item(path)?
+            .as_array()?
+            .get(index)?
+            .span()
+            .map(SourceSpan::from)
+    }
+}
+
+/// Returns true if the spec of a binary package allows any version and build.
+fn is_unconstrained(spec: &PixiSpec) -> bool {
+    match spec {
+        PixiSpec::Version(version) => matches!(version, VersionSpec::Any),
+        PixiSpec::DetailedVersion(detailed) => {
+            matches!(detailed.version, None | Some(VersionSpec::Any))
+                && detailed.build.is_none()
+                && detailed.build_number.is_none()
+                && detailed.file_name.is_none()
+                && detailed.md5.is_none()
+                && detailed.sha256.is_none()
+        }
+        _ => false,
+    }
+}
+
+fn feature_description(feature: &Feature) -> String {
+    match &feature.name {
+        FeatureName::Default => "the project".to_string(),
+        FeatureName::Named(name) => format!("the feature '{name}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Returns the kind and the spanned text of the lints, sorted.
+    fn lint(contents: &str, locked_package_urls: Option<&[Url]>) -> Vec<(&'static str, String)> {
+        let manifest = Manifest::from_str(Path::new("pixi.toml"), contents).unwrap();
+        let channel_config = ChannelConfig::default_with_root_dir(std::env::current_dir().unwrap());
+        manifest
+            .lint(locked_package_urls, &channel_config)
+            .into_iter()
+            .map(|lint| {
+                let spanned = lint
+                    .span
+                    .map(|span| contents[span.offset()..span.offset() + span.len()].trim())
+                    .unwrap_or_default()
+                    .to_string();
+                (lint.kind.as_str(), spanned)
+            })
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        let contents = r#"
+        [project]
+        name = "foo"
+        channels = ["conda-forge", "bioconda"]
+        platforms = ["linux-64", "osx-arm64"]
+
+        [dependencies]
+        python = ">=3.12,<3.13"
+        numpy = "*"
+        cmake = "3.*"
+
+        [host-dependencies]
+        cmake = "3.*"
+
+        [tasks]
+        test = { cmd = "pytest", depends-on = ["build"] }
+
+        [feature.linux]
+        platforms = ["linux-64"]
+
+        [feature.tools.target.osx-arm64.dependencies]
+        clang = "18.*"
+
+        [environments]
+        linux = ["linux", "tools"]
+        "#;
+
+        let locked =
+            [
+                Url::parse(
+                    "https://conda.anaconda.org/conda-forge/linux-64/python-3.12.0-h1.conda",
+                )
+                .unwrap(),
+            ];
+        let lints = lint(contents, Some(&locked));
+
+        assert_eq!(
+            lints,
+            vec![
+                ("duplicate-dependency", r#""3.*""#.to_string()),
+                ("missing-task-dependency", r#"["build"]"#.to_string()),
+                ("unconstrained-dependency", r#""*""#.to_string()),
+                ("unused-channel", r#""bioconda""#.to_string()),
+                ("unused-target", "osx-arm64".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_lints() {
+        let contents = r#"
+        [project]
+        name = "foo"
+        channels = ["conda-forge"]
+        platforms = ["linux-64"]
+
+        [dependencies]
+        python = ">=3.12,<3.13"
+
+        [tasks]
+        build = "make"
+        test = { cmd = "pytest", depends-on = ["build"] }
+        "#;
+        assert!(lint(contents, None).is_empty());
+    }
+}
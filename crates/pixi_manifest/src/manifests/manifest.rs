@@ -9,7 +9,6 @@ use std::{
 };
 
 use indexmap::{Equivalent, IndexSet};
-use itertools::Itertools;
 use miette::{miette, IntoDiagnostic, NamedSource, Report, WrapErr};
 use pixi_spec::PixiSpec;
 use rattler_conda_types::{ChannelConfig, MatchSpec, PackageName, Platform, Version};
@@ -160,10 +159,11 @@ impl Manifest {
         })
     }
 
-    /// Save the manifest to the file and update the contents
+    /// Save the manifest to the file and update the contents. The file is
+    /// replaced atomically, so an interrupted save never truncates it.
     pub fn save(&mut self) -> miette::Result<()> {
         let contents = self.source.to_string();
-        fs_err::write(&self.path, &contents).into_diagnostic()?;
+        crate::utils::write_atomic(&self.path, &contents).into_diagnostic()?;
         self.contents = Some(contents);
         Ok(())
     }
@@ -215,6 +215,32 @@ impl Manifest {
         Ok(())
     }
 
+    /// Replaces the definition of an existing task of the given target and
+    /// feature.
+    pub fn set_task(
+        &mut self,
+        name: TaskName,
+        task: Task,
+        target: Option<&TargetSelector>,
+        feature_name: &FeatureName,
+    ) -> miette::Result<()> {
+        let target_tasks = &mut self
+            .feature_mut(feature_name)?
+            .targets
+            .for_opt_target_mut(target)
+            .ok_or_else(|| miette::miette!("task {} does not exist", name))?
+            .tasks;
+        let Some(existing) = target_tasks.get_mut(&name) else {
+            miette::bail!("task {} does not exist", name);
+        };
+        *existing = task.clone();
+
+        self.source
+            .set_task(name.as_str(), task, target, feature_name)?;
+
+        Ok(())
+    }
+
     /// Adds an environment to the project. Overwrites the entry if it already
     /// exists.
     pub fn add_environment(
@@ -279,6 +305,37 @@ impl Manifest {
         Ok(true)
     }
 
+    /// Copies the environment `from` to a new environment `to`, or renames it
+    /// if `rename` is set.
+    pub fn copy_environment(&mut self, from: &str, to: &str, rename: bool) -> miette::Result<()> {
+        if self.environment(to).is_some() {
+            miette::bail!("the environment '{to}' already exists");
+        }
+        if self.environment(from).is_none() {
+            miette::bail!("the environment '{from}' does not exist");
+        }
+
+        let mut source = self.source.clone();
+        if !source.copy_environment(from, to, rename)? {
+            // The default environment exists without being defined in the
+            // manifest.
+            if rename {
+                miette::bail!(
+                    help =
+                        "define the default environment in the `[environments]` table to rename it",
+                    "the default environment cannot be renamed"
+                );
+            }
+            source.add_environment(to, Some(Vec::new()), None, false)?;
+        }
+
+        let manifest = Manifest::from_str(&self.path, source.to_string())?;
+        self.source = manifest.source;
+        self.workspace = manifest.workspace;
+        self.package = manifest.package;
+        Ok(())
+    }
+
     /// Remove a task from the project, and the tasks that depend on it
     pub fn remove_task(
         &mut self,
@@ -359,9 +416,12 @@ impl Manifest {
         current.retain(|p| retained.contains(p));
 
         // And from the TOML document
-        let retained = retained.iter().map(|p| p.to_string()).collect_vec();
         let platforms = self.source.get_array_mut("platforms", feature_name)?;
-        platforms.retain(|x| retained.contains(&x.to_string()));
+        platforms.retain(|x| {
+            x.as_str()
+                .and_then(|platform| Platform::from_str(platform).ok())
+                .map_or(true, |platform| retained.contains(&platform))
+        });
 
         Ok(())
     }
@@ -667,6 +727,40 @@ impl Manifest {
         Ok(())
     }
 
+    /// Sets the value at the dotted key, e.g. `project.version`, relative to
+    /// the pixi configuration in the manifest.
+    ///
+    /// The value is parsed as TOML and otherwise used as a string. The
+    /// manifest is parsed again to validate the new value, if it is not valid
+    /// the manifest is left unchanged.
+    pub fn set_value(&mut self, key: &str, value: &str) -> miette::Result<()> {
+        let string_value = Value::from(value);
+        let candidates = match value.parse::<Value>() {
+            Ok(parsed) if parsed.is_str() => vec![parsed],
+            // A value like `1.0` is also tried as a string, e.g. for a version.
+            Ok(parsed) => vec![parsed, string_value],
+            Err(_) => vec![string_value],
+        };
+
+        let mut first_error = None;
+        for candidate in candidates {
+            let mut source = self.source.clone();
+            source.set_value(key, candidate)?;
+            match Manifest::from_str(&self.path, source.to_string()) {
+                Ok(manifest) => {
+                    self.source = manifest.source;
+                    self.workspace = manifest.workspace;
+                    self.package = manifest.package;
+                    return Ok(());
+                }
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        Err(first_error.expect("there is always at least one candidate"))
+    }
+
     /// Returns a mutable reference to a target, creating it if needed
     pub fn get_or_insert_target_mut(
         &mut self,
@@ -773,6 +867,7 @@ mod tests {
     use glob::glob;
     use indexmap::IndexMap;
     use insta::assert_snapshot;
+    use itertools::Itertools;
     use miette::NarratableReportHandler;
     use rattler_conda_types::{
         NamedChannelOrUrl, ParseStrictness,
@@ -784,7 +879,7 @@ mod tests {
     use tempfile::tempdir;
 
     use super::*;
-    use crate::channel::PrioritizedChannel;
+    use crate::{channel::PrioritizedChannel, SolveStrategy};
 
     const PROJECT_BOILERPLATE: &str = r#"
         [project]
@@ -820,6 +915,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_value() {
+        let contents = r#"
+        [project]
+        name = "foo"
+        # The version of the project
+        version = "0.1.0" # comment
+        channels = []
+        platforms = ["linux-64", "win-64", "osx-64"]
+        "#;
+        let mut manifest = Manifest::from_str(Path::new("pixi.toml"), contents).unwrap();
+
+        // `1.0` is not a valid version as a float, so it is set as a string.
+        manifest.set_value("project.version", "1.0").unwrap();
+        assert_eq!(
+            manifest.workspace.workspace.version,
+            Some(Version::from_str("1.0").unwrap())
+        );
+        manifest
+            .set_value("target.linux-64.dependencies.foo", "\">=1.2\"")
+            .unwrap();
+
+        // A value of the wrong type is rejected and the manifest is not changed.
+        assert!(manifest.set_value("project.platforms", "true").is_err());
+        assert!(manifest.set_value("project.platforms.linux", "1").is_err());
+
+        let item = manifest
+            .source
+            .get_item("project.version")
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.as_str(), Some("1.0"));
+        assert!(manifest
+            .source
+            .get_item("project.unknown")
+            .unwrap()
+            .is_none());
+
+        // The formatting of the manifest is preserved.
+        let source = manifest.source.to_string();
+        assert!(
+            source.contains("# The version of the project\n        version = \"1.0\" # comment")
+        );
+        assert!(source.contains("[target.linux-64.dependencies]\nfoo = \">=1.2\""));
+    }
+
     #[test]
     fn test_activation_scripts() {
         let contents = r#"
@@ -1292,7 +1433,8 @@ feature_target_dep = "*"
             [project]
             name = "foo"
             version = "0.1.0"
-            description = "foo description"
+            # The description is shown on the index
+            description = "foo description" # keep it short
             channels = []
             platforms = ["linux-64", "win-64"]
         "#;
@@ -1324,6 +1466,11 @@ feature_target_dep = "*"
                 .clone(),
             String::from("my new description")
         );
+
+        // The comments around the description are preserved
+        let source = manifest.source.to_string();
+        assert!(source.contains("# The description is shown on the index\n"));
+        assert!(source.contains(r#"description = "my new description" # keep it short"#));
     }
 
     #[test]
@@ -1436,6 +1583,16 @@ feature_target_dep = "*"
             manifest.workspace.workspace.platforms.value,
             vec![Platform::Win64].into_iter().collect::<IndexSet<_>>()
         );
+        let document = DocumentMut::from_str(&manifest.source.to_string()).unwrap();
+        assert_eq!(
+            document["project"]["platforms"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|platform| platform.as_str())
+                .collect_vec(),
+            vec!["win-64"]
+        );
 
         assert_eq!(
             manifest
@@ -2008,6 +2165,63 @@ test = "test initial"
         assert_snapshot!(manifest.source.to_string());
     }
 
+    #[test]
+    fn test_set_task() {
+        let file_contents = r#"
+[project]
+name = "foo"
+channels = []
+platforms = ["linux-64", "win-64"]
+
+[tasks]
+first = "echo first"
+second = "echo second"
+
+[target.unix.tasks]
+unix = "echo unix"
+        "#;
+
+        let mut manifest = Manifest::from_str(Path::new("pixi.toml"), file_contents).unwrap();
+
+        manifest
+            .set_task(
+                "first".into(),
+                Task::Plain("echo changed".to_string()),
+                None,
+                &FeatureName::Default,
+            )
+            .unwrap();
+        manifest
+            .set_task(
+                "unix".into(),
+                Task::Plain("echo changed unix".to_string()),
+                Some(&TargetSelector::Unix),
+                &FeatureName::Default,
+            )
+            .unwrap();
+        assert!(manifest
+            .set_task(
+                "missing".into(),
+                Task::Plain("echo missing".to_string()),
+                None,
+                &FeatureName::Default,
+            )
+            .is_err());
+
+        let source = manifest.source.to_string();
+        assert!(source.contains("first = \"echo changed\"\nsecond = \"echo second\""));
+        assert!(source.contains("[target.unix.tasks]\nunix = \"echo changed unix\""));
+        assert_eq!(
+            manifest
+                .tasks(Some(Platform::Linux64), &FeatureName::Default)
+                .unwrap()
+                .get(&TaskName::from("unix"))
+                .and_then(|task| task.as_single_command())
+                .as_deref(),
+            Some("echo changed unix")
+        );
+    }
+
     #[test]
     fn test_add_dependency() {
         let file_contents = r#"
@@ -2131,6 +2345,62 @@ bar = "*"
         assert_snapshot!(manifest.source.to_string());
     }
 
+    #[test]
+    fn test_add_detailed_dependency() {
+        let file_contents = r#"
+[project]
+name = "foo"
+channels = []
+platforms = ["linux-64"]
+
+[dependencies]
+foo = "*" # keep this comment
+            "#;
+        let channel_config = default_channel_config();
+        let mut manifest = Manifest::from_str(Path::new("pixi.toml"), file_contents).unwrap();
+        for spec in [
+            "conda-forge/linux-64::numpy >=1.2 py*",
+            "foo[build_number=\">=3\"]",
+        ] {
+            manifest
+                .add_dependency(
+                    &MatchSpec::from_str(spec, Strict).unwrap(),
+                    SpecType::Run,
+                    &[],
+                    &FeatureName::Default,
+                    DependencyOverwriteBehavior::Overwrite,
+                    &channel_config,
+                )
+                .unwrap();
+        }
+
+        let source = manifest.source.to_string();
+        assert!(
+            source.contains(
+                r#"numpy = { version = ">=1.2", build = "py*", channel = "conda-forge", subdir = "linux-64" }"#
+            ),
+            "{source}"
+        );
+        assert!(
+            source.contains(r#"foo = { build-number = ">=3" } # keep this comment"#),
+            "{source}"
+        );
+
+        // The specs are the same after parsing the manifest again.
+        let parsed = Manifest::from_str(Path::new("pixi.toml"), source).unwrap();
+        let dependencies = |manifest: &Manifest| {
+            manifest
+                .default_feature()
+                .targets
+                .default()
+                .dependencies
+                .get(&SpecType::Run)
+                .cloned()
+                .unwrap()
+        };
+        assert_eq!(dependencies(&parsed), dependencies(&manifest));
+    }
+
     #[test]
     fn test_add_environment() {
         let contents = r#"
@@ -2148,6 +2418,50 @@ bar = "*"
         assert!(manifest.environment("test").is_some());
     }
 
+    #[test]
+    fn test_copy_environment() {
+        let contents = r#"
+        [project]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [feature.cuda]
+
+        [environments]
+        cuda = ["cuda"]
+        other = []
+        "#;
+        let mut manifest = Manifest::from_str(Path::new("pixi.toml"), contents).unwrap();
+
+        manifest.copy_environment("cuda", "gpu", false).unwrap();
+        assert_eq!(manifest.environment("gpu").unwrap().features, vec!["cuda"]);
+        assert!(manifest.environment("cuda").is_some());
+
+        manifest.copy_environment("cuda", "cuda12", true).unwrap();
+        assert!(manifest.environment("cuda").is_none());
+        assert_eq!(
+            manifest.environment("cuda12").unwrap().features,
+            vec!["cuda"]
+        );
+        // The renamed environment keeps its position.
+        let names = manifest
+            .workspace
+            .environments
+            .iter()
+            .map(|env| env.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["default", "cuda12", "other", "gpu"]);
+
+        // The default environment can be copied but not renamed.
+        manifest.copy_environment("default", "base", false).unwrap();
+        assert!(manifest.environment("base").is_some());
+        assert!(manifest.copy_environment("default", "main", true).is_err());
+
+        // Existing environments are not overwritten.
+        assert!(manifest.copy_environment("gpu", "other", false).is_err());
+    }
+
     #[test]
     fn test_add_environment_with_feature() {
         let contents = r#"
@@ -2281,6 +2595,36 @@ bar = "*"
         );
     }
 
+    #[test]
+    pub fn test_solve_strategy_manifest() {
+        let manifest = Manifest::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [project]
+        name = "foo"
+        platforms = []
+        channels = []
+        solve-strategy = "highest"
+
+        [feature.min-versions]
+        solve-strategy = "lowest-direct"
+
+        [environments]
+        min-versions = ["min-versions"]
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.default_feature().solve_strategy,
+            Some(SolveStrategy::Highest)
+        );
+        assert_eq!(
+            manifest.feature("min-versions").unwrap().solve_strategy,
+            Some(SolveStrategy::LowestDirect)
+        );
+    }
+
     #[test]
     pub fn test_unsupported_pep508_errors() {
         let manifest_error = Manifest::from_str(
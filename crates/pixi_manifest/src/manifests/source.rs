@@ -3,12 +3,12 @@ use std::fmt;
 use pixi_consts::{consts, consts::PYPROJECT_PIXI_PREFIX};
 use pixi_spec::PixiSpec;
 use rattler_conda_types::{PackageName, Platform};
-use toml_edit::{value, Array, Item, Table, Value};
+use toml_edit::{value, Array, InlineTable, Item, Key, Table, TableLike, Value};
 
 use crate::toml::TomlDocument;
 use crate::{
     manifests::table_name::TableName, pypi::PyPiPackageName, FeatureName, PyPiRequirement,
-    PypiDependencyLocation, SpecType, Task, TomlError,
+    PypiDependencyLocation, SpecType, TargetSelector, Task, TomlError,
 };
 
 /// Discriminates between a 'pixi.toml' and a 'pyproject.toml' manifest.
@@ -207,9 +207,18 @@ impl ManifestSource {
             .with_feature_name(Some(feature_name))
             .with_table(Some(spec_type.name()));
 
-        self.manifest_mut()
-            .get_or_insert_nested_table(dependency_table.to_string().as_str())
-            .map(|t| t.insert(name.as_normalized(), Item::Value(spec.to_toml_value())))?;
+        let table = self
+            .manifest_mut()
+            .get_or_insert_nested_table(dependency_table.to_string().as_str())?;
+
+        // Specs with more than a version, e.g. a channel or a build string, are
+        // written as an inline table. Comments around an existing dependency
+        // are kept.
+        let mut value = spec.to_toml_value();
+        if let Some(existing) = table.get(name.as_normalized()).and_then(Item::as_value) {
+            *value.decor_mut() = existing.decor().clone();
+        }
+        table.insert(name.as_normalized(), Item::Value(value));
 
         Ok(())
     }
@@ -415,6 +424,37 @@ impl ManifestSource {
         Ok(())
     }
 
+    /// Replaces the definition of an existing task in the TOML manifest while
+    /// keeping its position in the task table and its comments.
+    pub fn set_task(
+        &mut self,
+        name: &str,
+        task: Task,
+        target: Option<&TargetSelector>,
+        feature_name: &FeatureName,
+    ) -> Result<(), TomlError> {
+        let task_table = TableName::new()
+            .with_prefix(self.table_prefix())
+            .with_target(target)
+            .with_feature_name(Some(feature_name))
+            .with_table(Some("tasks"));
+
+        let table = self
+            .manifest_mut()
+            .get_or_insert_nested_table(task_table.to_string().as_str())?;
+        let Item::Value(new) = Item::from(task) else {
+            unreachable!("tasks are always converted to a value")
+        };
+        match table.get_mut(name) {
+            Some(existing) => replace_keeping_decor(existing, new),
+            None => {
+                table.insert(name, Item::Value(new));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Adds an environment to the manifest
     pub fn add_environment(
         &mut self,
@@ -470,19 +510,174 @@ impl ManifestSource {
             .is_some())
     }
 
+    /// Copies the environment `from` to a new environment `to`. If `rename` is
+    /// set the environment `from` is replaced by `to` instead, keeping its
+    /// position in the manifest. Returns `false` if the environment `from`
+    /// does not exist.
+    pub fn copy_environment(
+        &mut self,
+        from: &str,
+        to: &str,
+        rename: bool,
+    ) -> Result<bool, TomlError> {
+        let env_table = TableName::new()
+            .with_prefix(self.table_prefix())
+            .with_feature_name(Some(&FeatureName::Default))
+            .with_table(Some("environments"));
+        let table = self
+            .manifest_mut()
+            .get_or_insert_nested_table(env_table.to_string().as_str())?;
+
+        let Some(item) = table.get(from).cloned() else {
+            return Ok(false);
+        };
+
+        if !rename {
+            table.insert(to, item);
+            return Ok(true);
+        }
+
+        // Re-insert all environments to keep their order.
+        let names = table
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        for name in names {
+            let (key, item) = table.remove_entry(&name).expect("the key must exist");
+            if name == from {
+                let key = Key::new(to).with_leaf_decor(key.leaf_decor().clone());
+                table.insert_formatted(&key, item);
+            } else {
+                table.insert_formatted(&key, item);
+            }
+        }
+
+        Ok(true)
+    }
+
     /// Sets the name of the project
     pub fn set_name(&mut self, name: &str) {
-        self.as_table_mut()["project"]["name"] = value(name);
+        self.set_project_string("name", name);
     }
 
     /// Sets the description of the project
     pub fn set_description(&mut self, description: &str) {
-        self.as_table_mut()["project"]["description"] = value(description);
+        self.set_project_string("description", description);
     }
 
     /// Sets the version of the project
     pub fn set_version(&mut self, version: &str) {
-        self.as_table_mut()["project"]["version"] = value(version);
+        self.set_project_string("version", version);
+    }
+
+    /// Sets a string in the `project` table, keeping the comments and
+    /// whitespace around the value it replaces.
+    fn set_project_string(&mut self, key: &str, new: &str) {
+        let item = &mut self.as_table_mut()["project"][key];
+        match item {
+            Item::Value(existing) => {
+                let decor = existing.decor().clone();
+                *existing = Value::from(new);
+                *existing.decor_mut() = decor;
+            }
+            _ => *item = value(new),
+        }
+    }
+
+    /// Parses a dotted key, e.g. `project.version`, that is relative to the
+    /// pixi configuration in the manifest.
+    fn dotted_key(&self, key: &str) -> Result<Vec<String>, TomlError> {
+        let prefix = self.table_prefix().map(|prefix| format!("{prefix}."));
+        let key = format!("{}{key}", prefix.unwrap_or_default());
+        Ok(toml_edit::Key::parse(&key)?
+            .iter()
+            .map(|key| key.get().to_string())
+            .collect())
+    }
+
+    /// Returns the item at the dotted key, e.g. `project.version`, or `None`
+    /// if it does not exist.
+    pub fn get_item(&self, key: &str) -> Result<Option<&Item>, TomlError> {
+        let mut item = self.manifest().as_item();
+        for part in self.dotted_key(key)? {
+            match item.get(part.as_str()) {
+                Some(next) => item = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(item))
+    }
+
+    /// Sets the value at the dotted key, e.g. `project.version`, inserting
+    /// the tables that do not exist yet. The formatting of an existing value
+    /// is preserved.
+    pub fn set_value(&mut self, key: &str, mut value: Value) -> Result<(), TomlError> {
+        value.decor_mut().clear();
+        let parts = self.dotted_key(key)?;
+        let Some((last, parents)) = parts.split_last() else {
+            return Err(TomlError::Generic("the key is empty".into(), None));
+        };
+
+        let mut table: &mut dyn TableLike = self.as_table_mut();
+        for part in parents {
+            let mut implicit_table = Table::new();
+            implicit_table.set_implicit(true);
+            table = table
+                .entry(part)
+                .or_insert(Item::Table(implicit_table))
+                .as_table_like_mut()
+                .ok_or_else(|| TomlError::table_error(part, key))?;
+        }
+
+        match table.get_mut(last) {
+            Some(Item::Value(existing)) => {
+                let decor = existing.decor().clone();
+                *existing = value;
+                *existing.decor_mut() = decor;
+            }
+            _ => {
+                table.insert(last, Item::Value(value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces `item` with `new` while keeping the comments and whitespace around
+/// it. For tables the entries are updated in place, so the comments of the
+/// entries that are kept are preserved as well.
+fn replace_keeping_decor(item: &mut Item, new: Value) {
+    match (item, new) {
+        (Item::Table(existing), Value::InlineTable(new)) => update_table_like(existing, new),
+        (Item::Value(Value::InlineTable(existing)), Value::InlineTable(new)) => {
+            update_table_like(existing, new)
+        }
+        (Item::Value(existing), mut new) => {
+            *new.decor_mut() = existing.decor().clone();
+            *existing = new;
+        }
+        (item, new) => *item = Item::Value(new),
+    }
+}
+
+/// Updates the entries of `existing` to match `new`, see
+/// [`replace_keeping_decor`].
+fn update_table_like(existing: &mut dyn TableLike, new: InlineTable) {
+    let removed = existing
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !new.contains_key(key))
+        .collect::<Vec<_>>();
+    for key in removed {
+        existing.remove(&key);
+    }
+    for (key, value) in new {
+        match existing.get_mut(&key) {
+            Some(item) => replace_keeping_decor(item, value),
+            None => {
+                existing.insert(&key, Item::Value(value));
+            }
+        }
     }
 }
 
@@ -554,4 +749,40 @@ mod test {
             source.to_string()
         );
     }
+
+    #[test]
+    fn test_set_task_keeps_comments() {
+        let mut source = ManifestSource::PixiToml(TomlDocument::new(
+            r#"
+[tasks]
+# Start the application
+start = "python -m app" # the entry point
+serve = { cmd = "python -m app", cwd = "src" } # the server
+
+[tasks.docs]
+# Build the documentation
+cmd = "mkdocs build" # the docs
+"#
+            .parse()
+            .unwrap(),
+        ));
+        for (name, task) in [
+            ("start", Task::Plain("python -m other".to_string())),
+            ("serve", Task::Plain("python -m other".to_string())),
+        ] {
+            source
+                .set_task(name, task, None, &FeatureName::Default)
+                .unwrap();
+        }
+        let docs: Task = toml_edit::de::from_str(r#"cmd = "mkdocs serve""#).unwrap();
+        source
+            .set_task("docs", docs, None, &FeatureName::Default)
+            .unwrap();
+
+        let manifest = source.to_string();
+        assert!(manifest
+            .contains("# Start the application\nstart = \"python -m other\" # the entry point"));
+        assert!(manifest.contains("serve = \"python -m other\" # the server"));
+        assert!(manifest.contains("# Build the documentation\ncmd = \"mkdocs serve\" # the docs"));
+    }
 }
@@ -2,12 +2,13 @@ use std::fmt::{self, Display, Formatter};
 
 use rattler_conda_types::Platform;
 
-use crate::FeatureName;
+use crate::{FeatureName, TargetSelector};
 
 /// Struct that is used to access a table in `pixi.toml` or `pyproject.toml`.
 pub struct TableName<'a> {
     prefix: Option<&'static str>,
     platform: Option<&'a Platform>,
+    target: Option<&'a TargetSelector>,
     feature_name: Option<&'a FeatureName>,
     table: Option<&'a str>,
 }
@@ -24,6 +25,7 @@ impl<'a> TableName<'a> {
         Self {
             prefix: None,
             platform: None,
+            target: None,
             feature_name: None,
             table: None,
         }
@@ -41,6 +43,13 @@ impl<'a> TableName<'a> {
         self
     }
 
+    /// Set the target selector of the table, e.g. `unix`. Only used when no
+    /// platform is set.
+    pub fn with_target(mut self, target: Option<&'a TargetSelector>) -> Self {
+        self.target = target;
+        self
+    }
+
     /// Set the feature name of the table.
     pub fn with_feature_name(mut self, feature_name: Option<&'a FeatureName>) -> Self {
         self.feature_name = feature_name;
@@ -59,9 +68,10 @@ impl TableName<'_> {
     /// `table1.table2.array`). It is composed of
     /// - the 'tool.pixi' prefix if the manifest is a 'pyproject.toml' file
     /// - the feature if it is not the default feature
-    /// - the platform if it is not `None`
+    /// - the platform or target selector if it is not `None`
     /// - the name of a nested TOML table if it is not `None`
     fn to_toml_table_name(&self) -> String {
+        let target = self.target.map(|target| target.to_string());
         let mut parts = Vec::new();
 
         if self.prefix.is_some() {
@@ -84,6 +94,9 @@ impl TableName<'_> {
         if let Some(platform) = self.platform {
             parts.push("target");
             parts.push(platform.as_str());
+        } else if let Some(target) = target.as_deref() {
+            parts.push("target");
+            parts.push(target);
         }
         if let Some(table) = self.table {
             parts.push(table);
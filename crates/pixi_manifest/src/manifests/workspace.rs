@@ -1,12 +1,14 @@
-use std::hash::Hash;
+use std::{hash::Hash, path::PathBuf};
 
 use indexmap::{map::IndexMap, Equivalent};
+use rattler_conda_types::PackageName;
 
 use crate::{
     consts,
     environment::{Environment, EnvironmentName},
     environments::Environments,
     feature::{Feature, FeatureName},
+    pypi::PyPiPackageName,
     solve_group::SolveGroups,
     toml::ExternalWorkspaceProperties,
     workspace::Workspace,
@@ -28,6 +30,21 @@ pub struct WorkspaceManifest {
 
     /// The solve groups that are part of the project.
     pub solve_groups: SolveGroups,
+
+    /// Whether the discovery of manifests stops at this manifest, instead of
+    /// continuing in the parent directories.
+    pub discovery_boundary: bool,
+
+    /// Files that are generated from templates when an environment is
+    /// installed, mapping the template to the output path (both relative to
+    /// the project root).
+    pub generate: IndexMap<PathBuf, PathBuf>,
+
+    /// The names of the pypi packages that conda packages provide. These take
+    /// precedence over the conda-pypi mapping, so a pypi requirement that is
+    /// satisfied by a conda package with a different name is not installed
+    /// twice.
+    pub pypi_name_mapping: IndexMap<PackageName, PyPiPackageName>,
 }
 
 impl WorkspaceManifest {
@@ -82,11 +99,16 @@ impl WorkspaceManifest {
 
 #[cfg(test)]
 mod tests {
+    use std::path::{Path, PathBuf};
+
     use insta::{assert_debug_snapshot, assert_snapshot, assert_yaml_snapshot};
     use itertools::Itertools;
-    use rattler_conda_types::{NamedChannelOrUrl, Platform};
+    use rattler_conda_types::{NamedChannelOrUrl, PackageName, Platform};
 
-    use crate::{utils::test_utils::expect_parse_failure, TargetSelector, WorkspaceManifest};
+    use crate::{
+        pypi::PyPiPackageName, utils::test_utils::expect_parse_failure, TargetSelector,
+        WorkspaceManifest,
+    };
 
     const PROJECT_BOILERPLATE: &str = r#"
         [project]
@@ -507,4 +529,107 @@ mod tests {
             .collect::<Vec<_>>();
         assert_debug_snapshot!(resolved_win);
     }
+
+    #[test]
+    fn test_channel_overlays() {
+        let contents = r#"
+        [workspace]
+        name = "foo"
+        channels = ["https://prefix.dev/my-forks", "conda-forge"]
+        platforms = []
+        channel-overlays = { "https://prefix.dev/my-forks" = ["openssl", "libcurl"] }
+        "#;
+        let manifest = WorkspaceManifest::from_toml_str(contents).unwrap();
+        let (channel, packages) = manifest
+            .workspace
+            .channel_overlays
+            .iter()
+            .exactly_one()
+            .unwrap();
+        assert!(matches!(channel, NamedChannelOrUrl::Url(_)));
+        assert_eq!(
+            packages
+                .iter()
+                .map(|name| name.as_normalized())
+                .collect::<Vec<_>>(),
+            vec!["openssl", "libcurl"]
+        );
+    }
+
+    #[test]
+    fn test_script_dir() {
+        let contents = r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+        script-dir = "scripts"
+        "#;
+        let manifest = WorkspaceManifest::from_toml_str(contents).unwrap();
+        assert_eq!(
+            manifest.workspace.script_dir.as_deref(),
+            Some(Path::new("scripts"))
+        );
+    }
+
+    #[test]
+    fn test_members() {
+        let contents = r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+        members = ["packages/core", "packages/cli"]
+        "#;
+        let manifest = WorkspaceManifest::from_toml_str(contents).unwrap();
+        assert_eq!(
+            manifest.workspace.members,
+            vec![
+                PathBuf::from("packages/core"),
+                PathBuf::from("packages/cli")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let contents = r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [generate]
+        "templates/toolchain.cmake.j2" = "build/{{ environment.name }}/toolchain.cmake"
+        "#;
+        let manifest = WorkspaceManifest::from_toml_str(contents).unwrap();
+        assert_eq!(
+            manifest
+                .generate
+                .get(Path::new("templates/toolchain.cmake.j2"))
+                .map(PathBuf::as_path),
+            Some(Path::new("build/{{ environment.name }}/toolchain.cmake"))
+        );
+    }
+
+    #[test]
+    fn test_pypi_name_mapping() {
+        let contents = r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [pypi-name-mapping]
+        pytorch = "torch"
+        "#;
+        let manifest = WorkspaceManifest::from_toml_str(contents).unwrap();
+        assert_eq!(
+            manifest
+                .pypi_name_mapping
+                .get(&PackageName::new_unchecked("pytorch"))
+                .map(PyPiPackageName::as_source),
+            Some("torch")
+        );
+    }
 }
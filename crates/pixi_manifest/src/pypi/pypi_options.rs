@@ -65,6 +65,11 @@ pub struct PypiOptions {
     pub no_build_isolation: Option<Vec<String>>,
     /// The strategy to use when resolving against multiple index URLs.
     pub index_strategy: Option<IndexStrategy>,
+    /// Never build source distributions, only use wheels
+    pub no_build: Option<bool>,
+    /// Packages for which wheels are not used, they are always built from
+    /// source distributions
+    pub no_binary: Option<Vec<String>>,
 }
 
 /// Clones and deduplicates two iterators of values
@@ -87,6 +92,8 @@ impl PypiOptions {
         flat_indexes: Option<Vec<FindLinksUrlOrPath>>,
         no_build_isolation: Option<Vec<String>>,
         index_strategy: Option<IndexStrategy>,
+        no_build: Option<bool>,
+        no_binary: Option<Vec<String>>,
     ) -> Self {
         Self {
             index_url: index,
@@ -94,6 +101,8 @@ impl PypiOptions {
             find_links: flat_indexes,
             no_build_isolation,
             index_strategy,
+            no_build,
+            no_binary,
         }
     }
 
@@ -124,6 +133,9 @@ impl PypiOptions {
     ///   provided
     /// - Flat indexes are merged and deduplicated, in the order they are
     ///   provided
+    /// - Building source distributions is disabled if any of the options
+    ///   disables it
+    /// - Packages without binaries are merged and deduplicated
     pub fn union(&self, other: &PypiOptions) -> Result<PypiOptions, PypiOptionsMergeError> {
         let index = if let Some(other_index) = other.index_url.clone() {
             // Allow only one index
@@ -192,12 +204,31 @@ impl PypiOptions {
             })
             .or_else(|| other.no_build_isolation.clone());
 
+        let no_build = match (self.no_build, other.no_build) {
+            (Some(own_no_build), Some(other_no_build)) => Some(own_no_build || other_no_build),
+            (own_no_build, other_no_build) => own_no_build.or(other_no_build),
+        };
+
+        // Merge all the packages that should not use binaries
+        let no_binary = self
+            .no_binary
+            .as_ref()
+            .map(|no_binary| {
+                clone_and_deduplicate(
+                    no_binary.iter(),
+                    other.no_binary.clone().unwrap_or_default().iter(),
+                )
+            })
+            .or_else(|| other.no_binary.clone());
+
         Ok(PypiOptions {
             index_url: index,
             extra_index_urls: extra_indexes,
             find_links: flat_indexes,
             no_build_isolation,
             index_strategy,
+            no_build,
+            no_binary,
         })
     }
 }
@@ -275,6 +306,8 @@ mod tests {
                  index-url = "https://example.com/pypi"
                  extra-index-urls = ["https://example.com/extra"]
                  no-build-isolation = ["pkg1", "pkg2"]
+                 no-build = true
+                 no-binary = ["pkg3"]
 
                  [[find-links]]
                  path = "/path/to/flat/index"
@@ -294,6 +327,8 @@ mod tests {
                 ]),
                 no_build_isolation: Some(vec!["pkg1".to_string(), "pkg2".to_string()]),
                 index_strategy: None,
+                no_build: Some(true),
+                no_binary: Some(vec!["pkg3".to_string()]),
             },
         );
     }
@@ -310,6 +345,8 @@ mod tests {
             ]),
             no_build_isolation: Some(vec!["foo".to_string(), "bar".to_string()]),
             index_strategy: None,
+            no_build: Some(false),
+            no_binary: Some(vec!["foo".to_string()]),
         };
 
         // Create the second set of options
@@ -322,6 +359,8 @@ mod tests {
             ]),
             no_build_isolation: Some(vec!["foo".to_string()]),
             index_strategy: None,
+            no_build: Some(true),
+            no_binary: Some(vec!["foo".to_string(), "bar".to_string()]),
         };
 
         // Merge the two options
@@ -339,6 +378,8 @@ mod tests {
             find_links: None,
             no_build_isolation: None,
             index_strategy: None,
+            no_build: None,
+            no_binary: None,
         };
 
         // Create the second set of options
@@ -348,6 +389,8 @@ mod tests {
             find_links: None,
             no_build_isolation: None,
             index_strategy: None,
+            no_build: None,
+            no_binary: None,
         };
 
         // Merge the two options
@@ -365,6 +408,8 @@ mod tests {
             find_links: None,
             no_build_isolation: None,
             index_strategy: Some(IndexStrategy::FirstIndex),
+            no_build: None,
+            no_binary: None,
         };
 
         // Create the second set of options
@@ -374,6 +419,8 @@ mod tests {
             find_links: None,
             no_build_isolation: None,
             index_strategy: Some(IndexStrategy::UnsafeBestMatch),
+            no_build: None,
+            no_binary: None,
         };
 
         // Merge the two options
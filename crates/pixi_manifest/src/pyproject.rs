@@ -5,10 +5,13 @@ use miette::{Diagnostic, IntoDiagnostic, Report, WrapErr};
 use pep440_rs::{Version, VersionSpecifiers};
 use pep508_rs::Requirement;
 use pixi_spec::PixiSpec;
-use pyproject_toml::{self, pep735_resolve::Pep735Error, Contact, DependencyGroups, Project};
+use pyproject_toml::{
+    self, pep735_resolve::Pep735Error, Contact, DependencyGroups, License, Project, ReadMe,
+};
 use rattler_conda_types::{PackageName, ParseStrictness::Lenient, VersionSpec};
 use serde::Deserialize;
 use thiserror::Error;
+use url::Url;
 
 use super::{
     error::{RequirementConversionError, TomlError},
@@ -202,6 +205,10 @@ pub struct PyProjectFields {
     pub description: Option<String>,
     pub version: Option<Version>,
     pub authors: Option<Vec<Contact>>,
+    pub license: Option<String>,
+    pub license_file: Option<PathBuf>,
+    pub readme: Option<PathBuf>,
+    pub urls: Option<IndexMap<String, String>>,
     pub requires_python: Option<VersionSpecifiers>,
     pub dependencies: Option<Vec<Requirement>>,
     pub optional_dependencies: Option<IndexMap<String, Vec<Requirement>>>,
@@ -209,11 +216,23 @@ pub struct PyProjectFields {
 
 impl From<pyproject_toml::Project> for PyProjectFields {
     fn from(project: pyproject_toml::Project) -> Self {
+        let (license, license_file) = match project.license {
+            Some(License::Spdx(license)) => (Some(license), None),
+            Some(License::File { file }) => (None, Some(file)),
+            Some(License::Text { .. }) | None => (None, None),
+        };
         Self {
             name: Some(project.name),
             description: project.description,
             version: project.version,
             authors: project.authors,
+            license,
+            license_file,
+            readme: project.readme.and_then(|readme| match readme {
+                ReadMe::RelativePath(path) => Some(PathBuf::from(path)),
+                ReadMe::Table { file, .. } => file.map(PathBuf::from),
+            }),
+            urls: project.urls,
             requires_python: project.requires_python,
             dependencies: project.dependencies,
             optional_dependencies: project.optional_dependencies,
@@ -221,6 +240,26 @@ impl From<pyproject_toml::Project> for PyProjectFields {
     }
 }
 
+impl PyProjectFields {
+    /// Returns the first of the `project.urls` with one of the labels. The
+    /// labels are compared like PEP 753 normalizes them, so `Source Code`
+    /// matches `sourcecode`.
+    fn url(&self, labels: &[&str]) -> Option<Url> {
+        self.urls
+            .iter()
+            .flatten()
+            .find(|(label, _)| {
+                let label = label
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase();
+                labels.contains(&label.as_str())
+            })
+            .and_then(|(_, url)| Url::parse(url).ok())
+    }
+}
+
 impl PyProjectManifest {
     #[allow(clippy::result_large_err)]
     pub fn into_manifests(
@@ -247,11 +286,9 @@ impl PyProjectManifest {
         let poetry = poetry.unwrap_or_default();
 
         // Convert the TOML document into a pixi manifest.
-        // TODO:  would be nice to add license, license-file, readme, homepage,
-        // repository, documentation, regarding the above, the types are a bit
-        // different than we expect, so the conversion is not straightforward we
-        // could change these types or we can convert. Let's decide when we make it.
-        // etc.
+        let homepage = project.url(&["homepage"]);
+        let repository = project.url(&["repository", "source", "sourcecode"]);
+        let documentation = project.url(&["documentation", "docs"]);
         let (mut workspace_manifest, package_manifest) =
             pixi.into_manifests(ExternalWorkspaceProperties {
                 name: project.name,
@@ -261,12 +298,12 @@ impl PyProjectManifest {
                     .or(poetry.version.and_then(|v| v.parse().ok())),
                 description: project.description.or(poetry.description),
                 authors: project.authors.map(contacts_to_authors).or(poetry.authors),
-                license: None,
-                license_file: None,
-                readme: None,
-                homepage: None,
-                repository: None,
-                documentation: None,
+                license: project.license,
+                license_file: project.license_file,
+                readme: project.readme,
+                homepage,
+                repository,
+                documentation,
             })?;
 
         // Add python as dependency based on the `project.requires_python` property
@@ -388,11 +425,15 @@ fn contacts_to_authors(contacts: Vec<Contact>) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-    use std::{path::Path, str::FromStr};
+    use std::{
+        path::{Path, PathBuf},
+        str::FromStr,
+    };
 
     use insta::assert_snapshot;
     use pep440_rs::VersionSpecifiers;
     use rattler_conda_types::{ParseStrictness, VersionSpec};
+    use url::Url;
 
     use crate::{
         manifests::Manifest, pypi::PyPiPackageName, DependencyOverwriteBehavior, FeatureName,
@@ -564,6 +605,46 @@ mod tests {
         let _manifest = Manifest::from_str(Path::new("pyproject.toml"), PYPROJECT_FULL).unwrap();
     }
 
+    #[test]
+    fn test_project_metadata() {
+        let manifest = Manifest::from_str(
+            Path::new("pyproject.toml"),
+            r#"
+        [project]
+        name = "project"
+        license = "MIT"
+        readme = "README.md"
+
+        [project.urls]
+        Homepage = "https://project.com"
+        "Source Code" = "https://github.com/author/project"
+
+        [tool.pixi.project]
+        channels = ["conda-forge"]
+        platforms = ["linux-64"]
+        documentation = "https://docs.project.com"
+        "#,
+        )
+        .unwrap();
+
+        let workspace = &manifest.workspace.workspace;
+        assert_eq!(workspace.license.as_deref(), Some("MIT"));
+        assert_eq!(workspace.readme, Some(PathBuf::from("README.md")));
+        assert_eq!(
+            workspace.homepage.as_ref().map(Url::as_str),
+            Some("https://project.com/")
+        );
+        assert_eq!(
+            workspace.repository.as_ref().map(Url::as_str),
+            Some("https://github.com/author/project")
+        );
+        // The values of the pixi table take precedence
+        assert_eq!(
+            workspace.documentation.as_ref().map(Url::as_str),
+            Some("https://docs.project.com/")
+        );
+    }
+
     #[test]
     fn test_add_pypi_dependency() {
         let mut manifest =
@@ -0,0 +1,81 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::Deserialize;
+
+/// The strategy the solver uses to select the versions of the packages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SolveStrategy {
+    /// Select the highest compatible version of every package.
+    #[default]
+    Highest,
+
+    /// Select the lowest compatible version of every package.
+    Lowest,
+
+    /// Select the lowest compatible version of the direct dependencies and the
+    /// highest compatible version of their dependencies.
+    LowestDirect,
+}
+
+impl SolveStrategy {
+    /// Returns the name of the strategy as used in the manifest.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SolveStrategy::Highest => "highest",
+            SolveStrategy::Lowest => "lowest",
+            SolveStrategy::LowestDirect => "lowest-direct",
+        }
+    }
+}
+
+impl Display for SolveStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SolveStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "highest" => Ok(SolveStrategy::Highest),
+            "lowest" => Ok(SolveStrategy::Lowest),
+            "lowest-direct" => Ok(SolveStrategy::LowestDirect),
+            _ => Err(format!(
+                "unknown solve strategy '{s}', expected one of 'highest', 'lowest' or 'lowest-direct'"
+            )),
+        }
+    }
+}
+
+impl From<SolveStrategy> for rattler_solve::SolveStrategy {
+    fn from(value: SolveStrategy) -> Self {
+        match value {
+            SolveStrategy::Highest => rattler_solve::SolveStrategy::Highest,
+            SolveStrategy::Lowest => rattler_solve::SolveStrategy::LowestVersion,
+            SolveStrategy::LowestDirect => rattler_solve::SolveStrategy::LowestVersionDirect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_strategy_roundtrip() {
+        for strategy in [
+            SolveStrategy::Highest,
+            SolveStrategy::Lowest,
+            SolveStrategy::LowestDirect,
+        ] {
+            assert_eq!(
+                SolveStrategy::from_str(strategy.as_str()).unwrap(),
+                strategy
+            );
+        }
+        assert!(SolveStrategy::from_str("newest").is_err());
+    }
+}
@@ -5,6 +5,7 @@ use serde_with::{formats::PreferMany, serde_as, OneOrMany};
 use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use toml_edit::{Array, Item, Table, Value};
 
 /// Represents a task name
@@ -174,6 +175,35 @@ impl Task {
             _ => None,
         }
     }
+
+    /// Returns the number of times the task should be retried when it fails.
+    pub fn retries(&self) -> u32 {
+        match self {
+            Task::Execute(exe) => exe.retries,
+            _ => 0,
+        }
+    }
+
+    /// Returns the sandbox the task should be executed in, if any.
+    pub fn sandbox(&self) -> Option<Sandbox> {
+        match self {
+            Task::Execute(exe) => exe.sandbox,
+            _ => None,
+        }
+    }
+
+    /// Returns the delay before the given retry attempt (starting at 1). The
+    /// configured backoff is doubled for every subsequent attempt.
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        match self {
+            Task::Execute(Execute {
+                retry_backoff: Some(backoff),
+                ..
+            }) => Duration::from_secs(*backoff)
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1))),
+            _ => Duration::ZERO,
+        }
+    }
 }
 
 /// A command script executes a single command from the environment
@@ -209,6 +239,62 @@ pub struct Execute {
     /// Isolate the task from the running machine
     #[serde(default)]
     pub clean_env: bool,
+
+    /// The number of times the task is retried when it exits with a non-zero
+    /// exit code
+    #[serde(default)]
+    pub retries: u32,
+
+    /// The number of seconds to wait before the first retry, doubled for
+    /// every subsequent retry
+    pub retry_backoff: Option<u64>,
+
+    /// Restricts what the command is allowed to do using the sandboxing
+    /// primitives of the operating system
+    pub sandbox: Option<Sandbox>,
+}
+
+/// The restrictions that are applied to a sandboxed task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Sandbox {
+    /// The task has no network access.
+    NetworkOff,
+
+    /// The task can only write to the project directory and the temporary
+    /// directory.
+    RestrictedFs,
+
+    /// Combines `network-off` and `restricted-fs`.
+    Strict,
+}
+
+impl Sandbox {
+    /// Returns the string representation of the sandbox as used in the
+    /// manifest.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sandbox::NetworkOff => "network-off",
+            Sandbox::RestrictedFs => "restricted-fs",
+            Sandbox::Strict => "strict",
+        }
+    }
+
+    /// Returns true if network access is denied.
+    pub fn denies_network(&self) -> bool {
+        matches!(self, Sandbox::NetworkOff | Sandbox::Strict)
+    }
+
+    /// Returns true if writing outside of the project is denied.
+    pub fn restricts_filesystem(&self) -> bool {
+        matches!(self, Sandbox::RestrictedFs | Sandbox::Strict)
+    }
+}
+
+impl Display for Sandbox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl From<Execute> for Task {
@@ -317,6 +403,15 @@ impl Display for Task {
             write!(f, ", description = {:?}", description)?;
         }
 
+        let retries = self.retries();
+        if retries > 0 {
+            write!(f, ", retries = {}", retries)?;
+        }
+
+        if let Some(sandbox) = self.sandbox() {
+            write!(f, ", sandbox = {}", sandbox)?;
+        }
+
         Ok(())
     }
 }
@@ -378,6 +473,18 @@ impl From<Task> for Item {
                 if let Some(description) = process.description {
                     table.insert("description", description.into());
                 }
+                if process.retries > 0 {
+                    table.insert("retries", i64::from(process.retries).into());
+                }
+                if let Some(retry_backoff) = process.retry_backoff {
+                    table.insert(
+                        "retry-backoff",
+                        i64::try_from(retry_backoff).unwrap_or(i64::MAX).into(),
+                    );
+                }
+                if let Some(sandbox) = process.sandbox {
+                    table.insert("sandbox", sandbox.as_str().into());
+                }
                 Item::Value(Value::InlineTable(table))
             }
             Task::Alias(alias) => {
@@ -401,7 +508,8 @@ impl From<Task> for Item {
 
 #[cfg(test)]
 mod tests {
-    use super::quote;
+    use super::{quote, Task};
+    use std::time::Duration;
 
     #[test]
     fn test_quote() {
@@ -417,4 +525,24 @@ mod tests {
         );
         assert_eq!(quote("name=[64,64]"), "\"name=[64,64]\"");
     }
+
+    #[test]
+    fn test_retry_delay() {
+        let task: Task = toml_edit::de::from_str(
+            r#"
+            cmd = "pytest"
+            retries = 3
+            retry-backoff = 2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(task.retries(), 3);
+        assert_eq!(task.retry_delay(1), Duration::from_secs(2));
+        assert_eq!(task.retry_delay(2), Duration::from_secs(4));
+        assert_eq!(task.retry_delay(3), Duration::from_secs(8));
+
+        let task: Task = toml_edit::de::from_str(r#"cmd = "pytest""#).unwrap();
+        assert_eq!(task.retries(), 0);
+        assert_eq!(task.retry_delay(1), Duration::ZERO);
+    }
 }
@@ -26,6 +26,11 @@ impl TomlDocument {
         Self(document)
     }
 
+    /// Returns the root of the manifest as an item
+    pub fn as_item(&self) -> &Item {
+        self.0.as_item()
+    }
+
     /// Returns the manifest as a mutable table
     pub fn as_table_mut(&mut self) -> &mut Table {
         self.0.as_table_mut()
@@ -11,6 +11,10 @@ pub struct TomlEnvironment {
     pub solve_group: Option<String>,
     #[serde(default)]
     pub no_default_feature: bool,
+    /// Creates an environment for each of these python versions instead of a
+    /// single environment.
+    #[serde(default)]
+    pub python_matrix: Option<PixiSpanned<Vec<String>>>,
 }
 
 #[derive(Debug)]
@@ -8,10 +8,10 @@ use serde_with::serde_as;
 
 use crate::{
     pypi::{pypi_options::PypiOptions, PyPiPackageName},
-    toml::{TomlPrioritizedChannel, TomlTarget},
+    toml::{target::move_platform_specific_dependencies, TomlPrioritizedChannel, TomlTarget},
     utils::{package_map::UniquePackageMap, PixiSpanned},
-    Activation, Feature, FeatureName, Preview, PyPiRequirement, SystemRequirements, TargetSelector,
-    Targets, Task, TaskName, TomlError,
+    Activation, Feature, FeatureName, Preview, PyPiRequirement, SolveStrategy, SystemRequirements,
+    TargetSelector, Targets, Task, TaskName, TomlError,
 };
 
 #[serde_as]
@@ -25,6 +25,8 @@ pub struct TomlFeature {
     #[serde(default)]
     pub channel_priority: Option<ChannelPriority>,
     #[serde(default)]
+    pub solve_strategy: Option<SolveStrategy>,
+    #[serde(default)]
     pub system_requirements: SystemRequirements,
     #[serde(default)]
     pub target: IndexMap<PixiSpanned<TargetSelector>, TomlTarget>,
@@ -52,7 +54,7 @@ pub struct TomlFeature {
 
 impl TomlFeature {
     pub fn into_feature(self, name: FeatureName, preview: &Preview) -> Result<Feature, TomlError> {
-        let default_target = TomlTarget {
+        let mut default_target = TomlTarget {
             dependencies: self.dependencies,
             host_dependencies: self.host_dependencies,
             build_dependencies: self.build_dependencies,
@@ -60,11 +62,13 @@ impl TomlFeature {
             pypi_dependencies: self.pypi_dependencies,
             activation: self.activation,
             tasks: self.tasks,
-        }
-        .into_feature_target(preview)?;
+        };
+        let mut feature_targets = self.target;
+        move_platform_specific_dependencies(&mut default_target, &mut feature_targets)?;
+        let default_target = default_target.into_feature_target(preview)?;
 
         let mut targets = IndexMap::new();
-        for (selector, target) in self.target {
+        for (selector, target) in feature_targets {
             let target = target.into_feature_target(preview)?;
             targets.insert(selector, target);
         }
@@ -76,6 +80,7 @@ impl TomlFeature {
                 .channels
                 .map(|channels| channels.into_iter().map(|channel| channel.into()).collect()),
             channel_priority: self.channel_priority,
+            solve_strategy: self.solve_strategy,
             system_requirements: self.system_requirements,
             pypi_options: self.pypi_options,
             targets: Targets::from_default_and_user_defined(default_target, targets),
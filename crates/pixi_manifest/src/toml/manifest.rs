@@ -1,8 +1,10 @@
-use std::{borrow::Cow, collections::HashMap, fmt::Formatter};
+use std::{borrow::Cow, collections::HashMap, fmt::Formatter, path::PathBuf, str::FromStr};
 
 use indexmap::IndexMap;
 use itertools::chain;
 use miette::LabeledSpan;
+use pixi_spec::PixiSpec;
+use rattler_conda_types::{PackageName, ParseStrictness, VersionSpec};
 use serde::{
     de::{MapAccess, Visitor},
     Deserialize, Deserializer,
@@ -15,14 +17,14 @@ use crate::{
     manifests::PackageManifest,
     pypi::{pypi_options::PypiOptions, PyPiPackageName},
     toml::{
-        environment::TomlEnvironmentList, ExternalPackageProperties, ExternalWorkspaceProperties,
-        PackageError, TomlBuildSystem, TomlFeature, TomlPackage, TomlTarget, TomlWorkspace,
-        WorkspaceError,
+        environment::TomlEnvironmentList, target::move_platform_specific_dependencies,
+        ExternalPackageProperties, ExternalWorkspaceProperties, PackageError, TomlBuildSystem,
+        TomlFeature, TomlPackage, TomlTarget, TomlWorkspace, WorkspaceError,
     },
     utils::{package_map::UniquePackageMap, PixiSpanned},
     Activation, Environment, EnvironmentName, Environments, Feature, FeatureName,
-    KnownPreviewFeature, PyPiRequirement, SolveGroups, SystemRequirements, TargetSelector, Targets,
-    Task, TaskName, TomlError, WorkspaceManifest,
+    KnownPreviewFeature, PyPiRequirement, SolveGroups, SpecType, SystemRequirements,
+    TargetSelector, Targets, Task, TaskName, TomlError, WorkspaceManifest,
 };
 
 /// Raw representation of a pixi manifest. This is the deserialized form of the
@@ -85,6 +87,16 @@ pub struct TomlManifest {
     #[serde(default)]
     pub pypi_options: Option<PypiOptions>,
 
+    /// Files that are generated from templates when an environment is
+    /// installed, mapping the template to the output path.
+    #[serde(default)]
+    pub generate: IndexMap<PathBuf, PathBuf>,
+
+    /// The names of the pypi packages that conda packages provide, overriding
+    /// the conda-pypi mapping.
+    #[serde(default)]
+    pub pypi_name_mapping: IndexMap<PackageName, PyPiPackageName>,
+
     /// The build section
     #[serde(default)]
     pub build_system: Option<PixiSpanned<TomlBuildSystem>>,
@@ -94,6 +106,11 @@ pub struct TomlManifest {
     #[serde(default)]
     pub build_backend: Option<TomlBuildBackendConfig>,
 
+    /// Whether the discovery of manifests stops at this manifest, instead of
+    /// continuing in the parent directories.
+    #[serde(default)]
+    pub discovery_boundary: bool,
+
     /// The URI for the manifest schema which is unused by pixi
     #[serde(rename = "$schema")]
     pub _schema: Option<String>,
@@ -228,7 +245,7 @@ impl TomlManifest {
         let preview = &self.workspace.value.preview;
         let pixi_build_enabled = self.is_pixi_build_enabled();
 
-        let default_top_level_target = TomlTarget {
+        let mut default_top_level_target = TomlTarget {
             dependencies: self.dependencies,
             host_dependencies: self.host_dependencies,
             build_dependencies: self.build_dependencies,
@@ -238,12 +255,15 @@ impl TomlManifest {
             tasks: self.tasks,
         };
 
+        let mut targets = self.target;
+        move_platform_specific_dependencies(&mut default_top_level_target, &mut targets)?;
+
         let (default_workspace_target, default_package_target) =
             default_top_level_target.into_top_level_targets(preview)?;
 
         let mut workspace_targets = IndexMap::new();
         let mut package_targets = IndexMap::new();
-        for (selector, target) in self.target {
+        for (selector, target) in targets {
             let (workspace_target, package_target) = target.into_top_level_targets(preview)?;
             if let Some(package_target) = package_target {
                 package_targets.insert(selector.clone(), package_target);
@@ -261,6 +281,7 @@ impl TomlManifest {
             channels: None,
 
             channel_priority: self.workspace.value.channel_priority,
+            solve_strategy: self.workspace.value.solve_strategy,
 
             system_requirements: self.system_requirements,
 
@@ -286,7 +307,8 @@ impl TomlManifest {
                 Ok((name, feature))
             })
             .collect::<Result<IndexMap<FeatureName, Feature>, TomlError>>()?;
-        let features = features.into_iter().chain(named_features).collect();
+        let mut features: IndexMap<FeatureName, Feature> =
+            features.into_iter().chain(named_features).collect();
 
         // Construct the environments including the default environment
         let mut environments = Environments::default();
@@ -303,25 +325,49 @@ impl TomlManifest {
         // Add all named environments
         for (name, env) in self.environments {
             // Decompose the TOML
-            let (features, features_source_loc, solve_group, no_default_feature) = match env {
-                TomlEnvironmentList::Map(env) => (
-                    env.features.value,
-                    env.features.span,
-                    env.solve_group,
-                    env.no_default_feature,
-                ),
-                TomlEnvironmentList::Seq(features) => (features, None, None, false),
+            let (env_features, features_source_loc, solve_group, no_default_feature, python_matrix) =
+                match env {
+                    TomlEnvironmentList::Map(env) => (
+                        env.features.value,
+                        env.features.span,
+                        env.solve_group,
+                        env.no_default_feature,
+                        env.python_matrix,
+                    ),
+                    TomlEnvironmentList::Seq(features) => (features, None, None, false, None),
+                };
+
+            // An environment with a python matrix is expanded into an environment per
+            // python version.
+            let variants = match python_matrix {
+                Some(python_matrix) => expand_python_matrix(
+                    &name,
+                    &env_features,
+                    solve_group.as_deref(),
+                    python_matrix,
+                    &mut features,
+                )?,
+                None => vec![(name, env_features, solve_group)],
             };
 
-            let environment_idx = EnvironmentIdx(environments.environments.len());
-            environments.by_name.insert(name.clone(), environment_idx);
-            environments.environments.push(Some(Environment {
-                name,
-                features,
-                features_source_loc,
-                solve_group: solve_group.map(|sg| solve_groups.add(sg, environment_idx)),
-                no_default_feature,
-            }));
+            for (name, env_features, solve_group) in variants {
+                if environments.by_name.contains_key(&name) {
+                    return Err(TomlError::Generic(
+                        format!("the environment '{}' is defined more than once", name).into(),
+                        features_source_loc.clone(),
+                    ));
+                }
+
+                let environment_idx = EnvironmentIdx(environments.environments.len());
+                environments.by_name.insert(name.clone(), environment_idx);
+                environments.environments.push(Some(Environment {
+                    name,
+                    features: env_features,
+                    features_source_loc: features_source_loc.clone(),
+                    solve_group: solve_group.map(|sg| solve_groups.add(sg, environment_idx)),
+                    no_default_feature,
+                }));
+            }
         }
 
         // Get the name from the [package] section if it's missing from the workspace.
@@ -481,15 +527,84 @@ impl TomlManifest {
             features,
             environments,
             solve_groups,
+            discovery_boundary: self.discovery_boundary,
+            generate: self.generate,
+            pypi_name_mapping: self.pypi_name_mapping,
         };
 
         Ok((workspace_manifest, package_manifest))
     }
 }
 
+/// Expands an environment with a `python-matrix` into an environment per
+/// python version, e.g. `test` becomes `test-py310` and `test-py311`.
+///
+/// Every variant additionally uses a feature named after the python version
+/// (e.g. `py311`) that depends on that version of python. The feature is only
+/// generated if it is not already defined in the manifest, which allows adding
+/// version specific dependencies to it. Variants are put in a separate
+/// solve-group per python version since they can't be solved together.
+fn expand_python_matrix(
+    name: &EnvironmentName,
+    env_features: &[String],
+    solve_group: Option<&str>,
+    python_matrix: PixiSpanned<Vec<String>>,
+    features: &mut IndexMap<FeatureName, Feature>,
+) -> Result<Vec<(EnvironmentName, Vec<String>, Option<String>)>, TomlError> {
+    let PixiSpanned {
+        value: python_versions,
+        span,
+    } = python_matrix;
+
+    if name.is_default() {
+        return Err(TomlError::Generic(
+            "the default environment cannot have a `python-matrix`".into(),
+            span,
+        ));
+    }
+
+    python_versions
+        .into_iter()
+        .map(|version| {
+            let invalid_version = || {
+                TomlError::Generic(
+                    format!("invalid python version '{version}' in `python-matrix`").into(),
+                    span.clone(),
+                )
+            };
+            let spec = VersionSpec::from_str(&format!("{version}.*"), ParseStrictness::Strict)
+                .map_err(|_| invalid_version())?;
+
+            let suffix = format!("py{}", version.replace('.', ""));
+            let env_name = EnvironmentName::from_str(&format!("{}-{suffix}", name.as_str()))
+                .map_err(|_| invalid_version())?;
+
+            let feature_name = FeatureName::Named(suffix.clone());
+            features.entry(feature_name.clone()).or_insert_with(|| {
+                let mut feature = Feature::new(feature_name);
+                feature.targets.default_mut().add_dependency(
+                    &PackageName::new_unchecked("python"),
+                    &PixiSpec::Version(spec),
+                    SpecType::Run,
+                );
+                feature
+            });
+
+            let env_features = env_features
+                .iter()
+                .cloned()
+                .chain([suffix.clone()])
+                .collect();
+            let solve_group = solve_group.map(|solve_group| format!("{solve_group}-{suffix}"));
+            Ok((env_name, env_features, solve_group))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use insta::assert_snapshot;
+    use rattler_conda_types::Platform;
 
     use super::*;
     use crate::utils::test_utils::expect_parse_failure;
@@ -591,6 +706,154 @@ mod test {
         assert_eq!(workspace_manifest.workspace.name, "foo");
     }
 
+    #[test]
+    fn test_python_matrix() {
+        let workspace_manifest = WorkspaceManifest::from_toml_str(
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [feature.test.dependencies]
+        pytest = "*"
+
+        [feature.py39.dependencies]
+        python = "3.9.*"
+        tomli = "*"
+
+        [environments]
+        test = { features = ["test"], solve-group = "test", python-matrix = ["3.9", "3.11"] }
+        "#,
+        )
+        .unwrap();
+
+        let env_names = workspace_manifest
+            .environments
+            .iter()
+            .map(|env| env.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(env_names, vec!["default", "test-py39", "test-py311"]);
+
+        let env = workspace_manifest.environment("test-py311").unwrap();
+        assert_eq!(env.features, vec!["test", "py311"]);
+        let solve_group = &workspace_manifest.solve_groups[env.solve_group.unwrap()];
+        assert_eq!(solve_group.name, "test-py311");
+
+        // The generated feature depends on the python version.
+        let feature = &workspace_manifest.features[&FeatureName::Named("py311".into())];
+        let python = feature
+            .targets
+            .default()
+            .run_dependencies()
+            .unwrap()
+            .get(&PackageName::new_unchecked("python"))
+            .unwrap();
+        assert_eq!(python.as_version_spec().unwrap().to_string(), "3.11.*");
+
+        // Existing features are not overwritten.
+        let feature = &workspace_manifest.features[&FeatureName::Named("py39".into())];
+        assert!(feature
+            .targets
+            .default()
+            .run_dependencies()
+            .unwrap()
+            .contains_key(&PackageName::new_unchecked("tomli")));
+    }
+
+    #[test]
+    fn test_python_matrix_on_default_environment() {
+        let error = expect_parse_failure(
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [environments]
+        default = { python-matrix = ["3.11"] }
+        "#,
+        );
+        assert!(error.contains("the default environment cannot have a `python-matrix`"));
+    }
+
+    #[test]
+    fn test_platform_specific_dependencies() {
+        let workspace_manifest = WorkspaceManifest::from_toml_str(
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["win-64", "linux-64"]
+
+        [dependencies]
+        python = "*"
+        pywin32 = { version = "*", platforms = ["win-64"] }
+
+        [feature.test.dependencies]
+        pytest = { version = "*", platforms = ["linux-64"] }
+        "#,
+        )
+        .unwrap();
+
+        let pywin32 = PackageName::new_unchecked("pywin32");
+        let targets = &workspace_manifest.default_feature().targets;
+        assert!(!targets
+            .default()
+            .run_dependencies()
+            .unwrap()
+            .contains_key(&pywin32));
+        assert!(targets
+            .for_target(&TargetSelector::Platform(Platform::Win64))
+            .unwrap()
+            .run_dependencies()
+            .unwrap()
+            .contains_key(&pywin32));
+
+        let feature = &workspace_manifest.features[&FeatureName::Named("test".into())];
+        assert!(feature
+            .targets
+            .for_target(&TargetSelector::Platform(Platform::Linux64))
+            .unwrap()
+            .run_dependencies()
+            .unwrap()
+            .contains_key(&PackageName::new_unchecked("pytest")));
+    }
+
+    #[test]
+    fn test_platform_specific_dependencies_conflict() {
+        let error = expect_parse_failure(
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["win-64"]
+
+        [dependencies]
+        pywin32 = { version = "*", platforms = ["win-64"] }
+
+        [target.win-64.dependencies]
+        pywin32 = "300"
+        "#,
+        );
+        assert!(error.contains("is also specified in the `[target.win-64]` table"));
+
+        let error = expect_parse_failure(
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["win-64"]
+
+        [target.win.dependencies]
+        pywin32 = { version = "*", platforms = ["win-64"] }
+        "#,
+        );
+        assert!(error.contains(
+            "`platforms` can only be used for dependencies outside of a `[target]` table"
+        ));
+    }
+
     #[test]
     fn test_run_dependencies_without_pixi_build() {
         assert_snapshot!(expect_parse_failure(
@@ -10,8 +10,8 @@ use crate::{
     pypi::PyPiPackageName,
     target::PackageTarget,
     utils::{package_map::UniquePackageMap, PixiSpanned},
-    Activation, KnownPreviewFeature, Preview, PyPiRequirement, SpecType, Task, TaskName, TomlError,
-    WorkspaceTarget,
+    Activation, KnownPreviewFeature, Preview, PyPiRequirement, SpecType, TargetSelector, Task,
+    TaskName, TomlError, WorkspaceTarget,
 };
 
 #[serde_as]
@@ -163,3 +163,80 @@ pub(super) fn combine_target_dependencies(
         .filter_map(|(ty, deps)| deps.map(|deps| (ty, deps.value.into())))
         .collect()
 }
+
+/// The conda dependency tables of a target.
+const DEPENDENCY_TABLES: [fn(&mut TomlTarget) -> &mut Option<PixiSpanned<UniquePackageMap>>; 4] = [
+    |target| &mut target.dependencies,
+    |target| &mut target.host_dependencies,
+    |target| &mut target.build_dependencies,
+    |target| &mut target.run_dependencies,
+];
+
+/// Moves the dependencies that are restricted to specific platforms with the
+/// `platforms` key into the targets of these platforms, e.g.
+/// `pywin32 = { version = "*", platforms = ["win-64"] }` is the same as adding
+/// `pywin32` to `[target.win-64.dependencies]`.
+pub(super) fn move_platform_specific_dependencies(
+    default_target: &mut TomlTarget,
+    targets: &mut IndexMap<PixiSpanned<TargetSelector>, TomlTarget>,
+) -> Result<(), TomlError> {
+    for target in targets.values_mut() {
+        for table in DEPENDENCY_TABLES {
+            let Some(dependencies) = table(target) else {
+                continue;
+            };
+            if let Some(name) = dependencies.value.platforms.keys().next() {
+                return Err(TomlError::Generic(
+                    "`platforms` can only be used for dependencies outside of a `[target]` table"
+                        .into(),
+                    dependencies.value.value_spans.get(name).cloned(),
+                ));
+            }
+        }
+    }
+
+    for table in DEPENDENCY_TABLES {
+        let Some(dependencies) = table(default_target) else {
+            continue;
+        };
+        let platform_specific = std::mem::take(&mut dependencies.value.platforms);
+        for (name, platforms) in platform_specific {
+            let Some(spec) = dependencies.value.specs.shift_remove(&name) else {
+                continue;
+            };
+            let name_span = dependencies.value.name_spans.shift_remove(&name);
+            let value_span = dependencies.value.value_spans.shift_remove(&name);
+
+            for platform in platforms {
+                let target = targets
+                    .entry(PixiSpanned::from(TargetSelector::Platform(platform)))
+                    .or_default();
+                let target_dependencies =
+                    &mut table(target).get_or_insert_with(Default::default).value;
+                if target_dependencies.specs.contains_key(&name) {
+                    return Err(TomlError::Generic(
+                        format!(
+                            "'{}' is restricted to '{platform}' but is also specified in the `[target.{platform}]` table",
+                            name.as_source()
+                        )
+                        .into(),
+                        value_span,
+                    ));
+                }
+                if let Some(name_span) = &name_span {
+                    target_dependencies
+                        .name_spans
+                        .insert(name.clone(), name_span.clone());
+                }
+                if let Some(value_span) = &value_span {
+                    target_dependencies
+                        .value_spans
+                        .insert(name.clone(), value_span.clone());
+                }
+                target_dependencies.specs.insert(name.clone(), spec.clone());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use indexmap::{IndexMap, IndexSet};
-use rattler_conda_types::{NamedChannelOrUrl, Platform, Version};
+use rattler_conda_types::{NamedChannelOrUrl, PackageName, Platform, Version};
 use rattler_solve::ChannelPriority;
 use serde::Deserialize;
 use serde_with::{serde_as, DisplayFromStr};
@@ -10,7 +10,7 @@ use url::Url;
 
 use crate::{
     preview::Preview, pypi::pypi_options::PypiOptions, utils::PixiSpanned, PrioritizedChannel,
-    TargetSelector, Targets, Workspace,
+    SolveStrategy, TargetSelector, Targets, Workspace,
 };
 
 #[derive(Debug, Clone, Deserialize)]
@@ -37,6 +37,8 @@ pub struct TomlWorkspace {
     pub channels: IndexSet<PrioritizedChannel>,
     #[serde(default)]
     pub channel_priority: Option<ChannelPriority>,
+    #[serde(default)]
+    pub solve_strategy: Option<SolveStrategy>,
     // TODO: This is actually slightly different from the rattler_conda_types::Platform because it
     //     should not include noarch.
     pub platforms: PixiSpanned<IndexSet<Platform>>,
@@ -47,7 +49,12 @@ pub struct TomlWorkspace {
     pub repository: Option<Url>,
     pub documentation: Option<Url>,
     pub conda_pypi_map: Option<HashMap<NamedChannelOrUrl, String>>,
+    pub channel_overlays: Option<HashMap<NamedChannelOrUrl, Vec<PackageName>>>,
     pub pypi_options: Option<PypiOptions>,
+    pub script_dir: Option<PathBuf>,
+
+    #[serde(default)]
+    pub members: Vec<PathBuf>,
 
     #[serde(default)]
     pub preview: Preview,
@@ -103,9 +110,13 @@ impl TomlWorkspace {
             documentation: self.documentation.or(external.documentation),
             channels: self.channels,
             channel_priority: self.channel_priority,
+            solve_strategy: self.solve_strategy,
             platforms: self.platforms,
             conda_pypi_map: self.conda_pypi_map,
+            channel_overlays: self.channel_overlays.unwrap_or_default(),
             pypi_options: self.pypi_options,
+            script_dir: self.script_dir,
+            members: self.members,
             preview: self.preview,
             build_variants: Targets::from_default_and_user_defined(
                 self.build_variants,
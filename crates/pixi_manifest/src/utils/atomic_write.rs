@@ -0,0 +1,71 @@
+use std::{
+    io,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Writes the contents to the file at `path` without ever leaving a partially
+/// written file behind.
+///
+/// The contents are written to a temporary file in the same directory, which
+/// is flushed to disk and then renamed over the original file. If the process
+/// is interrupted, the file either has the old or the new contents.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    // Write through symlinks instead of replacing them.
+    let path = fs_err::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!(".{file_name}."))
+        .suffix(".tmp")
+        .tempfile_in(&dir)?;
+    file.write_all(contents.as_ref())?;
+    file.as_file().sync_all()?;
+
+    // Keep the permissions of the file that is replaced.
+    if let Ok(metadata) = fs_err::metadata(&path) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+
+    file.persist(&path).map_err(|e| {
+        io::Error::new(
+            e.error.kind(),
+            format!("failed to write '{}': {}", path.display(), e.error),
+        )
+    })?;
+
+    // Make sure the rename itself is persisted.
+    #[cfg(unix)]
+    if let Ok(dir) = std::fs::File::open(&dir) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("pixi.toml");
+
+        write_atomic(&path, "first").unwrap();
+        assert_eq!(fs_err::read_to_string(&path).unwrap(), "first");
+
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs_err::read_to_string(&path).unwrap(), "second");
+
+        // No temporary files are left behind.
+        assert_eq!(fs_err::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+}
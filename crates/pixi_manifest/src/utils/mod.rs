@@ -1,9 +1,11 @@
+mod atomic_write;
 pub mod package_map;
 mod spanned;
 
 #[cfg(test)]
 pub(crate) mod test_utils;
 
+pub use atomic_write::write_atomic;
 pub use spanned::PixiSpanned;
 use url::Url;
 
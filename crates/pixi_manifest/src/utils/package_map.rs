@@ -1,10 +1,12 @@
 use crate::utils::PixiSpanned;
 use indexmap::IndexMap;
 use pixi_spec::PixiSpec;
+use rattler_conda_types::Platform;
 use serde::{
-    de::{DeserializeSeed, MapAccess, Visitor},
+    de::{DeserializeSeed, IntoDeserializer, MapAccess, Visitor},
     Deserialize, Deserializer, Serialize,
 };
+use std::collections::BTreeMap;
 use std::ops::Range;
 use std::{fmt, marker::PhantomData};
 
@@ -18,6 +20,11 @@ pub struct UniquePackageMap {
 
     #[serde(skip)]
     pub value_spans: IndexMap<rattler_conda_types::PackageName, Range<usize>>,
+
+    /// The platforms that dependencies are restricted to with the `platforms`
+    /// key, e.g. `pywin32 = { version = "*", platforms = ["win-64"] }`.
+    #[serde(skip)]
+    pub platforms: IndexMap<rattler_conda_types::PackageName, Vec<Platform>>,
 }
 
 impl From<UniquePackageMap> for IndexMap<rattler_conda_types::PackageName, PixiSpec> {
@@ -56,7 +63,7 @@ impl<'de> Deserialize<'de> for UniquePackageMap {
                 let mut result = UniquePackageMap::default();
                 while let Some((package_name, spec)) = map.next_entry_seed::<PackageMap, _>(
                     PackageMap(&result.specs),
-                    PhantomData::<PixiSpanned<PixiSpec>>,
+                    PhantomData::<PixiSpanned<PlatformSpec>>,
                 )? {
                     let PixiSpanned {
                         span: package_name_span,
@@ -64,7 +71,7 @@ impl<'de> Deserialize<'de> for UniquePackageMap {
                     } = package_name;
                     let PixiSpanned {
                         span: spec_span,
-                        value: spec,
+                        value: PlatformSpec { spec, platforms },
                     } = spec;
                     if let Some(package_name_span) = package_name_span {
                         result
@@ -74,6 +81,9 @@ impl<'de> Deserialize<'de> for UniquePackageMap {
                     if let Some(spec_span) = spec_span {
                         result.value_spans.insert(package_name.clone(), spec_span);
                     }
+                    if let Some(platforms) = platforms {
+                        result.platforms.insert(package_name.clone(), platforms);
+                    }
                     result.specs.insert(package_name, spec);
                 }
 
@@ -85,6 +95,50 @@ impl<'de> Deserialize<'de> for UniquePackageMap {
     }
 }
 
+/// A spec that is optionally restricted to specific platforms.
+struct PlatformSpec {
+    spec: PixiSpec,
+    platforms: Option<Vec<Platform>>,
+}
+
+impl<'de> Deserialize<'de> for PlatformSpec {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serde_untagged::UntaggedEnumVisitor::new()
+            .expecting(
+                "a version string like \">=0.9.8\" or a detailed dependency like { version = \">=0.9.8\" }",
+            )
+            .string(|str| {
+                Ok(PlatformSpec {
+                    spec: PixiSpec::deserialize(IntoDeserializer::<
+                        serde_untagged::de::Error,
+                    >::into_deserializer(str))?,
+                    platforms: None,
+                })
+            })
+            .map(|map| {
+                let mut fields: BTreeMap<serde_value::Value, serde_value::Value> =
+                    map.deserialize()?;
+                let platforms = fields
+                    .remove(&serde_value::Value::String(String::from("platforms")))
+                    .map(|platforms| platforms.deserialize_into::<Vec<Platform>>())
+                    .transpose()
+                    .map_err(serde_untagged::de::Error::custom)?;
+                if platforms.as_ref().is_some_and(Vec::is_empty) {
+                    return Err(serde_untagged::de::Error::custom(
+                        "`platforms` must contain at least one platform",
+                    ));
+                }
+                let spec = PixiSpec::deserialize(serde_value::Value::Map(fields))
+                    .map_err(serde_untagged::de::Error::custom)?;
+                Ok(PlatformSpec { spec, platforms })
+            })
+            .deserialize(deserializer)
+    }
+}
+
 struct PackageMap<'a>(&'a IndexMap<rattler_conda_types::PackageName, PixiSpec>);
 
 impl<'de, 'a> DeserializeSeed<'de> for PackageMap<'a> {
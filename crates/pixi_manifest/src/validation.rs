@@ -126,6 +126,27 @@ impl WorkspaceManifest {
         check_file_existence(&self.workspace.license_file)?;
         check_file_existence(&self.workspace.readme)?;
 
+        // An overlay channel has to be one of the channels of the project.
+        for overlay in self.workspace.channel_overlays.keys() {
+            let is_used = self
+                .workspace
+                .channels
+                .iter()
+                .chain(
+                    self.features
+                        .values()
+                        .flat_map(|f| f.channels.iter().flatten()),
+                )
+                .any(|channel| &channel.channel == overlay);
+            if !is_used {
+                return Err(miette::miette!(
+                    help = "add the channel to the `channels` of the project or a feature",
+                    "the overlay channel '{}' is not used by the project",
+                    overlay
+                ));
+            }
+        }
+
         // Validate the environments defined in the project
         for env in self.environments.iter() {
             if let Err(report) = self.validate_environment(env, self.default_feature()) {
@@ -1,12 +1,12 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use indexmap::IndexSet;
-use rattler_conda_types::{NamedChannelOrUrl, Platform, Version};
+use rattler_conda_types::{NamedChannelOrUrl, PackageName, Platform, Version};
 use rattler_solve::ChannelPriority;
 use url::Url;
 
 use super::pypi::pypi_options::PypiOptions;
-use crate::{preview::Preview, utils::PixiSpanned, PrioritizedChannel, Targets};
+use crate::{preview::Preview, utils::PixiSpanned, PrioritizedChannel, SolveStrategy, Targets};
 
 /// Describes the contents of the `[workspace]` section of the project manifest.
 #[derive(Debug, Clone)]
@@ -29,6 +29,9 @@ pub struct Workspace {
     /// Channel priority for the whole project
     pub channel_priority: Option<ChannelPriority>,
 
+    /// The strategy the solver uses to select the versions of the packages
+    pub solve_strategy: Option<SolveStrategy>,
+
     /// The platforms this project supports
     // TODO: This is actually slightly different from the rattler_conda_types::Platform because it
     //     should not include noarch.
@@ -55,9 +58,21 @@ pub struct Workspace {
     /// URL or Path of the conda to pypi name mapping
     pub conda_pypi_map: Option<HashMap<NamedChannelOrUrl, String>>,
 
+    /// Channels that shadow the listed packages of the other channels, only
+    /// the listed packages are used from these channels.
+    pub channel_overlays: HashMap<NamedChannelOrUrl, Vec<PackageName>>,
+
     /// The pypi options supported in the project
     pub pypi_options: Option<PypiOptions>,
 
+    /// Directory with scripts (relative to the project root) that is added to
+    /// the `PATH` of the activated environments
+    pub script_dir: Option<PathBuf>,
+
+    /// The directories (relative to the project root) of the projects that
+    /// are members of the workspace of this project
+    pub members: Vec<PathBuf>,
+
     /// Preview features
     pub preview: Preview,
 
@@ -1,9 +1,12 @@
 use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState};
+use serde::Serialize;
 use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::fmt::Write;
 use std::future::Future;
-use std::sync::LazyLock;
+use std::io::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
 use std::time::Duration;
 use tokio::sync::mpsc::{channel, Sender};
 
@@ -21,11 +24,177 @@ pub fn global_multi_progress() -> MultiProgress {
     GLOBAL_MP.clone()
 }
 
+/// The colors and symbols that are used to style the output of pixi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// The color of spinners, progress bars and other highlights, `None` to
+    /// use the default color of each element.
+    pub accent_color: Option<console::Color>,
+
+    /// Whether emoji and other unicode symbols can be used to draw progress
+    /// bars and reports.
+    pub emoji: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent_color: None,
+            emoji: true,
+        }
+    }
+}
+
+impl Theme {
+    /// The name of the accent color as used in [`indicatif`] templates, or
+    /// `default` if no accent color is set.
+    pub fn accent_template_color(&self, default: &str) -> String {
+        let Some(color) = self.accent_color else {
+            return default.to_string();
+        };
+        match color {
+            console::Color::Black => "black".to_string(),
+            console::Color::Red => "red".to_string(),
+            console::Color::Green => "green".to_string(),
+            console::Color::Yellow => "yellow".to_string(),
+            console::Color::Blue => "blue".to_string(),
+            console::Color::Magenta => "magenta".to_string(),
+            console::Color::Cyan => "cyan".to_string(),
+            console::Color::White => "white".to_string(),
+            console::Color::Color256(n) => n.to_string(),
+        }
+    }
+}
+
+static THEME: RwLock<Theme> = RwLock::new(Theme {
+    accent_color: None,
+    emoji: true,
+});
+
+/// Sets the global [`Theme`] that is used for all output.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// Returns the global [`Theme`].
+pub fn theme() -> Theme {
+    *THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Makes pixi print its output as [`JsonEvent`]s on stdout instead of the human
+/// readable output. The progress bars are hidden, the steps they show are
+/// printed as progress events instead.
+pub fn set_json_output(enabled: bool) {
+    JSON_OUTPUT.store(enabled, Ordering::Relaxed);
+    if enabled {
+        global_multi_progress().set_draw_target(ProgressDrawTarget::hidden());
+    }
+}
+
+/// Returns true if the output is printed as [`JsonEvent`]s.
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// An event of the JSON output, printed as a single line on stdout.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum JsonEvent {
+    /// A step of the command started.
+    ProgressStarted { message: String },
+    /// A step of the command finished.
+    ProgressFinished { message: String },
+    /// The result of the command.
+    Result { data: serde_json::Value },
+    /// The command failed.
+    Error {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        help: Option<String>,
+        /// The messages of the errors that caused this error.
+        causes: Vec<String>,
+    },
+}
+
+impl JsonEvent {
+    /// Prints the event on stdout.
+    pub fn emit(&self) {
+        let line = serde_json::to_string(self).expect("events can always be serialized");
+        let mut stdout = std::io::stdout().lock();
+        // Like `println!`, but a closed stdout, e.g. a consumer that already
+        // exited, is not a reason to panic.
+        let _ = writeln!(stdout, "{line}");
+        let _ = stdout.flush();
+    }
+}
+
+/// Prints the result of a command as a [`JsonEvent::Result`].
+pub fn emit_json_result(data: impl Serialize) -> serde_json::Result<()> {
+    JsonEvent::Result {
+        data: serde_json::to_value(data)?,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Prints the `--json` output of a command on stdout, or as a
+/// [`JsonEvent::Result`] if the output is printed as [`JsonEvent`]s.
+pub fn print_json(data: impl Serialize, pretty: bool) -> serde_json::Result<()> {
+    if json_output() {
+        return emit_json_result(data);
+    }
+    let json = if pretty {
+        serde_json::to_string_pretty(&data)?
+    } else {
+        serde_json::to_string(&data)?
+    };
+    println!("{json}");
+    Ok(())
+}
+
+/// Prints a progress event with the message of a progress bar, without
+/// styling, if the output is printed as [`JsonEvent`]s.
+pub fn emit_json_progress(message: &str, finished: bool) {
+    if !json_output() {
+        return;
+    }
+    let message = console::strip_ansi_codes(message.trim()).into_owned();
+    if finished {
+        JsonEvent::ProgressFinished { message }
+    } else {
+        JsonEvent::ProgressStarted { message }
+    }
+    .emit();
+}
+
+/// Returns `emoji` if emoji are enabled in the global [`Theme`] and supported
+/// by the terminal, otherwise `fallback`.
+fn symbol(emoji: &str, fallback: &str) -> String {
+    if theme().emoji {
+        console::Emoji(emoji, fallback).to_string()
+    } else {
+        fallback.to_string()
+    }
+}
+
+/// The characters used to draw the bar of a progressbar.
+fn progress_chars() -> &'static str {
+    if theme().emoji {
+        "━━╾─"
+    } else {
+        "=>-"
+    }
+}
+
 /// Returns the style to use for a progressbar that is currently in progress.
 pub fn default_bytes_style() -> indicatif::ProgressStyle {
     indicatif::ProgressStyle::default_bar()
-        .template("  {spinner:.dim} {prefix:20!} [{elapsed_precise}] [{bar:20!.bright.yellow/dim.white}] {bytes:>8} @ {smoothed_bytes_per_sec:8}").unwrap()
-        .progress_chars("━━╾─")
+        .template(&format!("  {{spinner:.dim}} {{prefix:20!}} [{{elapsed_precise}}] [{{bar:20!.bright.{}/dim.white}}] {{bytes:>8}} @ {{smoothed_bytes_per_sec:8}}", theme().accent_template_color("yellow"))).unwrap()
+        .progress_chars(progress_chars())
         .with_key(
             "smoothed_bytes_per_sec",
             |s: &ProgressState, w: &mut dyn Write| match (s.pos(), s.elapsed().as_millis()) {
@@ -40,8 +209,8 @@ pub fn default_bytes_style() -> indicatif::ProgressStyle {
 /// Returns the style to use for a progressbar that is currently in progress.
 pub fn default_progress_style() -> indicatif::ProgressStyle {
     indicatif::ProgressStyle::default_bar()
-        .template("  {spinner:.dim} {prefix:20!} [{elapsed_precise}] [{bar:20!.bright.yellow/dim.white}] {pos:>4}/{len:4} {wide_msg:.dim}").unwrap()
-        .progress_chars("━━╾─")
+        .template(&format!("  {{spinner:.dim}} {{prefix:20!}} [{{elapsed_precise}}] [{{bar:20!.bright.{}/dim.white}}] {{pos:>4}}/{{len:4}} {{wide_msg:.dim}}", theme().accent_template_color("yellow"))).unwrap()
+        .progress_chars(progress_chars())
 }
 
 /// Returns the style to use for a progressbar that is in Deserializing state.
@@ -49,7 +218,7 @@ pub fn deserializing_progress_style() -> indicatif::ProgressStyle {
     indicatif::ProgressStyle::default_bar()
         .template("  {spinner:.dim} {prefix:20!} [{elapsed_precise}] {wide_msg}")
         .unwrap()
-        .progress_chars("━━╾─")
+        .progress_chars(progress_chars())
 }
 
 /// Returns the style to use for a progressbar that is finished.
@@ -57,10 +226,10 @@ pub fn finished_progress_style() -> indicatif::ProgressStyle {
     indicatif::ProgressStyle::default_bar()
         .template(&format!(
             "  {} {{prefix:20!}} [{{elapsed_precise}}] {{msg:.bold}}",
-            console::style(console::Emoji("✔", " ")).green()
+            console::style(symbol("✔", " ")).green()
         ))
         .unwrap()
-        .progress_chars("━━╾─")
+        .progress_chars(progress_chars())
 }
 
 /// Returns the style to use for a progressbar that is in error state.
@@ -68,25 +237,32 @@ pub fn errored_progress_style() -> indicatif::ProgressStyle {
     indicatif::ProgressStyle::default_bar()
         .template(&format!(
             "  {} {{prefix:20!}} [{{elapsed_precise}}] {{msg:.bold.red}}",
-            console::style(console::Emoji("❌", " ")).red()
+            console::style(symbol("❌", " ")).red()
         ))
         .unwrap()
-        .progress_chars("━━╾─")
+        .progress_chars(progress_chars())
 }
 
 /// Returns the style to use for a progressbar that is indeterminate and simply shows a spinner.
 pub fn long_running_progress_style() -> indicatif::ProgressStyle {
-    indicatif::ProgressStyle::with_template("{prefix}{spinner:.green} {msg}").unwrap()
+    indicatif::ProgressStyle::with_template(&format!(
+        "{{prefix}}{{spinner:.{}}} {{msg}}",
+        theme().accent_template_color("green")
+    ))
+    .unwrap()
 }
 
 /// Displays a spinner with the given message while running the specified function to completion.
 pub fn wrap_in_progress<T, F: FnOnce() -> T>(msg: impl Into<Cow<'static, str>>, func: F) -> T {
+    let msg = msg.into();
+    emit_json_progress(&msg, false);
     let pb = global_multi_progress().add(ProgressBar::new_spinner());
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_style(long_running_progress_style());
-    pb.set_message(msg);
+    pb.set_message(msg.clone());
     let result = func();
     pb.finish_and_clear();
+    emit_json_progress(&msg, true);
     result
 }
 
@@ -101,6 +277,7 @@ pub async fn await_in_progress<T, F: FnOnce(ProgressBar) -> Fut, Fut: Future<Out
         _ => ("", msg.as_ref()),
     };
 
+    emit_json_progress(msg, false);
     let pb = global_multi_progress().add(ProgressBar::new_spinner());
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_style(long_running_progress_style());
@@ -108,6 +285,7 @@ pub async fn await_in_progress<T, F: FnOnce(ProgressBar) -> Fut, Fut: Future<Out
     pb.set_message(msg.to_string());
     let result = future(pb.clone()).await;
     pb.finish_and_clear();
+    emit_json_progress(msg, true);
     result
 }
 
@@ -245,3 +423,30 @@ impl ProgressBarMessageFormatter {
         self.pb
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_event_format() {
+        let event = JsonEvent::ProgressStarted {
+            message: "solving default:linux-64".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"progress-started","message":"solving default:linux-64"}"#
+        );
+
+        let event = JsonEvent::Error {
+            message: "the lock-file is not up-to-date".to_string(),
+            code: Some("pixi::lock_file_out_of_date".to_string()),
+            help: None,
+            causes: vec![],
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"error","message":"the lock-file is not up-to-date","code":"pixi::lock_file_out_of_date","causes":[]}"#
+        );
+    }
+}
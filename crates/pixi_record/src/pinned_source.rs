@@ -184,8 +184,21 @@ impl From<PinnedPathSpec> for UrlOrPath {
 }
 
 impl From<PinnedGitSpec> for UrlOrPath {
-    fn from(_value: PinnedGitSpec) -> Self {
-        unimplemented!()
+    /// Encodes the git source as `git+<url>?<branch|tag|rev>=<ref>#<commit>`.
+    fn from(value: PinnedGitSpec) -> Self {
+        let mut url = Url::parse(&format!("{}{}", GIT_URL_PREFIX, value.git))
+            .expect("prefixing a url with `git+` results in a valid url");
+        url.set_query(None);
+        if let Some(rev) = &value.rev {
+            let key = match rev {
+                GitReference::Branch(_) => "branch",
+                GitReference::Tag(_) => "tag",
+                GitReference::Rev(_) => "rev",
+            };
+            url.query_pairs_mut().append_pair(key, &rev.to_string());
+        }
+        url.set_fragment(Some(&value.commit));
+        UrlOrPath::Url(url)
     }
 }
 
@@ -195,20 +208,63 @@ impl From<PinnedUrlSpec> for UrlOrPath {
     }
 }
 
+/// The prefix of the scheme of a url that refers to a git source.
+const GIT_URL_PREFIX: &str = "git+";
+
 #[derive(Debug, Error)]
-pub enum ParseError {}
+pub enum ParseError {
+    #[error("the git source '{0}' does not specify a commit")]
+    MissingGitCommit(Url),
+
+    #[error("'{0}' is not a valid git source")]
+    InvalidGitUrl(Url),
+
+    #[error("the url source '{0}' is not supported, only git and path sources are")]
+    UnsupportedUrl(Url),
+}
 
 impl TryFrom<UrlOrPath> for PinnedSourceSpec {
     type Error = ParseError;
 
     fn try_from(value: UrlOrPath) -> Result<Self, Self::Error> {
         match value {
-            UrlOrPath::Url(_) => unimplemented!(),
+            UrlOrPath::Url(url) if url.scheme().starts_with(GIT_URL_PREFIX) => {
+                Ok(PinnedGitSpec::try_from(url)?.into())
+            }
+            UrlOrPath::Url(url) => Err(ParseError::UnsupportedUrl(url)),
             UrlOrPath::Path(path) => Ok(PinnedPathSpec { path }.into()),
         }
     }
 }
 
+impl TryFrom<Url> for PinnedGitSpec {
+    type Error = ParseError;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        let commit = url
+            .fragment()
+            .filter(|commit| !commit.is_empty())
+            .ok_or_else(|| ParseError::MissingGitCommit(url.clone()))?
+            .to_string();
+
+        let rev = url
+            .query_pairs()
+            .find_map(|(key, value)| match key.as_ref() {
+                "branch" => Some(GitReference::Branch(value.into_owned())),
+                "tag" => Some(GitReference::Tag(value.into_owned())),
+                "rev" => Some(GitReference::Rev(value.into_owned())),
+                _ => None,
+            });
+
+        let mut git = Url::parse(&url.as_str()[GIT_URL_PREFIX.len()..])
+            .map_err(|_| ParseError::InvalidGitUrl(url.clone()))?;
+        git.set_query(None);
+        git.set_fragment(None);
+
+        Ok(PinnedGitSpec { git, commit, rev })
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum SourceMismatchError {
     #[error("the locked path '{locked}' does not match the requested path '{requested}'")]
@@ -355,3 +411,49 @@ impl Display for PinnedGitSpec {
         write!(f, "{}@{}", self.git, self.commit)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_git_url_roundtrip() {
+        let pinned = PinnedGitSpec {
+            git: Url::parse("https://github.com/org/repo").unwrap(),
+            commit: "8c6c6d5b1b9ab2d1ccbcfa7f2a3a3d3a5c0e0f1a".to_string(),
+            rev: Some(GitReference::Branch("main".to_string())),
+        };
+
+        let url_or_path = UrlOrPath::from(pinned.clone());
+        assert_eq!(
+            url_or_path.to_string(),
+            "git+https://github.com/org/repo?branch=main#8c6c6d5b1b9ab2d1ccbcfa7f2a3a3d3a5c0e0f1a"
+        );
+
+        let parsed = PinnedSourceSpec::try_from(url_or_path)
+            .unwrap()
+            .into_git()
+            .unwrap();
+        assert_eq!(parsed.git, pinned.git);
+        assert_eq!(parsed.commit, pinned.commit);
+        assert_eq!(parsed.rev, pinned.rev);
+    }
+
+    #[test]
+    fn test_git_url_without_commit() {
+        let url = Url::parse("git+https://github.com/org/repo").unwrap();
+        assert!(matches!(
+            PinnedSourceSpec::try_from(UrlOrPath::Url(url)),
+            Err(ParseError::MissingGitCommit(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_url() {
+        let url = Url::parse("https://example.com/source.tar.gz").unwrap();
+        assert!(matches!(
+            PinnedSourceSpec::try_from(UrlOrPath::Url(url)),
+            Err(ParseError::UnsupportedUrl(_))
+        ));
+    }
+}
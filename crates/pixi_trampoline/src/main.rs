@@ -75,7 +75,7 @@ fn trampoline() -> miette::Result<()> {
     let metadata = read_metadata(&current_exe)?;
 
     // Create a new Command for the specified executable
-    let mut cmd = Command::new(metadata.exe);
+    let mut cmd = Command::new(&metadata.exe);
 
     // Set any additional environment variables
     for (key, value) in metadata.env.iter() {
@@ -95,9 +95,14 @@ fn trampoline() -> miette::Result<()> {
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit());
 
-    // Spawn the child process
+    // Replace the current process, `exec` only returns if it failed
     #[cfg(target_family = "unix")]
-    cmd.exec();
+    {
+        let err = cmd.exec();
+        Err(err)
+            .into_diagnostic()
+            .wrap_err(format!("Couldn't execute {:?}", metadata.exe))
+    }
 
     #[cfg(target_os = "windows")]
     {
@@ -115,7 +120,6 @@ fn trampoline() -> miette::Result<()> {
         // Exit with the same status code as the child process
         std::process::exit(status.code().unwrap_or(1));
     }
-    Ok(())
 }
 
 // Entry point for the trampoline
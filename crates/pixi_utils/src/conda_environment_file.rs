@@ -56,6 +56,8 @@ pub struct CondaEnvFile {
     #[serde(default)]
     channels: Vec<NamedChannelOrUrl>,
     dependencies: Vec<CondaEnvDep>,
+    #[serde(default)]
+    variables: Option<serde_yaml::Mapping>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -121,6 +123,13 @@ impl CondaEnvFile {
         Vec<pep508_rs::Requirement>,
         Vec<NamedChannelOrUrl>,
     )> {
+        if self.variables.as_ref().is_some_and(|v| !v.is_empty()) {
+            tracing::warn!("Skipping the environment variables of the environment file");
+            tracing::warn!(
+                "Please add them to the `[activation.env]` table of the manifest manually"
+            );
+        }
+
         let mut channels = parse_channels(self.channels().clone());
         let (conda_deps, pip_deps, extra_channels) =
             parse_dependencies(self.dependencies().clone())?;
@@ -155,15 +164,31 @@ fn parse_dependencies(deps: Vec<CondaEnvDep>) -> miette::Result<ParsedDependenci
                 }
                 conda_deps.push(match_spec);
             }
-            CondaEnvDep::Pip { pip } => pip_deps.extend(
-                pip.iter()
-                    .map(|dep| {
-                        pep508_rs::Requirement::from_str(dep)
+            CondaEnvDep::Pip { pip } => {
+                for dep in pip {
+                    if let Some(path) = dep
+                        .strip_prefix("-e ")
+                        .or_else(|| dep.strip_prefix("--editable "))
+                    {
+                        tracing::warn!("Skipping editable pip dependency: \"{}\"", dep);
+                        tracing::warn!(
+                            "Please add it to the `[pypi-dependencies]` manually, e.g. `{{ path = \"{}\", editable = true }}`",
+                            path.trim()
+                        );
+                        continue;
+                    }
+                    if dep.starts_with('-') {
+                        tracing::warn!("Skipping unsupported pip option: \"{}\"", dep);
+                        tracing::warn!("Please add it to the manifest manually");
+                        continue;
+                    }
+                    pip_deps.push(
+                        pep508_rs::Requirement::from_str(&dep)
                             .into_diagnostic()
-                            .wrap_err(format!("Can't parse '{}' as pypi dependency", dep))
-                    })
-                    .collect::<miette::Result<Vec<_>>>()?,
-            ),
+                            .wrap_err(format!("Can't parse '{}' as pypi dependency", dep))?,
+                    );
+                }
+            }
         }
     }
 
@@ -303,6 +328,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_conda_env_file_with_pip_options() {
+        let example_conda_env_file = r#"
+        channels:
+          - conda-forge
+        dependencies:
+          - python
+          - pip:
+            - --extra-index-url https://download.pytorch.org/whl/cpu
+            - -r requirements.txt
+            - -e .
+            - requests
+        variables:
+          MY_VAR: value
+        "#;
+
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        f.write_all(example_conda_env_file.as_bytes()).unwrap();
+        let (_file, path) = f.into_parts();
+
+        let conda_env_file_data = CondaEnvFile::from_path(&path).unwrap();
+        let (conda_deps, pip_deps, _) =
+            conda_env_file_data.to_manifest(&Config::default()).unwrap();
+
+        assert_eq!(
+            conda_deps,
+            vec![MatchSpec::from_str("python", Strict).unwrap()]
+        );
+        // The pip options are skipped with a warning.
+        assert_eq!(
+            pip_deps,
+            vec![pep508_rs::Requirement::from_str("requests").unwrap()]
+        );
+    }
+
     #[test]
     fn test_parse_conda_env_file_with_explicit_pip_dep() {
         let example_conda_env_file = r#"
@@ -3,9 +3,13 @@ pub mod conda_environment_file;
 pub mod indicatif;
 mod prefix_guard;
 pub mod reqwest;
+mod s3_middleware;
 
 mod executable_utils;
 pub use executable_utils::{executable_from_path, is_binary_folder, strip_executable_extension};
 
 pub use cache::EnvironmentHash;
-pub use prefix_guard::{PrefixGuard, WriteGuard};
+pub use prefix_guard::{
+    mark_prefix_used, remove_unused_prefixes, PrefixGuard, WriteGuard, UNUSED_PREFIX_MAX_AGE,
+};
+pub use s3_middleware::S3Middleware;
@@ -2,6 +2,7 @@ use std::{
     io,
     io::{Read, Seek, Write},
     path::Path,
+    time::{Duration, SystemTime},
 };
 
 use fd_lock::RwLockWriteGuard;
@@ -9,6 +10,14 @@ use serde::{Deserialize, Serialize};
 
 const GUARD_PATH: &str = ".guard";
 
+/// The file in a prefix whose modification time is the last time the prefix
+/// was used.
+const LAST_USED_PATH: &str = ".last-used";
+
+/// Cached prefixes that have not been used for this long are removed by
+/// [`remove_unused_prefixes`].
+pub const UNUSED_PREFIX_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 enum GuardState {
@@ -102,3 +111,82 @@ impl PrefixGuard {
         WriteGuard::new(self.guard.write()?)
     }
 }
+
+/// Records that the prefix is used now, which prevents it from being removed
+/// by [`remove_unused_prefixes`].
+pub fn mark_prefix_used(prefix: &Path) {
+    if let Err(err) = fs_err::write(prefix.join(LAST_USED_PATH), "") {
+        tracing::warn!("failed to mark environment as used: {err}");
+    }
+}
+
+/// Returns the last time the prefix was used. Prefixes that were created
+/// before the usage was recorded fall back to the time the prefix was last
+/// installed.
+fn last_used(prefix: &Path) -> Option<SystemTime> {
+    [LAST_USED_PATH, GUARD_PATH]
+        .iter()
+        .find_map(|file| prefix.join(file).metadata().ok())
+        .and_then(|metadata| metadata.modified().ok())
+}
+
+/// Removes the prefixes in `dir` that have not been used for longer than
+/// `max_age`. Directories that are not prefixes are left alone. Failing to
+/// remove a prefix is not an error, it is retried the next time.
+pub fn remove_unused_prefixes(dir: &Path, max_age: Duration, now: SystemTime) {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(last_used) = last_used(&path) else {
+            continue;
+        };
+        if now.duration_since(last_used).unwrap_or_default() <= max_age {
+            continue;
+        }
+
+        // Wait for a running installation of the prefix to finish.
+        let Ok(mut guard) = PrefixGuard::new(&path) else {
+            continue;
+        };
+        let Ok(_write_guard) = guard.write() else {
+            continue;
+        };
+
+        tracing::info!("removing unused environment {}", path.display());
+        if let Err(err) = fs_err::remove_dir_all(&path) {
+            tracing::warn!("failed to remove unused environment: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_unused_prefixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let used = dir.path().join("used");
+        fs_err::create_dir_all(&used).unwrap();
+        mark_prefix_used(&used);
+        // A prefix from before the usage was recorded.
+        let legacy = dir.path().join("legacy");
+        PrefixGuard::new(&legacy).unwrap();
+        // A directory that is not a prefix.
+        let unknown = dir.path().join("unknown");
+        fs_err::create_dir_all(&unknown).unwrap();
+
+        let max_age = Duration::from_secs(60);
+        remove_unused_prefixes(dir.path(), max_age, SystemTime::now());
+        assert!(used.is_dir());
+        assert!(legacy.is_dir());
+
+        remove_unused_prefixes(dir.path(), max_age, SystemTime::now() + max_age * 2);
+        assert!(!used.exists());
+        assert!(!legacy.exists());
+        assert!(unknown.is_dir());
+    }
+}
@@ -11,17 +11,25 @@ use rattler_networking::{
 
 use reqwest::Client;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::RetryTransientMiddleware;
 use std::collections::HashMap;
 
 use pixi_config::Config;
 
-/// The default retry policy employed by pixi.
-/// TODO: At some point we might want to make this configurable.
-pub fn default_retry_policy() -> ExponentialBackoff {
-    ExponentialBackoff::builder().build_with_max_retries(3)
+use crate::S3Middleware;
+
+/// The retry policy with the number of retries of the `max-retries`
+/// configuration.
+fn retry_policy(config: &Config) -> ExponentialBackoff {
+    ExponentialBackoff::builder().build_with_max_retries(config.max_retries())
 }
 
-fn auth_middleware(config: &Config) -> Result<AuthenticationMiddleware, FileStorageError> {
+/// Returns the storage that is used to look up the credentials of a host.
+///
+/// If an authentication file is configured only that file is used, otherwise
+/// the credentials are looked up in the keyring, the default credentials file
+/// and the `.netrc` file.
+pub fn authentication_storage(config: &Config) -> Result<AuthenticationStorage, FileStorageError> {
     if let Some(auth_file) = config.authentication_override_file() {
         tracing::info!("Loading authentication from file: {:?}", auth_file);
 
@@ -34,10 +42,16 @@ fn auth_middleware(config: &Config) -> Result<AuthenticationMiddleware, FileStor
             authentication_storage::backends::file::FileStorage::new(PathBuf::from(&auth_file))?,
         ));
 
-        return Ok(AuthenticationMiddleware::new(store));
+        return Ok(store);
     }
 
-    Ok(AuthenticationMiddleware::default())
+    Ok(AuthenticationStorage::default())
+}
+
+fn auth_middleware(config: &Config) -> Result<AuthenticationMiddleware, FileStorageError> {
+    Ok(AuthenticationMiddleware::new(authentication_storage(
+        config,
+    )?))
 }
 
 pub fn mirror_middleware(config: &Config) -> MirrorMiddleware {
@@ -99,15 +113,24 @@ pub fn build_reqwest_clients(config: Option<&Config>) -> (Client, ClientWithMidd
         .build()
         .expect("failed to create reqwest Client");
 
-    let mut client_builder = ClientBuilder::new(client.clone());
+    // Retry transient failures with an exponential backoff. This is the
+    // outermost middleware, so that a retry goes through the mirrors and the
+    // authentication again. It is the only retry layer, requests made with this
+    // client should not be retried again on top of it.
+    let mut client_builder = ClientBuilder::new(client.clone()).with(
+        RetryTransientMiddleware::new_with_policy(retry_policy(&config)),
+    );
 
     if !config.mirror_map().is_empty() {
-        client_builder = client_builder
-            .with(mirror_middleware(&config))
-            .with(oci_middleware());
+        client_builder = client_builder.with(mirror_middleware(&config));
     }
 
+    // Resolves `oci://` urls, both of channels hosted in a container registry
+    // and of mirrors.
+    client_builder = client_builder.with(oci_middleware());
+
     client_builder = client_builder.with(GCSMiddleware);
+    client_builder = client_builder.with(S3Middleware::default());
 
     client_builder = client_builder.with_arc(Arc::new(
         auth_middleware(&config).expect("could not create auth middleware"),
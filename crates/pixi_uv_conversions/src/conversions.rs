@@ -173,6 +173,24 @@ pub fn names_to_build_isolation<'a>(
     packages_to_build_isolation(names, env)
 }
 
+/// Convert the `no-build` and `no-binary` pypi options to
+/// `uv_configuration::BuildOptions`
+pub fn to_build_options(
+    no_build: Option<bool>,
+    no_binary: Option<&[String]>,
+) -> Result<uv_configuration::BuildOptions, InvalidNameError> {
+    let no_build = if no_build.unwrap_or(false) {
+        uv_configuration::NoBuild::All
+    } else {
+        uv_configuration::NoBuild::None
+    };
+    let no_binary = match isolated_names_to_packages(no_binary)? {
+        Some(packages) if !packages.is_empty() => uv_configuration::NoBinary::Packages(packages),
+        _ => uv_configuration::NoBinary::None,
+    };
+    Ok(uv_configuration::BuildOptions::new(no_binary, no_build))
+}
+
 /// Convert pixi `IndexStrategy` to `uv_types::IndexStrategy`
 pub fn to_index_strategy(
     index_strategy: Option<&IndexStrategy>,
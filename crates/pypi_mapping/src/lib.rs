@@ -9,9 +9,8 @@ use async_once_cell::OnceCell as AsyncCell;
 use custom_pypi_mapping::fetch_mapping_from_path;
 use http_cache_reqwest::{CACacheManager, Cache, CacheMode, HttpCache, HttpCacheOptions};
 use pixi_config::get_cache_dir;
-use rattler_conda_types::{PackageRecord, PackageUrl, RepoDataRecord};
+use rattler_conda_types::{PackageName, PackageRecord, PackageUrl, RepoDataRecord};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use url::Url;
 
 use crate::custom_pypi_mapping::fetch_mapping_from_url;
@@ -141,9 +140,8 @@ pub async fn amend_pypi_purls(
     conda_packages: impl IntoIterator<Item = &mut RepoDataRecord>,
     reporter: Option<Arc<dyn Reporter>>,
 ) -> miette::Result<()> {
-    // Construct a client with a retry policy and local caching
-    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-    let retry_strategy = RetryTransientMiddleware::new_with_policy(retry_policy);
+    // Construct a client with local caching, failed requests are already retried
+    // by the client.
     let cache_strategy = Cache(HttpCache {
         mode: CacheMode::Default,
         manager: CACacheManager {
@@ -156,7 +154,6 @@ pub async fn amend_pypi_purls(
 
     let client = ClientBuilder::from_client(client)
         .with(cache_strategy)
-        .with(retry_strategy)
         .build();
 
     match mapping_source {
@@ -183,6 +180,31 @@ pub async fn amend_pypi_purls(
     Ok(())
 }
 
+/// Replaces the pypi purls of the conda packages for which the project
+/// defines the name of the pypi package they provide. This takes precedence
+/// over the mapping, e.g. for packages that are named differently on conda and
+/// pypi which the mapping doesn't know about.
+pub fn override_pypi_purls<'a, 'n>(
+    overrides: impl IntoIterator<Item = (&'n PackageName, &'n pep508_rs::PackageName)>,
+    conda_packages: impl IntoIterator<Item = &'a mut RepoDataRecord>,
+) {
+    let overrides: HashMap<_, _> = overrides.into_iter().collect();
+    if overrides.is_empty() {
+        return;
+    }
+
+    for record in conda_packages {
+        if let Some(pypi_name) = overrides.get(&record.package_record.name) {
+            let purl = PackageUrl::builder(String::from("pypi"), pypi_name.to_string())
+                .with_qualifier("source", PurlSource::ProjectDefinedMapping.as_str())
+                .expect("valid qualifier")
+                .build()
+                .expect("valid pypi package url");
+            record.package_record.purls = Some(BTreeSet::from([purl]));
+        }
+    }
+}
+
 /// Returns `true` if the specified record refers to a conda-forge package.
 pub fn is_conda_forge_record(record: &RepoDataRecord) -> bool {
     record
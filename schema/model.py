@@ -13,6 +13,7 @@ from pydantic import (
     AnyHttpUrl,
     BaseModel,
     Field,
+    NonNegativeInt,
     PositiveFloat,
     StringConstraints,
 )
@@ -93,6 +94,14 @@ class ChannelPriority(str, Enum):
     strict = "strict"
 
 
+class SolveStrategy(str, Enum):
+    """The strategy the solver uses to select the versions of the packages."""
+
+    highest = "highest"
+    lowest = "lowest"
+    lowest_direct = "lowest-direct"
+
+
 PixiBuildFeature = Annotated[
     Literal["pixi-build"], Field(description="Enables building of source records")
 ]
@@ -130,6 +139,11 @@ class Workspace(StrictBaseModel):
         "- 'strict': only take the package from the channel it exist in first."
         "- 'disabled': group all dependencies together as if there is no channel difference.",
     )
+    solve_strategy: SolveStrategy | None = Field(
+        None,
+        examples=["highest", "lowest", "lowest-direct"],
+        description="The strategy the solver uses to select the versions of the packages",
+    )
     platforms: list[Platform] = Field(description="The platforms that the project supports")
     license: NonEmptyStr | None = Field(
         None,
@@ -151,9 +165,23 @@ class Workspace(StrictBaseModel):
     conda_pypi_map: dict[ChannelName, AnyHttpUrl | NonEmptyStr] | None = Field(
         None, description="The `conda` to PyPI mapping configuration"
     )
+    channel_overlays: dict[ChannelName, list[NonEmptyStr]] | None = Field(
+        None,
+        description="Channels that shadow the listed packages of the other channels, only the listed packages are used from an overlay channel",
+    )
     pypi_options: PyPIOptions | None = Field(
         None, description="Options related to PyPI indexes for this project"
     )
+    script_dir: PathNoBackslash | None = Field(
+        None,
+        description="The directory with scripts of the project that is added to the `PATH` of the activated environments",
+        examples=["scripts"],
+    )
+    members: list[PathNoBackslash] | None = Field(
+        None,
+        description="The directories of the projects that are members of the workspace of this project",
+        examples=[["packages/core", "packages/cli"]],
+    )
     preview: list[KnownPreviewFeature | str] | bool | None = Field(
         None, description="Defines the enabling of preview features of the project"
     )
@@ -232,6 +260,12 @@ class MatchspecTable(StrictBaseModel):
     tag: NonEmptyStr | None = Field(None, description="A git tag to use")
     branch: NonEmptyStr | None = Field(None, description="A git branch to use")
 
+    platforms: list[Platform] | None = Field(
+        None,
+        description="The platforms the dependency is restricted to, the same as adding it to the `target.<platform>` tables",
+        examples=[["win-64"], ["linux-64", "osx-arm64"]],
+    )
+
 
 MatchSpec = NonEmptyStr | MatchspecTable
 CondaPackageName = NonEmptyStr
@@ -386,6 +420,18 @@ class TaskInlineTable(StrictBaseModel):
         None,
         description="Whether to run in a clean environment, removing all environment variables except those defined in `env` and by pixi itself.",
     )
+    retries: NonNegativeInt | None = Field(
+        None,
+        description="The number of times the task is retried when it exits with a non-zero exit code.",
+    )
+    retry_backoff: NonNegativeInt | None = Field(
+        None,
+        description="The number of seconds to wait before the first retry, doubled for every subsequent retry.",
+    )
+    sandbox: Literal["network-off", "restricted-fs", "strict"] | None = Field(
+        None,
+        description="Run the task in a sandbox: `network-off` disables network access, `restricted-fs` only allows writing to the project and temporary directories, `strict` combines both. Only supported on Linux and macOS.",
+    )
 
 
 #######################
@@ -439,6 +485,11 @@ class Environment(StrictBaseModel):
         False,
         description="Whether to add the default feature to this environment",
     )
+    python_matrix: list[NonEmptyStr] | None = Field(
+        None,
+        description="Create an environment for each of these python versions, e.g. `test-py311`, instead of a single environment",
+        examples=[["3.10", "3.11", "3.12"]],
+    )
 
 
 ######################
@@ -452,9 +503,9 @@ class Activation(StrictBaseModel):
         description="The scripts to run when the environment is activated",
         examples=["activate.sh", "activate.bat"],
     )
-    env: dict[NonEmptyStr, NonEmptyStr] | None = Field(
+    env: dict[NonEmptyStr, NonEmptyStr | list[NonEmptyStr]] | None = Field(
         None,
-        description="A map of environment variables to values, used in the activation of the environment. These will be set in the shell. Thus these variables are shell specific. Using '$' might not expand to a value in different shells.",
+        description="A map of environment variables to values, used in the activation of the environment. These will be set in the shell. References written as '${VAR}' are expanded in every shell, other uses of '$' might not expand to a value in different shells. A list of values is joined with the path separator of the platform.",
         examples=[{"key": "value"}, {"ARGUMENT": "value"}],
     )
 
@@ -499,6 +550,11 @@ class Feature(StrictBaseModel):
         "- 'strict': only take the package from the channel it exist in first."
         "- 'disabled': group all dependencies together as if there is no channel difference.",
     )
+    solve_strategy: SolveStrategy | None = Field(
+        None,
+        examples=["highest", "lowest", "lowest-direct"],
+        description="The strategy the solver uses to select the versions of the packages",
+    )
     platforms: list[Platform] | None = Field(
         None,
         description="The platforms that the feature supports: a union of all features combined in one environment is used for the environment.",
@@ -574,6 +630,15 @@ class PyPIOptions(StrictBaseModel):
         description="Packages that should NOT be isolated during the build process",
         examples=[["numpy"]],
     )
+    no_build: bool | None = Field(
+        None,
+        description="Never build source distributions, only install packages from wheels",
+    )
+    no_binary: list[PyPIPackageName] = Field(
+        None,
+        description="Packages that should never be installed from a wheel, they are always built from a source distribution",
+        examples=[["numpy"]],
+    )
     index_strategy: (
         Literal["first-index"] | Literal["unsafe-first-match"] | Literal["unsafe-best-match"] | None
     ) = Field(
@@ -641,6 +706,20 @@ class BaseManifest(StrictBaseModel):
         description="The targets of the project",
         examples=[{"linux": {"dependencies": {"python": "3.8"}}}],
     )
+    discovery_boundary: bool | None = Field(
+        None,
+        description="Whether the search for a manifest stops in this directory, instead of continuing in the parent directories",
+    )
+    generate: dict[NonEmptyStr, NonEmptyStr] | None = Field(
+        None,
+        description="Files that are generated from templates when an environment is installed, mapping the template to the output path",
+        examples=[{"toolchain.cmake.j2": "build/{{ environment.name }}/toolchain.cmake"}],
+    )
+    pypi_name_mapping: dict[CondaPackageName, PyPIPackageName] | None = Field(
+        None,
+        description="The names of the PyPI packages that conda packages provide, overriding the conda-pypi mapping",
+        examples=[{"pytorch": "torch"}],
+    )
     tool: dict[str, Any] = Field(
         None, description="Third-party tool configurations, ignored by pixi"
     )
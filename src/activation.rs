@@ -1,5 +1,6 @@
 use crate::{project::Environment, Project};
 use crate::{project::HasProjectRef, task::EnvironmentHash};
+use fancy_display::FancyDisplay;
 use fs_err::tokio as tokio_fs;
 use indexmap::IndexMap;
 use itertools::Itertools;
@@ -13,11 +14,11 @@ use rattler_shell::{
         ActivationError, ActivationError::FailedToRunActivationScript, ActivationVariables,
         Activator, PathModificationBehavior,
     },
-    shell::ShellEnum,
+    shell::{Shell, ShellEnum},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 
 // Setting a base prefix for the pixi package
 const PROJECT_PREFIX: &str = "PIXI_PROJECT_";
@@ -136,6 +137,16 @@ pub(crate) fn get_activator<'p>(
         }
     }
 
+    // The `${VAR}` references in the environment variables are written in the
+    // syntax of the shell that runs the activation.
+    let env_vars = get_static_environment_variables(environment)
+        .into_iter()
+        .map(|(key, value)| {
+            let value = format_env_references(&value, &shell);
+            (key, value)
+        })
+        .collect_vec();
+
     let mut activator =
         Activator::from_path(environment.dir().as_path(), shell, Platform::current())?;
 
@@ -145,13 +156,147 @@ pub(crate) fn get_activator<'p>(
         .extend(additional_activation_scripts);
 
     // Add the environment variables from the project.
-    activator
-        .env_vars
-        .extend(get_static_environment_variables(environment));
+    activator.env_vars.extend(env_vars);
+
+    // The scripts of the project take precedence over the executables of the
+    // environment.
+    if let Some(script_dir) = environment.project().script_dir() {
+        if !script_dir.is_dir() {
+            tracing::warn!(
+                "Could not find the script directory: {}",
+                script_dir.display()
+            );
+        }
+        activator.paths.insert(0, script_dir);
+    }
 
     Ok(activator)
 }
 
+/// Rewrites the `${VAR}` references in the value of an environment variable to
+/// the syntax of the given shell, e.g. `%VAR%` for `cmd.exe`. This allows the
+/// same value to be used on all platforms.
+fn format_env_references(value: &str, shell: &ShellEnum) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(length) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + length];
+        let end = start + 3 + length;
+        result.push_str(&rest[..start]);
+        let is_variable_name =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        match shell {
+            _ if !is_variable_name => result.push_str(&rest[start..end]),
+            ShellEnum::CmdExe(_) => result.push_str(&format!("%{name}%")),
+            ShellEnum::PowerShell(_) => result.push_str(&format!("$Env:{name}")),
+            ShellEnum::Fish(_) | ShellEnum::Xonsh(_) => result.push_str(&format!("${name}")),
+            _ => result.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Resolves conflicts between the executables of the environment and the
+/// executables exposed by `pixi global`, the one that is found first on the
+/// `PATH` of the activated environment is the one that is used.
+///
+/// When the user prefers the global executables the global binary directory is
+/// moved to the front of the `PATH`. When the user did not make a choice a
+/// warning is shown that lists the executables that are provided by both.
+pub(crate) fn resolve_global_executable_conflicts(
+    environment: &Environment<'_>,
+    env_vars: &mut HashMap<String, String>,
+) {
+    let Some(global_bin_dir) = pixi_config::pixi_home().map(|home| home.join("bin")) else {
+        return;
+    };
+    let shell = ShellEnum::default();
+    let path_var = shell.path_var(&Platform::current());
+    let Some(path) = env_vars.get(path_var) else {
+        return;
+    };
+    let mut paths = std::env::split_paths(path).collect_vec();
+    let Some(global_index) = paths.iter().position(|p| p == &global_bin_dir) else {
+        return;
+    };
+
+    match environment.project().config().prefer_project_executables() {
+        Some(true) => {}
+        Some(false) => {
+            let bin_dir = paths.remove(global_index);
+            paths.insert(0, bin_dir);
+            if let Ok(path) = std::env::join_paths(paths) {
+                env_vars.insert(path_var.to_string(), path.to_string_lossy().into_owned());
+            }
+        }
+        None => {
+            let prefix = environment.dir();
+            let shadowed = shadowed_executables(&paths, &prefix, &global_bin_dir);
+            if shadowed.is_empty() {
+                return;
+            }
+            let project_first = paths
+                .iter()
+                .position(|p| p.starts_with(&prefix))
+                .is_some_and(|index| index < global_index);
+            let (first, second) = if project_first {
+                (prefix.as_path(), global_bin_dir.as_path())
+            } else {
+                (global_bin_dir.as_path(), prefix.as_path())
+            };
+            tracing::warn!(
+                "{} provided by both the {} environment and `pixi global`, the executables are resolved in this order:\n  1. {}\n  2. {}\nUse `pixi config set prefer-project-executables <true|false>` to choose explicitly and silence this warning",
+                shadowed.iter().map(|name| format!("`{name}`")).join(", "),
+                environment.name().fancy_display(),
+                first.display(),
+                second.display(),
+            );
+        }
+    }
+}
+
+/// Returns the names of the executables in the global binary directory that
+/// are also provided by one of the directories of the environment on the
+/// `PATH`.
+fn shadowed_executables(paths: &[PathBuf], prefix: &Path, global_bin_dir: &Path) -> Vec<String> {
+    let global_executables = executable_names(global_bin_dir);
+    let project_executables: BTreeSet<_> = paths
+        .iter()
+        .filter(|path| path.starts_with(prefix))
+        .flat_map(|path| executable_names(path))
+        .collect();
+    global_executables
+        .intersection(&project_executables)
+        .cloned()
+        .collect()
+}
+
+/// Returns the names of the files in the directory, without the extension on
+/// Windows since executables are found without it.
+fn executable_names(dir: &Path) -> BTreeSet<String> {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return BTreeSet::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = if cfg!(windows) {
+                path.file_stem()?.to_string_lossy().to_lowercase()
+            } else {
+                path.file_name()?.to_string_lossy().into_owned()
+            };
+            Some(name)
+        })
+        .collect()
+}
+
 /// Get the environment variables from the shell environment.
 /// This method retrieves the specified environment variables from the shell and returns them as a HashMap.
 /// If the variable is not set, its value will be `None`.
@@ -218,6 +363,13 @@ async fn try_get_valid_activation_cache(
     }
 }
 
+/// Returns the prefix of the environment that pixi activated in the current
+/// process, if any.
+fn activated_pixi_prefix() -> Option<PathBuf> {
+    std::env::var_os("PIXI_IN_SHELL")?;
+    std::env::var_os("CONDA_PREFIX").map(PathBuf::from)
+}
+
 /// Runs and caches the activation script.
 pub async fn run_activation(
     environment: &Environment<'_>,
@@ -256,22 +408,36 @@ pub async fn run_activation(
         ))
     })?;
 
-    let path_modification_behavior = match env_var_behavior {
-        // We need to replace the full environment path with the new one.
-        // So only the executables from the pixi environment are available.
-        CurrentEnvVarBehavior::Clean => PathModificationBehavior::Replace,
-        _ => PathModificationBehavior::Prepend,
-    };
+    let (conda_prefix, path, path_modification_behavior) =
+        match (env_var_behavior, activated_pixi_prefix()) {
+            // We need to replace the full environment path with the new one.
+            // So only the executables from the pixi environment are available.
+            (CurrentEnvVarBehavior::Clean, _) => (None, None, PathModificationBehavior::Replace),
+            // When pixi is called from an environment that pixi activated, e.g. a
+            // task that calls `pixi run` or a `pixi shell`, that environment is
+            // deactivated first. Otherwise its paths would stay on the `PATH`
+            // after the ones of this environment.
+            (_, Some(outer_prefix)) => {
+                tracing::debug!(
+                    "deactivating the outer pixi environment at {}",
+                    outer_prefix.display()
+                );
+                let path =
+                    std::env::var_os("PATH").map(|path| std::env::split_paths(&path).collect_vec());
+                (Some(outer_prefix), path, PathModificationBehavior::Replace)
+            }
+            _ => (None, None, PathModificationBehavior::Prepend),
+        };
 
     let activator_result = match tokio::task::spawn_blocking(move || {
         // Run and cache the activation script
         activator.run_activation(
             ActivationVariables {
-                // Get the current PATH variable
-                path: Default::default(),
+                // The current PATH variable, or `None` to extend it in the shell
+                path,
 
-                // Start from an empty prefix
-                conda_prefix: None,
+                // The prefix to deactivate, if any
+                conda_prefix,
 
                 // Prepending environment paths so they get found first.
                 path_modification_behavior,
@@ -754,6 +920,111 @@ packages:
         );
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_activation_cache_based_on_activation_scripts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = r#"
+        [project]
+        name = "pixi"
+        channels = []
+        platforms = []
+
+        [activation]
+        scripts = ["setup_env.sh"]
+        "#;
+        let script = temp_dir.path().join("setup_env.sh");
+        tokio_fs::write(&script, "export TEST=ACTIVATION123\n")
+            .await
+            .unwrap();
+        let project =
+            Project::from_str(temp_dir.path().join("pixi.toml").as_path(), project).unwrap();
+        let default_env = project.default_environment();
+        let env = run_activation(
+            &default_env,
+            &CurrentEnvVarBehavior::Include,
+            Some(&LockFile::default()),
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(env.get("TEST").unwrap(), "ACTIVATION123");
+
+        // Modify the variable in cache
+        let cache_file = project.default_environment().activation_cache_file_path();
+        let contents = tokio_fs::read_to_string(&cache_file).await.unwrap();
+        let modified = contents.replace("ACTIVATION123", "ACTIVATION456");
+        tokio_fs::write(&cache_file, modified).await.unwrap();
+
+        // Check that the cache is invalidated when the contents of the script change.
+        tokio_fs::write(&script, "export TEST=ACTIVATION789\n")
+            .await
+            .unwrap();
+        let env = run_activation(
+            &default_env,
+            &CurrentEnvVarBehavior::Include,
+            Some(&LockFile::default()),
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            env.get("TEST").unwrap(),
+            "ACTIVATION789",
+            "The script should be run again"
+        );
+    }
+
+    #[test]
+    fn test_format_env_references() {
+        use rattler_shell::shell::{Bash, CmdExe, Fish, PowerShell};
+
+        let value = "${PIXI_PROJECT_ROOT}/bin ${not a var} ${";
+        assert_eq!(
+            format_env_references(value, &ShellEnum::Bash(Bash)),
+            "${PIXI_PROJECT_ROOT}/bin ${not a var} ${"
+        );
+        assert_eq!(
+            format_env_references(value, &ShellEnum::CmdExe(CmdExe)),
+            "%PIXI_PROJECT_ROOT%/bin ${not a var} ${"
+        );
+        assert_eq!(
+            format_env_references(value, &ShellEnum::PowerShell(PowerShell::default())),
+            "$Env:PIXI_PROJECT_ROOT/bin ${not a var} ${"
+        );
+        assert_eq!(
+            format_env_references(value, &ShellEnum::Fish(Fish)),
+            "$PIXI_PROJECT_ROOT/bin ${not a var} ${"
+        );
+    }
+
+    #[test]
+    fn test_shadowed_executables() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let prefix = temp_dir.path().join("envs/default");
+        let env_bin = prefix.join("bin");
+        let global_bin = temp_dir.path().join("global/bin");
+        let other_bin = temp_dir.path().join("other/bin");
+        for (dir, names) in [
+            (&env_bin, ["python", "pip"]),
+            (&global_bin, ["python", "rg"]),
+            (&other_bin, ["pip", "rg"]),
+        ] {
+            fs_err::create_dir_all(dir).unwrap();
+            for name in names {
+                fs_err::write(dir.join(name), "").unwrap();
+            }
+        }
+
+        let paths = vec![env_bin, global_bin.clone(), other_bin];
+        assert_eq!(
+            shadowed_executables(&paths, &prefix, &global_bin),
+            vec![String::from("python")]
+        );
+    }
+
     // This test works, most of the times.., so this is a good test to run locally.
     // But it is to flaky for CI unfortunately!
     // #[tokio::test]
@@ -1,7 +1,7 @@
 use std::{
     hash::{Hash, Hasher},
     io::SeekFrom,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use crate::{
@@ -11,16 +11,30 @@ use crate::{
 use async_fd_lock::{LockWrite, RwLockWriteGuard};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use rattler_conda_types::{GenericVirtualPackage, Platform, RepoDataRecord};
+use rattler_digest::Sha256;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use url::Url;
 use xxhash_rust::xxh3::Xxh3;
 
+/// The name of the directory in which the builds are stored. It includes a
+/// version number to ensure the cache is never corrupted if the format changes.
+const CACHE_DIR_NAME: &str = "source-builds-v0";
+
+/// The name of the file that describes a build in the shared cache.
+const SHARED_BUILD_FILE: &str = "build.json";
+
 /// A cache for caching build artifacts of a source checkout.
+///
+/// Builds of immutable sources (e.g. a specific git revision) can additionally
+/// be shared through a shared cache directory. Builds that are not found
+/// locally are copied from the shared cache and new builds are published to
+/// it.
 #[derive(Clone)]
 pub struct BuildCache {
     root: PathBuf,
+    shared_root: Option<PathBuf>,
 }
 
 #[derive(Debug, Error)]
@@ -101,7 +115,17 @@ impl BuildCache {
     /// never corrupted if the format changes in the future.
     pub fn new(root: PathBuf) -> Self {
         Self {
-            root: root.join("source-builds-v0"),
+            root: root.join(CACHE_DIR_NAME),
+            shared_root: None,
+        }
+    }
+
+    /// Sets the directory that is used to share builds of immutable sources
+    /// with other machines.
+    pub fn with_shared_dir(self, shared_dir: Option<PathBuf>) -> Self {
+        Self {
+            shared_root: shared_dir.map(|dir| dir.join(CACHE_DIR_NAME)),
+            ..self
         }
     }
 
@@ -109,6 +133,9 @@ impl BuildCache {
     /// cache. If the cache doesn't contain an entry for this source and input,
     /// it returns `None`.
     ///
+    /// If the local cache doesn't contain the build but the shared cache does,
+    /// the build is copied from the shared cache into the local cache.
+    ///
     /// This function also returns a [`CacheEntry`] which can be used to update
     /// the cache. The [`CacheEntry`] also holds an exclusive lock on the cache
     /// which prevents other processes from accessing the cache entry. Drop
@@ -118,13 +145,10 @@ impl BuildCache {
         source: &SourceCheckout,
         input: &BuildInput,
     ) -> Result<(Option<CachedBuild>, CacheEntry), BuildCacheError> {
-        let input_key = input.hash_key();
+        let relative_dir = Path::new(&source_checkout_cache_key(source)).join(input.hash_key());
 
         // Ensure the cache directory exists
-        let cache_dir = self
-            .root
-            .join(source_checkout_cache_key(source))
-            .join(input_key);
+        let cache_dir = self.root.join(&relative_dir);
         tokio::fs::create_dir_all(&cache_dir).await.map_err(|e| {
             BuildCacheError::IoError("creating cache directory".to_string(), cache_dir.clone(), e)
         })?;
@@ -167,15 +191,33 @@ impl BuildCache {
                 )
             })?;
 
-        let metadata = serde_json::from_str(&cache_file_contents).ok();
-        Ok((
-            metadata,
-            CacheEntry {
-                file: locked_cache_file,
-                cache_dir,
-                cache_file_path,
-            },
-        ))
+        let mut entry = CacheEntry {
+            file: locked_cache_file,
+            cache_dir,
+            cache_file_path,
+            // Only immutable sources result in the same build on other machines.
+            shared_dir: self
+                .shared_root
+                .as_ref()
+                .filter(|_| source.pinned.is_immutable())
+                .map(|root| root.join(&relative_dir)),
+        };
+
+        let mut metadata = serde_json::from_str(&cache_file_contents).ok();
+        if metadata.is_none() {
+            if let Some(shared_dir) = &entry.shared_dir {
+                match import_shared_build(shared_dir, &entry.cache_dir).await {
+                    Ok(Some(build)) => {
+                        tracing::debug!("found a build in the shared cache");
+                        metadata = Some(entry.store(build).await?);
+                    }
+                    Ok(None) => {}
+                    Err(err) => tracing::warn!("failed to read from the shared build cache: {err}"),
+                }
+            }
+        }
+
+        Ok((metadata, entry))
     }
 }
 
@@ -201,22 +243,51 @@ pub struct CacheEntry {
     file: RwLockWriteGuard<tokio::fs::File>,
     cache_dir: PathBuf,
     cache_file_path: PathBuf,
+    shared_dir: Option<PathBuf>,
 }
 
 impl CacheEntry {
-    /// Consumes this instance and writes the given metadata to the cache.
+    /// Consumes this instance and writes the given metadata to the cache. If a
+    /// shared cache is configured, the build is also published to it.
     pub async fn insert(
         mut self,
-        mut metadata: CachedBuild,
+        metadata: CachedBuild,
     ) -> Result<RepoDataRecord, BuildCacheError> {
+        let metadata = self.store(metadata).await?;
+
+        if let Some(shared_dir) = self.shared_dir.clone() {
+            let build =
+                serde_json::to_vec(&metadata).expect("serialization to JSON should not fail");
+            let artifact = metadata.record.url.to_file_path().ok();
+            let result = tokio::task::spawn_blocking(move || {
+                publish_shared_build(&shared_dir, &build, artifact.as_deref())
+            })
+            .await
+            .unwrap_or_else(|e| match e.try_into_panic() {
+                Ok(e) => std::panic::resume_unwind(e),
+                Err(_err) => Err(std::io::Error::other("cancelled")),
+            });
+            if let Err(err) = result {
+                tracing::warn!("failed to publish the build to the shared build cache: {err}");
+            }
+        }
+
+        Ok(metadata.record)
+    }
+
+    /// Moves the build artifact into the cache directory and writes the
+    /// metadata to the cache file.
+    async fn store(&mut self, mut metadata: CachedBuild) -> Result<CachedBuild, BuildCacheError> {
         // Move the file into the cache
         if let Ok(file_path) = metadata.record.url.to_file_path() {
             let file_name = file_path
                 .file_name()
                 .expect("the path cannot be empty because that wouldnt be a valid url");
             let destination = self.cache_dir.join(file_name);
-            if let Err(err) = move_file(&file_path, &destination) {
-                return Err(BuildCacheError::MoveError(file_path, destination, err));
+            if file_path != destination {
+                if let Err(err) = move_file(&file_path, &destination) {
+                    return Err(BuildCacheError::MoveError(file_path, destination, err));
+                }
             }
 
             metadata.record.url = Url::from_file_path(&destination)
@@ -250,6 +321,150 @@ impl CacheEntry {
                 )
             })?;
 
-        Ok(metadata.record)
+        Ok(metadata)
+    }
+}
+
+/// Copies a build from the shared cache directory into the local cache
+/// directory. Returns `None` if the shared cache doesn't contain a valid build.
+async fn import_shared_build(
+    shared_dir: &Path,
+    cache_dir: &Path,
+) -> Result<Option<CachedBuild>, BuildCacheError> {
+    let build_file_path = shared_dir.join(SHARED_BUILD_FILE);
+    let contents = match tokio::fs::read_to_string(&build_file_path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(BuildCacheError::IoError(
+                "reading shared build file".to_string(),
+                build_file_path,
+                e,
+            ))
+        }
+    };
+    let Ok(mut build) = serde_json::from_str::<CachedBuild>(&contents) else {
+        return Ok(None);
+    };
+
+    let source = shared_dir.join(&build.record.file_name);
+    let destination = cache_dir.join(&build.record.file_name);
+    tokio::fs::copy(&source, &destination)
+        .await
+        .map_err(|e| BuildCacheError::IoError("copying shared build".to_string(), source, e))?;
+
+    // Make sure the artifact was not modified after it was published.
+    let sha = rattler_digest::compute_file_digest::<Sha256>(&destination).map_err(|e| {
+        BuildCacheError::IoError("computing the hash of".to_string(), destination.clone(), e)
+    })?;
+    if build.record.package_record.sha256 != Some(sha) {
+        tracing::warn!(
+            "ignoring the shared build of {} because its hash does not match",
+            build.record.file_name
+        );
+        let _ = tokio::fs::remove_file(&destination).await;
+        return Ok(None);
+    }
+
+    build.record.url =
+        Url::from_file_path(&destination).expect("the cache directory path should be a valid url");
+    Ok(Some(build))
+}
+
+/// Publishes a build to the shared cache directory. The artifact is written
+/// before the build file and both are written to a temporary file first to
+/// ensure other machines never observe a partial build.
+fn publish_shared_build(
+    shared_dir: &Path,
+    build: &[u8],
+    artifact: Option<&Path>,
+) -> std::io::Result<()> {
+    let build_file_path = shared_dir.join(SHARED_BUILD_FILE);
+    if build_file_path.is_file() {
+        // Another machine already published this build.
+        return Ok(());
+    }
+    fs_err::create_dir_all(shared_dir)?;
+
+    if let Some(artifact) = artifact {
+        let file_name = artifact
+            .file_name()
+            .expect("the path cannot be empty because that wouldnt be a valid url");
+        let temp_file = tempfile::NamedTempFile::new_in(shared_dir)?;
+        fs_err::copy(artifact, temp_file.path())?;
+        temp_file
+            .persist(shared_dir.join(file_name))
+            .map_err(|e| e.error)?;
+    }
+
+    let mut temp_file = tempfile::NamedTempFile::new_in(shared_dir)?;
+    std::io::Write::write_all(&mut temp_file, build)?;
+    temp_file.persist(build_file_path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use rattler_conda_types::{PackageName, PackageRecord, Version};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shared_build_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared_dir = dir.path().join("shared");
+        let cache_dir = dir.path().join("local");
+        fs_err::create_dir_all(&cache_dir).unwrap();
+
+        // Nothing has been published yet.
+        assert!(import_shared_build(&shared_dir, &cache_dir)
+            .await
+            .unwrap()
+            .is_none());
+
+        let artifact = dir.path().join("foo-1.0-0.conda");
+        fs_err::write(&artifact, "artifact").unwrap();
+        let mut package_record = PackageRecord::new(
+            PackageName::from_str("foo").unwrap(),
+            Version::from_str("1.0").unwrap(),
+            "0".to_string(),
+        );
+        package_record.sha256 =
+            Some(rattler_digest::compute_file_digest::<Sha256>(&artifact).unwrap());
+        let build = CachedBuild {
+            source: None,
+            record: RepoDataRecord {
+                package_record,
+                file_name: "foo-1.0-0.conda".to_string(),
+                url: Url::from_file_path(&artifact).unwrap(),
+                channel: None,
+            },
+        };
+        publish_shared_build(
+            &shared_dir,
+            &serde_json::to_vec(&build).unwrap(),
+            Some(&artifact),
+        )
+        .unwrap();
+
+        let imported = import_shared_build(&shared_dir, &cache_dir)
+            .await
+            .unwrap()
+            .expect("the build should have been published");
+        let local_artifact = cache_dir.join("foo-1.0-0.conda");
+        assert_eq!(
+            imported.record.url,
+            Url::from_file_path(&local_artifact).unwrap()
+        );
+        assert_eq!(fs_err::read_to_string(&local_artifact).unwrap(), "artifact");
+
+        // A modified artifact in the shared cache is ignored.
+        fs_err::write(shared_dir.join("foo-1.0-0.conda"), "modified").unwrap();
+        assert!(import_shared_build(&shared_dir, &cache_dir)
+            .await
+            .unwrap()
+            .is_none());
     }
 }
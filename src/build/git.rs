@@ -0,0 +1,279 @@
+//! Fetches git sources by invoking the `git` executable.
+//!
+//! Every repository is fetched into a bare repository in the cache, a
+//! checkout of a specific commit is created from that bare repository. Since
+//! a commit never changes, checkouts are reused between builds.
+
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use miette::Diagnostic;
+use pixi_consts::consts;
+use pixi_record::PinnedGitSpec;
+use pixi_spec::{GitReference, GitSpec};
+use thiserror::Error;
+use url::Url;
+use xxhash_rust::xxh3::Xxh3;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum GitError {
+    #[error("failed to execute `git {0}`")]
+    #[diagnostic(help("git sources require `git` to be installed and on the PATH"))]
+    Execute(String, #[source] std::io::Error),
+
+    #[error("`git {0}` failed:\n{1}")]
+    Command(String, String),
+
+    #[error("could not find '{1}' in {0}")]
+    UnknownReference(Url, String),
+
+    #[error("failed to create the checkout directory {}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+
+    #[error("failed to lock {}", .0.display())]
+    Lock(PathBuf, #[source] std::io::Error),
+}
+
+/// An exclusive lock on a directory in the git cache, held by a lock-file next
+/// to the directory. This prevents concurrent pixi processes from modifying
+/// the same repository or checkout at the same time.
+struct DirectoryLock {
+    path: PathBuf,
+    lock: fd_lock::RwLock<std::fs::File>,
+}
+
+impl DirectoryLock {
+    /// Opens the lock-file of the directory without acquiring the lock.
+    fn new(dir: &Path) -> Result<Self, GitError> {
+        let mut path = dir.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent).map_err(|e| GitError::Io(parent.to_path_buf(), e))?;
+        }
+        let file = std::fs::File::options()
+            .write(true)
+            .read(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| GitError::Lock(path.clone(), e))?;
+        Ok(Self {
+            path,
+            lock: fd_lock::RwLock::new(file),
+        })
+    }
+
+    /// Blocks until the lock is acquired, the lock is released when the
+    /// returned guard is dropped.
+    fn lock(&mut self) -> Result<fd_lock::RwLockWriteGuard<'_, std::fs::File>, GitError> {
+        self.lock
+            .write()
+            .map_err(|e| GitError::Lock(self.path.clone(), e))
+    }
+}
+
+/// Fetches the repository of the spec and resolves the requested reference to
+/// a commit. Returns the checkout of that commit.
+pub fn fetch(cache_dir: &Path, spec: &GitSpec) -> Result<(PathBuf, PinnedGitSpec), GitError> {
+    let db = repository_dir(cache_dir, &spec.git);
+    let reference = match &spec.rev {
+        Some(GitReference::Branch(branch)) => format!("refs/heads/{branch}"),
+        Some(GitReference::Tag(tag)) => format!("refs/tags/{tag}"),
+        Some(GitReference::Rev(rev)) => rev.clone(),
+        None => String::from("HEAD"),
+    };
+
+    let mut db_lock = DirectoryLock::new(&db)?;
+    let db_guard = db_lock.lock()?;
+
+    // A commit never changes, so if we already have it there is no need to go
+    // to the network.
+    let commit = match &spec.rev {
+        Some(GitReference::Rev(_)) if db.exists() => resolve(&db, &reference).ok(),
+        _ => None,
+    };
+    let commit = match commit {
+        Some(commit) => commit,
+        None => {
+            update_repository(&db, &spec.git)?;
+            resolve(&db, &reference)
+                .map_err(|_| GitError::UnknownReference(spec.git.clone(), reference))?
+        }
+    };
+
+    drop(db_guard);
+
+    let pinned = PinnedGitSpec {
+        git: spec.git.clone(),
+        commit,
+        rev: spec.rev.clone(),
+    };
+    let path = checkout(cache_dir, &pinned)?;
+    Ok((path, pinned))
+}
+
+/// Returns the checkout of the pinned commit, fetching the repository if the
+/// commit is not available locally.
+pub fn checkout(cache_dir: &Path, pinned: &PinnedGitSpec) -> Result<PathBuf, GitError> {
+    let destination = cache_dir
+        .join(consts::GIT_CACHE_DIR)
+        .join("checkouts")
+        .join(url_key(&pinned.git))
+        .join(&pinned.commit);
+    if destination.exists() {
+        return Ok(destination);
+    }
+
+    // Another process might be creating the same checkout, wait for it and
+    // check again.
+    let mut checkout_lock = DirectoryLock::new(&destination)?;
+    let _checkout_guard = checkout_lock.lock()?;
+    if destination.exists() {
+        return Ok(destination);
+    }
+
+    let db = repository_dir(cache_dir, &pinned.git);
+    let mut db_lock = DirectoryLock::new(&db)?;
+    let _db_guard = db_lock.lock()?;
+    if !db.exists() || resolve(&db, &pinned.commit).is_err() {
+        update_repository(&db, &pinned.git)?;
+    }
+
+    // Checkout into a temporary directory first so an interrupted checkout is
+    // never mistaken for a complete one.
+    let temp_destination = destination.with_extension("tmp");
+    if temp_destination.exists() {
+        fs_err::remove_dir_all(&temp_destination)
+            .map_err(|e| GitError::Io(temp_destination.clone(), e))?;
+    }
+    if let Some(parent) = destination.parent() {
+        fs_err::create_dir_all(parent).map_err(|e| GitError::Io(parent.to_path_buf(), e))?;
+    }
+    git(
+        None,
+        &[
+            "clone",
+            "--quiet",
+            "--no-checkout",
+            &db.to_string_lossy(),
+            &temp_destination.to_string_lossy(),
+        ],
+    )?;
+    git(
+        Some(&temp_destination),
+        &["checkout", "--quiet", "--detach", &pinned.commit],
+    )?;
+    git(
+        Some(&temp_destination),
+        &["submodule", "update", "--quiet", "--init", "--recursive"],
+    )?;
+    fs_err::rename(&temp_destination, &destination)
+        .map_err(|e| GitError::Io(destination.clone(), e))?;
+
+    Ok(destination)
+}
+
+/// Clones or updates the bare repository that contains all the branches and
+/// tags of the remote.
+fn update_repository(db: &Path, url: &Url) -> Result<(), GitError> {
+    if !db.exists() {
+        git(None, &["init", "--quiet", "--bare", &db.to_string_lossy()])?;
+    }
+    tracing::info!("fetching git repository {}", url);
+    git(
+        Some(db),
+        &[
+            "fetch",
+            "--quiet",
+            "--force",
+            url.as_str(),
+            "+HEAD:refs/remotes/origin/HEAD",
+            "+refs/heads/*:refs/heads/*",
+            "+refs/tags/*:refs/tags/*",
+        ],
+    )?;
+    Ok(())
+}
+
+/// Resolves a reference to a commit hash.
+fn resolve(db: &Path, reference: &str) -> Result<String, GitError> {
+    // `HEAD` of a bare repository that we fetched into does not follow the
+    // remote, use the fetched remote `HEAD` instead.
+    let reference = if reference == "HEAD" {
+        "refs/remotes/origin/HEAD"
+    } else {
+        reference
+    };
+    git(
+        Some(db),
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{reference}^{{commit}}"),
+        ],
+    )
+    .map(|output| output.trim().to_string())
+}
+
+/// Executes git with the given arguments and returns its stdout.
+fn git(cwd: Option<&Path>, args: &[&str]) -> Result<String, GitError> {
+    let mut command = Command::new("git");
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let output = command
+        .args(args)
+        .output()
+        .map_err(|e| GitError::Execute(args.join(" "), e))?;
+    if !output.status.success() {
+        return Err(GitError::Command(
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the directory of the bare repository of the given url.
+fn repository_dir(cache_dir: &Path, url: &Url) -> PathBuf {
+    cache_dir
+        .join(consts::GIT_CACHE_DIR)
+        .join("db")
+        .join(url_key(url))
+}
+
+/// Returns a unique, semi-human-readable, name for the repository.
+fn url_key(url: &Url) -> String {
+    let mut hasher = Xxh3::new();
+    url.as_str().trim_end_matches('/').hash(&mut hasher);
+    let unique_key = URL_SAFE_NO_PAD.encode(hasher.finish().to_ne_bytes());
+    let name = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .map(|name| name.trim_end_matches(".git"));
+    match name {
+        Some(name) if !name.is_empty() => format!("{name}-{unique_key}"),
+        _ => unique_key,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_url_key() {
+        let key = url_key(&Url::parse("https://github.com/org/repo.git").unwrap());
+        assert!(key.starts_with("repo-"));
+        assert_eq!(
+            url_key(&Url::parse("https://github.com/org/repo/").unwrap()),
+            url_key(&Url::parse("https://github.com/org/repo").unwrap())
+        );
+    }
+}
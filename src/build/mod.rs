@@ -1,4 +1,5 @@
 mod cache;
+mod git;
 mod reporters;
 
 use std::{
@@ -89,6 +90,27 @@ pub enum BuildError {
 
     #[error(transparent)]
     BuildFolderNotWritable(#[from] std::io::Error),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::GitError),
+
+    #[error("fetching the git source was cancelled")]
+    GitCancelled,
+}
+
+/// Runs a blocking git operation on a separate thread. Panics of the
+/// operation are propagated to the caller.
+async fn spawn_git<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, git::GitError> + Send + 'static,
+) -> Result<T, BuildError> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => Ok(result?),
+        Err(err) => match err.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(_) => Err(BuildError::GitCancelled),
+        },
+    }
 }
 
 /// Location of the source code for a package. This will be used as the input
@@ -113,6 +135,31 @@ pub struct SourceMetadata {
     pub records: Vec<SourceRecord>,
 }
 
+/// Resolves the variant configuration for the given platform, the values of
+/// the most specific target take precedence.
+pub(crate) fn resolve_variant_config(
+    variant_config: &Targets<Option<HashMap<String, Vec<String>>>>,
+    platform: Platform,
+) -> HashMap<String, Vec<String>> {
+    let mut result = HashMap::new();
+
+    // Resolves from most specific to least specific.
+    for variants in variant_config.resolve(Some(platform)).flatten() {
+        // Update the hash map, but only items that are not already in the map.
+        for (key, value) in variants {
+            result.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    tracing::info!(
+        "resolved variant configuration for {}: {:?}",
+        platform,
+        result
+    );
+
+    result
+}
+
 impl BuildContext {
     pub fn new(
         cache_dir: PathBuf,
@@ -141,14 +188,24 @@ impl BuildContext {
             .build_variants
             .clone();
 
-        Self::new(
+        Ok(Self::new(
             get_cache_dir()?,
             project.pixi_dir(),
             project.channel_config(),
             variant,
             Arc::new(ToolContext::default()),
         )
-        .into_diagnostic()
+        .into_diagnostic()?
+        .with_shared_build_cache(project.config().shared_build_cache().cloned()))
+    }
+
+    /// Sets the directory that is used to share builds of immutable sources
+    /// with other machines.
+    pub fn with_shared_build_cache(self, shared_build_cache: Option<PathBuf>) -> Self {
+        Self {
+            build_cache: self.build_cache.with_shared_dir(shared_build_cache),
+            ..self
+        }
     }
 
     pub fn with_tool_context(self, tool_context: Arc<ToolContext>) -> Self {
@@ -167,23 +224,7 @@ impl BuildContext {
     }
 
     fn resolve_variant(&self, platform: Platform) -> HashMap<String, Vec<String>> {
-        let mut result = HashMap::new();
-
-        // Resolves from most specific to least specific.
-        for variants in self.variant_config.resolve(Some(platform)).flatten() {
-            // Update the hash map, but only items that are not already in the map.
-            for (key, value) in variants {
-                result.entry(key.clone()).or_insert_with(|| value.clone());
-            }
-        }
-
-        tracing::info!(
-            "resolved variant configuration for {}: {:?}",
-            platform,
-            result
-        );
-
-        result
+        resolve_variant_config(&self.variant_config, platform)
     }
 
     /// Extracts the metadata for a package from the given source specification.
@@ -385,7 +426,15 @@ impl BuildContext {
     ) -> Result<SourceCheckout, BuildError> {
         match source_spec {
             SourceSpec::Url(_) => unimplemented!("fetching URL sources is not yet implemented"),
-            SourceSpec::Git(_) => unimplemented!("fetching Git sources is not yet implemented"),
+            SourceSpec::Git(git_spec) => {
+                let cache_dir = self.cache_dir.clone();
+                let git_spec = git_spec.clone();
+                let (path, pinned) = spawn_git(move || git::fetch(&cache_dir, &git_spec)).await?;
+                Ok(SourceCheckout {
+                    path,
+                    pinned: pinned.into(),
+                })
+            }
             SourceSpec::Path(path) => {
                 let source_path = self
                     .resolve_path(path.path.to_path())
@@ -413,8 +462,10 @@ impl BuildContext {
             PinnedSourceSpec::Url(_) => {
                 unimplemented!("fetching URL sources is not yet implemented")
             }
-            PinnedSourceSpec::Git(_) => {
-                unimplemented!("fetching Git sources is not yet implemented")
+            PinnedSourceSpec::Git(pinned) => {
+                let cache_dir = self.cache_dir.clone();
+                let pinned = pinned.clone();
+                spawn_git(move || git::checkout(&cache_dir, &pinned)).await
             }
             PinnedSourceSpec::Path(path) => self
                 .resolve_path(path.path.to_path())
@@ -1,12 +1,22 @@
+use std::io::IsTerminal;
+
 use clap::Parser;
+use dialoguer::theme::ColorfulTheme;
 use indexmap::IndexMap;
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use pixi_config::{Config, PinningStrategy};
 use pixi_manifest::FeatureName;
+use pixi_spec::PixiSpec;
+use rattler_conda_types::MatchSpec;
+use serde::Serialize;
 
 use super::has_specs::HasSpecs;
 use crate::{
     cli::cli_config::{DependencyConfig, PrefixUpdateConfig, ProjectConfig},
-    environment::verify_prefix_location_unchanged,
-    project::{DependencyType, Project},
+    diff::LockFileJsonDiff,
+    environment::{verify_prefix_location_unchanged, LockFileUsage},
+    project::{DependencyType, MatchSpecs, Project, UpdateDeps},
 };
 
 /// Adds dependencies to the project
@@ -62,6 +72,12 @@ use crate::{
 /// that are not following the semver versioning scheme but will use
 /// the minor version by default:
 /// Python, Rust, Julia, GCC, GXX, GFortran, NodeJS, Deno, R, R-Base, Perl
+///
+/// If the dependencies cannot be solved in an interactive terminal, pixi
+/// proposes to relax the version constraints of existing dependencies and
+/// shows the versions that would be picked:
+/// - `pixi add "numpy>=2"` with `python = "3.8.*"` in the manifest offers to
+///   replace the `python` constraint.
 #[derive(Parser, Debug, Default)]
 #[clap(arg_required_else_help = true, verbatim_doc_comment)]
 pub struct Args {
@@ -77,6 +93,53 @@ pub struct Args {
     /// Whether the pypi requirement should be editable
     #[arg(long, requires = "pypi")]
     pub editable: bool,
+
+    /// Print the added dependencies and the changes to the lock file as JSON
+    /// to stdout, instead of the human readable output
+    #[arg(long)]
+    pub json: bool,
+
+    /// The strategy used to derive a version constraint from the solved
+    /// version, for dependencies added without one. Overrides the
+    /// `pinning-strategy` of the config
+    #[arg(long, value_name = "STRATEGY")]
+    pub pinning_strategy: Option<PinningStrategy>,
+}
+
+impl Args {
+    /// Loads the project with the config from the command line applied.
+    fn load_project(&self) -> miette::Result<Project> {
+        Ok(
+            Project::load_or_else_discover(self.project_config.manifest_path.as_deref())?
+                .with_cli_config(self.prefix_update_config.config.clone())
+                .with_cli_config(Config {
+                    pinning_strategy: self.pinning_strategy,
+                    ..Config::default()
+                }),
+        )
+    }
+}
+
+/// The result of `pixi add --json`.
+#[derive(Serialize)]
+struct AddedJson {
+    /// The added dependencies as they were given on the command line.
+    added: Vec<AddedDependencyJson>,
+    /// The changes to the lock file.
+    lock_file_diff: LockFileJsonDiff,
+}
+
+#[derive(Serialize)]
+struct AddedDependencyJson {
+    spec: String,
+    /// The constraint that was written to the manifest, if the spec didn't
+    /// contain a version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    constraint: Option<String>,
+    /// The version(s) the package was solved to, comma separated if it
+    /// differs between environments or platforms.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
@@ -86,8 +149,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         &args.project_config,
     );
 
-    let mut project = Project::load_or_else_discover(project_config.manifest_path.as_deref())?
-        .with_cli_config(prefix_update_config.config.clone());
+    let mut project = args.load_project()?;
 
     // Sanity check of prefix location
     verify_prefix_location_unchanged(project.default_environment().dir().as_path()).await?;
@@ -120,9 +182,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     // TODO: add dry_run logic to add
     let dry_run = false;
 
-    let update_deps = project
+    let update_deps = match project
         .update_dependencies(
-            match_specs,
+            match_specs.clone(),
             pypi_deps,
             prefix_update_config,
             &args.dependency_config.feature,
@@ -130,13 +192,204 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             args.editable,
             dry_run,
         )
-        .await?;
+        .await
+    {
+        Ok(update_deps) => update_deps,
+        Err(err) => match relax_constraints_interactive(&args, match_specs).await? {
+            Some(update_deps) => Some(update_deps),
+            None => return Err(err),
+        },
+    };
 
     if let Some(update_deps) = update_deps {
-        // Notify the user we succeeded
-        dependency_config.display_success("Added", update_deps.implicit_constraints);
+        if args.json || pixi_progress::json_output() {
+            let UpdateDeps {
+                mut implicit_constraints,
+                solved_versions,
+                lock_file_diff,
+            } = update_deps;
+            let added = AddedJson {
+                added: dependency_config
+                    .specs
+                    .iter()
+                    .map(|spec| AddedDependencyJson {
+                        spec: spec.clone(),
+                        constraint: implicit_constraints.remove(spec),
+                        version: dependency_config
+                            .package_name(spec)
+                            .and_then(|name| solved_versions.get(&name).cloned()),
+                    })
+                    .collect(),
+                lock_file_diff: LockFileJsonDiff::new(&project, lock_file_diff),
+            };
+            pixi_progress::print_json(&added, true).into_diagnostic()?;
+        } else {
+            // Notify the user we succeeded
+            dependency_config.display_success(
+                "Added",
+                update_deps.implicit_constraints,
+                update_deps.solved_versions,
+            );
+        }
     }
 
     Project::warn_on_discovered_from_env(project_config.manifest_path.as_deref());
     Ok(())
 }
+
+/// Called when the added dependencies cannot be solved. Tries to solve the
+/// project again with the version constraint of one of the existing
+/// dependencies removed, and lets the user pick one of the relaxations that
+/// solve. The chosen relaxation is written to the manifest with a new
+/// constraint based on the solved version.
+///
+/// Returns `None` if not running in a terminal, if no relaxation solves the
+/// project or if the user did not choose one.
+async fn relax_constraints_interactive(
+    args: &Args,
+    match_specs: MatchSpecs,
+) -> miette::Result<Option<UpdateDeps>> {
+    let DependencyType::CondaDependency(spec_type) = args.dependency_config.dependency_type()
+    else {
+        return Ok(None);
+    };
+    if !std::io::stderr().is_terminal()
+        || pixi_progress::json_output()
+        || !args.dependency_config.platforms.is_empty()
+        || args.prefix_update_config.lock_file_usage() != LockFileUsage::Update
+    {
+        return Ok(None);
+    }
+
+    // The manifest is modified by every attempt, so the project is loaded
+    // again each time.
+    let load_project = || args.load_project();
+
+    // The existing dependencies of the feature that have a version constraint.
+    let project = load_project()?;
+    let channel_config = project.channel_config();
+    let candidates = project
+        .manifest
+        .feature(&args.dependency_config.feature)
+        .and_then(|feature| feature.dependencies(spec_type, None))
+        .into_iter()
+        .flat_map(|deps| deps.into_owned())
+        .filter(|(name, spec)| !match_specs.contains_key(name) && spec.has_version_spec())
+        .filter_map(|(name, spec)| {
+            let relaxed = match &spec {
+                PixiSpec::Version(_) => MatchSpec::from(name.clone()),
+                PixiSpec::DetailedVersion(detailed) => {
+                    let mut nameless_match_spec = detailed
+                        .clone()
+                        .try_into_nameless_match_spec(&channel_config)
+                        .ok()?;
+                    nameless_match_spec.version = None;
+                    MatchSpec::from_nameless(nameless_match_spec, Some(name.clone()))
+                }
+                _ => return None,
+            };
+            let current = spec.as_version_spec()?.to_string();
+            Some((name, current, relaxed))
+        })
+        .collect_vec();
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    eprintln!(
+        "{}The dependencies could not be solved, trying to relax the constraints of {} existing {}",
+        console::style(console::Emoji("⚠️ ", "")).yellow(),
+        candidates.len(),
+        if candidates.len() == 1 {
+            "dependency"
+        } else {
+            "dependencies"
+        }
+    );
+
+    let mut solutions = Vec::new();
+    for (name, current, relaxed) in candidates {
+        let mut match_specs = match_specs.clone();
+        match_specs.insert(name.clone(), (relaxed, spec_type));
+        let result = load_project()?
+            .update_dependencies(
+                match_specs.clone(),
+                IndexMap::default(),
+                &args.prefix_update_config,
+                &args.dependency_config.feature,
+                &[],
+                args.editable,
+                true,
+            )
+            .await;
+        match result {
+            Ok(Some(update_deps)) => solutions.push((name, current, match_specs, update_deps)),
+            Ok(None) => {}
+            Err(err) => tracing::debug!(
+                "relaxing '{}' does not solve the project: {err}",
+                name.as_source()
+            ),
+        }
+    }
+    if solutions.is_empty() {
+        return Ok(None);
+    }
+
+    // Show the constraints the packages would get after relaxing.
+    let items = solutions
+        .iter()
+        .map(|(name, current, _, update_deps)| {
+            let constraints = update_deps
+                .implicit_constraints
+                .iter()
+                .sorted()
+                .map(|(package, constraint)| format!("{package} {constraint}"))
+                .join(", ");
+            format!("relax {} {current} ({constraints})", name.as_source())
+        })
+        .collect_vec();
+    let theme = ColorfulTheme {
+        active_item_style: console::Style::new().for_stderr().magenta(),
+        ..ColorfulTheme::default()
+    };
+    let Some(idx) = dialoguer::Select::with_theme(&theme)
+        .with_prompt("Select the constraint to relax")
+        .report(false)
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .ok()
+        .flatten()
+    else {
+        return Ok(None);
+    };
+
+    let (name, current, match_specs, _) = solutions.swap_remove(idx);
+    let update_deps = load_project()?
+        .update_dependencies(
+            match_specs,
+            IndexMap::default(),
+            &args.prefix_update_config,
+            &args.dependency_config.feature,
+            &[],
+            args.editable,
+            false,
+        )
+        .await?;
+    if let Some(update_deps) = &update_deps {
+        eprintln!(
+            "{}Relaxed {} {} to {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            console::style(name.as_source()).bold(),
+            console::style(current).dim(),
+            console::style(
+                update_deps
+                    .implicit_constraints
+                    .get(name.as_source())
+                    .map_or("*", String::as_str)
+            )
+            .dim()
+        );
+    }
+    Ok(update_deps)
+}
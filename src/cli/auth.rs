@@ -0,0 +1,250 @@
+use std::{path::PathBuf, sync::Arc};
+
+use clap::{Parser, ValueEnum};
+use miette::Diagnostic;
+use pixi_config::Config;
+use rattler_networking::{
+    authentication_storage::backends::{file::FileStorage, keyring::KeyringAuthenticationStorage},
+    Authentication, AuthenticationStorage,
+};
+use thiserror::Error;
+
+/// Login to prefix.dev, anaconda.org, artifactory or any other host that
+/// requires authentication.
+///
+/// The credentials are picked up when downloading repodata and packages from
+/// the host.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Parser, Debug)]
+enum Command {
+    /// Store authentication information for a given host.
+    Login(LoginArgs),
+    /// Remove authentication information for a given host.
+    Logout(LogoutArgs),
+}
+
+#[derive(Parser, Debug)]
+struct LoginArgs {
+    /// The host to authenticate with (e.g. repo.prefix.dev)
+    host: String,
+
+    /// The token to use (for authentication with prefix.dev or artifactory)
+    #[arg(long)]
+    token: Option<String>,
+
+    /// The username to use (for basic HTTP authentication)
+    #[arg(long, requires = "password")]
+    username: Option<String>,
+
+    /// The password to use (for basic HTTP authentication)
+    #[arg(long, requires = "username")]
+    password: Option<String>,
+
+    /// The token to use on anaconda.org / quetz authentication
+    #[arg(long)]
+    conda_token: Option<String>,
+
+    #[clap(flatten)]
+    storage: StorageArgs,
+}
+
+#[derive(Parser, Debug)]
+struct LogoutArgs {
+    /// The host to remove the authentication for
+    host: String,
+
+    #[clap(flatten)]
+    storage: StorageArgs,
+}
+
+#[derive(Parser, Debug)]
+struct StorageArgs {
+    /// Where to store the credentials, by default the keyring is used and the
+    /// credentials file if no keyring is available
+    #[arg(long, value_enum, conflicts_with = "auth_file")]
+    backend: Option<Backend>,
+
+    /// Path to the file to store the credentials in, defaults to the
+    /// `authentication-override-file` of the configuration
+    #[arg(long, env = "RATTLER_AUTH_FILE")]
+    auth_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Backend {
+    /// The keyring of the operating system
+    Keyring,
+    /// The credentials file in `~/.rattler/credentials.json`
+    File,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub(crate) enum AuthenticationError {
+    #[error("no authentication method provided")]
+    #[diagnostic(help("use `--token`, `--conda-token` or `--username` and `--password`"))]
+    NoAuthenticationMethod,
+
+    #[error("multiple authentication methods provided")]
+    #[diagnostic(help(
+        "use only one of `--token`, `--conda-token` or `--username` and `--password`"
+    ))]
+    MultipleAuthenticationMethods,
+
+    #[error("prefix.dev only supports token authentication")]
+    #[diagnostic(help("use `--token` to authenticate with prefix.dev"))]
+    PrefixDevBadMethod,
+
+    #[error("anaconda.org only supports conda token authentication")]
+    #[diagnostic(help("use `--conda-token` to authenticate with anaconda.org"))]
+    AnacondaOrgBadMethod,
+
+    #[error("failed to parse the host '{0}'")]
+    InvalidHost(String, #[source] url::ParseError),
+
+    #[error("could not determine the home directory")]
+    NoHomeDirectory,
+
+    #[error("failed to open the credentials file")]
+    FileStorage(
+        #[from] rattler_networking::authentication_storage::backends::file::FileStorageError,
+    ),
+
+    #[error("failed to access the credentials: {0}")]
+    Storage(String),
+}
+
+/// Returns the host that credentials are stored for. Urls are reduced to
+/// their host, and a domain without a subdomain also matches all its
+/// subdomains.
+pub(crate) fn normalize_host(host: &str) -> Result<String, AuthenticationError> {
+    let host = if host.contains("://") {
+        let url = url::Url::parse(host)
+            .map_err(|e| AuthenticationError::InvalidHost(host.to_string(), e))?;
+        url.host_str()
+            .ok_or_else(|| {
+                AuthenticationError::InvalidHost(host.to_string(), url::ParseError::EmptyHost)
+            })?
+            .to_string()
+    } else {
+        host.trim_end_matches('/').to_string()
+    };
+
+    if host.matches('.').count() == 1 {
+        Ok(format!("*.{host}"))
+    } else {
+        Ok(host)
+    }
+}
+
+/// Determines the authentication from the arguments and validates that the
+/// host supports it.
+fn authentication(host: &str, args: &LoginArgs) -> Result<Authentication, AuthenticationError> {
+    let mut methods = Vec::new();
+    if let Some(token) = &args.token {
+        methods.push(Authentication::BearerToken(token.clone()));
+    }
+    if let Some(conda_token) = &args.conda_token {
+        methods.push(Authentication::CondaToken(conda_token.clone()));
+    }
+    if let (Some(username), Some(password)) = (&args.username, &args.password) {
+        methods.push(Authentication::BasicHTTP {
+            username: username.clone(),
+            password: password.clone(),
+        });
+    }
+
+    if methods.len() > 1 {
+        return Err(AuthenticationError::MultipleAuthenticationMethods);
+    }
+    let authentication = methods
+        .pop()
+        .ok_or(AuthenticationError::NoAuthenticationMethod)?;
+
+    if host.contains("prefix.dev") && !matches!(authentication, Authentication::BearerToken(_)) {
+        return Err(AuthenticationError::PrefixDevBadMethod);
+    }
+    if host.contains("anaconda.org") && !matches!(authentication, Authentication::CondaToken(_)) {
+        return Err(AuthenticationError::AnacondaOrgBadMethod);
+    }
+
+    Ok(authentication)
+}
+
+/// Returns the storage to store the credentials in. Without any arguments
+/// this is the same storage that is used when downloading packages.
+fn storage(args: StorageArgs) -> Result<AuthenticationStorage, AuthenticationError> {
+    let config = Config::load_global();
+    let auth_file = args
+        .auth_file
+        .or_else(|| config.authentication_override_file().cloned());
+
+    let mut storage = AuthenticationStorage::new();
+    match (auth_file, args.backend) {
+        (Some(auth_file), _) => storage.add_backend(Arc::from(FileStorage::new(auth_file)?)),
+        (None, Some(Backend::Keyring)) => {
+            storage.add_backend(Arc::from(KeyringAuthenticationStorage::default()))
+        }
+        (None, Some(Backend::File)) => {
+            let path = dirs::home_dir()
+                .ok_or(AuthenticationError::NoHomeDirectory)?
+                .join(".rattler")
+                .join("credentials.json");
+            storage.add_backend(Arc::from(FileStorage::new(path)?))
+        }
+        (None, None) => return Ok(AuthenticationStorage::default()),
+    }
+    Ok(storage)
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    match args.command {
+        Command::Login(args) => {
+            let host = normalize_host(&args.host)?;
+            let authentication = authentication(&host, &args)?;
+            storage(args.storage)?
+                .store(&host, &authentication)
+                .map_err(|e| AuthenticationError::Storage(e.to_string()))?;
+            eprintln!(
+                "{}Stored the credentials for {}",
+                console::style(console::Emoji("✔ ", "")).green(),
+                console::style(&host).bold()
+            );
+        }
+        Command::Logout(args) => {
+            let host = normalize_host(&args.host)?;
+            storage(args.storage)?
+                .delete(&host)
+                .map_err(|e| AuthenticationError::Storage(e.to_string()))?;
+            eprintln!(
+                "{}Removed the credentials for {}",
+                console::style(console::Emoji("✔ ", "")).green(),
+                console::style(&host).bold()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(normalize_host("prefix.dev").unwrap(), "*.prefix.dev");
+        assert_eq!(
+            normalize_host("repo.prefix.dev").unwrap(),
+            "repo.prefix.dev"
+        );
+        assert_eq!(
+            normalize_host("https://myquetz.server.com/channels/foo").unwrap(),
+            "myquetz.server.com"
+        );
+        assert!(normalize_host("https://").is_err());
+    }
+}
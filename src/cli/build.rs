@@ -1,4 +1,9 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use clap::{ArgAction, Parser};
 use indicatif::ProgressBar;
@@ -12,6 +17,7 @@ use pixi_manifest::FeaturesExt;
 use rattler_conda_types::{GenericVirtualPackage, Platform};
 
 use crate::{
+    build::resolve_variant_config,
     cli::cli_config::ProjectConfig,
     repodata::Repodata,
     utils::{move_file, MoveError},
@@ -38,6 +44,57 @@ pub struct Args {
     /// Use system backend installed tool
     #[arg(long, action = ArgAction::SetTrue)]
     pub with_system: bool,
+
+    /// A conda-forge style variant configuration file, e.g. `variants.yaml`.
+    ///
+    /// Every key maps to the list of values to build, the package is built for
+    /// every combination of the values that it uses. The keys in the file take
+    /// precedence over the `build-variants` of the manifest.
+    #[arg(long, value_name = "FILE")]
+    pub variants: Option<PathBuf>,
+}
+
+/// Reads a conda-forge style variant configuration file.
+///
+/// Only keys with a list of values or a single value are supported, other keys
+/// like `zip_keys` are ignored with a warning.
+fn read_variant_config(path: &Path) -> miette::Result<HashMap<String, Vec<String>>> {
+    let contents = fs_err::read_to_string(path).into_diagnostic()?;
+    let config: serde_yaml::Mapping = serde_yaml::from_str(&contents)
+        .into_diagnostic()
+        .with_context(|| format!("failed to parse the variants from '{}'", path.display()))?;
+
+    let mut variants = HashMap::new();
+    for (key, value) in config {
+        let Some(key) = key.as_str() else {
+            miette::bail!("the keys in '{}' must be strings", path.display());
+        };
+        let values = match value {
+            serde_yaml::Value::Sequence(values) => values.iter().map(variant_value).collect(),
+            value => vec![variant_value(&value)],
+        };
+        match values.into_iter().collect::<Option<Vec<_>>>() {
+            Some(values) => {
+                variants.insert(key.to_string(), values);
+            }
+            None => tracing::warn!(
+                "ignoring the '{}' key in '{}', only lists of values are supported",
+                key,
+                path.display()
+            ),
+        }
+    }
+    Ok(variants)
+}
+
+/// Converts a scalar of the variant configuration to a string.
+fn variant_value(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
 }
 
 struct ProgressReporter {
@@ -135,6 +192,15 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .map(GenericVirtualPackage::from)
         .collect();
 
+    // The variants of the manifest, extended with the variants from the file.
+    let mut variant_configuration = resolve_variant_config(
+        &project.manifest().workspace.workspace.build_variants,
+        args.target_platform,
+    );
+    if let Some(path) = &args.variants {
+        variant_configuration.extend(read_variant_config(path)?);
+    }
+
     // Host platform virtual packages
     let host_platform_virtual_packages: Vec<GenericVirtualPackage> = project
         .default_environment()
@@ -167,7 +233,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 outputs: None,
                 editable: false,
                 work_directory: work_dir.path().to_path_buf(),
-                variant_configuration: Some(Default::default()),
+                variant_configuration: Some(variant_configuration),
             },
             progress.clone(),
         )
@@ -232,3 +298,31 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_variant_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("variants.yaml");
+        fs_err::write(
+            &path,
+            r#"
+python:
+  - "3.11"
+  - 3.12
+blas_impl: openblas
+zip_keys:
+  - [python, numpy]
+"#,
+        )
+        .unwrap();
+
+        let variants = read_variant_config(&path).unwrap();
+        assert_eq!(variants["python"], vec!["3.11", "3.12"]);
+        assert_eq!(variants["blas_impl"], vec!["openblas"]);
+        assert!(!variants.contains_key("zip_keys"));
+    }
+}
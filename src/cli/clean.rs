@@ -1,16 +1,18 @@
+use crate::environment::read_environment_file;
+use crate::project::history::HistoryEntry;
 use crate::Project;
 /// Command to clean the parts of your system which are touched by pixi.
-use pixi_config;
+use pixi_config::{self, Config};
 use pixi_consts::consts;
 use pixi_manifest::EnvironmentName;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::cli::cli_config::ProjectConfig;
 use clap::Parser;
 use fancy_display::FancyDisplay;
 use fs_err::tokio as tokio_fs;
-use indicatif::ProgressBar;
+use indicatif::{HumanBytes, ProgressBar};
 use miette::IntoDiagnostic;
 use pixi_progress::{global_multi_progress, long_running_progress_style};
 use std::str::FromStr;
@@ -102,6 +104,28 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 })
                 .transpose()?;
 
+            let pixi_dir = project.pixi_dir();
+            let environments_dir = project.environments_dir();
+            // Contains the environments and the solve group environments.
+            let environments_root = environments_dir
+                .parent()
+                .unwrap_or(&environments_dir)
+                .to_path_buf();
+            // Remember which environments are installed, to record their removal
+            // in the history afterwards.
+            let installed_environments = match &explicit_environment {
+                Some(explicit_env) if !args.activation_cache => vec![explicit_env.clone()],
+                Some(_) => Vec::new(),
+                None => project.environments(),
+            }
+            .into_iter()
+            .filter_map(|env| {
+                let hash = read_environment_file(&env.dir()).ok().flatten()?;
+                Some((env, hash.environment_lock_file_hash))
+            })
+            .collect::<Vec<_>>();
+
+            let mut removed = 0;
             if let Some(explicit_env) = explicit_environment {
                 if args.activation_cache {
                     remove_file(explicit_env.activation_cache_file_path(), false).await?;
@@ -110,26 +134,66 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                         explicit_env.name().fancy_display()
                     );
                 } else {
-                    remove_folder_with_progress(explicit_env.dir(), true).await?;
+                    removed +=
+                        remove_folder_with_progress(explicit_env.dir(), &environments_root, true)
+                            .await?;
                     remove_file(explicit_env.activation_cache_file_path(), false).await?;
                     tracing::info!("Skipping removal of task cache and solve group environments for explicit environment '{}'", explicit_env.name().fancy_display());
                 }
             } else {
                 // Remove all pixi related work from the project.
-                if !project.environments_dir().starts_with(project.pixi_dir())
+                if !environments_dir.starts_with(&pixi_dir)
                     && project.default_environments_dir().exists()
                 {
-                    remove_folder_with_progress(project.default_environments_dir(), false).await?;
-                    remove_folder_with_progress(
+                    removed += remove_folder_with_progress(
+                        project.default_environments_dir(),
+                        &pixi_dir,
+                        false,
+                    )
+                    .await?;
+                    removed += remove_folder_with_progress(
                         project.default_solve_group_environments_dir(),
+                        &pixi_dir,
                         false,
                     )
                     .await?;
                 }
-                remove_folder_with_progress(project.environments_dir(), true).await?;
-                remove_folder_with_progress(project.solve_group_environments_dir(), false).await?;
-                remove_folder_with_progress(project.task_cache_folder(), false).await?;
-                remove_folder_with_progress(project.activation_env_cache_folder(), false).await?;
+                removed +=
+                    remove_folder_with_progress(environments_dir.clone(), &environments_root, true)
+                        .await?;
+                removed += remove_folder_with_progress(
+                    project.solve_group_environments_dir(),
+                    &environments_root,
+                    false,
+                )
+                .await?;
+                let task_cache_root = project
+                    .config()
+                    .task_cache_dir()
+                    .map_or_else(|| pixi_dir.clone(), Path::to_path_buf);
+                removed += remove_folder_with_progress(
+                    project.task_cache_folder(),
+                    &task_cache_root,
+                    false,
+                )
+                .await?;
+                removed += remove_folder_with_progress(
+                    project.activation_env_cache_folder(),
+                    &pixi_dir,
+                    false,
+                )
+                .await?;
+            }
+            print_total_removed(removed);
+
+            for (env, hash) in installed_environments {
+                if !env.dir().exists() {
+                    project.record_history(&HistoryEntry::environment(
+                        env.name().as_str(),
+                        Some(hash.as_str().to_string()),
+                        None,
+                    ));
+                }
             }
 
             Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref())
@@ -141,16 +205,17 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 /// Clean the pixi cache folders.
 async fn clean_cache(args: CacheArgs) -> miette::Result<()> {
     let cache_dir = pixi_config::get_cache_dir()?;
+    let config = Config::load_global();
     let mut dirs = vec![];
 
     if args.pypi {
-        dirs.push(cache_dir.join(consts::PYPI_CACHE_DIR));
+        dirs.push(config.pypi_cache_dir()?);
     }
     if args.conda {
-        dirs.push(cache_dir.join(consts::CONDA_PACKAGE_CACHE_DIR));
+        dirs.push(config.package_cache_dir()?);
     }
     if args.repodata {
-        dirs.push(cache_dir.join(consts::CONDA_REPODATA_CACHE_DIR));
+        dirs.push(config.repodata_cache_dir()?);
     }
     if args.mapping {
         dirs.push(cache_dir.join(consts::CONDA_PYPI_MAPPING_CACHE_DIR));
@@ -170,7 +235,7 @@ async fn clean_cache(args: CacheArgs) -> miette::Result<()> {
                 .into_diagnostic()?
                 .unwrap_or(false))
             {
-                dirs.push(cache_dir);
+                dirs.push(cache_dir.clone());
             }
 
     if dirs.is_empty() {
@@ -178,16 +243,91 @@ async fn clean_cache(args: CacheArgs) -> miette::Result<()> {
         return Ok(());
     }
 
+    let mut removed = 0;
     for dir in dirs {
-        remove_folder_with_progress(dir, true).await?;
+        // The caches that are configured in `cache-dirs` can be located outside
+        // of the cache directory.
+        let root = if dir.starts_with(&cache_dir) {
+            cache_dir.clone()
+        } else {
+            dir.clone()
+        };
+        removed += remove_folder_with_progress(dir, &root, true).await?;
     }
+    print_total_removed(removed);
     Ok(())
 }
 
+/// Prints the total size of the removed folders.
+fn print_total_removed(removed: u64) {
+    if removed > 0 {
+        eprintln!(
+            "{}Freed {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            HumanBytes(removed)
+        );
+    }
+}
+
+/// Returns an error if the folder is not located in the directory that pixi
+/// manages, or if the folder contains the home directory. This protects
+/// against removing unrelated files when e.g. the cache directory is
+/// misconfigured.
+fn verify_safe_to_remove(folder: &Path, root: &Path) -> miette::Result<()> {
+    let canonicalize =
+        |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    // Only the parent is resolved, a symlink is removed and not its target.
+    let folder = match (folder.parent(), folder.file_name()) {
+        (Some(parent), Some(name)) => canonicalize(parent).join(name),
+        _ => folder.to_path_buf(),
+    };
+    let root = canonicalize(root);
+
+    let contains_home =
+        dirs::home_dir().is_some_and(|home| canonicalize(&home).starts_with(&folder));
+    if folder.parent().is_none() || contains_home || !folder.starts_with(&root) {
+        miette::bail!(
+            help = "check the configuration of the pixi directories, e.g. `PIXI_CACHE_DIR` or `detached-environments`",
+            "refusing to remove '{}', it is not a directory managed by pixi",
+            folder.display()
+        );
+    }
+    Ok(())
+}
+
+/// Returns the total size of the files in the folder, symlinks are not
+/// followed.
+fn folder_size(folder: &Path) -> u64 {
+    if folder.is_symlink() {
+        return 0;
+    }
+    let mut size = 0;
+    let mut stack = vec![folder.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs_err::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(metadata) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else {
+                size += metadata.len();
+            }
+        }
+    }
+    size
+}
+
+/// Removes the folder, which has to be located in `root`, and returns the size
+/// of the removed files.
 async fn remove_folder_with_progress(
     folder: PathBuf,
+    root: &Path,
     warning_non_existent: bool,
-) -> miette::Result<()> {
+) -> miette::Result<u64> {
     if !folder.exists() {
         if warning_non_existent {
             eprintln!(
@@ -195,8 +335,10 @@ async fn remove_folder_with_progress(
                 console::style(format!("Folder {:?} was already clean.", &folder)).yellow()
             );
         }
-        return Ok(());
+        return Ok(0);
     }
+    verify_safe_to_remove(&folder, root)?;
+    let size = folder_size(&folder);
     let pb = global_multi_progress().add(ProgressBar::new_spinner());
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_style(long_running_progress_style());
@@ -213,11 +355,12 @@ async fn remove_folder_with_progress(
     }
 
     pb.finish_with_message(format!(
-        "{} {}",
+        "{} {} ({})",
         console::style("removed").green(),
-        folder.display()
+        folder.display(),
+        HumanBytes(size)
     ));
-    Ok(())
+    Ok(size)
 }
 
 async fn remove_file(file: PathBuf, warning_non_existent: bool) -> miette::Result<()> {
@@ -240,3 +383,26 @@ async fn remove_file(file: PathBuf, warning_non_existent: bool) -> miette::Resul
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_safe_to_remove() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join(".pixi");
+        let envs = root.join("envs");
+        fs_err::create_dir_all(&envs).unwrap();
+        fs_err::write(envs.join("file"), "12345").unwrap();
+
+        assert!(verify_safe_to_remove(&envs, &root).is_ok());
+        assert!(verify_safe_to_remove(&root, &root).is_ok());
+        assert!(verify_safe_to_remove(temp_dir.path(), &root).is_err());
+        if let Some(home) = dirs::home_dir() {
+            assert!(verify_safe_to_remove(&home, &home).is_err());
+        }
+
+        assert_eq!(folder_size(&root), 5);
+    }
+}
@@ -3,7 +3,7 @@ use crate::environment::LockFileUsage;
 use crate::lock_file::UpdateMode;
 use crate::DependencyType;
 use crate::Project;
-use clap::Parser;
+use clap::{Args, FromArgMatches, Parser};
 use indexmap::IndexSet;
 use itertools::Itertools;
 use miette::IntoDiagnostic;
@@ -11,19 +11,75 @@ use pixi_config::{Config, ConfigCli};
 use pixi_consts::consts;
 use pixi_manifest::FeaturesExt;
 use pixi_manifest::{FeatureName, SpecType};
-use rattler_conda_types::ChannelConfig;
 use rattler_conda_types::{Channel, NamedChannelOrUrl, Platform};
+use rattler_conda_types::{ChannelConfig, MatchSpec, ParseStrictness};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Project configuration
-#[derive(Parser, Debug, Default, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct ProjectConfig {
-    /// The path to `pixi.toml` or `pyproject.toml`
-    #[arg(long, global = true)]
+    /// The path to `pixi.toml`, `pyproject.toml`, or the project directory,
+    /// either from `--manifest-path` or `--project-root`.
     pub manifest_path: Option<PathBuf>,
 }
 
+/// The arguments that select the project, see [`ProjectConfig`].
+#[derive(Args, Debug)]
+struct ProjectConfigArgs {
+    /// The path to `pixi.toml`, `pyproject.toml`, or the project directory.
+    /// Overrides the discovery of the nearest manifest.
+    #[arg(long, global = true)]
+    manifest_path: Option<PathBuf>,
+
+    /// The root directory of the project. Overrides the discovery of the
+    /// nearest manifest, the manifest in this directory is used.
+    #[arg(long, global = true, conflicts_with = "manifest_path", value_parser = parse_project_root)]
+    project_root: Option<PathBuf>,
+}
+
+/// Parses the value of `--project-root`, which has to be a directory.
+fn parse_project_root(value: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+    if path.is_dir() {
+        Ok(path)
+    } else {
+        Err(format!("'{value}' is not a directory"))
+    }
+}
+
+impl From<ProjectConfigArgs> for ProjectConfig {
+    fn from(args: ProjectConfigArgs) -> Self {
+        Self {
+            manifest_path: args.project_root.or(args.manifest_path),
+        }
+    }
+}
+
+impl FromArgMatches for ProjectConfig {
+    fn from_arg_matches(matches: &clap::ArgMatches) -> Result<Self, clap::Error> {
+        ProjectConfigArgs::from_arg_matches(matches).map(Into::into)
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &clap::ArgMatches) -> Result<(), clap::Error> {
+        let args = ProjectConfigArgs::from_arg_matches(matches)?;
+        if let Some(path) = args.project_root.or(args.manifest_path) {
+            self.manifest_path = Some(path);
+        }
+        Ok(())
+    }
+}
+
+impl Args for ProjectConfig {
+    fn augment_args(cmd: clap::Command) -> clap::Command {
+        ProjectConfigArgs::augment_args(cmd)
+    }
+
+    fn augment_args_for_update(cmd: clap::Command) -> clap::Command {
+        ProjectConfigArgs::augment_args_for_update(cmd)
+    }
+}
+
 /// Channel configuration
 #[derive(Parser, Debug, Default)]
 pub struct ChannelsConfig {
@@ -172,20 +228,43 @@ impl DependencyConfig {
         }
     }
 
+    /// Returns the normalized package name of one of the specs.
+    pub(crate) fn package_name(&self, spec: &str) -> Option<String> {
+        if self.pypi {
+            spec.parse::<pep508_rs::Requirement>()
+                .ok()
+                .map(|requirement| requirement.name.to_string())
+        } else {
+            MatchSpec::from_str(spec, ParseStrictness::Lenient)
+                .ok()
+                .and_then(|spec| spec.name)
+                .map(|name| name.as_normalized().to_string())
+        }
+    }
+
     pub(crate) fn display_success(
         &self,
         operation: &str,
         implicit_constraints: HashMap<String, String>,
+        solved_versions: HashMap<String, String>,
     ) {
         for package in self.specs.clone() {
+            let solved_version = self
+                .package_name(&package)
+                .and_then(|name| solved_versions.get(&name));
             eprintln!(
-                "{}{operation} {}{}",
+                "{}{operation} {}{}{}",
                 console::style(console::Emoji("✔ ", "")).green(),
                 console::style(&package).bold(),
                 if let Some(constraint) = implicit_constraints.get(&package) {
                     format!(" {}", console::style(constraint).dim())
                 } else {
                     "".to_string()
+                },
+                if let Some(version) = solved_version {
+                    format!(", solved to {}", console::style(version).bold())
+                } else {
+                    "".to_string()
                 }
             );
         }
@@ -1,8 +1,15 @@
+use crate::cli::cli_config::ChannelsConfig;
 use crate::cli::Args as CommandArgs;
+use crate::Project;
 use clap::{CommandFactory, Parser, ValueEnum};
 use clap_complete::{shells, Generator};
 use clap_complete_nushell::Nushell;
+use itertools::Itertools;
 use miette::IntoDiagnostic;
+use pixi_config::Config;
+use pixi_utils::reqwest::build_reqwest_clients;
+use rattler_conda_types::Platform;
+use rattler_repodata_gateway::{fetch::CacheAction, Gateway};
 use regex::Regex;
 use std::borrow::Cow;
 use std::io::Write;
@@ -11,8 +18,14 @@ use std::io::Write;
 #[derive(Parser, Debug)]
 pub struct Args {
     /// The shell to generate a completion script for
-    #[arg(short, long)]
+    #[arg(short, long, required = true)]
     shell: Shell,
+
+    /// Print the names of the packages in the cached repodata of the channels
+    /// of the project that start with the given prefix, instead of the
+    /// completion script. Used by the completion scripts to complete `pixi add`.
+    #[arg(long, hide = true, value_name = "PREFIX")]
+    package_names: Option<String>,
 }
 
 /// Defines the shells for which we can provide completions
@@ -58,12 +71,19 @@ impl Generator for Shell {
 }
 
 /// Generate completions for the pixi cli, and print those to the stdout
-pub(crate) fn execute(args: Args) -> miette::Result<()> {
+pub(crate) async fn execute(args: Args) -> miette::Result<()> {
+    if let Some(prefix) = args.package_names {
+        let names = cached_package_names(&prefix).await;
+        println!("{}", names.join("\n"));
+        return Ok(());
+    }
+    let shell = args.shell;
+
     // Generate the original completion script.
-    let script = get_completion_script(args.shell);
+    let script = get_completion_script(shell);
 
     // For supported shells, modify the script to include more context sensitive completions.
-    let script = match args.shell {
+    let script = match shell {
         Shell::Bash => replace_bash_completion(&script),
         Shell::Zsh => replace_zsh_completion(&script),
         Shell::Fish => replace_fish_completion(&script),
@@ -79,6 +99,55 @@ pub(crate) fn execute(args: Args) -> miette::Result<()> {
     Ok(())
 }
 
+/// Returns the names of the packages that start with the given prefix, from the
+/// repodata of the channels of the project that is already cached. The
+/// repodata is never fetched, completions have to be fast and work offline.
+async fn cached_package_names(prefix: &str) -> Vec<String> {
+    let project = Project::load_or_else_discover(None).ok();
+    let config = project
+        .as_ref()
+        .map_or_else(Config::load_global, |project| project.config().clone());
+    let Ok(channels) = ChannelsConfig::default().resolve_from_project(project.as_ref()) else {
+        return Vec::new();
+    };
+
+    let mut channel_config = rattler_repodata_gateway::ChannelConfig::from(&config);
+    channel_config.default.cache_action = CacheAction::ForceCacheOnly;
+    for source_config in channel_config.per_channel.values_mut() {
+        source_config.cache_action = CacheAction::ForceCacheOnly;
+    }
+    let Ok(cache_dir) = config.repodata_cache_dir() else {
+        return Vec::new();
+    };
+    let client = project
+        .as_ref()
+        .map(|project| project.authenticated_client().clone())
+        .unwrap_or_else(|| build_reqwest_clients(Some(&config)).1);
+    let gateway = Gateway::builder()
+        .with_client(client)
+        .with_cache_dir(cache_dir)
+        .with_channel_config(channel_config)
+        .finish();
+
+    let names = match gateway
+        .names(channels, [Platform::current(), Platform::NoArch])
+        .await
+    {
+        Ok(names) => names,
+        Err(err) => {
+            tracing::debug!("failed to load the cached package names: {err}");
+            return Vec::new();
+        }
+    };
+    names
+        .into_iter()
+        .map(|name| name.as_normalized().to_string())
+        .filter(|name| name.starts_with(prefix))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
 /// Generate the completion script using clap_complete for a specified shell.
 fn get_completion_script(shell: Shell) -> String {
     let mut buf = vec![];
@@ -104,7 +173,22 @@ fn replace_bash_completion(script: &str) -> Cow<str> {
                fi
             fi"#;
     let re = Regex::new(pattern).unwrap();
-    re.replace(script, replacement)
+    let script = re.replace(script, replacement);
+
+    // Adds tab completion of the package names to the pixi add command.
+    // NOTE THIS IS FORMATTED BY HAND
+    let pattern = r#"(?s)pixi__add\).*?opts="(.*?)".*?(if.*?fi)"#;
+    let replacement = r#"pixi__add)
+            opts="$1"
+            if [[ $${cur} == -* ]] ; then
+               COMPREPLY=( $$(compgen -W "$${opts}" -- "$${cur}") )
+               return 0
+            elif [[ $${prev} != -* ]]; then
+               COMPREPLY=( $$(pixi completion --shell bash --package-names "$${cur}" 2> /dev/null) )
+               return 0
+            fi"#;
+    let re = Regex::new(pattern).unwrap();
+    Cow::Owned(re.replace(&script, replacement).into_owned())
 }
 
 /// Replace the parts of the zsh completion script that need different functionality.
@@ -124,13 +208,22 @@ fi
 $2::task"#;
 
     let re = Regex::new(pattern).unwrap();
-    re.replace(script, replacement)
+    let script = re.replace(script, replacement);
+
+    // Adds tab completion of the package names to the pixi add command.
+    // NOTE THIS IS FORMATTED BY HAND
+    let pattern = r"(?ms)(\(add\)\n_arguments.*?'\*::specs -- [^\n]*?):'";
+    let replacement = r#"$1:{compadd -- $$(pixi completion --shell zsh --package-names "$$PREFIX" 2> /dev/null)}'"#;
+    let re = Regex::new(pattern).unwrap();
+    Cow::Owned(re.replace(&script, replacement).into_owned())
 }
 
 fn replace_fish_completion(script: &str) -> Cow<str> {
     // Adds tab completion to the pixi run command.
     let addition = "complete -c pixi -n \"__fish_seen_subcommand_from run\" -f -a \"(string split ' ' (pixi task list --machine-readable  2> /dev/null))\"";
-    let new_script = format!("{}{}\n", script, addition);
+    // Adds tab completion of the package names to the pixi add command.
+    let add_addition = "complete -c pixi -n \"__fish_seen_subcommand_from add; or __fish_seen_subcommand_from a\" -f -a \"(pixi completion --shell fish --package-names (commandline -ct) 2> /dev/null)\"";
+    let new_script = format!("{}{}\n{}\n", script, addition, add_addition);
     let pattern = r#"-n "__fish_seen_subcommand_from run""#;
     let replacement = r#"-n "__fish_seen_subcommand_from run; or __fish_seen_subcommand_from r""#;
     let re = Regex::new(pattern).unwrap();
@@ -283,6 +376,24 @@ _arguments "${_arguments_options[@]}" \
         assert_ne!(replace_bash_completion(&script), script);
     }
 
+    #[test]
+    pub(crate) fn test_bash_package_name_completion() {
+        let script = get_completion_script(Shell::Bash);
+        let replaced_script = replace_bash_completion(&script);
+        assert!(
+            replaced_script.contains(r#"pixi completion --shell bash --package-names "${cur}""#)
+        );
+    }
+
+    #[test]
+    pub(crate) fn test_zsh_package_name_completion() {
+        let script = get_completion_script(Shell::Zsh);
+        let replaced_script = replace_zsh_completion(&script);
+        assert!(
+            replaced_script.contains(r#"pixi completion --shell zsh --package-names "$PREFIX""#)
+        );
+    }
+
     #[test]
     pub(crate) fn test_zsh_completion_working_regex() {
         // Generate the original completion script.
@@ -170,6 +170,11 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 partial_config(&mut config, &key)?;
             }
 
+            if pixi_progress::json_output() {
+                pixi_progress::emit_json_result(&config).into_diagnostic()?;
+                return Ok(());
+            }
+
             let out = if args.json {
                 serde_json::to_string_pretty(&config).into_diagnostic()?
             } else {
@@ -234,24 +239,26 @@ fn determine_config_write_path(common_args: &CommonArgs) -> miette::Result<PathB
             }
         }
 
-        let mut global_locations = pixi_config::config_path_global();
-        let mut to = global_locations
-            .pop()
-            .expect("should have at least one global config path");
-
-        for p in global_locations {
-            if p.exists() {
-                to = p;
-                break;
-            }
-        }
-
-        to
+        global_config_write_path()
     };
 
     Ok(write_path)
 }
 
+/// Returns the path of the global config file that is written to, the first
+/// global config file that exists or the last location otherwise.
+pub(crate) fn global_config_write_path() -> PathBuf {
+    let mut global_locations = pixi_config::config_path_global();
+    let to = global_locations
+        .pop()
+        .expect("should have at least one global config path");
+
+    global_locations
+        .into_iter()
+        .find(|p| p.exists())
+        .unwrap_or(to)
+}
+
 fn alter_config(
     common_args: &CommonArgs,
     key: &str,
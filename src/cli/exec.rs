@@ -1,10 +1,13 @@
-use std::{path::Path, str::FromStr};
+use std::{path::Path, str::FromStr, time::SystemTime};
 
 use clap::{Parser, ValueHint};
 use miette::{Context, IntoDiagnostic};
 use pixi_config::{self, Config, ConfigCli};
 use pixi_progress::{await_in_progress, global_multi_progress, wrap_in_progress};
-use pixi_utils::{reqwest::build_reqwest_clients, EnvironmentHash, PrefixGuard};
+use pixi_utils::{
+    mark_prefix_used, remove_unused_prefixes, reqwest::build_reqwest_clients, EnvironmentHash,
+    PrefixGuard, UNUSED_PREFIX_MAX_AGE,
+};
 use rattler::{
     install::{IndicatifReporter, Installer},
     package_cache::PackageCache,
@@ -15,7 +18,7 @@ use rattler_virtual_packages::{VirtualPackage, VirtualPackageOverrides};
 use reqwest_middleware::ClientWithMiddleware;
 
 use super::cli_config::ChannelsConfig;
-use crate::prefix::Prefix;
+use crate::{lock_file::SolveError, prefix::Prefix};
 
 /// Run a command in a temporary environment.
 #[derive(Parser, Debug)]
@@ -55,6 +58,13 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     let command = command_args.next().ok_or_else(|| miette::miette!(help ="i.e when specifying specs explicitly use a command at the end: `pixi exec -s python==3.12 python`", "missing required command to execute",))?;
     let (_, client) = build_reqwest_clients(Some(&config));
 
+    // Clean up the environments that haven't been used for a while.
+    remove_unused_prefixes(
+        &cache_dir.join(pixi_consts::consts::CACHED_ENVS_DIR),
+        UNUSED_PREFIX_MAX_AGE,
+        SystemTime::now(),
+    );
+
     // Create the environment to run the command in.
     let prefix = create_exec_prefix(&args, &cache_dir, &config, &client).await?;
 
@@ -116,6 +126,7 @@ pub async fn create_exec_prefix(
             prefix.root().display()
         );
         let _ = write_guard.finish();
+        mark_prefix_used(prefix.root());
         return Ok(prefix);
     }
 
@@ -181,7 +192,7 @@ pub async fn create_exec_prefix(
             ..SolverTask::from_iter(&repodata)
         })
     })
-    .into_diagnostic()
+    .map_err(SolveError::new)
     .context("failed to solve environment")?;
 
     // Install the environment
@@ -194,15 +205,14 @@ pub async fn create_exec_prefix(
                 .clear_when_done(true)
                 .finish(),
         )
-        .with_package_cache(PackageCache::new(
-            cache_dir.join(pixi_consts::consts::CONDA_PACKAGE_CACHE_DIR),
-        ))
+        .with_package_cache(PackageCache::new(config.package_cache_dir()?))
         .install(prefix.root(), solved_records)
         .await
         .into_diagnostic()
         .context("failed to create environment")?;
 
     let _ = write_guard.finish();
+    mark_prefix_used(prefix.root());
     Ok(prefix)
 }
 
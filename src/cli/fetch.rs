@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use futures::{StreamExt, TryStreamExt};
+use indicatif::ProgressBar;
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use pixi_config::ConfigCli;
+use rattler::package_cache::PackageCache;
+use rattler_conda_types::RepoDataRecord;
+use rattler_lock::{CondaPackageData, LockedPackageRef};
+
+use crate::{
+    cli::cli_config::ProjectConfig, resumable_download::get_or_fetch_resumable, Project,
+    UpdateLockFileOptions,
+};
+
+/// Download the packages of the lock file into the cache without installing
+/// them.
+///
+/// This makes it possible to prepare a machine before going offline, or to
+/// warm the cache in a separate docker layer.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    #[clap(flatten)]
+    pub lock_file_usage: super::LockFileUsageArgs,
+
+    /// The environment to fetch, defaults to all environments.
+    #[arg(long, short)]
+    pub environment: Option<Vec<String>>,
+
+    /// Fetch the packages for all platforms of the project instead of only the
+    /// current platform.
+    #[arg(long)]
+    pub all_platforms: bool,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+        .with_cli_config(args.config);
+
+    let environments = match args.environment {
+        Some(names) => names
+            .into_iter()
+            .map(|name| project.environment_from_name_or_env_var(Some(name)))
+            .collect::<miette::Result<Vec<_>>>()?,
+        None => project.environments(),
+    };
+
+    // Make sure the lock-file is up-to-date, without installing anything.
+    let lock_file = project
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_usage.into(),
+            no_install: true,
+            max_concurrent_solves: project.config().max_concurrent_solves(),
+        })
+        .await?
+        .lock_file;
+
+    // Collect the unique conda packages of the selected environments.
+    let mut seen = HashSet::new();
+    let mut records = Vec::new();
+    let mut skipped_pypi_packages = false;
+    for environment in &environments {
+        let Some(locked_environment) = lock_file.environment(environment.name().as_str()) else {
+            continue;
+        };
+        let platforms = if args.all_platforms {
+            locked_environment.platforms().collect_vec()
+        } else {
+            vec![environment.best_platform()]
+        };
+        for platform in platforms {
+            for package in locked_environment.packages(platform).into_iter().flatten() {
+                match package {
+                    LockedPackageRef::Conda(CondaPackageData::Binary(data)) => {
+                        if seen.insert(data.location.to_string()) {
+                            records.push(RepoDataRecord::try_from(data.clone()).into_diagnostic()?);
+                        }
+                    }
+                    LockedPackageRef::Conda(CondaPackageData::Source(_)) => {}
+                    LockedPackageRef::Pypi(..) => skipped_pypi_packages = true,
+                }
+            }
+        }
+    }
+
+    if skipped_pypi_packages {
+        tracing::warn!("PyPI packages are not fetched, they are downloaded during installation");
+    }
+
+    let package_cache = PackageCache::new(project.config().package_cache_dir()?);
+    let client = project.authenticated_client().clone();
+
+    let progress_bar =
+        pixi_progress::global_multi_progress().add(ProgressBar::new(records.len() as u64));
+    progress_bar.set_style(pixi_progress::default_progress_style());
+    progress_bar.set_prefix("fetching packages");
+
+    let fetched = records.len();
+    futures::stream::iter(records)
+        .map(|record| {
+            let package_cache = &package_cache;
+            let client = client.clone();
+            let progress_bar = &progress_bar;
+            async move {
+                // Transient failures are already retried by the client, a download
+                // that broke off is resumed.
+                get_or_fetch_resumable(package_cache, &record, client).await?;
+                progress_bar.inc(1);
+                Ok::<_, miette::Report>(())
+            }
+        })
+        .buffer_unordered(project.config().max_concurrent_downloads())
+        .try_collect::<Vec<_>>()
+        .await?;
+    progress_bar.finish_and_clear();
+
+    eprintln!(
+        "{}Fetched {} packages of {} into the cache.",
+        console::style(console::Emoji("✔ ", "")).green(),
+        fetched,
+        environments
+            .iter()
+            .map(|env| env.name().fancy_display())
+            .join(", "),
+    );
+
+    Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+    Ok(())
+}
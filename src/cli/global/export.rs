@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use crate::global::Project;
+use clap::Parser;
+use miette::IntoDiagnostic;
+use pixi_config::{Config, ConfigCli};
+
+/// Export the global manifest to recreate the global environments on another machine
+///
+/// The dependencies are pinned to the installed versions, unless `--no-pin` is passed.
+///
+/// Example:
+/// - pixi global export > pixi-global.toml
+/// - pixi global export --no-pin pixi-global.toml
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct Args {
+    /// The file to write the manifest to, defaults to stdout
+    output_path: Option<PathBuf>,
+
+    /// Export the dependencies as they are specified in the manifest instead
+    /// of pinning them to the installed versions
+    #[arg(long)]
+    no_pin: bool,
+
+    #[clap(flatten)]
+    config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+    let project = Project::discover_or_create().await?.with_cli_config(config);
+
+    let toml = if args.no_pin {
+        project.manifest.document.to_string()
+    } else {
+        project.pinned_manifest().await?.document.to_string()
+    };
+
+    if let Some(output_path) = args.output_path {
+        fs_err::write(output_path, toml).into_diagnostic()?;
+    } else {
+        print!("{}", toml);
+    }
+
+    Ok(())
+}
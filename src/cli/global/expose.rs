@@ -40,6 +40,11 @@ pub struct RemoveArgs {
     #[arg(num_args = 1..)]
     exposed_names: Vec<ExposedName>,
 
+    /// The environment from which the exposed names should be removed.
+    /// Defaults to the environment that exposes each name.
+    #[clap(short, long)]
+    environment: Option<EnvironmentName>,
+
     #[clap(flatten)]
     config: ConfigCli,
 }
@@ -129,10 +134,13 @@ pub async fn remove(args: RemoveArgs) -> miette::Result<()> {
         .exposed_names
         .iter()
         .map(|exposed_name| {
-            project_original
-                .manifest
-                .match_exposed_name_to_environment(exposed_name)
-                .map(|env_name| (exposed_name.clone(), env_name))
+            let env_name = match &args.environment {
+                Some(env_name) => Ok(env_name.clone()),
+                None => project_original
+                    .manifest
+                    .match_exposed_name_to_environment(exposed_name),
+            };
+            env_name.map(|env_name| (exposed_name.clone(), env_name))
         })
         .collect_vec();
 
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use crate::global::{project::Manifest, Project, StateChanges};
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use miette::Context;
+use pixi_config::{Config, ConfigCli};
+
+/// Import the environments of a global manifest and install them
+///
+/// Environments with the same name are replaced.
+///
+/// Example:
+/// - pixi global import pixi-global.toml
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct Args {
+    /// The manifest to import, e.g. created by `pixi global export`
+    path: PathBuf,
+
+    #[clap(flatten)]
+    config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+    let mut project = Project::discover_or_create().await?.with_cli_config(config);
+
+    let imported = Manifest::from_path(&args.path)?;
+    let env_names = project.manifest.import(&imported)?;
+    project.manifest.save().await?;
+
+    let mut state_changes = StateChanges::default();
+    for env_name in &env_names {
+        state_changes |= project
+            .sync_environment(env_name, None)
+            .await
+            .wrap_err_with(|| {
+                format!(
+                    "Couldn't install environment {}, run `pixi global sync` to try again",
+                    env_name.fancy_display()
+                )
+            })?;
+    }
+    state_changes.report();
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::cli::global::revert_environment_after_error;
+use crate::cli::has_specs::HasSpecs;
+use crate::global::{EnvironmentName, Project, StateChanges};
+use clap::Parser;
+use itertools::Itertools;
+use miette::Context;
+use pixi_config::{Config, ConfigCli};
+use rattler_conda_types::MatchSpec;
+
+/// Injects packages into an existing environment, without exposing their executables
+///
+/// Example:
+/// - pixi global inject --environment ipython numpy matplotlib
+#[derive(Parser, Debug, Clone)]
+#[clap(arg_required_else_help = true, verbatim_doc_comment)]
+pub struct Args {
+    /// Specifies the packages that are to be injected into the environment.
+    #[arg(num_args = 1.., required = true)]
+    packages: Vec<String>,
+
+    /// Specifies the environment that the packages need to be injected into.
+    #[clap(short, long, required = true)]
+    environment: EnvironmentName,
+
+    #[clap(flatten)]
+    config: ConfigCli,
+}
+
+impl HasSpecs for Args {
+    fn packages(&self) -> Vec<&str> {
+        self.packages.iter().map(AsRef::as_ref).collect()
+    }
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+    let project_original = Project::discover_or_create()
+        .await?
+        .with_cli_config(config.clone());
+
+    if project_original.environment(&args.environment).is_none() {
+        miette::bail!("Environment {} doesn't exist. You can create a new environment with `pixi global install`.", &args.environment);
+    }
+
+    async fn apply_changes(
+        env_name: &EnvironmentName,
+        specs: &[MatchSpec],
+        project: &mut Project,
+    ) -> miette::Result<StateChanges> {
+        let mut state_changes = StateChanges::new_with_env(env_name.clone());
+
+        // Add specs to the manifest and mark them as injected
+        for spec in specs {
+            project.manifest.add_dependency(
+                env_name,
+                spec,
+                project.clone().config().global_channel_config(),
+            )?;
+            if let Some(package_name) = &spec.name {
+                project
+                    .manifest
+                    .add_injected_package(env_name, package_name)?;
+            }
+        }
+
+        // Sync environment
+        state_changes |= project.sync_environment(env_name, None).await?;
+
+        // Figure out added packages and their corresponding versions
+        state_changes |= project.added_packages(specs, env_name).await?;
+
+        project.manifest.save().await?;
+
+        Ok(state_changes)
+    }
+
+    let mut project_modified = project_original.clone();
+    let specs = args
+        .specs()?
+        .into_iter()
+        .map(|(_, specs)| specs)
+        .collect_vec();
+
+    match apply_changes(&args.environment, specs.as_slice(), &mut project_modified).await {
+        Ok(state_changes) => {
+            state_changes.report();
+            Ok(())
+        }
+        Err(err) => {
+            revert_environment_after_error(&args.environment, &project_original)
+                .await
+                .wrap_err(format!(
+                    "Couldn't inject {:?}. Reverting also failed.",
+                    args.packages
+                ))?;
+            Err(err)
+        }
+    }
+}
@@ -175,6 +175,16 @@ async fn setup_environment(
         project.manifest.set_platform(env_name, platform)?;
     }
 
+    let with_package_names = args
+        .with
+        .iter()
+        .map(|spec| {
+            spec.name
+                .clone()
+                .ok_or_else(|| miette::miette!("could not find package name in MatchSpec {}", spec))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
     // Add the dependencies to the environment
     for spec in specs.iter().chain(&args.with) {
         project.manifest.add_dependency(
@@ -184,6 +194,20 @@ async fn setup_environment(
         )?;
     }
 
+    // Keep track of which dependencies are injected next to the primary packages
+    for spec in specs {
+        if let Some(package_name) = &spec.name {
+            project
+                .manifest
+                .remove_injected_package(env_name, package_name)?;
+        }
+    }
+    for package_name in &with_package_names {
+        project
+            .manifest
+            .add_injected_package(env_name, package_name)?;
+    }
+
     if !args.expose.is_empty() {
         project.manifest.remove_all_exposed_mappings(env_name)?;
         // Only add the exposed mappings that were requested
@@ -199,16 +223,6 @@ async fn setup_environment(
     // Installing the environment to be able to find the bin paths later
     let _ = project.install_environment(env_name).await?;
 
-    let with_package_names = args
-        .with
-        .iter()
-        .map(|spec| {
-            spec.name
-                .clone()
-                .ok_or_else(|| miette::miette!("could not find package name in MatchSpec {}", spec))
-        })
-        .collect::<miette::Result<Vec<_>>>()?;
-
     // Sync exposed binaries
     let expose_type = ExposedType::new(args.expose.clone(), with_package_names);
 
@@ -1,4 +1,6 @@
-use crate::global::list::{list_environment, list_global_environments, GlobalSortBy};
+use crate::global::list::{
+    list_environment, list_global_environments, list_global_environments_json, GlobalSortBy,
+};
 use crate::global::{EnvironmentName, Project};
 use clap::Parser;
 use fancy_display::FancyDisplay;
@@ -12,6 +14,7 @@ use std::str::FromStr;
 /// - Green: the packages that are explicit dependencies of the environment.
 /// - Blue: the version of the installed package.
 /// - Cyan: the name of the environment.
+/// - Dim: the disk usage of the environment.
 ///
 /// Per environment:
 /// - Green: packages that are explicitly installed.
@@ -33,6 +36,11 @@ pub struct Args {
     /// Sorting strategy for the package table of an environment
     #[arg(long, default_value = "name", value_enum, requires = "environment")]
     sort_by: GlobalSortBy,
+
+    /// Output the environments, their exposed executables and disk usage in
+    /// JSON format.
+    #[arg(long)]
+    json: bool,
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
@@ -48,13 +56,18 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             tracing::warn!("The environment {} is not in sync with the manifest, to sync run\n\tpixi global sync", env_name.fancy_display());
         }
 
-        list_environment(&project, &env_name, args.sort_by, args.regex).await?;
+        let json = args.json || pixi_progress::json_output();
+        list_environment(&project, &env_name, args.sort_by, args.regex, json).await?;
     } else {
         // Verify that the environments are in sync with the manifest and report to the user otherwise
         if !project.environments_in_sync().await? {
             tracing::warn!("The environments are not in sync with the manifest, to sync run\n\tpixi global sync");
         }
-        list_global_environments(&project, None, None, args.regex).await?;
+        if args.json || pixi_progress::json_output() {
+            list_global_environments_json(&project, args.regex).await?;
+        } else {
+            list_global_environments(&project, None, None, args.regex).await?;
+        }
     }
 
     Ok(())
@@ -4,10 +4,15 @@ use crate::global::{self, EnvironmentName};
 
 mod add;
 mod edit;
+mod export;
 mod expose;
+mod import;
+mod inject;
 mod install;
 mod list;
 mod remove;
+mod run;
+mod shell_completion;
 mod sync;
 mod uninstall;
 mod update;
@@ -19,6 +24,9 @@ pub enum Command {
     #[clap(visible_alias = "a")]
     Add(add::Args),
     Edit(edit::Args),
+    Export(export::Args),
+    Import(import::Args),
+    Inject(inject::Args),
     #[clap(visible_alias = "i")]
     Install(install::Args),
     Uninstall(uninstall::Args),
@@ -28,14 +36,14 @@ pub enum Command {
     List(list::Args),
     #[clap(visible_alias = "s")]
     Sync(sync::Args),
+    Run(run::Args),
+    ShellCompletion(shell_completion::Args),
     #[clap(visible_alias = "e")]
     #[command(subcommand)]
     Expose(expose::SubCommand),
     Update(update::Args),
-    #[command(hide = true)]
     Upgrade(upgrade::Args),
     #[clap(alias = "ua")]
-    #[command(hide = true)]
     UpgradeAll(upgrade_all::Args),
 }
 
@@ -55,11 +63,16 @@ pub async fn execute(cmd: Args) -> miette::Result<()> {
     match cmd.command {
         Command::Add(args) => add::execute(args).await?,
         Command::Edit(args) => edit::execute(args).await?,
+        Command::Export(args) => export::execute(args).await?,
+        Command::Import(args) => import::execute(args).await?,
+        Command::Inject(args) => inject::execute(args).await?,
         Command::Install(args) => install::execute(args).await?,
         Command::Uninstall(args) => uninstall::execute(args).await?,
         Command::Remove(args) => remove::execute(args).await?,
         Command::List(args) => list::execute(args).await?,
         Command::Sync(args) => sync::execute(args).await?,
+        Command::Run(args) => run::execute(args).await?,
+        Command::ShellCompletion(args) => shell_completion::execute(args).await?,
         Command::Expose(subcommand) => expose::execute(subcommand).await?,
         Command::Update(args) => update::execute(args).await?,
         Command::Upgrade(args) => upgrade::execute(args).await?,
@@ -0,0 +1,124 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+use clap::{Parser, ValueHint};
+use fancy_display::FancyDisplay;
+use miette::{Context, IntoDiagnostic};
+use pixi_config::{Config, ConfigCli};
+
+use crate::global::{EnvironmentName, Project};
+
+/// Run a command in a global environment.
+///
+/// The environment is activated and the command is executed inside of it,
+/// without the need to expose the executable.
+///
+/// Example:
+///    pixi global run gcc gcc-nm --version
+#[derive(Parser, Debug)]
+#[clap(
+    trailing_var_arg = true,
+    arg_required_else_help = true,
+    verbatim_doc_comment
+)]
+pub struct Args {
+    /// The name of the environment to run the command in.
+    pub environment: String,
+
+    /// The command to run, followed by its arguments.
+    #[clap(num_args = 1.., required = true, value_hint = ValueHint::CommandWithArguments)]
+    pub command: Vec<String>,
+
+    #[clap(flatten)]
+    config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+    let project = Project::discover_or_create().await?.with_cli_config(config);
+
+    let env_name = EnvironmentName::from_str(&args.environment)?;
+    if project.environment(&env_name).is_none() {
+        miette::bail!(
+            help = "Use `pixi global list` to see the available environments",
+            "Environment {} doesn't exist",
+            env_name.fancy_display()
+        );
+    }
+
+    // Make sure the environment is installed and up to date.
+    if !project.environment_in_sync(&env_name).await? {
+        project.install_environment(&env_name).await?;
+    }
+
+    let prefix = project.environment_prefix(&env_name).await?;
+    let activation_env = prefix.run_activation().await?;
+
+    // Ignore CTRL+C so that the child is responsible for its own signal handling.
+    let _ctrl_c = tokio::spawn(async { while tokio::signal::ctrl_c().await.is_ok() {} });
+
+    // The `PATH` of the activated environment is used to find the command.
+    let mut command_args = args.command.iter();
+    let command = command_args.next().expect("the command is required");
+    let executable = find_executable(command, &activation_env)?;
+    let status = tokio::process::Command::new(&executable)
+        .args(command_args)
+        .envs(activation_env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+        .status()
+        .await
+        .into_diagnostic()
+        .with_context(|| format!("failed to execute '{}'", executable.display()))?;
+
+    // Return the exit code of the command
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+/// Finds the executable of the command on the `PATH` of the activated
+/// environment, instead of the `PATH` of the current process.
+fn find_executable(
+    command: &str,
+    activation_env: &HashMap<String, String>,
+) -> miette::Result<PathBuf> {
+    // Environment variables are case-insensitive on Windows, e.g. `Path`.
+    let path = activation_env.iter().find_map(|(key, value)| {
+        let is_path = if cfg!(windows) {
+            key.eq_ignore_ascii_case("PATH")
+        } else {
+            key == "PATH"
+        };
+        is_path.then_some(value)
+    });
+    let cwd = std::env::current_dir().into_diagnostic()?;
+    which::which_in(command, path, cwd)
+        .into_diagnostic()
+        .with_context(|| format!("failed to find '{}' in the environment", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_executable_in_activated_path() {
+        let bin_dir = tempfile::tempdir().unwrap();
+        let name = if cfg!(windows) { "tool.exe" } else { "tool" };
+        let executable = bin_dir.path().join(name);
+        fs_err::write(&executable, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs_err::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let activation_env = HashMap::from([(
+            "PATH".to_string(),
+            bin_dir.path().to_string_lossy().into_owned(),
+        )]);
+        assert_eq!(
+            find_executable("tool", &activation_env).unwrap(),
+            executable
+        );
+
+        // The executable is not found without the activated `PATH`
+        assert!(find_executable("tool", &HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use crate::global::completions::{CompletionsDir, Shell};
+use clap::Parser;
+
+/// Print the line that loads the shell completions of the globally installed tools
+///
+/// Add it to the startup file of your shell:
+/// - bash: `eval "$(pixi global shell-completion --shell bash)"` in `~/.bashrc`
+/// - zsh: `eval "$(pixi global shell-completion --shell zsh)"` in `~/.zshrc`, before `compinit`
+/// - fish: `pixi global shell-completion --shell fish | source` in `~/.config/fish/config.fish`
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct Args {
+    /// The shell to print the line for
+    #[arg(short, long)]
+    shell: Shell,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let completions_dir = CompletionsDir::from_env()?;
+    println!("{}", args.shell.hook(&completions_dir));
+    Ok(())
+}
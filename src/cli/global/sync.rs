@@ -1,11 +1,18 @@
+use super::update::update_environment;
 use crate::global;
 use clap::Parser;
 use fancy_display::FancyDisplay;
+use itertools::Itertools;
 use pixi_config::{Config, ConfigCli};
 
 /// Sync global manifest with installed environments
 #[derive(Parser, Debug)]
 pub struct Args {
+    /// Solve the environments again instead of installing the packages of
+    /// their lock-files, and update the lock-files
+    #[arg(long)]
+    update: bool,
+
     #[clap(flatten)]
     config: ConfigCli,
 }
@@ -13,14 +20,16 @@ pub struct Args {
 /// Sync global manifest with installed environments
 pub async fn execute(args: Args) -> miette::Result<()> {
     let config = Config::with_cli_config(&args.config);
-    let project = global::Project::discover_or_create()
+    let mut project = global::Project::discover_or_create()
         .await?
         .with_cli_config(config.clone());
 
     let mut has_changed = false;
 
-    // Prune environments that are not listed
-    let state_change = project.prune_old_environments().await?;
+    // Prune environments that are not listed, and the exposed executables
+    // of environments that no longer exist
+    let mut state_change = project.prune_old_environments().await?;
+    state_change |= project.prune_orphaned_exposed().await?;
 
     if state_change.has_changed() {
         has_changed = true;
@@ -33,8 +42,14 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     }
 
     let mut errors = Vec::new();
-    for env_name in project.environments().keys() {
-        match project.sync_environment(env_name, None).await {
+    let env_names = project.environments().keys().cloned().collect_vec();
+    for env_name in &env_names {
+        let state_change = if args.update {
+            update_environment(env_name, &mut project).await
+        } else {
+            project.sync_environment(env_name, None).await
+        };
+        match state_change {
             Ok(state_change) => {
                 if state_change.has_changed() {
                     has_changed = true;
@@ -45,6 +60,10 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         }
     }
 
+    if args.update {
+        project.manifest.save().await?;
+    }
+
     if !has_changed {
         eprintln!(
             "{}Nothing to do. The pixi global installation is already up-to-date.",
@@ -8,6 +8,10 @@ use pixi_config::{Config, ConfigCli};
 
 /// Uninstalls environments from the global environment.
 ///
+/// The exposed executables of the environments are removed as well, together
+/// with the exposed executables that point into environments that no longer
+/// exist.
+///
 /// Example:
 /// pixi global uninstall pixi-pack rattler-build
 #[derive(Parser, Debug, Clone)]
@@ -33,6 +37,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     ) -> miette::Result<StateChanges> {
         let mut state_changes = StateChanges::new_with_env(env_name.clone());
         state_changes |= project_modified.remove_environment(env_name).await?;
+        state_changes |= project_modified.prune_orphaned_exposed().await?;
 
         project_modified.manifest.save().await?;
         Ok(state_changes)
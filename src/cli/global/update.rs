@@ -1,10 +1,11 @@
 use crate::cli::global::revert_environment_after_error;
 use crate::global::common::check_all_exposed;
-use crate::global::project::ExposedType;
+use crate::global::project::{ExposedType, SolvedEnvironment};
 use crate::global::{self, StateChanges};
 use crate::global::{EnvironmentName, Project};
 use clap::Parser;
 use fancy_display::FancyDisplay;
+use indexmap::IndexMap;
 use pixi_config::{Config, ConfigCli};
 
 /// Updates environments in the global environment.
@@ -23,47 +24,6 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .await?
         .with_cli_config(config.clone());
 
-    async fn apply_changes(
-        env_name: &EnvironmentName,
-        project: &mut Project,
-    ) -> miette::Result<StateChanges> {
-        // See what executables were installed prior to update
-        let env_binaries = project.executables(env_name).await?;
-
-        // Get the exposed binaries from mapping
-        let exposed_mapping_binaries = project
-            .environment(env_name)
-            .ok_or_else(|| miette::miette!("Environment {} not found", env_name.fancy_display()))?
-            .exposed();
-
-        // Check if they were all auto-exposed, or if the user manually exposed a subset of them
-        let expose_type = if check_all_exposed(&env_binaries, exposed_mapping_binaries) {
-            ExposedType::default()
-        } else {
-            ExposedType::subset()
-        };
-
-        // Reinstall the environment
-        let environment_update = project.install_environment(env_name).await?;
-
-        let mut state_changes = StateChanges::default();
-
-        state_changes.insert_change(
-            env_name,
-            global::StateChange::UpdatedEnvironment(environment_update),
-        );
-
-        // Sync executables exposed names with the manifest
-        project.sync_exposed_names(env_name, expose_type).await?;
-
-        // Expose or prune executables of the new environment
-        state_changes |= project
-            .expose_executables_from_environment(env_name)
-            .await?;
-
-        Ok(state_changes)
-    }
-
     // Update all environments if the user did not specify any
     let env_names = match args.environments {
         Some(env_names) => env_names,
@@ -76,7 +36,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     for env_name in env_names {
         let mut project = last_updated_project.clone();
 
-        match apply_changes(&env_name, &mut project).await {
+        match update_environment(&env_name, &mut project).await {
             Ok(state_changes) => state_changes.report(),
             Err(err) => {
                 revert_environment_after_error(&env_name, &last_updated_project).await?;
@@ -88,3 +48,65 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     last_updated_project.manifest.save().await?;
     Ok(())
 }
+
+/// Solves the environment again and applies the changes.
+pub(super) async fn update_environment(
+    env_name: &EnvironmentName,
+    project: &mut Project,
+) -> miette::Result<StateChanges> {
+    let solved_environment = project.solve_environment(env_name).await?;
+    apply_changes(env_name, project, solved_environment).await
+}
+
+/// Installs the solved environment and exposes the executables of the updated
+/// environment.
+pub(super) async fn apply_changes(
+    env_name: &EnvironmentName,
+    project: &mut Project,
+    solved_environment: SolvedEnvironment,
+) -> miette::Result<StateChanges> {
+    // See what executables were installed prior to update
+    let env_binaries = project.executables(env_name).await?;
+
+    let environment = project
+        .environment(env_name)
+        .ok_or_else(|| miette::miette!("Environment {} not found", env_name.fancy_display()))?;
+
+    // Injected packages are never exposed, so they don't count
+    let env_binaries = env_binaries
+        .into_iter()
+        .filter(|(package_name, _)| !environment.is_injected(package_name))
+        .collect::<IndexMap<_, _>>();
+
+    // Get the exposed binaries from mapping
+    let exposed_mapping_binaries = environment.exposed();
+
+    // Check if they were all auto-exposed, or if the user manually exposed a subset of them
+    let expose_type = if check_all_exposed(&env_binaries, exposed_mapping_binaries) {
+        ExposedType::default()
+    } else {
+        ExposedType::subset()
+    };
+
+    // Reinstall the environment
+    let environment_update = project
+        .install_solved_environment(env_name, solved_environment)
+        .await?;
+
+    let mut state_changes = StateChanges::default();
+
+    state_changes.insert_change(
+        env_name,
+        global::StateChange::UpdatedEnvironment(environment_update),
+    );
+
+    // Sync executables exposed names with the manifest
+    project.sync_exposed_names(env_name, expose_type).await?;
+
+    // Expose or prune executables of the new environment
+    state_changes |= project
+        .expose_executables_from_environment(env_name)
+        .await?;
+
+    Ok(state_changes)
+}
@@ -1,37 +1,106 @@
 use clap::Parser;
-use rattler_conda_types::Platform;
+use fancy_display::FancyDisplay;
+use itertools::Itertools;
+use pixi_config::{Config, ConfigCli};
+use rattler_conda_types::PackageName;
 
-use crate::cli::{cli_config::ChannelsConfig, has_specs::HasSpecs};
+use super::update::apply_changes;
+use crate::cli::global::revert_environment_after_error;
+use crate::global::{self, EnvironmentName, Project, StateChanges};
 
-/// Upgrade specific package which is installed globally.
-/// This command has been removed, please use `pixi global update` instead
+/// Upgrade specific packages which are installed globally
+///
+/// Every environment that depends on one of the packages is solved again with
+/// the latest repodata, and only reinstalled when the solution differs from the
+/// installed packages.
 #[derive(Parser, Debug)]
-// TODO: Uncomment as soon we implement this
-//#[clap(arg_required_else_help = true)]
+#[clap(arg_required_else_help = true)]
 pub struct Args {
     /// Specifies the packages to upgrade.
-    // TODO: Uncomment as soon we implement this
-    //#[arg(required = true)]
-    pub specs: Vec<String>,
+    #[arg(required = true)]
+    pub packages: Vec<PackageName>,
 
     #[clap(flatten)]
-    channels: ChannelsConfig,
+    config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+    let project = global::Project::discover_or_create()
+        .await?
+        .with_cli_config(config);
+
+    for package in &args.packages {
+        if !project
+            .environments()
+            .values()
+            .any(|env| env.dependencies().contains_key(package))
+        {
+            miette::bail!(
+                help = "use `pixi global list` to see the installed packages",
+                "package '{}' is not a dependency of any global environment",
+                package.as_source()
+            );
+        }
+    }
+
+    let env_names = project
+        .environments()
+        .iter()
+        .filter(|(_, env)| {
+            args.packages
+                .iter()
+                .any(|package| env.dependencies().contains_key(package))
+        })
+        .map(|(env_name, _)| env_name.clone())
+        .collect_vec();
 
-    /// The platform to install the package for.
-    #[clap(long, default_value_t = Platform::current())]
-    platform: Platform,
+    upgrade_environments(project, env_names).await
 }
 
-impl HasSpecs for Args {
-    fn packages(&self) -> Vec<&str> {
-        self.specs.iter().map(AsRef::as_ref).collect()
+/// Solves the environments again and installs the environments of which the
+/// solution changed. The changes of an environment are reverted if upgrading
+/// it fails.
+pub(super) async fn upgrade_environments(
+    project_original: Project,
+    env_names: Vec<EnvironmentName>,
+) -> miette::Result<()> {
+    let mut last_updated_project = project_original;
+
+    for env_name in env_names {
+        let mut project = last_updated_project.clone();
+
+        match upgrade_environment(&env_name, &mut project).await {
+            Ok(state_changes) => state_changes.report(),
+            Err(err) => {
+                revert_environment_after_error(&env_name, &last_updated_project).await?;
+                return Err(err);
+            }
+        }
+        last_updated_project = project;
     }
+    last_updated_project.manifest.save().await?;
+    Ok(())
 }
 
-pub async fn execute(_args: Args) -> miette::Result<()> {
-    Err(
-        miette::miette!("You can call `pixi global update` for most use cases").wrap_err(
-            "`pixi global upgrade` has been removed, and will be re-added in future releases",
-        ),
-    )
+/// Solves the environment again and installs it, unless the solution equals
+/// the installed packages.
+async fn upgrade_environment(
+    env_name: &EnvironmentName,
+    project: &mut Project,
+) -> miette::Result<StateChanges> {
+    let solved_environment = project.solve_environment(env_name).await?;
+    if project
+        .is_solved_environment_installed(env_name, &solved_environment)
+        .await?
+    {
+        eprintln!(
+            "{}Environment {} was already up-to-date.",
+            console::style(console::Emoji("✔ ", "")).green(),
+            env_name.fancy_display(),
+        );
+        return Ok(StateChanges::default());
+    }
+
+    apply_changes(env_name, project, solved_environment).await
 }
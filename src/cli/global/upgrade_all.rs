@@ -1,27 +1,25 @@
 use clap::Parser;
-use pixi_config::ConfigCli;
-use rattler_conda_types::Platform;
+use pixi_config::{Config, ConfigCli};
 
-use crate::cli::cli_config::ChannelsConfig;
+use super::upgrade::upgrade_environments;
+use crate::global;
 
 /// Upgrade all globally installed packages
-/// This command has been removed, please use `pixi global update` instead
+///
+/// Every environment is solved again with the latest repodata, and only
+/// reinstalled when the solution differs from the installed packages.
 #[derive(Parser, Debug)]
 pub struct Args {
-    #[clap(flatten)]
-    channels: ChannelsConfig,
-
     #[clap(flatten)]
     config: ConfigCli,
-
-    /// The platform to install the package for.
-    #[clap(long, default_value_t = Platform::current())]
-    platform: Platform,
 }
 
-pub async fn execute(_args: Args) -> miette::Result<()> {
-    Err(
-        miette::miette!("You can call `pixi global update` for most use cases")
-            .wrap_err("`pixi global upgrade-all` has been removed"),
-    )
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+    let project = global::Project::discover_or_create()
+        .await?
+        .with_cli_config(config);
+
+    let env_names = project.environments().keys().cloned().collect();
+    upgrade_environments(project, env_names).await
 }
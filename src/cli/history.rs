@@ -0,0 +1,94 @@
+use std::io::{stdout, Write};
+
+use chrono::Local;
+use clap::Parser;
+use miette::IntoDiagnostic;
+
+use crate::{cli::cli_config::ProjectConfig, project::history::HistoryEntry, Project};
+
+/// Show the history of changes to the project.
+///
+/// Every command that changes the manifest or the lock-file, or that installs
+/// or removes an environment, records the command, the user, the time, what was
+/// changed and the hashes before and after the change in `.pixi/history.jsonl`.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// Only show the last `N` entries
+    #[arg(long, short = 'n', value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Whether to output in json format
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+
+    let mut history = project.history()?;
+    if let Some(limit) = args.limit {
+        history.drain(..history.len().saturating_sub(limit));
+    }
+
+    if args.json || pixi_progress::json_output() {
+        pixi_progress::print_json(&history, true).expect("Cannot serialize history to JSON");
+    } else if history.is_empty() {
+        eprintln!(
+            "No changes have been recorded in {}",
+            project.history_file_path().display()
+        );
+    } else {
+        print_history_as_table(&history).into_diagnostic()?;
+    }
+
+    Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+    Ok(())
+}
+
+fn print_history_as_table(history: &[HistoryEntry]) -> std::io::Result<()> {
+    let mut writer = tabwriter::TabWriter::new(stdout());
+
+    let header_style = console::Style::new().bold();
+    writeln!(
+        writer,
+        "{}\t{}\t{}\t{}\t{}",
+        header_style.apply_to("Date"),
+        header_style.apply_to("User"),
+        header_style.apply_to("Change"),
+        header_style.apply_to("Hash"),
+        header_style.apply_to("Command"),
+    )?;
+
+    for entry in history {
+        let change = match &entry.environment {
+            Some(environment) => format!("{} '{}'", entry.change, environment),
+            None => entry.change.to_string(),
+        };
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{} -> {}\t{}",
+            entry
+                .timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            entry.user.as_deref().unwrap_or("unknown"),
+            change,
+            console::style(short_hash(entry.hash_before.as_deref())).dim(),
+            console::style(short_hash(entry.hash_after.as_deref())).cyan(),
+            entry.command,
+        )?;
+    }
+
+    writer.flush()
+}
+
+/// Shortens a hash for display purposes.
+fn short_hash(hash: Option<&str>) -> &str {
+    match hash {
+        Some(hash) => &hash[..hash.len().min(8)],
+        None => "none",
+    }
+}
@@ -19,6 +19,7 @@ use tokio::task::spawn_blocking;
 use crate::cli::cli_config::ProjectConfig;
 
 use crate::{
+    environment::LockedEnvironmentHash,
     global,
     global::{BinDir, EnvRoot},
     task::TaskName,
@@ -40,6 +41,19 @@ pub struct Args {
     #[arg(long)]
     json: bool,
 
+    /// Only print a stable hash of the locked packages, the platform and the
+    /// system requirements of an environment, e.g. to use as a cache key in CI
+    #[arg(long)]
+    environment_hash: bool,
+
+    /// The environment to hash, defaults to the default environment
+    #[arg(long, short, requires = "environment_hash")]
+    environment: Option<String>,
+
+    /// The platform to hash, defaults to the best platform of the environment
+    #[arg(long, short, requires = "environment_hash")]
+    platform: Option<Platform>,
+
     #[clap(flatten)]
     pub project_config: ProjectConfig,
 }
@@ -346,7 +360,60 @@ fn last_updated(path: impl Into<PathBuf>) -> miette::Result<String> {
     Ok(formatted_time)
 }
 
+/// Prints the hash that identifies the installation of an environment for a
+/// platform.
+async fn print_environment_hash(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+    let environment = project.environment_from_name_or_env_var(args.environment)?;
+    let platform = args.platform.unwrap_or_else(|| environment.best_platform());
+    if !environment.platforms().contains(&platform) {
+        miette::bail!(
+            "the environment '{}' does not support the platform '{}'",
+            environment.name(),
+            platform
+        );
+    }
+
+    let lock_file = crate::lock_file::load_lock_file(&project).await?;
+    let Some(locked_environment) = lock_file.environment(environment.name().as_str()) else {
+        miette::bail!(
+            help = "run `pixi lock` to update the lock file",
+            "the environment '{}' is not part of the lock file",
+            environment.name()
+        );
+    };
+
+    let virtual_packages = environment
+        .virtual_packages(platform)
+        .into_iter()
+        .map(GenericVirtualPackage::from)
+        .collect_vec();
+    let hash = LockedEnvironmentHash::from_environment_and_system_requirements(
+        locked_environment,
+        platform,
+        &virtual_packages,
+    );
+
+    if args.json || pixi_progress::json_output() {
+        let info = serde_json::json!({
+            "environment": environment.name().as_str(),
+            "platform": platform.as_str(),
+            "hash": hash.as_str(),
+        });
+        pixi_progress::print_json(&info, true).into_diagnostic()?;
+    } else {
+        println!("{}", hash.as_str());
+    }
+
+    Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+    Ok(())
+}
+
 pub async fn execute(args: Args) -> miette::Result<()> {
+    if args.environment_hash {
+        return print_environment_hash(args).await;
+    }
+
     let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref()).ok();
 
     let (pixi_folder_size, cache_size) = if args.extended {
@@ -451,8 +518,8 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         config_locations: config.loaded_from.clone(),
     };
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&info).into_diagnostic()?);
+    if args.json || pixi_progress::json_output() {
+        pixi_progress::print_json(&info, true).into_diagnostic()?;
 
         Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
         Ok(())
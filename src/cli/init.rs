@@ -59,6 +59,10 @@ pub struct Args {
     /// Source Control Management used for this project
     #[arg(short = 's', long = "scm", ignore_case = true)]
     pub scm: Option<GitAttributes>,
+
+    /// The language template to pre-populate the dependencies and tasks with.
+    #[arg(short, long, conflicts_with_all = ["env_file", "pyproject_toml"], ignore_case = true)]
+    pub template: Option<ProjectTemplate>,
 }
 
 /// The pixi.toml template
@@ -82,8 +86,14 @@ version = "{{ version }}"
 {%- endif %}
 
 [tasks]
+{%- for task in tasks %}
+{{ task[0] }} = "{{ task[1] }}"
+{%- endfor %}
 
 [dependencies]
+{%- for dependency in dependencies %}
+{{ dependency[0] }} = "{{ dependency[1] }}"
+{%- endfor %}
 
 "#;
 
@@ -179,6 +189,40 @@ pixi.lock gitlab-language=yaml gitlab-generated=true
     }
 }
 
+#[derive(Parser, Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ProjectTemplate {
+    Python,
+    Rust,
+    Cpp,
+}
+
+impl ProjectTemplate {
+    /// The dependencies of the template, as name and version spec.
+    fn dependencies(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ProjectTemplate::Python => &[("python", "3.12.*"), ("pytest", "*")],
+            ProjectTemplate::Rust => &[("rust", ">=1.80")],
+            ProjectTemplate::Cpp => &[("cmake", "*"), ("cxx-compiler", "*"), ("ninja", "*")],
+        }
+    }
+
+    /// The tasks of the template, as name and command.
+    fn tasks(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ProjectTemplate::Python => &[("test", "pytest")],
+            ProjectTemplate::Rust => &[
+                ("build", "cargo build --release"),
+                ("start", "cargo run"),
+                ("test", "cargo test"),
+            ],
+            ProjectTemplate::Cpp => &[
+                ("configure", "cmake -GNinja -S . -B .build"),
+                ("build", "cmake --build .build"),
+            ],
+        }
+    }
+}
+
 pub async fn execute(args: Args) -> miette::Result<()> {
     let env = Environment::new();
     let dir = get_dir(args.path).into_diagnostic()?;
@@ -237,6 +281,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             &platforms,
             None,
             &vec![],
+            None,
         );
         let mut project = Project::from_str(&pixi_manifest_path, &rv)?;
         let channel_config = project.channel_config();
@@ -298,6 +343,14 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             args.format == Some(ManifestFormat::Pyproject) || args.pyproject_toml
         };
 
+        if pyproject && args.template.is_some() {
+            miette::bail!(
+                "'{}' is only supported for '{}' manifests",
+                console::style("--template").bold(),
+                consts::PROJECT_MANIFEST
+            );
+        }
+
         // Inject a tool.pixi.project section into an existing pyproject.toml file if
         // there is one without '[tool.pixi.project]'
         if pyproject && pyproject_manifest_path.is_file() {
@@ -427,6 +480,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 &platforms,
                 index_url.as_ref(),
                 &extra_index_urls,
+                args.template,
             );
             save_manifest_file(&pixi_manifest_path, rv)?;
         };
@@ -465,6 +519,7 @@ fn render_project(
     platforms: &Vec<String>,
     index_url: Option<&Url>,
     extra_index_urls: &Vec<Url>,
+    template: Option<ProjectTemplate>,
 ) -> String {
     env.render_named_str(
         consts::PROJECT_MANIFEST,
@@ -477,6 +532,8 @@ fn render_project(
             platforms,
             index_url,
             extra_index_urls,
+            dependencies => template.map(|template| template.dependencies()).unwrap_or_default(),
+            tasks => template.map(|template| template.tasks()).unwrap_or_default(),
         },
     )
     .unwrap()
@@ -549,6 +606,7 @@ mod tests {
         path::{Path, PathBuf},
     };
 
+    use itertools::Itertools;
     use tempfile::tempdir;
 
     use super::*;
@@ -628,6 +686,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_render_project_template() {
+        let env = Environment::new();
+        let render = |template| {
+            render_project(
+                &env,
+                "test".to_string(),
+                "0.1.0",
+                None,
+                vec![NamedChannelOrUrl::from_str("conda-forge").unwrap()],
+                &vec!["linux-64".to_string()],
+                None,
+                &vec![],
+                template,
+            )
+        };
+
+        let manifest = render(None);
+        assert!(manifest.contains("[tasks]\n\n[dependencies]"));
+
+        let manifest = render(Some(ProjectTemplate::Rust));
+        let project = Project::from_str(Path::new("pixi.toml"), &manifest).unwrap();
+        let environment = project.default_environment();
+        assert!(environment
+            .dependencies(SpecType::Run, None)
+            .names()
+            .any(|name| name.as_normalized() == "rust"));
+        assert_eq!(
+            environment
+                .tasks(None)
+                .unwrap()
+                .keys()
+                .map(|name| name.as_str())
+                .sorted()
+                .collect_vec(),
+            vec!["build", "start", "test"]
+        );
+    }
+
     #[test]
     fn test_multiple_scm_values() {
         let test_cases = vec![
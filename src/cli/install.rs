@@ -1,11 +1,19 @@
 use crate::cli::cli_config::ProjectConfig;
-use crate::environment::get_update_lock_file_and_prefix;
-use crate::lock_file::UpdateMode;
-use crate::{Project, UpdateLockFileOptions};
+use crate::cli::verify::{finish_repair, repair_environment};
+use crate::environment::{get_update_lock_file_and_prefix, LockFileUsage};
+use crate::lock_file::{set_source_date_epoch, ReproducibilityReport, UpdateMode};
+use crate::project::virtual_packages::{
+    verify_current_platform_has_required_virtual_packages, VerifyCurrentPlatformError,
+};
+use crate::{load_lock_file, Project, UpdateLockFileOptions};
 use clap::Parser;
 use fancy_display::FancyDisplay;
 use itertools::Itertools;
+use miette::IntoDiagnostic;
 use pixi_config::ConfigCli;
+use pixi_consts::consts;
+use serde::Serialize;
+use std::path::PathBuf;
 
 /// Install all dependencies
 #[derive(Parser, Debug)]
@@ -25,10 +33,35 @@ pub struct Args {
 
     #[arg(long, short, conflicts_with = "environment")]
     pub all: bool,
+
+    /// Only install when all locked packages of the environments are
+    /// reproducible, and print a compliance report
+    ///
+    /// The lock-file is not updated, as with `--locked`. Mirrors are ignored
+    /// and `SOURCE_DATE_EPOCH` is set for builds.
+    #[arg(long)]
+    pub reproducible: bool,
+
+    /// Verify the installed packages against the lock file and reinstall the
+    /// packages that are broken, e.g. after an interrupted installation
+    #[arg(long)]
+    pub repair: bool,
+
+    /// Print the installed environments as JSON to stdout, instead of the
+    /// human readable output
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// An installed environment in the output of `pixi install --json`.
+#[derive(Serialize)]
+struct InstalledEnvironmentJson {
+    name: String,
+    prefix: PathBuf,
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+    let mut project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
         .with_cli_config(args.config);
 
     // Install either:
@@ -48,24 +81,113 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         vec![project.default_environment().name().to_string()]
     };
 
+    let mut lock_file_usage: LockFileUsage = args.lock_file_usage.into();
+    if args.reproducible {
+        project = project.without_mirrors();
+        set_source_date_epoch();
+        if lock_file_usage == LockFileUsage::Update {
+            lock_file_usage = LockFileUsage::Locked;
+        }
+
+        let env_names = envs
+            .iter()
+            .map(|env| {
+                project
+                    .environment_from_name_or_env_var(Some(env.clone()))
+                    .map(|env| env.name().to_string())
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+        let lock_file = load_lock_file(&project).await?;
+        let report = ReproducibilityReport::from_lock_file(&lock_file, Some(&env_names));
+        report.print();
+        if !report.is_compliant() {
+            miette::bail!(
+                help = "pin the packages by a hash and use prebuilt wheels instead of source distributions",
+                "the environments are not reproducible"
+            );
+        }
+    }
+
+    let repair_lock_file = if args.repair {
+        Some(load_lock_file(&project).await?)
+    } else {
+        None
+    };
     let mut installed_envs = Vec::with_capacity(envs.len());
     for env in envs {
         let environment = project.environment_from_name_or_env_var(Some(env))?;
 
+        // Remove the broken packages, so they are installed again below.
+        let mut repairing = false;
+        if let Some(lock_file) = repair_lock_file
+            .as_ref()
+            .filter(|_| environment.dir().join(consts::CONDA_META_DIR).is_dir())
+        {
+            let packages = repair_environment(&environment, lock_file).await?;
+            repairing = !packages.is_empty();
+            if repairing {
+                eprintln!(
+                    "{}Repairing {} of the {} environment: {}",
+                    console::style(console::Emoji("🔧 ", "")).yellow(),
+                    if packages.len() == 1 {
+                        "1 package".to_string()
+                    } else {
+                        format!("{} packages", packages.len())
+                    },
+                    environment.name().fancy_display(),
+                    packages.iter().map(|name| name.as_normalized()).join(", ")
+                );
+            }
+        }
+
+        // The environment is still installed when the machine doesn't meet the
+        // system requirements, but running anything in it will fail.
+        match verify_current_platform_has_required_virtual_packages(&environment) {
+            Ok(()) | Err(VerifyCurrentPlatformError::UnsupportedPlatform(_)) => {}
+            Err(err) => tracing::warn!(
+                "the {} environment can't be used on this machine: {:?}",
+                environment.name().fancy_display(),
+                miette::Report::from(err)
+            ),
+        }
+
         // Update the prefix by installing all packages
-        get_update_lock_file_and_prefix(
+        let result = get_update_lock_file_and_prefix(
             &environment,
             UpdateMode::Revalidate,
             UpdateLockFileOptions {
-                lock_file_usage: args.lock_file_usage.into(),
+                lock_file_usage,
                 no_install: false,
                 max_concurrent_solves: project.config().max_concurrent_solves(),
             },
         )
-        .await?;
+        .await;
+        // Restore the removed packages if reinstalling them failed.
+        if repairing {
+            finish_repair(&environment.dir(), result).await?;
+        } else {
+            result?;
+        }
+
+        installed_envs.push(environment.clone());
+    }
 
-        installed_envs.push(environment.name().clone());
+    if args.json || pixi_progress::json_output() {
+        let installed_envs = installed_envs
+            .iter()
+            .map(|environment| InstalledEnvironmentJson {
+                name: environment.name().to_string(),
+                prefix: environment.dir(),
+            })
+            .collect_vec();
+        pixi_progress::print_json(&installed_envs, true).into_diagnostic()?;
+        Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+        return Ok(());
     }
+    let installed_envs = installed_envs
+        .iter()
+        .map(|environment| environment.name())
+        .collect_vec();
 
     // Message what's installed
     let detached_envs_message =
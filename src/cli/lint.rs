@@ -0,0 +1,73 @@
+use clap::Parser;
+use itertools::Itertools;
+use miette::{NamedSource, Report};
+use rattler_lock::{LockedPackageRef, UrlOrPath};
+
+use crate::{cli::cli_config::ProjectConfig, load_lock_file, Project};
+
+/// Check the manifest for likely mistakes
+///
+/// Reports targets that no environment uses, packages that are a run and a
+/// host or build dependency at the same time, dependencies without a version
+/// constraint, tasks that depend on tasks that are not defined and, if the
+/// project has a lock file, channels that none of the locked packages come
+/// from. Exits with an error if any issue is found.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+
+    // The channels are only checked against an existing lock file, linting
+    // should not solve the project.
+    let locked_package_urls = if project.lock_file_path().is_file() {
+        let lock_file = load_lock_file(&project).await?;
+        Some(
+            lock_file
+                .environments()
+                .flat_map(|(_, environment)| environment.packages_by_platform())
+                .flat_map(|(_, packages)| packages)
+                .filter(|package| matches!(package, LockedPackageRef::Conda(_)))
+                .filter_map(|package| match package.location() {
+                    UrlOrPath::Url(url) => Some(url.clone()),
+                    UrlOrPath::Path(_) => None,
+                })
+                .unique()
+                .collect_vec(),
+        )
+    } else {
+        None
+    };
+
+    let manifest = project.manifest();
+    let lints = manifest.lint(locked_package_urls.as_deref(), &project.channel_config());
+    let file_name = manifest
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if lints.is_empty() {
+        eprintln!(
+            "{}No issues found in {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            file_name
+        );
+        return Ok(());
+    }
+
+    let contents = manifest.contents.clone().unwrap_or_default();
+    for lint in &lints {
+        let report = Report::new(lint.clone())
+            .with_source_code(NamedSource::new(&file_name, contents.clone()));
+        eprintln!("{:?}", report);
+    }
+    miette::bail!(
+        "found {} {} in {}",
+        lints.len(),
+        if lints.len() == 1 { "issue" } else { "issues" },
+        file_name
+    )
+}
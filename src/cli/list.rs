@@ -257,7 +257,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         }
     }
 
-    if packages_to_output.is_empty() {
+    if packages_to_output.is_empty() && !pixi_progress::json_output() {
         eprintln!(
             "{}No packages found.",
             console::style(console::Emoji("✘ ", "")).red(),
@@ -267,7 +267,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     }
 
     // Print as table string or JSON
-    if args.json || args.json_pretty {
+    if args.json || args.json_pretty || pixi_progress::json_output() {
         // print packages as json
         json_packages(&packages_to_output, args.json_pretty);
     } else {
@@ -335,14 +335,7 @@ fn print_packages_as_table(packages: &Vec<PackageToOutput>) -> io::Result<()> {
 }
 
 fn json_packages(packages: &Vec<PackageToOutput>, json_pretty: bool) {
-    let json_string = if json_pretty {
-        serde_json::to_string_pretty(&packages)
-    } else {
-        serde_json::to_string(&packages)
-    }
-    .expect("Cannot serialize packages to JSON");
-
-    println!("{}", json_string);
+    pixi_progress::print_json(packages, json_pretty).expect("Cannot serialize packages to JSON");
 }
 
 fn create_package_to_output<'a, 'b>(
@@ -0,0 +1,169 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::Parser;
+use miette::{Context, IntoDiagnostic};
+use pixi_config::ConfigCli;
+use pixi_manifest::SolveStrategy;
+use rattler_lock::LockFile;
+
+use crate::{
+    cli::cli_config::ProjectConfig,
+    diff::{LockFileDiff, LockFileJsonDiff},
+    load_lock_file,
+    lock_file::{set_source_date_epoch, EnvironmentAnalysis, ReproducibilityReport, UpdateContext},
+    Project,
+};
+
+/// Solve the environments of the project and update the lock file, without
+/// installing the environments.
+///
+/// With `--as-of` all environments are solved again with only the packages
+/// that were published at the given moment, e.g. to reconstruct the
+/// environments of that time when debugging a regression.
+///
+/// With `--strategy lowest` all environments are solved again for the lowest
+/// compatible versions, e.g. to test the lower bounds of the dependencies.
+#[derive(Parser, Debug, Default)]
+pub struct Args {
+    #[clap(flatten)]
+    pub config: ConfigCli,
+
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// Only use the packages that were published before the given date or
+    /// time, e.g. `2024-01-15` (the end of that day in UTC) or
+    /// `2024-01-15T12:00:00Z`
+    #[arg(long, value_parser = parse_as_of)]
+    pub as_of: Option<DateTime<Utc>>,
+
+    /// The strategy to select the versions of the packages with, overrides
+    /// the `solve-strategy` of the manifest: `highest`, `lowest` or
+    /// `lowest-direct`
+    #[arg(long)]
+    pub strategy: Option<SolveStrategy>,
+
+    /// Don't install the (solve) environments needed for pypi-dependencies
+    /// solving.
+    #[arg(long)]
+    pub no_install: bool,
+
+    /// Don't actually write the lockfile.
+    #[clap(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// Output the changes in JSON format.
+    #[clap(long)]
+    pub json: bool,
+
+    /// Fail when a locked package is not reproducible, e.g. because it isn't
+    /// pinned by a hash or is built from source, and print a compliance report
+    ///
+    /// Mirrors are ignored and `SOURCE_DATE_EPOCH` is set for builds that
+    /// happen while solving.
+    #[clap(long)]
+    pub reproducible: bool,
+
+    /// Warn about dependencies that make the environments unusually heavy,
+    /// e.g. very large packages, multiple BLAS implementations or packages
+    /// that are installed both from conda and PyPI, with a suggestion for each
+    #[clap(long)]
+    pub analyze: bool,
+}
+
+/// Parses the value of `--as-of`, either a date or an RFC 3339 timestamp.
+fn parse_as_of(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).expect("valid time");
+        return Ok(date.and_time(end_of_day).and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| {
+            format!("expected a date like `2024-01-15` or a time like `2024-01-15T12:00:00Z`, got `{value}`")
+        })
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let mut project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+        .with_cli_config(args.config);
+    if args.reproducible {
+        project = project.without_mirrors();
+        set_source_date_epoch();
+    }
+
+    let loaded_lock_file = load_lock_file(&project).await?;
+
+    // Solving against an older state of the channels or with another strategy
+    // starts from scratch, otherwise only the outdated environments are solved.
+    let lock_file = if args.as_of.is_some() || args.strategy.is_some() {
+        LockFile::default()
+    } else {
+        loaded_lock_file.clone()
+    };
+
+    let updated_lock_file = UpdateContext::builder(&project)
+        .with_lock_file(lock_file)
+        .with_no_install(args.no_install)
+        .with_exclude_newer(args.as_of)
+        .with_solve_strategy(args.strategy)
+        .finish()
+        .await?
+        .update()
+        .await?;
+
+    if args.reproducible {
+        let report = ReproducibilityReport::from_lock_file(&updated_lock_file.lock_file, None);
+        report.print();
+        if !report.is_compliant() {
+            miette::bail!(
+                help = "pin the packages by a hash and use prebuilt wheels instead of source distributions",
+                "the lock-file is not reproducible"
+            );
+        }
+    }
+
+    if args.analyze {
+        EnvironmentAnalysis::from_lock_file(&updated_lock_file.lock_file).print();
+    }
+
+    if !args.dry_run {
+        updated_lock_file.write_to_disk()?;
+    }
+
+    let diff = LockFileDiff::from_lock_files(&loaded_lock_file, &updated_lock_file.lock_file);
+    if args.json || pixi_progress::json_output() {
+        let json_diff = LockFileJsonDiff::new(&project, diff);
+        pixi_progress::print_json(&json_diff, true).expect("failed to convert to json");
+    } else if diff.is_empty() {
+        eprintln!(
+            "{}Lock-file was already up-to-date",
+            console::style(console::Emoji("✔ ", "")).green()
+        );
+    } else {
+        diff.print()
+            .into_diagnostic()
+            .context("failed to print lock-file diff")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_as_of() {
+        assert_eq!(
+            parse_as_of("2024-01-15").unwrap().to_rfc3339(),
+            "2024-01-15T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_as_of("2024-01-15T12:00:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-01-15T10:00:00+00:00"
+        );
+        assert!(parse_as_of("15-01-2024").is_err());
+    }
+}
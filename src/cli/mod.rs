@@ -1,9 +1,10 @@
 use clap::Parser;
 use clap_verbosity_flag::Verbosity;
 use indicatif::ProgressDrawTarget;
-use miette::IntoDiagnostic;
+use miette::{GraphicalTheme, IntoDiagnostic, ThemeCharacters, ThemeStyles};
+use owo_colors::{AnsiColors, DynColors};
 use pixi_consts::consts;
-use pixi_progress::global_multi_progress;
+use pixi_progress::{global_multi_progress, JsonEvent};
 use pixi_utils::indicatif::IndicatifWriter;
 use std::{env, io::IsTerminal};
 use tracing_subscriber::{
@@ -12,23 +13,31 @@ use tracing_subscriber::{
 };
 
 pub mod add;
+pub mod auth;
 mod build;
 pub mod clean;
 pub mod cli_config;
 pub mod completion;
 pub mod config;
 mod exec;
+pub mod fetch;
 pub mod global;
 pub mod has_specs;
+pub mod history;
 pub mod info;
 pub mod init;
 pub mod install;
+pub mod lint;
 pub mod list;
+pub mod lock;
 pub mod project;
 pub mod remove;
+pub mod repl;
 pub mod run;
+mod run_script;
 pub mod search;
 pub mod self_update;
+pub mod setup;
 pub mod shell;
 pub mod shell_hook;
 pub mod task;
@@ -36,6 +45,7 @@ pub mod tree;
 pub mod update;
 pub mod upgrade;
 pub mod upload;
+pub mod verify;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -82,6 +92,12 @@ struct Args {
     /// Hide all progress bars, always turned on if stderr is not a terminal.
     #[clap(long, default_value = "false", global = true, env = "PIXI_NO_PROGRESS")]
     no_progress: bool,
+
+    /// Print the progress, the result and errors as JSON events on stdout, one
+    /// per line, instead of the human readable output. The result contains
+    /// the same data as the `--json` output of the command.
+    #[clap(long, global = true)]
+    json_events: bool,
 }
 impl Args {
     /// Whether to show progress bars or not, based on the terminal and the user's preference.
@@ -97,6 +113,7 @@ impl Args {
 #[derive(Parser, Debug)]
 pub enum Command {
     Init(init::Args),
+    Setup(setup::Args),
 
     // Installation commands
     #[clap(visible_alias = "a")]
@@ -105,8 +122,11 @@ pub enum Command {
     Remove(remove::Args),
     #[clap(visible_alias = "i")]
     Install(install::Args),
+    Fetch(fetch::Args),
     Update(update::Args),
     Upgrade(upgrade::Args),
+    Lock(lock::Args),
+    Verify(verify::Args),
 
     #[clap(visible_alias = "r")]
     Run(run::Args),
@@ -115,21 +135,24 @@ pub enum Command {
     #[clap(visible_alias = "s")]
     Shell(shell::Args),
     ShellHook(shell_hook::Args),
+    Repl(repl::Args),
 
     // Project modification commands
     Project(project::Args),
     Task(task::Args),
+    Lint(lint::Args),
 
     // Environment inspection
     #[clap(visible_alias = "ls")]
     List(list::Args),
     #[clap(visible_alias = "t")]
     Tree(tree::Args),
+    History(history::Args),
 
     // Global level commands
     #[clap(visible_alias = "g")]
     Global(global::Args),
-    Auth(rattler::cli::auth::Args),
+    Auth(auth::Args),
     Config(config::Args),
     Info(info::Args),
     Upload(upload::Args),
@@ -141,6 +164,9 @@ pub enum Command {
 
     // Build
     Build(build::Args),
+
+    #[clap(name = "run-script", hide = true)]
+    RunScript(run_script::Args),
 }
 
 #[derive(Parser, Debug, Default, Copy, Clone)]
@@ -157,6 +183,51 @@ pub struct LockFileUsageArgs {
     pub locked: bool,
 }
 
+/// The exit code of pixi when a command fails, so that scripts can tell the
+/// kind of failure apart without parsing the error message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// The command failed.
+    Failure = 1,
+    /// The lock-file is not up-to-date with the project, but it may not be
+    /// updated, e.g. because of `--locked`.
+    LockFileOutOfDate = 3,
+    /// The requirements of an environment can't be satisfied.
+    SolveFailed = 4,
+}
+
+impl ExitCode {
+    /// Determines the exit code for the error a command failed with.
+    pub fn from_error(err: &miette::Report) -> Self {
+        if err
+            .downcast_ref::<crate::lock_file::LockFileOutOfDateError>()
+            .is_some()
+        {
+            ExitCode::LockFileOutOfDate
+        } else if err.downcast_ref::<crate::lock_file::SolveError>().is_some() {
+            ExitCode::SolveFailed
+        } else {
+            ExitCode::Failure
+        }
+    }
+}
+
+/// Reports the error a command failed with, as a JSON event on stdout when
+/// `--json-events` is used and as a rendered report on stderr otherwise.
+pub fn report_error(err: &miette::Report) {
+    if !pixi_progress::json_output() {
+        eprintln!("{err:?}");
+        return;
+    }
+    JsonEvent::Error {
+        message: err.to_string(),
+        code: err.code().map(|code| code.to_string()),
+        help: err.help().map(|help| help.to_string()),
+        causes: err.chain().skip(1).map(|cause| cause.to_string()).collect(),
+    }
+    .emit();
+}
+
 impl From<LockFileUsageArgs> for crate::environment::LockFileUsage {
     fn from(value: LockFileUsageArgs) -> Self {
         if value.frozen {
@@ -175,18 +246,21 @@ pub async fn execute() -> miette::Result<()> {
     let use_colors = console::colors_enabled_stderr();
 
     // Set up the default miette handler based on whether we want colors or not.
+    // The theme is only known once a command loaded the configuration, so it
+    // is looked up when a report is rendered.
     miette::set_hook(Box::new(move |_| {
-        Box::new(
-            miette::MietteHandlerOpts::default()
-                .color(use_colors)
-                .build(),
-        )
+        let mut opts = miette::MietteHandlerOpts::default().color(use_colors);
+        if let Some(report_theme) = report_theme(pixi_progress::theme(), use_colors) {
+            opts = opts.graphical_theme(report_theme);
+        }
+        Box::new(opts.build())
     }))?;
 
     // Hide all progress bars if the user requested it.
     if args.no_progress() {
         global_multi_progress().set_draw_target(ProgressDrawTarget::hidden());
     }
+    pixi_progress::set_json_output(args.json_events);
 
     let (low_level_filter, level_filter, pixi_level) = match args.verbose.log_level_filter() {
         clap_verbosity_flag::LevelFilter::Off => {
@@ -250,6 +324,15 @@ pub async fn execute() -> miette::Result<()> {
         .with(fmt_layer)
         .init();
 
+    // The theme is only read from the global configuration, apply it to all
+    // output before the command prints anything.
+    let config = pixi_config::Config::load_global();
+    let theme = config.theme();
+    pixi_progress::set_theme(pixi_progress::Theme {
+        accent_color: theme.accent_color().map(Into::into),
+        emoji: theme.emoji(),
+    });
+
     // Execute the command
     execute_command(args.command).await
 }
@@ -257,18 +340,22 @@ pub async fn execute() -> miette::Result<()> {
 /// Execute the actual command
 pub async fn execute_command(command: Command) -> miette::Result<()> {
     match command {
-        Command::Completion(cmd) => completion::execute(cmd),
+        Command::Completion(cmd) => completion::execute(cmd).await,
         Command::Config(cmd) => config::execute(cmd).await,
         Command::Init(cmd) => init::execute(cmd).await,
+        Command::Setup(cmd) => setup::execute(cmd).await,
         Command::Add(cmd) => add::execute(cmd).await,
         Command::Clean(cmd) => clean::execute(cmd).await,
         Command::Run(cmd) => run::execute(cmd).await,
         Command::Global(cmd) => global::execute(cmd).await,
-        Command::Auth(cmd) => rattler::cli::auth::execute(cmd).await.into_diagnostic(),
+        Command::Auth(cmd) => auth::execute(cmd).await,
         Command::Install(cmd) => install::execute(cmd).await,
+        Command::Fetch(cmd) => fetch::execute(cmd).await,
         Command::Shell(cmd) => shell::execute(cmd).await,
         Command::ShellHook(cmd) => shell_hook::execute(cmd).await,
+        Command::Repl(cmd) => repl::execute(cmd).await,
         Command::Task(cmd) => task::execute(cmd),
+        Command::Lint(cmd) => lint::execute(cmd).await,
         Command::Info(cmd) => info::execute(cmd).await,
         Command::Upload(cmd) => upload::execute(cmd).await,
         Command::Search(cmd) => search::execute(cmd).await,
@@ -280,10 +367,14 @@ pub async fn execute_command(command: Command) -> miette::Result<()> {
         Command::SelfUpdate(cmd) => self_update::execute_stub(cmd).await,
         Command::List(cmd) => list::execute(cmd).await,
         Command::Tree(cmd) => tree::execute(cmd).await,
+        Command::History(cmd) => history::execute(cmd).await,
         Command::Update(cmd) => update::execute(cmd).await,
         Command::Upgrade(cmd) => upgrade::execute(cmd).await,
+        Command::Lock(cmd) => lock::execute(cmd).await,
+        Command::Verify(cmd) => verify::execute(cmd).await,
         Command::Exec(args) => exec::execute(args).await,
         Command::Build(args) => build::execute(args).await,
+        Command::RunScript(args) => run_script::execute(args).await,
     }
 }
 
@@ -299,15 +390,58 @@ pub enum ColorOutput {
     Auto,
 }
 
+/// Returns the theme of the error reports for the configured theme, or `None`
+/// to let miette pick its default theme.
+fn report_theme(theme: pixi_progress::Theme, use_colors: bool) -> Option<GraphicalTheme> {
+    if theme.emoji && theme.accent_color.is_none() {
+        return None;
+    }
+
+    let characters = if theme.emoji && console::Term::stderr().features().wants_emoji() {
+        ThemeCharacters::unicode()
+    } else {
+        ThemeCharacters::ascii()
+    };
+
+    let mut styles = if use_colors {
+        ThemeStyles::ansi()
+    } else {
+        ThemeStyles::none()
+    };
+    if let Some(accent_color) = theme.accent_color.filter(|_| use_colors) {
+        let accent = owo_colors::Style::new().color(match accent_color {
+            console::Color::Black => DynColors::Ansi(AnsiColors::Black),
+            console::Color::Red => DynColors::Ansi(AnsiColors::Red),
+            console::Color::Green => DynColors::Ansi(AnsiColors::Green),
+            console::Color::Yellow => DynColors::Ansi(AnsiColors::Yellow),
+            console::Color::Blue => DynColors::Ansi(AnsiColors::Blue),
+            console::Color::Magenta => DynColors::Ansi(AnsiColors::Magenta),
+            console::Color::Cyan => DynColors::Ansi(AnsiColors::Cyan),
+            console::Color::White => DynColors::Ansi(AnsiColors::White),
+            console::Color::Color256(n) => DynColors::Xterm(n.into()),
+        });
+        styles.link = accent.underline();
+        styles.highlights.insert(0, accent.bold());
+    }
+
+    Some(GraphicalTheme { characters, styles })
+}
+
+/// Returns true if the environment variable is set to a non-empty value.
+fn env_var_is_set(key: &str) -> bool {
+    env::var_os(key).is_some_and(|value| !value.is_empty())
+}
+
 fn set_console_colors(args: &Args) {
     // Honor FORCE_COLOR and NO_COLOR environment variables.
-    // Those take precedence over the CLI flag and PIXI_COLOR
-    let color = match env::var("FORCE_COLOR") {
-        Ok(_) => &ColorOutput::Always,
-        Err(_) => match env::var("NO_COLOR") {
-            Ok(_) => &ColorOutput::Never,
-            Err(_) => &args.color,
-        },
+    // Those take precedence over the CLI flag and PIXI_COLOR. Following
+    // https://no-color.org, empty values are ignored.
+    let color = if env_var_is_set("FORCE_COLOR") {
+        &ColorOutput::Always
+    } else if env_var_is_set("NO_COLOR") {
+        &ColorOutput::Never
+    } else {
+        &args.color
     };
 
     match color {
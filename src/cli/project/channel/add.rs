@@ -1,6 +1,10 @@
+use miette::{IntoDiagnostic, WrapErr};
+use rattler_conda_types::{NamedChannelOrUrl, Platform};
+
 use crate::{
     environment::{get_update_lock_file_and_prefix, LockFileUsage},
     lock_file::UpdateMode,
+    repodata::Repodata,
     Project, UpdateLockFileOptions,
 };
 
@@ -10,6 +14,9 @@ pub async fn execute(args: AddRemoveArgs) -> miette::Result<()> {
     let mut project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
         .with_cli_config(args.clone().prefix_update_config.config);
 
+    // Make sure the channels exist before they end up in the manifest
+    validate_channels(&project, &args.channel).await?;
+
     // Add the channels to the manifest
     project.manifest.add_channels(
         args.prioritized_channels(),
@@ -35,3 +42,25 @@ pub async fn execute(args: AddRemoveArgs) -> miette::Result<()> {
 
     Ok(())
 }
+
+/// Checks that the channels exist by fetching their `noarch` repodata, which
+/// every channel has.
+async fn validate_channels(
+    project: &Project,
+    channels: &[NamedChannelOrUrl],
+) -> miette::Result<()> {
+    let channel_config = project.channel_config();
+    for channel in channels {
+        let resolved = channel
+            .clone()
+            .into_channel(&channel_config)
+            .into_diagnostic()?;
+        project
+            .repodata_gateway()
+            .names([resolved], [Platform::NoArch])
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("channel '{channel}' does not exist or is not reachable"))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use clap::Parser;
+use miette::{Context, IntoDiagnostic};
+use pixi_manifest::EnvironmentName;
+
+use crate::{
+    lock_file::{copy_lock_file_environment, load_lock_file, relative_paths},
+    Project,
+};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The name of the environment to copy
+    pub name: EnvironmentName,
+
+    /// The name of the new environment
+    pub new_name: EnvironmentName,
+}
+
+pub async fn execute(project: Project, args: Args) -> miette::Result<()> {
+    copy_environment(project, &args.name, &args.new_name, false).await?;
+
+    eprintln!(
+        "{}Copied environment {} to {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        args.name,
+        args.new_name
+    );
+
+    Ok(())
+}
+
+/// Copies an environment in the manifest and the lock-file, or renames it if
+/// `rename` is set.
+///
+/// The prefix of an environment contains absolute paths, so it is never moved
+/// or copied. The prefix of a renamed environment is removed instead, the new
+/// environment is installed from the package cache when it is used.
+pub(super) async fn copy_environment(
+    mut project: Project,
+    from: &EnvironmentName,
+    to: &EnvironmentName,
+    rename: bool,
+) -> miette::Result<()> {
+    let old_prefix = project.environment(from).map(|env| env.dir());
+
+    project
+        .manifest
+        .copy_environment(from.as_str(), to.as_str(), rename)?;
+
+    // Keep the locked packages, so the environment doesn't have to be solved
+    // again.
+    let lock_file_path = project.lock_file_path();
+    if lock_file_path.is_file() {
+        let lock_file = load_lock_file(&project).await?;
+        let lock_file = copy_lock_file_environment(&lock_file, from.as_str(), to.as_str(), rename);
+        let contents = relative_paths(&lock_file, project.root())
+            .render_to_string()
+            .into_diagnostic()
+            .context("failed to render the lock-file")?;
+        pixi_manifest::utils::write_atomic(&lock_file_path, contents)
+            .into_diagnostic()
+            .context("failed to write lock-file to disk")?;
+    }
+
+    project.save()?;
+
+    if let Some(old_prefix) = old_prefix.filter(|prefix| rename && prefix.exists()) {
+        fs_err::remove_dir_all(&old_prefix)
+            .into_diagnostic()
+            .with_context(|| format!("failed to remove '{}'", old_prefix.display()))?;
+    }
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::Project;
 use fancy_display::FancyDisplay;
 use itertools::Itertools;
+use pixi_consts::consts;
 
 pub async fn execute(project: Project) -> miette::Result<()> {
     println!(
@@ -8,10 +9,15 @@ pub async fn execute(project: Project) -> miette::Result<()> {
         project
             .environments()
             .iter()
-            .format_with("\n", |e, f| f(&format_args!(
-                "- {}",
-                e.name().fancy_display()
-            )))
+            .format_with("\n", |e, f| match e.solve_group() {
+                // Environments in the same solve group share a single solution.
+                Some(solve_group) => f(&format_args!(
+                    "- {} (solve-group: {})",
+                    e.name().fancy_display(),
+                    consts::SOLVE_GROUP_STYLE.apply_to(solve_group.name())
+                )),
+                None => f(&format_args!("- {}", e.name().fancy_display())),
+            })
     );
 
     Ok(())
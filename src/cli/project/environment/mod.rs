@@ -1,6 +1,8 @@
 pub mod add;
+pub mod copy;
 pub mod list;
 pub mod remove;
+pub mod rename;
 
 use crate::Project;
 use clap::Parser;
@@ -29,6 +31,12 @@ pub enum Command {
     /// Remove an environment from the manifest file.
     #[clap(visible_alias = "rm")]
     Remove(remove::Args),
+    /// Rename an environment in the manifest and the lock file.
+    #[clap(visible_alias = "mv")]
+    Rename(rename::Args),
+    /// Copy an environment in the manifest and the lock file.
+    #[clap(visible_alias = "cp")]
+    Copy(copy::Args),
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
@@ -38,5 +46,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         Command::Add(args) => add::execute(project, args).await,
         Command::List => list::execute(project).await,
         Command::Remove(args) => remove::execute(project, args).await,
+        Command::Rename(args) => rename::execute(project, args).await,
+        Command::Copy(args) => copy::execute(project, args).await,
     }
 }
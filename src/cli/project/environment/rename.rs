@@ -0,0 +1,26 @@
+use clap::Parser;
+use pixi_manifest::EnvironmentName;
+
+use crate::Project;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The name of the environment to rename
+    pub name: EnvironmentName,
+
+    /// The new name of the environment
+    pub new_name: EnvironmentName,
+}
+
+pub async fn execute(project: Project, args: Args) -> miette::Result<()> {
+    super::copy::copy_environment(project, &args.name, &args.new_name, true).await?;
+
+    eprintln!(
+        "{}Renamed environment {} to {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        args.name,
+        args.new_name
+    );
+
+    Ok(())
+}
@@ -67,7 +67,7 @@ fn format_pip_dependency(name: &PyPiPackageName, requirement: &PyPiRequirement)
             }
 
             if let Some(ref subdirectory) = git_url.subdirectory {
-                git_string.push_str(&format!("#subdirectory=={subdirectory}"));
+                git_string.push_str(&format!("#subdirectory={subdirectory}"));
             }
 
             git_string
@@ -77,7 +77,7 @@ fn format_pip_dependency(name: &PyPiPackageName, requirement: &PyPiRequirement)
             editable,
             extras,
         } => {
-            if let Some(_editable) = editable {
+            if editable.unwrap_or_default() {
                 format!(
                     "-e {path}{extras}",
                     path = path.to_string_lossy(),
@@ -104,7 +104,7 @@ fn format_pip_dependency(name: &PyPiPackageName, requirement: &PyPiRequirement)
             );
 
             if let Some(ref subdirectory) = subdirectory {
-                url_string.push_str(&format!("#subdirectory=={subdirectory}"));
+                url_string.push_str(&format!("#subdirectory={subdirectory}"));
             }
 
             url_string
@@ -246,7 +246,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use std::path::Path;
+    use std::{path::Path, str::FromStr};
 
     use super::*;
 
@@ -443,6 +443,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_pip_dependency() {
+        let name = PyPiPackageName::from_str("foo").unwrap();
+
+        let requirement = PyPiRequirement::Path {
+            path: PathBuf::from("./foo"),
+            editable: Some(false),
+            extras: Vec::new(),
+        };
+        assert_eq!(format_pip_dependency(&name, &requirement), "./foo");
+
+        let requirement = PyPiRequirement::Path {
+            path: PathBuf::from("./foo"),
+            editable: Some(true),
+            extras: Vec::new(),
+        };
+        assert_eq!(format_pip_dependency(&name, &requirement), "-e ./foo");
+
+        let requirement = PyPiRequirement::Url {
+            url: "https://example.com/foo.zip".parse().unwrap(),
+            subdirectory: Some("python".to_string()),
+            extras: Vec::new(),
+        };
+        assert_eq!(
+            format_pip_dependency(&name, &requirement),
+            "foo @ https://example.com/foo.zip#subdirectory=python"
+        );
+    }
+
     #[test]
     fn test_channels_with_nodefaults() {
         let channels = vec![NamedChannelOrUrl::Name("main".to_string())];
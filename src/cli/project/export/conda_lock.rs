@@ -0,0 +1,360 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pep508_rs::VersionOrUrl;
+use rattler_conda_types::{PackageRecord, Platform, RepoDataRecord};
+use rattler_digest::Sha256;
+use rattler_lock::{CondaPackageData, Environment, LockedPackageRef, PypiPackageData};
+use serde::Serialize;
+
+use crate::{
+    cli::cli_config::{PrefixUpdateConfig, ProjectConfig},
+    lock_file::UpdateLockFileOptions,
+    Project,
+};
+
+#[derive(Debug, Parser)]
+#[clap(arg_required_else_help = false)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// Output directory for the rendered conda-lock files
+    pub output_dir: PathBuf,
+
+    /// The environment to render. Can be repeated for multiple environments.
+    /// Defaults to all environments in the lock file.
+    #[arg(short, long)]
+    pub environment: Option<Vec<String>>,
+
+    /// The platform to render. Can be repeated for multiple platforms.
+    /// Defaults to all platforms available for selected environments.
+    #[arg(short, long)]
+    pub platform: Option<Vec<Platform>>,
+
+    /// Source dependencies are not supported in a conda-lock file.
+    #[arg(long, default_value = "false")]
+    pub ignore_source_errors: bool,
+
+    #[clap(flatten)]
+    pub prefix_update_config: PrefixUpdateConfig,
+}
+
+/// A lock file in the conda-lock v1 format.
+#[derive(Debug, Serialize)]
+struct CondaLock {
+    version: u32,
+    metadata: CondaLockMetadata,
+    package: Vec<CondaLockPackage>,
+}
+
+#[derive(Debug, Serialize)]
+struct CondaLockMetadata {
+    content_hash: BTreeMap<String, String>,
+    channels: Vec<CondaLockChannel>,
+    platforms: Vec<String>,
+    sources: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CondaLockChannel {
+    url: String,
+    used_env_vars: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CondaLockPackage {
+    name: String,
+    version: String,
+    manager: &'static str,
+    platform: String,
+    dependencies: BTreeMap<String, String>,
+    url: String,
+    hash: CondaLockHash,
+    category: &'static str,
+    optional: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CondaLockHash {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    md5: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+}
+
+impl CondaLockPackage {
+    fn from_conda(record: &RepoDataRecord, platform: Platform) -> Self {
+        let package = &record.package_record;
+        let dependencies = package
+            .depends
+            .iter()
+            .map(|dependency| match dependency.split_once(' ') {
+                Some((name, spec)) => (name.to_string(), spec.trim().to_string()),
+                None => (dependency.clone(), String::from("*")),
+            })
+            .collect();
+
+        Self {
+            name: package.name.as_normalized().to_string(),
+            version: package.version.to_string(),
+            manager: "conda",
+            platform: platform.to_string(),
+            dependencies,
+            url: record.url.to_string(),
+            hash: CondaLockHash {
+                md5: package.md5.map(|hash| format!("{:x}", hash)),
+                sha256: package.sha256.map(|hash| format!("{:x}", hash)),
+            },
+            category: "main",
+            optional: false,
+        }
+    }
+
+    fn from_pypi(package: &PypiPackageData, platform: Platform) -> Self {
+        let dependencies = package
+            .requires_dist
+            .iter()
+            .map(|requirement| {
+                let spec = match &requirement.version_or_url {
+                    Some(VersionOrUrl::VersionSpecifier(specifiers)) => specifiers.to_string(),
+                    Some(VersionOrUrl::Url(url)) => url.to_string(),
+                    None => String::from("*"),
+                };
+                (requirement.name.to_string(), spec)
+            })
+            .collect();
+
+        Self {
+            name: package.name.to_string(),
+            version: package.version.to_string(),
+            manager: "pip",
+            platform: platform.to_string(),
+            dependencies,
+            url: package.location.to_string(),
+            hash: CondaLockHash {
+                md5: package
+                    .hash
+                    .as_ref()
+                    .and_then(|hash| hash.md5())
+                    .map(|hash| format!("{:x}", hash)),
+                sha256: package
+                    .hash
+                    .as_ref()
+                    .and_then(|hash| hash.sha256())
+                    .map(|hash| format!("{:x}", hash)),
+            },
+            category: "main",
+            optional: false,
+        }
+    }
+}
+
+/// Converts the packages of an environment from the lock file into a
+/// conda-lock file.
+fn build_conda_lock(
+    env_name: &str,
+    env: &Environment,
+    platforms: &[Platform],
+    ignore_source_errors: bool,
+) -> miette::Result<CondaLock> {
+    let mut content_hash = BTreeMap::new();
+    let mut packages = Vec::new();
+
+    for &platform in platforms {
+        let locked_packages = env.packages(platform).ok_or(miette::miette!(
+            "platform '{platform}' not found for env {}",
+            env_name,
+        ))?;
+
+        let mut conda_packages = Vec::new();
+        let mut pypi_packages = Vec::new();
+        for package in locked_packages {
+            match package {
+                LockedPackageRef::Conda(CondaPackageData::Binary(p)) => {
+                    conda_packages.push(RepoDataRecord::try_from(p.clone()).into_diagnostic()?)
+                }
+                LockedPackageRef::Conda(CondaPackageData::Source(p)) => {
+                    if ignore_source_errors {
+                        tracing::warn!(
+                            "ignoring source package {} since source packages are not supported",
+                            p.package_record.name.as_source()
+                        );
+                    } else {
+                        miette::bail!(
+                            "Conda source packages are not supported in a conda-lock file. \
+                            Specify `--ignore-source-errors` to ignore this error and create \
+                            a conda-lock file containing only the binary dependencies from the lockfile."
+                        );
+                    }
+                }
+                LockedPackageRef::Pypi(p, _) => pypi_packages.push(p),
+            }
+        }
+
+        let platform_packages = PackageRecord::sort_topologically(conda_packages)
+            .iter()
+            .map(|record| CondaLockPackage::from_conda(record, platform))
+            .chain(
+                pypi_packages
+                    .into_iter()
+                    .map(|package| CondaLockPackage::from_pypi(package, platform)),
+            )
+            .collect_vec();
+
+        // conda-lock uses the content hash to determine whether the lock file is
+        // outdated, we use the locked urls since the input specs are not
+        // available in the conda-lock format.
+        let urls = platform_packages
+            .iter()
+            .map(|package| package.url.as_str())
+            .sorted()
+            .join("\n");
+        content_hash.insert(
+            platform.to_string(),
+            format!(
+                "{:x}",
+                rattler_digest::compute_bytes_digest::<Sha256>(urls.as_bytes())
+            ),
+        );
+
+        packages.extend(platform_packages);
+    }
+
+    Ok(CondaLock {
+        version: 1,
+        metadata: CondaLockMetadata {
+            content_hash,
+            channels: env
+                .channels()
+                .iter()
+                .map(|channel| CondaLockChannel {
+                    url: channel.url.clone(),
+                    used_env_vars: Vec::new(),
+                })
+                .collect(),
+            platforms: platforms.iter().map(Platform::to_string).collect(),
+            sources: vec![String::from("pixi.lock")],
+        },
+        package: packages,
+    })
+}
+
+fn render_conda_lock(target: &Path, conda_lock: &CondaLock) -> miette::Result<()> {
+    let mut contents = String::new();
+    contents.push_str("# Generated by `pixi project export`\n");
+    contents.push_str(&serde_yaml::to_string(conda_lock).into_diagnostic()?);
+
+    fs_err::write(target, contents)
+        .into_diagnostic()
+        .with_context(|| format!("failed to write conda-lock file: {}", target.display()))
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+        .with_cli_config(args.prefix_update_config.config.clone());
+
+    let lockfile = project
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.prefix_update_config.lock_file_usage(),
+            no_install: args.prefix_update_config.no_install,
+            max_concurrent_solves: project.config().max_concurrent_solves(),
+        })
+        .await?
+        .lock_file;
+
+    let environments = match &args.environment {
+        Some(env_names) => env_names
+            .iter()
+            .map(|env_name| {
+                lockfile
+                    .environment(env_name)
+                    .map(|env| (env_name.as_str(), env))
+                    .ok_or(miette::miette!("unknown environment {}", env_name))
+            })
+            .collect::<miette::Result<Vec<_>>>()?,
+        None => lockfile.environments().collect(),
+    };
+
+    fs_err::create_dir_all(&args.output_dir).into_diagnostic()?;
+
+    for (env_name, env) in environments {
+        let platforms = env
+            .platforms()
+            .filter(|platform| {
+                args.platform
+                    .as_ref()
+                    .map_or(true, |platforms| platforms.contains(platform))
+            })
+            .sorted_by_key(|platform| platform.as_str())
+            .collect_vec();
+        if platforms.is_empty() {
+            tracing::warn!("No platforms selected for environment {env_name}. Skipping...");
+            continue;
+        }
+
+        tracing::info!("Creating conda-lock file for env: {env_name}");
+        let conda_lock = build_conda_lock(env_name, &env, &platforms, args.ignore_source_errors)?;
+        render_conda_lock(
+            &args.output_dir.join(format!("{env_name}.conda-lock.yml")),
+            &conda_lock,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use rattler_lock::LockFile;
+
+    use super::*;
+
+    #[test]
+    fn test_build_conda_lock() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mock-projects/test-project-export/pixi.lock");
+        let lockfile = LockFile::from_path(&path).unwrap();
+        let env = lockfile.default_environment().unwrap();
+
+        let conda_lock = build_conda_lock("default", &env, &[Platform::Linux64], false).unwrap();
+        assert_eq!(conda_lock.version, 1);
+        assert_eq!(conda_lock.metadata.platforms, vec!["linux-64"]);
+        assert_eq!(
+            conda_lock.metadata.channels[0].url,
+            "https://conda.anaconda.org/conda-forge/"
+        );
+
+        let python = conda_lock
+            .package
+            .iter()
+            .find(|package| package.name == "python")
+            .unwrap();
+        assert_eq!(python.manager, "conda");
+        assert_eq!(python.platform, "linux-64");
+        assert!(python.hash.md5.is_some());
+        assert!(python.hash.sha256.is_some());
+        assert!(python.dependencies.contains_key("bzip2"));
+
+        let rich = conda_lock
+            .package
+            .iter()
+            .find(|package| package.name == "rich")
+            .unwrap();
+        assert_eq!(rich.manager, "pip");
+        assert!(rich.url.ends_with("rich-13.8.0-py3-none-any.whl"));
+
+        // Every package should only be rendered for the selected platform.
+        assert!(conda_lock
+            .package
+            .iter()
+            .all(|package| package.platform == "linux-64"));
+    }
+}
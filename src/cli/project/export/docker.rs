@@ -0,0 +1,151 @@
+use std::{fmt::Write, path::PathBuf};
+
+use clap::Parser;
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use pixi_manifest::EnvironmentName;
+use rattler_conda_types::Platform;
+
+use crate::{cli::cli_config::ProjectConfig, container::container_platform, Project};
+
+/// The base image of the production stage when none is specified.
+const DEFAULT_BASE_IMAGE: &str = "ubuntu:24.04";
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// Explicit path to export the Dockerfile to, defaults to stdout
+    pub output_path: Option<PathBuf>,
+
+    /// The environment to install in the image.
+    /// Defaults to the default environment.
+    #[arg(short, long)]
+    pub environment: Option<String>,
+
+    /// The platform of the image, only linux platforms are supported.
+    /// Defaults to `linux-64`.
+    #[arg(short, long, default_value_t = Platform::Linux64)]
+    pub platform: Platform,
+
+    /// The base image of the final image that contains the environment.
+    #[arg(long, default_value = DEFAULT_BASE_IMAGE)]
+    pub base_image: String,
+}
+
+/// Renders a multi-stage Dockerfile. The first stage installs the locked
+/// environment with pixi, the second stage only contains the environment and
+/// a script that activates it.
+fn render_dockerfile(
+    environment: &EnvironmentName,
+    platform: Platform,
+    base_image: &str,
+) -> miette::Result<String> {
+    let docker_platform = container_platform(platform)?;
+    let env_name = environment.as_str();
+    let env_dir = format!(
+        "/app/{}/{}/{}",
+        consts::PIXI_DIR,
+        consts::ENVIRONMENTS_DIR,
+        env_name
+    );
+
+    let mut dockerfile = String::new();
+    let _ = writeln!(dockerfile, "# Generated by `pixi project export docker`");
+    let _ = writeln!(
+        dockerfile,
+        "FROM --platform={docker_platform} ghcr.io/prefix-dev/pixi:{} AS build",
+        consts::PIXI_VERSION
+    );
+    let _ = writeln!(dockerfile);
+    let _ = writeln!(dockerfile, "# copy the project, including pixi.lock");
+    let _ = writeln!(dockerfile, "WORKDIR /app");
+    let _ = writeln!(dockerfile, "COPY . .");
+    let _ = writeln!(
+        dockerfile,
+        "# install the locked packages of the environment to `{env_dir}`"
+    );
+    let _ = writeln!(dockerfile, "RUN pixi install --frozen -e {env_name}");
+    let _ = writeln!(
+        dockerfile,
+        "# create an entrypoint that activates the environment"
+    );
+    let _ = writeln!(dockerfile, "RUN echo \"#!/bin/bash\" > /app/entrypoint.sh");
+    let _ = writeln!(
+        dockerfile,
+        "RUN pixi shell-hook --frozen -e {env_name} -s bash >> /app/entrypoint.sh"
+    );
+    let _ = writeln!(dockerfile, "RUN echo 'exec \"$@\"' >> /app/entrypoint.sh");
+    let _ = writeln!(dockerfile);
+    let _ = writeln!(
+        dockerfile,
+        "FROM --platform={docker_platform} {base_image} AS production"
+    );
+    let _ = writeln!(dockerfile, "WORKDIR /app");
+    let _ = writeln!(
+        dockerfile,
+        "# the environment has to stay at the same location as in the build stage"
+    );
+    let _ = writeln!(dockerfile, "COPY --from=build {env_dir} {env_dir}");
+    let _ = writeln!(
+        dockerfile,
+        "COPY --from=build --chmod=0755 /app/entrypoint.sh /app/entrypoint.sh"
+    );
+    let _ = writeln!(dockerfile, "ENTRYPOINT [ \"/app/entrypoint.sh\" ]");
+
+    Ok(dockerfile)
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+    let environment = project.environment_from_name_or_env_var(args.environment)?;
+
+    if !environment.platforms().contains(&args.platform) {
+        miette::bail!(
+            "the environment '{}' does not support the platform '{}'",
+            environment.name(),
+            args.platform
+        );
+    }
+    if !project.lock_file_path().is_file() {
+        miette::bail!(
+            help = "run `pixi lock` to create the lock file",
+            "the Dockerfile installs the environment from the lock file, but there is no lock file yet"
+        );
+    }
+
+    let dockerfile = render_dockerfile(environment.name(), args.platform, &args.base_image)?;
+
+    if let Some(output_path) = args.output_path {
+        fs_err::write(&output_path, dockerfile)
+            .into_diagnostic()
+            .with_context(|| format!("failed to write Dockerfile: {}", output_path.display()))?;
+    } else {
+        print!("{}", dockerfile);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_dockerfile() {
+        let dockerfile = render_dockerfile(
+            &EnvironmentName::Named("prod".to_string()),
+            Platform::LinuxAarch64,
+            DEFAULT_BASE_IMAGE,
+        )
+        .unwrap();
+
+        assert!(dockerfile.contains("FROM --platform=linux/arm64 ghcr.io/prefix-dev/pixi:"));
+        assert!(dockerfile.contains("RUN pixi install --frozen -e prod\n"));
+        assert!(dockerfile.contains("FROM --platform=linux/arm64 ubuntu:24.04 AS production\n"));
+        assert!(
+            dockerfile.contains("COPY --from=build /app/.pixi/envs/prod /app/.pixi/envs/prod\n")
+        );
+    }
+}
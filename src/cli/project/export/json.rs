@@ -0,0 +1,198 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use indexmap::IndexMap;
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use pixi_manifest::{
+    pypi::pypi_options::PypiOptions, FeaturesExt, HasFeaturesIter, PyPiRequirement, SpecType,
+};
+use pixi_spec::PixiSpec;
+use rattler_conda_types::{GenericVirtualPackage, Platform};
+use serde::Serialize;
+
+use crate::{
+    cli::{cli_config::ProjectConfig, task::TaskInfo},
+    project::Environment,
+    Project,
+};
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// Explicit path to export the manifest to
+    pub output_path: Option<PathBuf>,
+
+    /// The platform to resolve the targets for.
+    /// Defaults to the current platform.
+    #[arg(short, long)]
+    pub platform: Option<Platform>,
+
+    /// The environment to export.
+    /// Defaults to the default environment.
+    #[arg(short, long)]
+    pub environment: Option<String>,
+}
+
+/// The manifest of an environment for a single platform. The field names are
+/// part of the output format, so they should not change.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ResolvedManifest {
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+    manifest_path: PathBuf,
+    environment: String,
+    platform: Platform,
+    features: Vec<String>,
+    channels: Vec<String>,
+    platforms: Vec<Platform>,
+    virtual_packages: Vec<String>,
+    dependencies: IndexMap<String, Vec<PixiSpec>>,
+    host_dependencies: IndexMap<String, Vec<PixiSpec>>,
+    build_dependencies: IndexMap<String, Vec<PixiSpec>>,
+    pypi_dependencies: IndexMap<String, Vec<PyPiRequirement>>,
+    pypi_options: PypiOptions,
+    activation: ResolvedActivation,
+    tasks: IndexMap<String, TaskInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct ResolvedActivation {
+    scripts: Vec<String>,
+    env: IndexMap<String, String>,
+}
+
+fn resolve_manifest(
+    project: &Project,
+    environment: &Environment<'_>,
+    platform: Platform,
+) -> miette::Result<ResolvedManifest> {
+    let conda_dependencies = |kind: SpecType| -> IndexMap<String, Vec<PixiSpec>> {
+        environment
+            .dependencies(kind, Some(platform))
+            .iter()
+            .map(|(name, specs)| {
+                (
+                    name.as_source().to_string(),
+                    specs.iter().cloned().collect_vec(),
+                )
+            })
+            .collect()
+    };
+
+    Ok(ResolvedManifest {
+        name: project.name().to_string(),
+        version: project
+            .version()
+            .as_ref()
+            .map(|version| version.to_string()),
+        description: project.description().clone(),
+        manifest_path: project.manifest_path(),
+        environment: environment.name().to_string(),
+        platform,
+        features: environment
+            .features()
+            .map(|feature| feature.name.as_str().to_string())
+            .collect(),
+        channels: environment
+            .channels()
+            .into_iter()
+            .map(|channel| channel.to_string())
+            .collect(),
+        platforms: environment
+            .platforms()
+            .into_iter()
+            .sorted_by_key(|platform| platform.as_str())
+            .collect(),
+        virtual_packages: environment
+            .virtual_packages(platform)
+            .into_iter()
+            .map(GenericVirtualPackage::from)
+            .map(|package| {
+                format!(
+                    "{}={}={}",
+                    package.name.as_normalized(),
+                    package.version,
+                    package.build_string
+                )
+            })
+            .collect(),
+        dependencies: conda_dependencies(SpecType::Run),
+        host_dependencies: conda_dependencies(SpecType::Host),
+        build_dependencies: conda_dependencies(SpecType::Build),
+        pypi_dependencies: environment
+            .pypi_dependencies(Some(platform))
+            .iter()
+            .map(|(name, requirements)| {
+                (
+                    name.as_source().to_string(),
+                    requirements.iter().cloned().collect_vec(),
+                )
+            })
+            .collect(),
+        pypi_options: environment.pypi_options(),
+        activation: ResolvedActivation {
+            scripts: environment.activation_scripts(Some(platform)),
+            env: environment.activation_env(Some(platform)),
+        },
+        tasks: environment
+            .tasks(Some(platform))?
+            .into_iter()
+            .sorted_by_key(|(name, _)| *name)
+            .map(|(name, task)| (name.to_string(), TaskInfo::from(task)))
+            .collect(),
+    })
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+    let environment = project.environment_from_name_or_env_var(args.environment)?;
+    let platform = args.platform.unwrap_or_else(|| environment.best_platform());
+
+    let manifest = resolve_manifest(&project, &environment, platform)?;
+    let json = serde_json::to_string_pretty(&manifest).into_diagnostic()?;
+
+    if let Some(output_path) = args.output_path {
+        fs_err::write(output_path, json).into_diagnostic()?;
+    } else {
+        println!("{}", json);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_manifest() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mock-projects/test-project-export/pixi.toml");
+        let project = Project::from_path(&path).unwrap();
+        let environment = project.environment("test").unwrap();
+
+        let manifest = resolve_manifest(&project, &environment, Platform::Linux64).unwrap();
+        assert_eq!(manifest.features, vec!["test", "default"]);
+        assert_eq!(
+            manifest.dependencies.keys().sorted().collect_vec(),
+            vec!["ca-certificates", "pytest", "python", "requests"]
+        );
+        assert!(manifest.pypi_dependencies.contains_key("rich"));
+
+        // The dependencies of other platforms are not part of the manifest
+        let manifest = resolve_manifest(&project, &environment, Platform::Osx64).unwrap();
+        assert!(!manifest.dependencies.contains_key("requests"));
+        assert!(manifest.dependencies.contains_key("pyyaml"));
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["environment"], "test");
+        assert_eq!(json["platform"], "osx-64");
+    }
+}
@@ -1,5 +1,9 @@
 pub mod conda_environment;
 pub mod conda_explicit_spec;
+pub mod conda_lock;
+pub mod docker;
+pub mod json;
+pub mod sbom;
 
 use clap::Parser;
 
@@ -17,12 +21,24 @@ pub enum Command {
     CondaExplicitSpec(conda_explicit_spec::Args),
     /// Export project environment to a conda environment.yaml file
     CondaEnvironment(conda_environment::Args),
+    /// Export project environments to conda-lock v1 files
+    CondaLock(conda_lock::Args),
+    /// Export a Dockerfile that installs a project environment into an image
+    Docker(docker::Args),
+    /// Export the manifest of an environment as JSON, resolved for a platform
+    Json(json::Args),
+    /// Export a software bill of materials (SBOM) of the locked packages
+    Sbom(sbom::Args),
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
     match args.command {
         Command::CondaExplicitSpec(args) => conda_explicit_spec::execute(args).await?,
         Command::CondaEnvironment(args) => conda_environment::execute(args).await?,
+        Command::CondaLock(args) => conda_lock::execute(args).await?,
+        Command::Docker(args) => docker::execute(args).await?,
+        Command::Json(args) => json::execute(args).await?,
+        Command::Sbom(args) => sbom::execute(args).await?,
     };
     Ok(())
 }
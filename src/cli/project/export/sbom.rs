@@ -0,0 +1,599 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::{Parser, ValueEnum};
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use rattler_conda_types::{Platform, RepoDataRecord};
+use rattler_digest::Sha256;
+use rattler_lock::{CondaPackageData, Environment, LockedPackageRef, PypiPackageData};
+use serde::Serialize;
+
+use crate::{
+    cli::cli_config::{PrefixUpdateConfig, ProjectConfig},
+    lock_file::UpdateLockFileOptions,
+    Project,
+};
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// Explicit path to export the SBOM to, defaults to stdout
+    pub output_path: Option<PathBuf>,
+
+    /// The format of the SBOM
+    #[arg(long, value_enum, default_value_t = SbomFormat::Cyclonedx)]
+    pub format: SbomFormat,
+
+    /// The environment to include in the SBOM. Can be repeated for multiple
+    /// environments. Defaults to all environments in the lock file.
+    #[arg(short, long)]
+    pub environment: Option<Vec<String>>,
+
+    /// The platform to include in the SBOM. Can be repeated for multiple
+    /// platforms. Defaults to all platforms of the selected environments.
+    #[arg(short, long)]
+    pub platform: Option<Vec<Platform>>,
+
+    #[clap(flatten)]
+    pub prefix_update_config: PrefixUpdateConfig,
+}
+
+/// The supported SBOM formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 in JSON
+    Cyclonedx,
+    /// SPDX 2.3 in JSON
+    Spdx,
+}
+
+/// A locked package as it is described in the SBOM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SbomPackage {
+    name: String,
+    version: String,
+    purl: String,
+    url: String,
+    channel: Option<String>,
+    license: Option<String>,
+    md5: Option<String>,
+    sha256: Option<String>,
+}
+
+impl SbomPackage {
+    fn from_conda(record: &RepoDataRecord) -> Self {
+        let package = &record.package_record;
+        let mut qualifiers = vec![
+            format!("build={}", package.build),
+            format!("subdir={}", package.subdir),
+        ];
+        if let Some(channel) = &record.channel {
+            qualifiers.push(format!("channel={}", purl_encode(channel)));
+        }
+        let archive_type = if record.file_name.ends_with(".tar.bz2") {
+            "tar.bz2"
+        } else {
+            "conda"
+        };
+        qualifiers.push(format!("type={archive_type}"));
+
+        Self {
+            name: package.name.as_normalized().to_string(),
+            version: package.version.to_string(),
+            purl: format!(
+                "pkg:conda/{}@{}?{}",
+                package.name.as_normalized(),
+                purl_encode(&package.version.to_string()),
+                qualifiers.join("&")
+            ),
+            url: record.url.to_string(),
+            channel: record.channel.clone(),
+            license: package.license.clone(),
+            md5: package.md5.map(|hash| format!("{:x}", hash)),
+            sha256: package.sha256.map(|hash| format!("{:x}", hash)),
+        }
+    }
+
+    fn from_pypi(package: &PypiPackageData) -> Self {
+        Self {
+            purl: format!(
+                "pkg:pypi/{}@{}",
+                package.name,
+                purl_encode(&package.version.to_string())
+            ),
+            name: package.name.to_string(),
+            version: package.version.to_string(),
+            url: package.location.to_string(),
+            channel: None,
+            license: None,
+            md5: package
+                .hash
+                .as_ref()
+                .and_then(|hash| hash.md5())
+                .map(|hash| format!("{:x}", hash)),
+            sha256: package
+                .hash
+                .as_ref()
+                .and_then(|hash| hash.sha256())
+                .map(|hash| format!("{:x}", hash)),
+        }
+    }
+}
+
+/// Percent-encodes the characters that are not allowed in a purl component.
+fn purl_encode(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' | '~' => c.to_string(),
+            _ => c
+                .to_string()
+                .bytes()
+                .map(|b| format!("%{:02X}", b))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Collects the unique packages of the given environments and platforms from
+/// the lock file, sorted by name and version.
+fn collect_packages(
+    environments: &[(&str, Environment)],
+    platforms: Option<&[Platform]>,
+) -> miette::Result<Vec<SbomPackage>> {
+    let mut packages = BTreeMap::new();
+    for (env_name, env) in environments {
+        let env_platforms = env
+            .platforms()
+            .filter(|platform| platforms.map_or(true, |platforms| platforms.contains(platform)))
+            .collect_vec();
+        if env_platforms.is_empty() {
+            tracing::warn!("No platforms selected for environment {env_name}. Skipping...");
+        }
+
+        for platform in env_platforms {
+            for package in env.packages(platform).into_iter().flatten() {
+                let package = match package {
+                    LockedPackageRef::Conda(CondaPackageData::Binary(data)) => {
+                        SbomPackage::from_conda(
+                            &RepoDataRecord::try_from(data.clone()).into_diagnostic()?,
+                        )
+                    }
+                    LockedPackageRef::Conda(CondaPackageData::Source(data)) => {
+                        tracing::warn!(
+                            "ignoring source package {} since it has no archive to describe",
+                            data.package_record.name.as_source()
+                        );
+                        continue;
+                    }
+                    LockedPackageRef::Pypi(data, _) => SbomPackage::from_pypi(data),
+                };
+                packages.entry(package.url.clone()).or_insert(package);
+            }
+        }
+    }
+
+    Ok(packages
+        .into_values()
+        .sorted_by(|a, b| (&a.name, &a.version, &a.purl).cmp(&(&b.name, &b.version, &b.purl)))
+        .collect())
+}
+
+/// A CycloneDX document, see <https://cyclonedx.org/docs/1.5/json/>.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CycloneDx {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: CycloneDxMetadata,
+    components: Vec<CycloneDxComponent>,
+}
+
+#[derive(Debug, Serialize)]
+struct CycloneDxMetadata {
+    timestamp: String,
+    tools: CycloneDxTools,
+    component: CycloneDxComponent,
+}
+
+#[derive(Debug, Serialize)]
+struct CycloneDxTools {
+    components: Vec<CycloneDxComponent>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CycloneDxComponent {
+    #[serde(rename = "type")]
+    component_type: &'static str,
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none")]
+    bom_ref: Option<String>,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<CycloneDxHash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<CycloneDxLicense>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_references: Vec<CycloneDxReference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<CycloneDxProperty>,
+}
+
+#[derive(Debug, Serialize)]
+struct CycloneDxHash {
+    alg: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CycloneDxProperty {
+    name: &'static str,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CycloneDxLicense {
+    expression: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CycloneDxReference {
+    #[serde(rename = "type")]
+    reference_type: &'static str,
+    url: String,
+}
+
+fn build_cyclonedx(
+    project_name: &str,
+    project_version: Option<String>,
+    timestamp: String,
+    packages: &[SbomPackage],
+) -> CycloneDx {
+    let components = packages
+        .iter()
+        .map(|package| {
+            let hashes = [("SHA-256", &package.sha256), ("MD5", &package.md5)]
+                .into_iter()
+                .filter_map(|(alg, hash)| {
+                    hash.clone().map(|content| CycloneDxHash { alg, content })
+                })
+                .collect();
+            CycloneDxComponent {
+                component_type: "library",
+                bom_ref: Some(package.purl.clone()),
+                name: package.name.clone(),
+                version: Some(package.version.clone()),
+                purl: Some(package.purl.clone()),
+                hashes,
+                licenses: package
+                    .license
+                    .iter()
+                    .map(|license| CycloneDxLicense {
+                        expression: license.clone(),
+                    })
+                    .collect(),
+                external_references: vec![CycloneDxReference {
+                    reference_type: "distribution",
+                    url: package.url.clone(),
+                }],
+                properties: package
+                    .channel
+                    .iter()
+                    .map(|channel| CycloneDxProperty {
+                        name: "pixi:channel",
+                        value: channel.clone(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    CycloneDx {
+        bom_format: "CycloneDX",
+        spec_version: "1.5",
+        version: 1,
+        metadata: CycloneDxMetadata {
+            timestamp,
+            tools: CycloneDxTools {
+                components: vec![CycloneDxComponent {
+                    component_type: "application",
+                    name: String::from("pixi"),
+                    version: Some(consts::PIXI_VERSION.to_string()),
+                    ..CycloneDxComponent::default()
+                }],
+            },
+            component: CycloneDxComponent {
+                component_type: "application",
+                name: project_name.to_string(),
+                version: project_version,
+                ..CycloneDxComponent::default()
+            },
+        },
+        components,
+    }
+}
+
+/// An SPDX document, see <https://spdx.github.io/spdx-spec/v2.3/>.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Spdx {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: SpdxCreationInfo,
+    packages: Vec<SpdxPackage>,
+    relationships: Vec<SpdxRelationship>,
+}
+
+#[derive(Debug, Serialize)]
+struct SpdxCreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    version_info: String,
+    download_location: String,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checksums: Vec<SpdxChecksum>,
+    license_concluded: &'static str,
+    license_declared: String,
+    external_refs: Vec<SpdxExternalRef>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxChecksum {
+    algorithm: &'static str,
+    checksum_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxRelationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+/// Returns a valid SPDX identifier for the package, only letters, numbers, `.`
+/// and `-` are allowed.
+fn spdx_id(index: usize, package: &SbomPackage) -> String {
+    let name = package
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    format!("SPDXRef-Package-{index}-{name}")
+}
+
+fn build_spdx(project_name: &str, timestamp: String, packages: &[SbomPackage]) -> Spdx {
+    // The namespace has to be unique for the contents of the document.
+    let content_hash = rattler_digest::compute_bytes_digest::<Sha256>(
+        packages
+            .iter()
+            .map(|package| package.url.as_str())
+            .join("\n")
+            .as_bytes(),
+    );
+
+    let spdx_packages = packages
+        .iter()
+        .enumerate()
+        .map(|(index, package)| SpdxPackage {
+            name: package.name.clone(),
+            spdx_id: spdx_id(index, package),
+            version_info: package.version.clone(),
+            download_location: package.url.clone(),
+            files_analyzed: false,
+            checksums: [("SHA256", &package.sha256), ("MD5", &package.md5)]
+                .into_iter()
+                .filter_map(|(algorithm, hash)| {
+                    hash.clone().map(|checksum_value| SpdxChecksum {
+                        algorithm,
+                        checksum_value,
+                    })
+                })
+                .collect(),
+            license_concluded: "NOASSERTION",
+            license_declared: package
+                .license
+                .clone()
+                .unwrap_or_else(|| String::from("NOASSERTION")),
+            external_refs: vec![SpdxExternalRef {
+                reference_category: "PACKAGE-MANAGER",
+                reference_type: "purl",
+                reference_locator: package.purl.clone(),
+            }],
+        })
+        .collect_vec();
+
+    let relationships = spdx_packages
+        .iter()
+        .map(|package| SpdxRelationship {
+            spdx_element_id: String::from("SPDXRef-DOCUMENT"),
+            relationship_type: "DESCRIBES",
+            related_spdx_element: package.spdx_id.clone(),
+        })
+        .collect();
+
+    Spdx {
+        spdx_version: "SPDX-2.3",
+        data_license: "CC0-1.0",
+        spdx_id: "SPDXRef-DOCUMENT",
+        name: project_name.to_string(),
+        document_namespace: format!(
+            "https://pixi.sh/spdx/{}-{:x}",
+            purl_encode(project_name),
+            content_hash
+        ),
+        creation_info: SpdxCreationInfo {
+            created: timestamp,
+            creators: vec![format!("Tool: pixi-{}", consts::PIXI_VERSION)],
+        },
+        packages: spdx_packages,
+        relationships,
+    }
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+        .with_cli_config(args.prefix_update_config.config.clone());
+
+    let lockfile = project
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.prefix_update_config.lock_file_usage(),
+            no_install: args.prefix_update_config.no_install,
+            max_concurrent_solves: project.config().max_concurrent_solves(),
+        })
+        .await?
+        .lock_file;
+
+    let environments = match &args.environment {
+        Some(env_names) => env_names
+            .iter()
+            .map(|env_name| {
+                lockfile
+                    .environment(env_name)
+                    .map(|env| (env_name.as_str(), env))
+                    .ok_or(miette::miette!("unknown environment {}", env_name))
+            })
+            .collect::<miette::Result<Vec<_>>>()?,
+        None => lockfile.environments().collect(),
+    };
+
+    let packages = collect_packages(&environments, args.platform.as_deref())?;
+    let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let contents = match args.format {
+        SbomFormat::Cyclonedx => serde_json::to_string_pretty(&build_cyclonedx(
+            project.name(),
+            project.version().as_ref().map(ToString::to_string),
+            timestamp,
+            &packages,
+        )),
+        SbomFormat::Spdx => {
+            serde_json::to_string_pretty(&build_spdx(project.name(), timestamp, &packages))
+        }
+    }
+    .into_diagnostic()?;
+
+    if let Some(output_path) = args.output_path {
+        fs_err::write(&output_path, contents)
+            .into_diagnostic()
+            .with_context(|| format!("failed to write SBOM: {}", output_path.display()))?;
+    } else {
+        println!("{}", contents);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use rattler_lock::LockFile;
+
+    use super::*;
+
+    fn test_packages() -> Vec<SbomPackage> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mock-projects/test-project-export/pixi.lock");
+        let lockfile = LockFile::from_path(&path).unwrap();
+        let env = lockfile.default_environment().unwrap();
+        collect_packages(&[("default", env)], Some(&[Platform::Linux64])).unwrap()
+    }
+
+    #[test]
+    fn test_collect_packages() {
+        let packages = test_packages();
+
+        let python = packages
+            .iter()
+            .find(|package| package.name == "python")
+            .unwrap();
+        assert!(python.purl.starts_with("pkg:conda/python@"));
+        assert!(python.purl.contains("subdir=linux-64"));
+        assert!(python.sha256.is_some());
+        assert!(python.license.is_some());
+        assert!(python
+            .channel
+            .as_deref()
+            .is_some_and(|channel| channel.starts_with("https://conda.anaconda.org/conda-forge")));
+
+        let rich = packages
+            .iter()
+            .find(|package| package.name == "rich")
+            .unwrap();
+        assert_eq!(rich.purl, "pkg:pypi/rich@13.8.0");
+
+        // Every package is only described once.
+        assert!(packages.iter().map(|package| &package.url).all_unique());
+    }
+
+    #[test]
+    fn test_build_sbom() {
+        let packages = test_packages();
+        let timestamp = String::from("2024-01-01T00:00:00Z");
+
+        let cyclonedx =
+            serde_json::to_value(build_cyclonedx("test", None, timestamp.clone(), &packages))
+                .unwrap();
+        assert_eq!(cyclonedx["bomFormat"], "CycloneDX");
+        assert_eq!(
+            cyclonedx["components"].as_array().unwrap().len(),
+            packages.len()
+        );
+        let component = &cyclonedx["components"][0];
+        assert_eq!(component["bom-ref"], component["purl"]);
+
+        let spdx = serde_json::to_value(build_spdx("test", timestamp, &packages)).unwrap();
+        assert_eq!(spdx["spdxVersion"], "SPDX-2.3");
+        assert_eq!(spdx["packages"].as_array().unwrap().len(), packages.len());
+        assert_eq!(
+            spdx["relationships"].as_array().unwrap().len(),
+            packages.len()
+        );
+    }
+
+    #[test]
+    fn test_purl_encode() {
+        assert_eq!(purl_encode("1.2.3"), "1.2.3");
+        assert_eq!(purl_encode("1.0+local"), "1.0%2Blocal");
+        assert_eq!(
+            purl_encode("https://conda.anaconda.org/conda-forge/"),
+            "https%3A%2F%2Fconda.anaconda.org%2Fconda-forge%2F"
+        );
+    }
+}
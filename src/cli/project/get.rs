@@ -0,0 +1,43 @@
+use clap::Parser;
+use toml_edit::{Item, Value};
+
+use crate::{cli::cli_config::ProjectConfig, Project};
+
+/// Get a value from the manifest.
+///
+/// The key is a dotted path relative to the pixi configuration, e.g.
+/// `project.version` or `feature.test.dependencies.pytest`.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// The dotted key of the value, e.g. `project.version`
+    pub key: String,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+
+    let Some(item) = project.manifest.source.get_item(&args.key)? else {
+        miette::bail!(
+            "'{}' is not set in {}",
+            args.key,
+            project.manifest_path().display()
+        );
+    };
+
+    // Strings are printed without quotes so they can be used directly in
+    // scripts, everything else is printed as TOML.
+    match item {
+        Item::Value(Value::String(value)) => println!("{}", value.value()),
+        Item::Value(value) => {
+            let mut value = value.clone();
+            value.decor_mut().clear();
+            println!("{}", value)
+        }
+        item => print!("{}", item),
+    }
+
+    Ok(())
+}
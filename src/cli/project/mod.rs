@@ -1,12 +1,15 @@
 use crate::cli::cli_config::ProjectConfig;
+use crate::Project;
 use clap::Parser;
 
 pub mod channel;
 pub mod description;
 pub mod environment;
 pub mod export;
+pub mod get;
 pub mod name;
 pub mod platform;
+pub mod set;
 pub mod version;
 
 #[derive(Debug, Parser)]
@@ -18,6 +21,10 @@ pub enum Command {
     Environment(environment::Args),
     Export(export::Args),
     Name(name::Args),
+    /// Rename the project, shorthand for `pixi project name set`.
+    Rename(name::set::Args),
+    Get(get::Args),
+    Set(set::Args),
 }
 
 /// Modify the project configuration file through the command line.
@@ -39,6 +46,13 @@ pub async fn execute(cmd: Args) -> miette::Result<()> {
         Command::Environment(args) => environment::execute(args).await?,
         Command::Export(cmd) => export::execute(cmd).await?,
         Command::Name(args) => name::execute(args).await?,
+        Command::Rename(args) => {
+            let project =
+                Project::load_or_else_discover(cmd.project_config.manifest_path.as_deref())?;
+            name::set::execute(project, args).await?
+        }
+        Command::Get(args) => get::execute(args).await?,
+        Command::Set(args) => set::execute(args).await?,
     };
     Ok(())
 }
@@ -1,5 +1,8 @@
 use crate::Project;
 use clap::Parser;
+use miette::IntoDiagnostic;
+use pixi_consts::consts;
+use pixi_manifest::{task::CmdArgs, Task};
 
 #[derive(Parser, Debug)]
 pub struct Args {
@@ -9,9 +12,18 @@ pub struct Args {
 }
 
 pub async fn execute(mut project: Project, args: Args) -> miette::Result<()> {
+    let old_name = project.name().to_string();
+
+    // The detached environments directory is derived from the project name, so
+    // remember where the environments were located before the rename.
+    let old_environments_dir = project.detached_environments_path();
+
     // Set the new project name
     project.manifest.set_name(&args.name)?;
 
+    // Update the tasks that refer to the project by its name
+    let renamed_tasks = rename_in_tasks(&mut project, &old_name, &args.name)?;
+
     // Save project
     project.save()?;
 
@@ -21,6 +33,211 @@ pub async fn execute(mut project: Project, args: Args) -> miette::Result<()> {
         console::style(console::Emoji("✔ ", "")).green(),
         project.manifest.workspace.workspace.name
     );
+    for task in renamed_tasks {
+        eprintln!(
+            "{}Updated the command of task '{}'.",
+            console::style(console::Emoji("✔ ", "")).green(),
+            task
+        );
+    }
+
+    // The activation cache contains the name of the project, so it is recomputed.
+    let activation_cache = project.activation_env_cache_folder();
+    if activation_cache.exists() {
+        fs_err::remove_dir_all(&activation_cache).into_diagnostic()?;
+    }
+
+    // Environments inside the project directory do not depend on the name.
+    let Some(old_environments_dir) = old_environments_dir else {
+        return Ok(());
+    };
+    let Some(new_environments_dir) = project.detached_environments_path() else {
+        return Ok(());
+    };
+    if !old_environments_dir.exists() || old_environments_dir == new_environments_dir {
+        return Ok(());
+    }
+    if new_environments_dir.exists() {
+        miette::bail!(
+            "cannot move the environments of '{}' to '{}' because that directory already exists",
+            old_name,
+            new_environments_dir.display()
+        );
+    }
+
+    // Move the detached environments to the directory of the new name. The
+    // symlink in the project directory still points to the old location, it is
+    // recreated when the environments are used next.
+    fs_err::rename(&old_environments_dir, &new_environments_dir).into_diagnostic()?;
+    let default_envs_dir = project.default_environments_dir();
+    if default_envs_dir.is_symlink() {
+        fs_err::remove_file(&default_envs_dir).into_diagnostic()?;
+    }
+
+    // Conda prefixes contain their own location and cannot be moved, so the
+    // stale prefixes are removed. They are recreated on the next install.
+    let envs_dir = new_environments_dir.join(consts::ENVIRONMENTS_DIR);
+    if envs_dir.is_dir() {
+        fs_err::remove_dir_all(&envs_dir).into_diagnostic()?;
+    }
+
+    eprintln!(
+        "{}Moved the environments directory to '{}', the environments will be recreated on the next install.",
+        console::style(console::Emoji("✔ ", "")).green(),
+        new_environments_dir.display()
+    );
 
     Ok(())
 }
+
+/// Replaces the old project name with the new one in the commands of all tasks
+/// and returns the names of the tasks that changed.
+fn rename_in_tasks(project: &mut Project, old: &str, new: &str) -> miette::Result<Vec<String>> {
+    let mut updates = Vec::new();
+    for (feature_name, feature) in &project.manifest.workspace.features {
+        for (target, selector) in feature.targets.iter() {
+            for (task_name, task) in &target.tasks {
+                if let Some(task) = rename_in_task(task, old, new) {
+                    updates.push((
+                        feature_name.clone(),
+                        selector.cloned(),
+                        task_name.clone(),
+                        task,
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut renamed = Vec::with_capacity(updates.len());
+    for (feature_name, selector, task_name, task) in updates {
+        renamed.push(task_name.to_string());
+        project
+            .manifest
+            .set_task(task_name, task, selector.as_ref(), &feature_name)?;
+    }
+    renamed.sort();
+    Ok(renamed)
+}
+
+/// Returns the task with the old project name replaced in its command, or
+/// `None` if the command does not refer to the old name.
+fn rename_in_task(task: &Task, old: &str, new: &str) -> Option<Task> {
+    match task {
+        Task::Plain(cmd) => rename_in_command(cmd, old, new).map(Task::Plain),
+        Task::Execute(execute) => {
+            let cmd = match &execute.cmd {
+                CmdArgs::Single(cmd) => CmdArgs::Single(rename_in_command(cmd, old, new)?),
+                CmdArgs::Multiple(args) => {
+                    let renamed = args
+                        .iter()
+                        .map(|arg| rename_in_command(arg, old, new))
+                        .collect::<Vec<_>>();
+                    if renamed.iter().all(Option::is_none) {
+                        return None;
+                    }
+                    CmdArgs::Multiple(
+                        renamed
+                            .into_iter()
+                            .zip(args)
+                            .map(|(renamed, arg)| renamed.unwrap_or_else(|| arg.clone()))
+                            .collect(),
+                    )
+                }
+            };
+            let mut execute = execute.clone();
+            execute.cmd = cmd;
+            Some(Task::Execute(execute))
+        }
+        Task::Alias(_) | Task::Custom(_) => None,
+    }
+}
+
+/// Replaces the old name where the command clearly refers to the project,
+/// which is when it runs the project as a python module, e.g.
+/// `python -m my_project`. Other occurrences are left alone, a project named
+/// `test` doesn't rename `cargo test`.
+fn rename_in_command(command: &str, old: &str, new: &str) -> Option<String> {
+    if old.is_empty() {
+        return None;
+    }
+
+    let mut result = String::with_capacity(command.len());
+    let mut last = 0;
+    let mut previous_word = None;
+    for word in command.split_whitespace() {
+        if previous_word == Some("-m") && word == old {
+            // The word is a slice of the command, so its offset is the start of the word.
+            let start = word.as_ptr() as usize - command.as_ptr() as usize;
+            result.push_str(&command[last..start]);
+            result.push_str(new);
+            last = start + word.len();
+        }
+        previous_word = Some(word);
+    }
+
+    if last == 0 {
+        return None;
+    }
+    result.push_str(&command[last..]);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_in_command() {
+        assert_eq!(
+            rename_in_command(
+                "python -m my_project --name my_project",
+                "my_project",
+                "other"
+            ),
+            Some("python -m other --name my_project".to_string())
+        );
+        assert_eq!(rename_in_command("cargo test", "test", "renamed"), None);
+        assert_eq!(
+            rename_in_command("mkdocs build docs", "docs", "renamed"),
+            None
+        );
+        assert_eq!(
+            rename_in_command(
+                "docker build -t my-project:latest .",
+                "my-project",
+                "renamed"
+            ),
+            None
+        );
+        assert_eq!(
+            rename_in_command("echo my-project-docs", "my-project", "renamed"),
+            None
+        );
+        assert_eq!(
+            rename_in_command("pytest tests/my-project", "my-project", "renamed"),
+            None
+        );
+        assert_eq!(
+            rename_in_command("python -m my-project.cli", "my-project", "renamed"),
+            None
+        );
+        assert_eq!(
+            rename_in_command("echo hello", "my-project", "renamed"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rename_in_task() {
+        let task = Task::Plain("python -m foo tests/foo".to_string());
+        assert_eq!(
+            rename_in_task(&task, "foo", "bar")
+                .unwrap()
+                .as_single_command()
+                .as_deref(),
+            Some("python -m bar tests/foo")
+        );
+        assert!(rename_in_task(&task, "baz", "bar").is_none());
+    }
+}
@@ -0,0 +1,37 @@
+use clap::Parser;
+
+use crate::{cli::cli_config::ProjectConfig, Project};
+
+/// Set a value in the manifest.
+///
+/// The key is a dotted path relative to the pixi configuration, e.g.
+/// `project.version`. The value is parsed as TOML, e.g. `true` or `["a", "b"]`,
+/// and otherwise used as a string. The manifest is validated before it is
+/// saved, the formatting and comments of the manifest are preserved.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// The dotted key of the value, e.g. `project.version`
+    pub key: String,
+
+    /// The new value
+    pub value: String,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let mut project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?;
+
+    project.manifest.set_value(&args.key, &args.value)?;
+    project.save()?;
+
+    eprintln!(
+        "{}Set '{}' to {}.",
+        console::style(console::Emoji("✔ ", "")).green(),
+        args.key,
+        args.value
+    );
+
+    Ok(())
+}
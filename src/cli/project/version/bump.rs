@@ -1,8 +1,8 @@
 use crate::Project;
 use miette::{Context, IntoDiagnostic};
-use rattler_conda_types::VersionBumpType;
+use rattler_conda_types::{Version, VersionBumpType};
 
-pub async fn execute(mut project: Project, bump_type: VersionBumpType) -> miette::Result<()> {
+pub async fn execute(mut project: Project, bump_type: VersionBumpType) -> miette::Result<Version> {
     // get version and exit with error if not found
     let current_version = project
         .version()
@@ -30,5 +30,5 @@ pub async fn execute(mut project: Project, bump_type: VersionBumpType) -> miette
         new_version,
     );
 
-    Ok(())
+    Ok(new_version)
 }
@@ -4,8 +4,10 @@ pub mod set;
 
 use crate::Project;
 use clap::Parser;
-use rattler_conda_types::VersionBumpType;
-use std::path::PathBuf;
+use miette::{IntoDiagnostic, WrapErr};
+use rattler_conda_types::{Version, VersionBumpType};
+use regex::Regex;
+use std::path::{Path, PathBuf};
 
 /// Commands to manage project version.
 #[derive(Parser, Debug)]
@@ -14,6 +16,11 @@ pub struct Args {
     #[clap(long, global = true)]
     pub manifest_path: Option<PathBuf>,
 
+    /// A file with a `__version__ = "..."` line, e.g. the `__init__.py` of a
+    /// Python package, to update to the new version as well
+    #[clap(long, global = true)]
+    pub version_file: Option<PathBuf>,
+
     /// The subcommand to execute
     #[clap(subcommand)]
     pub command: Command,
@@ -36,13 +43,71 @@ pub enum Command {
 pub async fn execute(args: Args) -> miette::Result<()> {
     let project = Project::load_or_else_discover(args.manifest_path.as_deref())?;
 
-    match args.command {
-        Command::Get(args) => get::execute(project, args).await?,
+    let new_version = match args.command {
+        Command::Get(args) => return get::execute(project, args).await,
         Command::Set(args) => set::execute(project, args).await?,
         Command::Major => bump::execute(project, VersionBumpType::Major).await?,
         Command::Minor => bump::execute(project, VersionBumpType::Minor).await?,
         Command::Patch => bump::execute(project, VersionBumpType::Patch).await?,
+    };
+
+    if let Some(version_file) = args.version_file {
+        update_version_file(&version_file, &new_version)?;
     }
 
     Ok(())
 }
+
+/// Updates the `__version__` in the given file to the new version.
+fn update_version_file(path: &Path, version: &Version) -> miette::Result<()> {
+    let contents = fs_err::read_to_string(path).into_diagnostic()?;
+    let updated = replace_version(&contents, version).ok_or_else(|| {
+        miette::miette!(
+            "could not find a `__version__ = \"...\"` line in '{}'",
+            path.display()
+        )
+    })?;
+    fs_err::write(path, updated)
+        .into_diagnostic()
+        .wrap_err("failed to update the version file")?;
+
+    eprintln!(
+        "{}Updated the version in '{}' to '{}'.",
+        console::style(console::Emoji("✔ ", "")).green(),
+        path.display(),
+        version,
+    );
+    Ok(())
+}
+
+/// Replaces the value of the `__version__` assignment, keeping the quotes that
+/// were used. Returns `None` if there is no such assignment.
+fn replace_version(contents: &str, version: &Version) -> Option<String> {
+    let re = Regex::new(r#"(?m)^(__version__\s*=\s*)(["'])[^"']*(["'])"#).unwrap();
+    if !re.is_match(contents) {
+        return None;
+    }
+    let replacement = format!("${{1}}${{2}}{version}${{3}}");
+    Some(re.replace(contents, replacement.as_str()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_replace_version() {
+        let version = Version::from_str("1.3.0").unwrap();
+        let contents = "\"\"\"My package.\"\"\"\n\n__version__ = '1.2.3'\n";
+        assert_eq!(
+            replace_version(contents, &version).unwrap(),
+            "\"\"\"My package.\"\"\"\n\n__version__ = '1.3.0'\n"
+        );
+        assert_eq!(
+            replace_version("__version__=\"0.1.0\"", &version).unwrap(),
+            "__version__=\"1.3.0\""
+        );
+        assert!(replace_version("version = \"0.1.0\"", &version).is_none());
+    }
+}
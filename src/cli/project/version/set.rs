@@ -1,5 +1,6 @@
 use crate::Project;
 use clap::Parser;
+use rattler_conda_types::Version;
 
 #[derive(Parser, Debug, Default)]
 pub struct Args {
@@ -8,7 +9,7 @@ pub struct Args {
     pub version: String,
 }
 
-pub async fn execute(mut project: Project, args: Args) -> miette::Result<()> {
+pub async fn execute(mut project: Project, args: Args) -> miette::Result<Version> {
     // Set the version
     project.manifest.set_version(&args.version)?;
 
@@ -16,11 +17,12 @@ pub async fn execute(mut project: Project, args: Args) -> miette::Result<()> {
     project.save()?;
 
     // Report back to the user
+    let version = project.version().clone().unwrap();
     eprintln!(
         "{}Updated project version to '{}'.",
         console::style(console::Emoji("✔ ", "")).green(),
-        project.version().as_ref().unwrap()
+        version
     );
 
-    Ok(())
+    Ok(version)
 }
@@ -1,5 +1,6 @@
 use clap::Parser;
 use miette::Context;
+use pixi_manifest::{FeaturesExt, HasFeaturesIter};
 
 use crate::environment::get_update_lock_file_and_prefix;
 use crate::Project;
@@ -76,10 +77,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     project.save()?;
 
-    // TODO: update all environments touched by this feature defined.
     // updating prefix after removing from toml
     if !prefix_update_config.no_lockfile_update {
-        get_update_lock_file_and_prefix(
+        let (mut lock_file, _) = get_update_lock_file_and_prefix(
             &project.default_environment(),
             UpdateMode::Revalidate,
             UpdateLockFileOptions {
@@ -89,9 +89,31 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             },
         )
         .await?;
+
+        // Also update the other installed environments that contain the feature, this
+        // uninstalls the packages that are no longer required instead of leaving them
+        // in the prefix until the next install.
+        if !prefix_update_config.no_install {
+            for environment in project.environments() {
+                if environment.is_default()
+                    || !environment.dir().exists()
+                    || !environment
+                        .platforms()
+                        .contains(&environment.best_platform())
+                    || !environment
+                        .features()
+                        .any(|f| f.name == dependency_config.feature)
+                {
+                    continue;
+                }
+                lock_file
+                    .prefix(&environment, UpdateMode::Revalidate)
+                    .await?;
+            }
+        }
     }
 
-    dependency_config.display_success("Removed", Default::default());
+    dependency_config.display_success("Removed", Default::default(), Default::default());
 
     Project::warn_on_discovered_from_env(project_config.manifest_path.as_deref());
     Ok(())
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, io::Write, time::SystemTime};
+
+use clap::Parser;
+use miette::IntoDiagnostic;
+
+use super::Command;
+use crate::{
+    cli::{
+        cli_config::{PrefixUpdateConfig, ProjectConfig},
+        run,
+    },
+    lock_file::UpdateLockFileOptions,
+    Project,
+};
+
+/// Start an interactive session to execute pixi commands.
+///
+/// The project is loaded and the environments are activated only once, which
+/// makes running tasks repeatedly a lot faster. Commands are entered without
+/// the `pixi` prefix, e.g. `run test` or `list`. Type `exit` or press Ctrl+D to
+/// leave the session.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    #[clap(flatten)]
+    pub prefix_update_config: PrefixUpdateConfig,
+}
+
+/// A line entered in the interactive session.
+#[derive(Parser, Debug)]
+#[clap(no_binary_name = true, disable_version_flag = true)]
+struct Line {
+    #[command(subcommand)]
+    command: Command,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    // Ctrl+C should stop the running task, not the session.
+    let _ctrl_c = tokio::spawn(async { while tokio::signal::ctrl_c().await.is_ok() {} });
+
+    'reload: loop {
+        let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+            .with_cli_config(args.prefix_update_config.config.clone());
+
+        let mut lock_file = project
+            .update_lock_file(UpdateLockFileOptions {
+                lock_file_usage: args.prefix_update_config.lock_file_usage(),
+                no_install: args.prefix_update_config.no_install,
+                max_concurrent_solves: project.config().max_concurrent_solves(),
+            })
+            .await?;
+        let loaded_at = last_modified(&project);
+
+        // The activated environments, these are reused until the project changes.
+        let mut task_envs = HashMap::new();
+
+        loop {
+            let Some(line) = read_line(&project).await? else {
+                return Ok(());
+            };
+            let Some(words) = shlex::split(&line) else {
+                eprintln!("{}", console::style("error: unbalanced quotes").red());
+                continue;
+            };
+            match words.first().map(String::as_str) {
+                None => continue,
+                Some("exit" | "quit") => return Ok(()),
+                _ => {}
+            }
+
+            let command = match Line::try_parse_from(words) {
+                Ok(line) => line.command,
+                Err(err) => {
+                    let _ = err.print();
+                    continue;
+                }
+            };
+
+            let result = match command {
+                Command::Repl(_) => Err(miette::miette!("already in an interactive session")),
+                Command::Shell(_) | Command::Exec(_) => Err(miette::miette!(
+                    help = "use `run` to execute a command in the environment",
+                    "this command is not supported in an interactive session"
+                )),
+                Command::Run(run_args) => {
+                    run::execute_in_session(&project, &mut lock_file, &mut task_envs, run_args)
+                        .await
+                        .map(|failed_exit_code| {
+                            if let Some(code) = failed_exit_code {
+                                eprintln!(
+                                    "{}",
+                                    console::style(format!("exited with code {code}")).red()
+                                );
+                            }
+                        })
+                }
+                command => Box::pin(super::execute_command(command)).await,
+            };
+            if let Err(err) = result {
+                eprintln!("{:?}", err);
+            }
+
+            // Commands like `add` modify the manifest or the lock-file, in that
+            // case the project is loaded again.
+            if last_modified(&project) != loaded_at {
+                continue 'reload;
+            }
+        }
+    }
+}
+
+/// Prints the prompt and reads the next line from stdin. Returns `None` when
+/// the end of the input is reached.
+async fn read_line(project: &Project) -> miette::Result<Option<String>> {
+    eprint!(
+        "{} ",
+        console::style(format!("pixi ({})>", project.name())).bold()
+    );
+    std::io::stderr().flush().into_diagnostic()?;
+
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line)? {
+            0 => Ok::<_, std::io::Error>(None),
+            _ => Ok(Some(line)),
+        }
+    })
+    .await
+    .unwrap_or_else(|e| match e.try_into_panic() {
+        Ok(panic) => std::panic::resume_unwind(panic),
+        Err(_err) => panic!("the task was cancelled"),
+    })
+    .into_diagnostic()
+}
+
+/// Returns the last time the manifest or the lock-file was modified.
+fn last_modified(project: &Project) -> [Option<SystemTime>; 2] {
+    [project.manifest_path(), project.lock_file_path()].map(|path| {
+        fs_err::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    })
+}
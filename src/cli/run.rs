@@ -1,6 +1,7 @@
 use clap::Parser;
 use dialoguer::theme::ColorfulTheme;
 use fancy_display::FancyDisplay;
+use futures::stream::{FuturesUnordered, StreamExt};
 use itertools::Itertools;
 use miette::{Diagnostic, IntoDiagnostic};
 use std::collections::hash_map::Entry;
@@ -8,19 +9,22 @@ use std::collections::HashSet;
 use std::convert::identity;
 use std::{collections::HashMap, string::String};
 
+use crate::activation::resolve_global_executable_conflicts;
 use crate::cli::cli_config::{PrefixUpdateConfig, ProjectConfig};
+use crate::container::run_in_container;
 use crate::environment::verify_prefix_location_unchanged;
-use crate::lock_file::UpdateLockFileOptions;
+use crate::lock_file::{LockFileDerivedData, UpdateLockFileOptions};
 use crate::project::errors::UnsupportedPlatformError;
 use crate::project::virtual_packages::verify_current_platform_has_required_virtual_packages;
 use crate::project::Environment;
 use crate::task::{
-    get_task_env, AmbiguousTask, CanSkip, ExecutableTask, FailedToParseShellScript,
-    InvalidWorkingDirectory, SearchEnvironments, TaskAndEnvironment, TaskGraph,
+    get_task_env, terminate_child_processes, AmbiguousTask, CanSkip, ExecutableTask, FileWatcher,
+    InvalidWorkingDirectory, SearchEnvironments, TaskAndEnvironment, TaskGraph, TaskHash,
 };
 use crate::Project;
 use pixi_config::ConfigCliActivation;
-use pixi_manifest::TaskName;
+use pixi_manifest::{FeaturesExt, TaskName};
+use rattler_conda_types::Platform;
 use thiserror::Error;
 use tracing::Level;
 
@@ -51,12 +55,58 @@ pub struct Args {
     /// minimum environment to activate the pixi environment in.
     #[arg(long)]
     pub clean_env: bool,
+
+    /// Re-run the task when the files matching the `inputs` of the tasks change
+    ///
+    /// A running task is stopped and restarted when a change is detected,
+    /// which makes this suitable for long-running processes like servers.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Additional paths or globs to watch for changes, implies `--watch`
+    #[arg(long = "watch-path", value_name = "GLOB")]
+    pub watch_paths: Vec<String>,
+
+    /// The maximum number of tasks to run concurrently
+    ///
+    /// Tasks that don't depend on each other are executed concurrently, the
+    /// output of each task is prefixed with its name.
+    #[arg(long, short = 'j', default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: u16,
+
+    /// Run the task in every environment that defines it
+    ///
+    /// This is useful to run the tests in all environments of a
+    /// `python-matrix`. Environments that don't support the current platform
+    /// are skipped.
+    #[arg(long, conflicts_with_all = ["environment", "watch", "watch_paths"])]
+    pub all_environments: bool,
+
+    /// Run the task for another platform in a container
+    ///
+    /// The task runs in the image that is configured for the platform in the
+    /// `container-runner` configuration, the environment is installed in the
+    /// container from the lock file. The current platform runs the task
+    /// without a container.
+    #[arg(long, short, conflicts_with_all = ["all_environments", "watch", "watch_paths"])]
+    pub platform: Option<Platform>,
+
+    /// Run the task in every member of the workspace that defines it
+    ///
+    /// A member runs the task after the members it depends on through a path
+    /// dependency. The root of the workspace runs the task last.
+    #[arg(long, conflicts_with_all = ["environment", "watch", "watch_paths", "platform"])]
+    pub workspace: bool,
 }
 
 /// CLI entry point for `pixi run`
 /// When running the sigints are ignored and child can react to them. As it
 /// pleases.
 pub async fn execute(args: Args) -> miette::Result<()> {
+    if args.workspace {
+        return execute_workspace(args).await;
+    }
+
     let cli_config = args
         .activation_config
         .merge_config(args.prefix_update_config.config.clone().into());
@@ -77,13 +127,40 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     // Print all available tasks if no task is provided
     if args.task.is_empty() {
-        command_not_found(&project, explicit_environment);
+        command_not_found(&project, explicit_environment, None);
         return Ok(());
     }
 
     // Print all available tasks if no task is provided
     if args.task.is_empty() {
-        command_not_found(&project, explicit_environment);
+        command_not_found(&project, explicit_environment, None);
+        return Ok(());
+    }
+
+    if let Some(platform) = args.platform.filter(|p| *p != Platform::current()) {
+        if !environment.platforms().contains(&platform) {
+            return Err(UnsupportedPlatformError {
+                environments_platforms: environment.platforms().into_iter().collect(),
+                environment: environment.name().clone(),
+                platform,
+            }
+            .into());
+        }
+
+        // The container installs the environment from the lock file, so it has to be
+        // up-to-date, but nothing is installed on the host.
+        project
+            .update_lock_file(UpdateLockFileOptions {
+                lock_file_usage: args.prefix_update_config.lock_file_usage(),
+                no_install: true,
+                max_concurrent_solves: project.config().max_concurrent_solves(),
+            })
+            .await?;
+
+        let code = run_in_container(&project, &environment, platform, &args.task).await?;
+        if code != 0 {
+            std::process::exit(code);
+        }
         return Ok(());
     }
 
@@ -108,6 +185,52 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         })
         .await?;
 
+    if args.all_environments {
+        let mut task_envs = HashMap::new();
+        let mut failed = Vec::new();
+        for environment in all_task_environments(&project, &args.task)? {
+            let search_environment = SearchEnvironments::from_opt_env(
+                &project,
+                Some(environment.clone()),
+                Some(environment.best_platform()),
+            );
+            let task_graph =
+                TaskGraph::from_cmd_args(&project, &search_environment, args.task.clone())?;
+            if let Some(code) = execute_task_graph(
+                &project,
+                &task_graph,
+                &mut lock_file,
+                &mut task_envs,
+                &args.prefix_update_config,
+                args.clean_env,
+                usize::from(args.jobs),
+            )
+            .await?
+            {
+                failed.push((environment, code));
+            }
+        }
+
+        // Exit with the exit code of the first environment that failed.
+        if let Some((_, code)) = failed.first() {
+            eprintln!(
+                "\n{}",
+                console::style(format!(
+                    "Failed in {}",
+                    failed
+                        .iter()
+                        .map(|(env, code)| format!("{} (exit code {})", env.name(), code))
+                        .join(", ")
+                ))
+                .red()
+            );
+            std::process::exit(*code);
+        }
+
+        Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+        return Ok(());
+    }
+
     // Construct a task graph from the input arguments
     let search_environment = SearchEnvironments::from_opt_env(
         &project,
@@ -116,16 +239,342 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     )
     .with_disambiguate_fn(disambiguate_task_interactive);
 
-    let task_graph = TaskGraph::from_cmd_args(&project, &search_environment, args.task)?;
+    let task_graph = TaskGraph::from_cmd_args(&project, &search_environment, args.task.clone())?;
 
     tracing::info!("Task graph: {}", task_graph);
 
+    // Set up the file watcher if the tasks should be re-run on changes.
+    let mut watcher = if args.watch || !args.watch_paths.is_empty() {
+        Some(create_watcher(&project, &task_graph, args.watch_paths).await?)
+    } else {
+        None
+    };
+
     // Traverse the task graph in topological order and execute each individual
-    // task.
-    let mut task_idx = 0;
+    // task. In watch mode the graph is executed again whenever a change is detected.
     let mut task_envs = HashMap::new();
+    'watch: loop {
+        let graph_future = execute_task_graph(
+            &project,
+            &task_graph,
+            &mut lock_file,
+            &mut task_envs,
+            &args.prefix_update_config,
+            args.clean_env,
+            usize::from(args.jobs),
+        );
+
+        // In watch mode a change stops the running tasks, including the processes
+        // they spawned, and restarts the task graph.
+        let failed_exit_code = match watcher.as_mut() {
+            Some(watcher) => tokio::select! {
+                result = graph_future => result?,
+                changed = watcher.changed() => {
+                    changed.into_diagnostic()?;
+                    eprintln!("{}", console::style("Files changed, restarting...").yellow());
+                    terminate_child_processes().await;
+                    continue 'watch;
+                }
+            },
+            None => graph_future.await?,
+        };
+
+        // If one of the tasks failed with a non-zero exit code, we exit this parent
+        // process with the same code. When watching, the next change restarts the
+        // tasks instead.
+        if let Some(code) = failed_exit_code {
+            if watcher.is_none() {
+                if code == 127 {
+                    command_not_found(&project, explicit_environment, args.task.first());
+                }
+                std::process::exit(code);
+            }
+        }
+
+        // Wait for changes before executing the tasks again, or stop if we are not watching.
+        let Some(watcher) = watcher.as_mut() else {
+            break 'watch;
+        };
+        eprintln!(
+            "\n{}",
+            console::style(format!(
+                "Watching for changes in {}, press Ctrl+C to stop",
+                watcher.globs().iter().format(", ")
+            ))
+            .dim()
+        );
+        tokio::select! {
+            changed = watcher.changed() => changed.into_diagnostic()?,
+            _ = tokio::signal::ctrl_c() => break 'watch,
+        }
+    }
+
+    Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+    Ok(())
+}
+
+/// Runs the task in every project of the workspace that defines it, the
+/// members in the order of the dependencies between them and the root of the
+/// workspace last. Stops at the first project in which the task fails.
+async fn execute_workspace(args: Args) -> miette::Result<()> {
+    let Some(task) = args.task.first() else {
+        miette::bail!("`--workspace` requires the name of a task");
+    };
+    let task_name = TaskName::from(task.as_str());
+
+    let root =
+        Project::load_workspace_or_else_discover(args.project_config.manifest_path.as_deref())?;
+    let projects = root
+        .workspace_members()?
+        .into_iter()
+        .chain([root])
+        .filter(|project| {
+            project
+                .environments()
+                .iter()
+                .any(|env| env.get_filtered_tasks().contains(&task_name))
+        })
+        .collect_vec();
+    if projects.is_empty() {
+        miette::bail!(
+            "none of the projects in the workspace defines the task {}",
+            task_name.fancy_display()
+        );
+    }
+
+    for project in projects {
+        eprintln!(
+            "{}",
+            console::style(format!("Running {} in {}", task, project.name())).bold()
+        );
+        let args = Args {
+            task: args.task.clone(),
+            project_config: ProjectConfig {
+                manifest_path: Some(project.manifest_path()),
+            },
+            prefix_update_config: args.prefix_update_config.clone(),
+            activation_config: args.activation_config.clone(),
+            clean_env: args.clean_env,
+            jobs: args.jobs,
+            all_environments: args.all_environments,
+            ..Args::default()
+        };
+        Box::pin(execute(args)).await?;
+    }
+    Ok(())
+}
+
+/// Runs the tasks of `args` in an already loaded project. The activated
+/// environments are stored in `task_envs` so that later calls can reuse them.
+///
+/// This is used by `pixi repl`, instead of exiting the process the exit code of
+/// the first task that failed is returned.
+pub(crate) async fn execute_in_session<'p>(
+    project: &'p Project,
+    lock_file: &mut LockFileDerivedData<'p>,
+    task_envs: &mut HashMap<Environment<'p>, HashMap<String, String>>,
+    args: Args,
+) -> miette::Result<Option<i32>> {
+    if args.watch || !args.watch_paths.is_empty() || args.all_environments || args.workspace {
+        miette::bail!(
+            "`--watch`, `--watch-path`, `--all-environments` and `--workspace` are not supported in an interactive session"
+        );
+    }
+
+    let environment = project.environment_from_name_or_env_var(args.environment.clone())?;
+    let explicit_environment = if args.environment.is_none() && environment.is_default() {
+        None
+    } else {
+        Some(environment.clone())
+    };
+
+    if args.task.is_empty() {
+        command_not_found(project, explicit_environment, None);
+        return Ok(None);
+    }
+
+    let search_environment = SearchEnvironments::from_opt_env(
+        project,
+        explicit_environment.clone(),
+        Some(environment.best_platform()),
+    )
+    .with_disambiguate_fn(disambiguate_task_interactive);
+    let task_graph = TaskGraph::from_cmd_args(project, &search_environment, args.task.clone())?;
+
+    let failed_exit_code = execute_task_graph(
+        project,
+        &task_graph,
+        lock_file,
+        task_envs,
+        &args.prefix_update_config,
+        args.clean_env,
+        usize::from(args.jobs),
+    )
+    .await?;
+    if failed_exit_code == Some(127) {
+        command_not_found(project, explicit_environment, args.task.first());
+    }
+    Ok(failed_exit_code)
+}
+
+/// Returns the environments to run the task in with `--all-environments`.
+///
+/// If the task is defined in any environment only the environments that
+/// define it are returned, otherwise the command is executed in every
+/// environment. Environments that can't run on the current platform are
+/// skipped.
+fn all_task_environments<'p>(
+    project: &'p Project,
+    task: &[String],
+) -> miette::Result<Vec<Environment<'p>>> {
+    let environments = project
+        .environments()
+        .into_iter()
+        .filter(|env| {
+            env.platforms().contains(&env.best_platform())
+                && verify_current_platform_has_required_virtual_packages(env).is_ok()
+        })
+        .collect_vec();
+
+    let task_name = TaskName::from(task[0].as_str());
+    let defining_environments = environments
+        .iter()
+        .filter(|env| env.get_filtered_tasks().contains(&task_name))
+        .cloned()
+        .collect_vec();
+
+    let environments = if defining_environments.is_empty() {
+        environments
+    } else {
+        defining_environments
+    };
+    if environments.is_empty() {
+        miette::bail!("none of the environments can run on the current platform");
+    }
+    Ok(environments)
+}
+
+/// Prints which task is being run.
+fn print_task_header(project: &Project, executable_task: &ExecutableTask) {
+    eprintln!(
+        "{}{}{}{}{}{}{}",
+        console::Emoji("✨ ", ""),
+        console::style("Pixi task (").bold(),
+        console::style(executable_task.name().unwrap_or("unnamed"))
+            .green()
+            .bold(),
+        // Only print environment if multiple environments are available
+        if project.environments().len() > 1 {
+            format!(
+                " in {}",
+                executable_task.run_environment.name().fancy_display()
+            )
+        } else {
+            "".to_string()
+        },
+        console::style("): ").bold(),
+        executable_task.display_command(),
+        if let Some(description) = executable_task.task().description() {
+            console::style(format!(": ({})", description)).yellow()
+        } else {
+            console::style("".to_string()).yellow()
+        }
+    );
+}
+
+/// Prints that a task was skipped because its cache is up-to-date.
+fn print_task_skipped(executable_task: &ExecutableTask) {
+    eprintln!(
+        "Task '{}' can be skipped (cache hit) 🚀",
+        console::style(executable_task.name().unwrap_or("")).bold()
+    );
+}
+
+/// Returns the environment variables to run the task with. The environments are
+/// computed once per run environment, which also ensures that the prefix of the
+/// environment is up-to-date.
+async fn task_environment<'e, 'p>(
+    project: &Project,
+    executable_task: &ExecutableTask<'p>,
+    lock_file: &mut LockFileDerivedData<'p>,
+    task_envs: &'e mut HashMap<Environment<'p>, HashMap<String, String>>,
+    prefix_update_config: &PrefixUpdateConfig,
+    clean_env: bool,
+) -> miette::Result<&'e HashMap<String, String>> {
+    match task_envs.entry(executable_task.run_environment.clone()) {
+        Entry::Occupied(env) => Ok(env.into_mut()),
+        Entry::Vacant(entry) => {
+            // Ensure there is a valid prefix
+            lock_file
+                .prefix(
+                    &executable_task.run_environment,
+                    prefix_update_config.update_mode(),
+                )
+                .await?;
+
+            let mut command_env = get_task_env(
+                &executable_task.run_environment,
+                clean_env || executable_task.task().clean_env(),
+                Some(&lock_file.lock_file),
+                project.config().force_activate(),
+                project.config().experimental_activation_cache_usage(),
+            )
+            .await?;
+            resolve_global_executable_conflicts(&executable_task.run_environment, &mut command_env);
+            Ok(entry.insert(command_env))
+        }
+    }
+}
+
+/// Executes the tasks of the task graph, independent tasks are executed
+/// concurrently if more than one job is allowed. Returns the exit code of the
+/// first task that failed.
+async fn execute_task_graph<'p>(
+    project: &'p Project,
+    task_graph: &TaskGraph<'p>,
+    lock_file: &mut LockFileDerivedData<'p>,
+    task_envs: &mut HashMap<Environment<'p>, HashMap<String, String>>,
+    prefix_update_config: &PrefixUpdateConfig,
+    clean_env: bool,
+    jobs: usize,
+) -> miette::Result<Option<i32>> {
+    if jobs > 1 {
+        execute_tasks_concurrently(
+            project,
+            task_graph,
+            lock_file,
+            task_envs,
+            prefix_update_config,
+            clean_env,
+            jobs,
+        )
+        .await
+    } else {
+        execute_tasks_sequentially(
+            project,
+            task_graph,
+            lock_file,
+            task_envs,
+            prefix_update_config,
+            clean_env,
+        )
+        .await
+    }
+}
+
+/// Traverses the task graph in topological order and executes each individual
+/// task. Stops at the first task that fails and returns its exit code.
+async fn execute_tasks_sequentially<'p>(
+    project: &'p Project,
+    task_graph: &TaskGraph<'p>,
+    lock_file: &mut LockFileDerivedData<'p>,
+    task_envs: &mut HashMap<Environment<'p>, HashMap<String, String>>,
+    prefix_update_config: &PrefixUpdateConfig,
+    clean_env: bool,
+) -> miette::Result<Option<i32>> {
+    let mut task_idx = 0;
     for task_id in task_graph.topological_order() {
-        let executable_task = ExecutableTask::from_task_graph(&task_graph, task_id);
+        let executable_task = ExecutableTask::from_task_graph(task_graph, task_id);
 
         // If the task is not executable (e.g. an alias), we skip it. This ensures we
         // don't instantiate a prefix for an alias.
@@ -139,31 +588,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 // Add a newline between task outputs
                 eprintln!();
             }
-            eprintln!(
-                "{}{}{}{}{}{}{}",
-                console::Emoji("✨ ", ""),
-                console::style("Pixi task (").bold(),
-                console::style(executable_task.name().unwrap_or("unnamed"))
-                    .green()
-                    .bold(),
-                // Only print environment if multiple environments are available
-                if project.environments().len() > 1 {
-                    format!(
-                        " in {}",
-                        executable_task.run_environment.name().fancy_display()
-                    )
-                } else {
-                    "".to_string()
-                },
-                console::style("): ").bold(),
-                executable_task.display_command(),
-                if let Some(description) = executable_task.task().description() {
-                    console::style(format!(": ({})", description)).yellow()
-                } else {
-                    console::style("".to_string()).yellow()
-                }
-            );
+            print_task_header(project, &executable_task);
         }
+        task_idx += 1;
 
         // check task cache
         let task_cache = match executable_task
@@ -173,70 +600,242 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         {
             CanSkip::No(cache) => cache,
             CanSkip::Yes => {
-                eprintln!(
-                    "Task '{}' can be skipped (cache hit) 🚀",
-                    console::style(executable_task.name().unwrap_or("")).bold()
-                );
-                task_idx += 1;
+                print_task_skipped(&executable_task);
                 continue;
             }
         };
 
-        // If we don't have a command environment yet, we need to compute it. We lazily
-        // compute the task environment because we only need the environment if
-        // a task is actually executed.
-        let task_env: &_ = match task_envs.entry(executable_task.run_environment.clone()) {
-            Entry::Occupied(env) => env.into_mut(),
-            Entry::Vacant(entry) => {
-                // Ensure there is a valid prefix
-                lock_file
-                    .prefix(
-                        &executable_task.run_environment,
-                        args.prefix_update_config.update_mode(),
-                    )
-                    .await?;
+        // If we don't have a command environment yet, we need to compute it. We
+        // lazily compute the task environment because we only need the
+        // environment if a task is actually executed.
+        let task_env = task_environment(
+            project,
+            &executable_task,
+            lock_file,
+            task_envs,
+            prefix_update_config,
+            clean_env,
+        )
+        .await?;
 
-                let command_env = get_task_env(
-                    &executable_task.run_environment,
-                    args.clean_env || executable_task.task().clean_env(),
-                    Some(&lock_file.lock_file),
-                    project.config().force_activate(),
-                    project.config().experimental_activation_cache_usage(),
-                )
-                .await?;
-                entry.insert(command_env)
+        if let Some(code) =
+            execute_and_save_cache(&executable_task, task_cache, lock_file, task_env, None).await?
+        {
+            return Ok(Some(code));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Executes a task that can't be skipped and updates its cache afterwards.
+/// Returns the exit code if the task failed.
+async fn execute_and_save_cache<'p>(
+    executable_task: &ExecutableTask<'p>,
+    task_cache: Option<TaskHash>,
+    lock_file: &LockFileDerivedData<'p>,
+    task_env: &HashMap<String, String>,
+    output_prefix: Option<&str>,
+) -> miette::Result<Option<i32>> {
+    match execute_task_with_retries(executable_task, task_env, output_prefix).await {
+        Ok(_) => {}
+        Err(TaskExecutionError::NonZeroExitCode(code)) => {
+            eprintln!(
+                "Task '{}' failed with exit code {}",
+                console::style(executable_task.name().unwrap_or("")).bold(),
+                code
+            );
+            return Ok(Some(code));
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    // Update the task cache with the new hash
+    executable_task
+        .save_cache(lock_file, task_cache)
+        .await
+        .into_diagnostic()?;
+    Ok(None)
+}
+
+/// Executes the tasks of the task graph concurrently, running at most `jobs`
+/// tasks at the same time. A task is started as soon as all the tasks it
+/// depends on have finished. The output of every task is prefixed with the name
+/// of the task.
+///
+/// When a task fails no new tasks are started, the tasks that are already
+/// running are allowed to finish. Returns the exit code of the first task that
+/// failed.
+async fn execute_tasks_concurrently<'p>(
+    project: &'p Project,
+    task_graph: &TaskGraph<'p>,
+    lock_file: &mut LockFileDerivedData<'p>,
+    task_envs: &mut HashMap<Environment<'p>, HashMap<String, String>>,
+    prefix_update_config: &PrefixUpdateConfig,
+    clean_env: bool,
+    jobs: usize,
+) -> miette::Result<Option<i32>> {
+    let mut pending = task_graph.topological_order();
+
+    // Instantiate the environments of all tasks upfront, installing prefixes
+    // can't be done concurrently with running tasks.
+    for &task_id in &pending {
+        let executable_task = ExecutableTask::from_task_graph(task_graph, task_id);
+        if executable_task.task().is_executable() {
+            task_environment(
+                project,
+                &executable_task,
+                lock_file,
+                task_envs,
+                prefix_update_config,
+                clean_env,
+            )
+            .await?;
+        }
+    }
+    let lock_file = &*lock_file;
+    let task_envs = &*task_envs;
+
+    let mut finished = HashSet::new();
+    let mut running = FuturesUnordered::new();
+    let mut failed_exit_code = None;
+    loop {
+        // Start all tasks for which the dependencies have finished.
+        while failed_exit_code.is_none() && running.len() < jobs {
+            let Some(idx) = pending.iter().position(|task_id| {
+                task_graph[*task_id]
+                    .dependencies
+                    .iter()
+                    .all(|dependency| finished.contains(dependency))
+            }) else {
+                break;
+            };
+            let task_id = pending.remove(idx);
+            let executable_task = ExecutableTask::from_task_graph(task_graph, task_id);
+
+            // Aliases don't have to be executed.
+            if !executable_task.task().is_executable() {
+                finished.insert(task_id);
+                continue;
             }
-        };
 
-        // Execute the task itself within the command environment. If one of the tasks
-        // failed with a non-zero exit code, we exit this parent process with
-        // the same code.
-        match execute_task(&executable_task, task_env).await {
-            Ok(_) => {
-                task_idx += 1;
+            if tracing::enabled!(Level::WARN) && !executable_task.task().is_custom() {
+                print_task_header(project, &executable_task);
             }
-            Err(TaskExecutionError::NonZeroExitCode(code)) => {
-                if code == 127 {
-                    command_not_found(&project, explicit_environment);
+
+            let task_env = &task_envs[&executable_task.run_environment];
+            running.push(async move {
+                let result = async {
+                    let task_cache = match executable_task
+                        .can_skip(&lock_file.lock_file)
+                        .await
+                        .into_diagnostic()?
+                    {
+                        CanSkip::No(cache) => cache,
+                        CanSkip::Yes => {
+                            print_task_skipped(&executable_task);
+                            return Ok(None);
+                        }
+                    };
+
+                    let prefix = console::style(format!(
+                        "[{}]",
+                        executable_task.name().unwrap_or("unnamed")
+                    ))
+                    .cyan()
+                    .to_string();
+                    execute_and_save_cache(
+                        &executable_task,
+                        task_cache,
+                        lock_file,
+                        task_env,
+                        Some(&prefix),
+                    )
+                    .await
                 }
-                std::process::exit(code);
+                .await;
+                (task_id, result)
+            });
+        }
+
+        // Wait for the next task to finish.
+        let Some((task_id, result)) = running.next().await else {
+            break;
+        };
+        match result? {
+            None => {
+                finished.insert(task_id);
+            }
+            Some(code) => {
+                failed_exit_code.get_or_insert(code);
             }
-            Err(err) => return Err(err.into()),
         }
+    }
 
-        // Update the task cache with the new hash
-        executable_task
-            .save_cache(&lock_file, task_cache)
-            .await
-            .into_diagnostic()?;
+    Ok(failed_exit_code)
+}
+
+/// Creates a watcher for the `inputs` of all tasks in the task graph and the
+/// additionally specified globs.
+async fn create_watcher(
+    project: &Project,
+    task_graph: &TaskGraph<'_>,
+    watch_paths: Vec<String>,
+) -> miette::Result<FileWatcher> {
+    let globs = task_graph
+        .topological_order()
+        .into_iter()
+        .filter_map(|task_id| task_graph[task_id].task.inputs())
+        .flatten()
+        .cloned()
+        .chain(watch_paths)
+        .unique()
+        .collect_vec();
+
+    if globs.is_empty() {
+        miette::bail!(
+            help = "define `inputs` for the task or pass the files to watch with `--watch-path`",
+            "there are no files to watch"
+        );
     }
 
-    Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
-    Ok(())
+    FileWatcher::new(project.root(), globs)
+        .await
+        .into_diagnostic()
 }
 
 /// Called when a command was not found.
-fn command_not_found<'p>(project: &'p Project, explicit_environment: Option<Environment<'p>>) {
+/// Prints the available tasks after a command could not be found. If the
+/// command is the name of a task of another environment than the explicit
+/// environment, the environments that define it are printed as well.
+fn command_not_found<'p>(
+    project: &'p Project,
+    explicit_environment: Option<Environment<'p>>,
+    command: Option<&String>,
+) {
+    if let (Some(explicit_environment), Some(command)) = (&explicit_environment, command) {
+        let task_name = TaskName::from(command.as_str());
+        let defining_environments = project
+            .environments()
+            .into_iter()
+            .filter(|env| env.get_filtered_tasks().contains(&task_name))
+            .collect_vec();
+        if let Some(environment) = defining_environments.first() {
+            eprintln!(
+                "\nThe task {} is not defined in the environment {}, but in: {}\nRun it with: {} run --environment {} {}",
+                task_name.fancy_display().bold(),
+                explicit_environment.name().fancy_display(),
+                defining_environments
+                    .iter()
+                    .map(|env| env.name().fancy_display())
+                    .format(", "),
+                env!("CARGO_PKG_NAME"),
+                environment.name().as_str(),
+                task_name
+            );
+        }
+    }
+
     let available_tasks: HashSet<TaskName> =
         if let Some(explicit_environment) = explicit_environment {
             explicit_environment.get_filtered_tasks()
@@ -268,7 +867,8 @@ enum TaskExecutionError {
     NonZeroExitCode(i32),
 
     #[error(transparent)]
-    FailedToParseShellScript(#[from] FailedToParseShellScript),
+    #[diagnostic(transparent)]
+    ExecutableTask(#[from] crate::task::TaskExecutionError),
 
     #[error(transparent)]
     InvalidWorkingDirectory(#[from] InvalidWorkingDirectory),
@@ -277,32 +877,104 @@ enum TaskExecutionError {
     UnsupportedPlatformError(#[from] UnsupportedPlatformError),
 }
 
+/// Executes a task and retries it as often as the task allows when it exits
+/// with a non-zero exit code.
+///
+/// If `output_prefix` is specified the output of the task is prefixed with it,
+/// see [`execute_task`].
+async fn execute_task_with_retries<'p>(
+    task: &ExecutableTask<'p>,
+    command_env: &HashMap<String, String>,
+    output_prefix: Option<&str>,
+) -> Result<(), TaskExecutionError> {
+    let retries = task.task().retries();
+    let mut attempt = 0;
+    loop {
+        match execute_task(task, command_env, output_prefix).await {
+            Ok(_) => {
+                if attempt > 0 {
+                    eprintln!(
+                        "Task '{}' succeeded after {} attempts",
+                        console::style(task.name().unwrap_or("")).bold(),
+                        attempt + 1
+                    );
+                }
+                return Ok(());
+            }
+            // A command that can't be found won't appear by retrying it.
+            Err(TaskExecutionError::NonZeroExitCode(code)) if code != 127 && attempt < retries => {
+                attempt += 1;
+                let delay = task.task().retry_delay(attempt);
+                eprintln!(
+                    "Task '{}' failed with exit code {}, retrying ({}/{}){}",
+                    console::style(task.name().unwrap_or("")).bold(),
+                    code,
+                    attempt,
+                    retries,
+                    if delay.is_zero() {
+                        String::new()
+                    } else {
+                        format!(" in {}", humantime::format_duration(delay))
+                    }
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => {
+                if attempt > 0 {
+                    eprintln!(
+                        "Task '{}' failed after {} attempts",
+                        console::style(task.name().unwrap_or("")).bold(),
+                        attempt + 1
+                    );
+                }
+                return Err(err);
+            }
+        }
+    }
+}
+
 /// Called to execute a single command.
 ///
+/// If `output_prefix` is specified the output of the command is forwarded line
+/// by line with the prefix prepended, otherwise the command inherits the stdio
+/// of this process.
+///
 /// This function is called from [`execute`].
 async fn execute_task<'p>(
     task: &ExecutableTask<'p>,
     command_env: &HashMap<String, String>,
+    output_prefix: Option<&str>,
 ) -> Result<(), TaskExecutionError> {
-    let Some(script) = task.as_deno_script()? else {
-        return Ok(());
-    };
-    let cwd = task.working_directory()?;
-
     // Ignore CTRL+C
     // Specifically so that the child is responsible for its own signal handling
     // NOTE: one CTRL+C is registered it will always stay registered for the rest of
     // the runtime of the program which is fine when using run in isolation,
     // however if we start to use run in conjunction with some other command we
     // might want to revaluate this.
-    let ctrl_c = tokio::spawn(async { while tokio::signal::ctrl_c().await.is_ok() {} });
-
-    let execute_future =
-        deno_task_shell::execute(script, command_env.clone(), &cwd, Default::default());
-    let status_code = tokio::select! {
-        code = execute_future => code,
-        // This should never exit
-        _ = ctrl_c => { unreachable!("Ctrl+C should not be triggered") }
+    // The listener is not spawned as a separate task, such that it is dropped
+    // together with the execution when a task is restarted in watch mode.
+    let ctrl_c = async { while tokio::signal::ctrl_c().await.is_ok() {} };
+
+    let status_code = if let Some(prefix) = output_prefix {
+        let execute_future = task.execute_with_prefixed_output(command_env, prefix);
+        tokio::select! {
+            code = execute_future => code?,
+            // This should never exit
+            _ = ctrl_c => { unreachable!("Ctrl+C should not be triggered") }
+        }
+    } else {
+        let Some(script) = task.as_deno_script()? else {
+            return Ok(());
+        };
+        let cwd = task.working_directory()?;
+
+        let execute_future =
+            deno_task_shell::execute(script, command_env.clone(), &cwd, Default::default());
+        tokio::select! {
+            code = execute_future => code,
+            // This should never exit
+            _ = ctrl_c => { unreachable!("Ctrl+C should not be triggered") }
+        }
     };
 
     if status_code != 0 {
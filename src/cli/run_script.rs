@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use clap::Parser;
+use miette::IntoDiagnostic;
+
+/// Runs a script with the shell that is used for tasks.
+///
+/// This is used internally to run sandboxed tasks with the same shell as tasks
+/// that are not sandboxed.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The script to run
+    #[arg(required = true)]
+    script: String,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let script = deno_task_shell::parser::parse(args.script.trim()).map_err(|e| {
+        crate::task::FailedToParseShellScript {
+            script: args.script.clone(),
+            error: e.to_string(),
+        }
+    })?;
+    let env: HashMap<String, String> = std::env::vars().collect();
+    let cwd = std::env::current_dir().into_diagnostic()?;
+
+    let status_code = deno_task_shell::execute(script, env, &cwd, Default::default()).await;
+    std::process::exit(status_code);
+}
@@ -5,6 +5,7 @@ use tar::Archive;
 
 use miette::{Context, IntoDiagnostic};
 use pixi_consts::consts;
+use rattler_digest::{compute_file_digest, Sha256};
 use reqwest::Client;
 use serde::Deserialize;
 use tempfile::{NamedTempFile, TempDir};
@@ -15,6 +16,10 @@ pub struct Args {
     /// The desired version (to downgrade or upgrade to). Update to the latest version if not specified.
     #[clap(long)]
     version: Option<String>,
+
+    /// Only show the version pixi would be updated to, without updating.
+    #[clap(long)]
+    dry_run: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,6 +88,16 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         return Ok(());
     }
 
+    if args.dry_run {
+        eprintln!(
+            "{}Pixi would be updated from {} to {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            current_version,
+            target_version
+        );
+        return Ok(());
+    }
+
     eprintln!(
         "{}Pixi will be updated from {} to {}",
         console::style(console::Emoji("✔ ", "")).green(),
@@ -126,6 +141,31 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         console::style(console::Emoji("✔ ", "")).green(),
     );
 
+    // Verify the checksum of the archive, if the release contains one
+    match expected_checksum(&client, &target_version_json, &archive_name).await? {
+        Some(expected) => {
+            let actual = format!(
+                "{:x}",
+                compute_file_digest::<Sha256>(archived_tempfile.path()).into_diagnostic()?
+            );
+            if actual != expected {
+                miette::bail!(
+                    "The checksum of the downloaded archive doesn't match, expected {} but got {}",
+                    expected,
+                    actual
+                );
+            }
+            eprintln!(
+                "{}Pixi archive checksum verified.",
+                console::style(console::Emoji("✔ ", "")).green(),
+            );
+        }
+        None => tracing::warn!(
+            "The release doesn't contain a checksum for {}, skipping verification",
+            archive_name
+        ),
+    }
+
     // Seek to the beginning of the file before uncompressing it
     let _ = archived_tempfile.rewind();
 
@@ -163,6 +203,46 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     Ok(())
 }
 
+/// Returns the sha256 of the archive from the `.sha256` asset of the release,
+/// or `None` if the release doesn't contain one.
+async fn expected_checksum(
+    client: &Client,
+    release: &GithubRelease,
+    archive_name: &str,
+) -> miette::Result<Option<String>> {
+    let checksum_name = format!("{}.sha256", archive_name);
+    let Some(asset) = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == checksum_name)
+    else {
+        return Ok(None);
+    };
+
+    let content = client
+        .get(&asset.browser_download_url)
+        .header("User-Agent", user_agent())
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .into_diagnostic()
+        .wrap_err("Failed to download the checksum of the archive")?
+        .text()
+        .await
+        .into_diagnostic()?;
+    parse_checksum(&content).map(Some)
+}
+
+/// Parses a checksum in the format of `sha256sum`, i.e. `<hash>  <file name>`.
+fn parse_checksum(content: &str) -> miette::Result<String> {
+    content
+        .split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|hash| hash.to_lowercase())
+        .ok_or_else(|| miette::miette!("Failed to parse the checksum: {}", content))
+}
+
 /// Unpack files from a tar.gz archive to a target directory.
 fn unpack_tar_gz(
     archived_tempfile: &NamedTempFile,
@@ -251,6 +331,21 @@ pub async fn execute_stub(_: Args) -> miette::Result<()> {
 mod tests {
     use std::path::PathBuf;
 
+    #[test]
+    pub fn test_parse_checksum() {
+        let hash = "8b1a9953c4611296a827abf8c47804d7e6c49c6b7d0e2c6bbf2e1e3c9e1f0a2b";
+        assert_eq!(
+            super::parse_checksum(&format!(
+                "{}  pixi-x86_64-unknown-linux-musl.tar.gz\n",
+                hash.to_uppercase()
+            ))
+            .unwrap(),
+            hash
+        );
+        assert!(super::parse_checksum("not a checksum").is_err());
+        assert!(super::parse_checksum("").is_err());
+    }
+
     #[test]
     pub fn test_unarchive_flat_structure() {
         // This archive contains a single file named "a_file"
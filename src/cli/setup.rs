@@ -0,0 +1,295 @@
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::Parser;
+use dialoguer::theme::ColorfulTheme;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_config::Config;
+use pixi_consts::consts;
+use pixi_utils::reqwest::build_reqwest_clients;
+use rattler_conda_types::NamedChannelOrUrl;
+use rattler_networking::{Authentication, AuthenticationStorage};
+use rattler_shell::shell::ShellEnum;
+
+use super::{auth::normalize_host, config::global_config_write_path};
+
+/// Configure pixi for first-time use.
+///
+/// Asks for the default channels of new projects, the cache directory and the
+/// credentials of private channels, writes them to the global configuration,
+/// sets up shell completions and checks that the channels can be reached.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Don't check whether the default channels can be reached
+    #[arg(long)]
+    pub no_verify: bool,
+}
+
+/// Parses a comma or whitespace separated list of channels.
+fn parse_channels(input: &str) -> miette::Result<Vec<NamedChannelOrUrl>> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|channel| !channel.is_empty())
+        .map(|channel| {
+            NamedChannelOrUrl::from_str(channel)
+                .into_diagnostic()
+                .with_context(|| format!("invalid channel '{channel}'"))
+        })
+        .collect()
+}
+
+/// Returns the startup file of the shell, relative to the home directory, and
+/// the line that loads the pixi completions.
+fn completion_line(shell: &ShellEnum) -> Option<(&'static str, &'static str)> {
+    match shell {
+        ShellEnum::Bash(_) => Some((".bashrc", r#"eval "$(pixi completion --shell bash)""#)),
+        ShellEnum::Zsh(_) => Some((".zshrc", r#"eval "$(pixi completion --shell zsh)""#)),
+        ShellEnum::Fish(_) => Some((
+            ".config/fish/config.fish",
+            "pixi completion --shell fish | source",
+        )),
+        _ => None,
+    }
+}
+
+/// Appends the line to the file, unless the file already contains it.
+/// Returns true if the file was changed.
+fn append_line(path: &Path, line: &str) -> miette::Result<bool> {
+    let contents = fs_err::read_to_string(path).unwrap_or_default();
+    if contents.lines().any(|existing| existing.trim() == line) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent).into_diagnostic()?;
+    }
+    let separator = if contents.is_empty() || contents.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    fs_err::write(path, format!("{contents}{separator}{line}\n")).into_diagnostic()?;
+    Ok(true)
+}
+
+/// Asks for the default channels of new projects.
+fn configure_channels(config: &mut Config, theme: &ColorfulTheme) -> miette::Result<()> {
+    let current = if config.default_channels.is_empty() {
+        consts::DEFAULT_CHANNELS.iter().join(", ")
+    } else {
+        config.default_channels.iter().join(", ")
+    };
+    let channels = dialoguer::Input::<String>::with_theme(theme)
+        .with_prompt("Default channels for new projects")
+        .default(current)
+        .validate_with(|input: &String| parse_channels(input).map(|_| ()))
+        .interact_text()
+        .into_diagnostic()?;
+    config.default_channels = parse_channels(&channels)?;
+    Ok(())
+}
+
+/// Asks for the cache directory, the package, repodata and PyPI caches are
+/// stored in it.
+fn configure_cache(config: &mut Config, theme: &ColorfulTheme) -> miette::Result<()> {
+    let current = pixi_config::get_cache_dir()?;
+    let cache_dir = dialoguer::Input::<String>::with_theme(theme)
+        .with_prompt("Cache directory")
+        .default(current.display().to_string())
+        .interact_text()
+        .into_diagnostic()?;
+    let cache_dir = PathBuf::from(cache_dir);
+    if cache_dir != current {
+        config.cache_dirs.packages = Some(cache_dir.join(consts::CONDA_PACKAGE_CACHE_DIR));
+        config.cache_dirs.repodata = Some(cache_dir.join(consts::CONDA_REPODATA_CACHE_DIR));
+        config.cache_dirs.pypi = Some(cache_dir.join(consts::PYPI_CACHE_DIR));
+    }
+    Ok(())
+}
+
+/// Asks for the tokens of the hosts that require authentication and stores
+/// them in the same storage as `pixi auth login`.
+fn configure_authentication(theme: &ColorfulTheme) -> miette::Result<()> {
+    let mut prompt = "Do any of your channels require authentication?";
+    while dialoguer::Confirm::with_theme(theme)
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .into_diagnostic()?
+    {
+        let host = dialoguer::Input::<String>::with_theme(theme)
+            .with_prompt("Host (e.g. repo.prefix.dev)")
+            .interact_text()
+            .into_diagnostic()?;
+        let host = normalize_host(&host)?;
+        let token = dialoguer::Password::with_theme(theme)
+            .with_prompt(format!("Token for {host}"))
+            .interact()
+            .into_diagnostic()?;
+
+        let authentication = if host.contains("anaconda.org") {
+            Authentication::CondaToken(token)
+        } else {
+            Authentication::BearerToken(token)
+        };
+        AuthenticationStorage::default()
+            .store(&host, &authentication)
+            .map_err(|e| miette::miette!("failed to store the credentials: {e}"))?;
+        eprintln!(
+            "{}Stored the credentials for {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            console::style(&host).bold()
+        );
+        prompt = "Do you want to add credentials for another host?";
+    }
+    Ok(())
+}
+
+/// Offers to load the pixi completions in the startup file of the shell.
+fn configure_shell(theme: &ColorfulTheme) -> miette::Result<()> {
+    let shell = ShellEnum::from_parent_process().or_else(ShellEnum::from_env);
+    let Some((startup_file, line)) = shell.as_ref().and_then(completion_line) else {
+        eprintln!(
+            "Shell completions are not set up automatically for your shell, see `pixi completion --help`"
+        );
+        return Ok(());
+    };
+    let Some(path) = dirs::home_dir().map(|home| home.join(startup_file)) else {
+        return Ok(());
+    };
+
+    if dialoguer::Confirm::with_theme(theme)
+        .with_prompt(format!("Add shell completions to {}?", path.display()))
+        .default(true)
+        .interact()
+        .into_diagnostic()?
+        && append_line(&path, line)?
+    {
+        eprintln!(
+            "{}Added shell completions to {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Checks that the repodata of the default channels can be downloaded.
+/// Returns true if all channels can be reached.
+async fn verify_channels(config: &Config) -> bool {
+    let (_, client) = build_reqwest_clients(Some(config));
+    let mut reachable = true;
+    for channel in &config.default_channels {
+        let result = match channel
+            .clone()
+            .into_base_url(config.global_channel_config())
+        {
+            Ok(url) => match url.url().join("noarch/repodata.json") {
+                Ok(url) => client
+                    .head(url)
+                    .send()
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|response| {
+                        response
+                            .error_for_status()
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    }),
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(()) => eprintln!(
+                "{}Channel {} can be reached",
+                console::style(console::Emoji("✔ ", "")).green(),
+                console::style(channel).bold()
+            ),
+            Err(err) => {
+                reachable = false;
+                eprintln!(
+                    "{}Channel {} can't be reached: {}",
+                    console::style(console::Emoji("✘ ", "")).red(),
+                    console::style(channel).bold(),
+                    err
+                );
+            }
+        }
+    }
+    reachable
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    if !std::io::stdin().is_terminal() {
+        miette::bail!(
+            help = "use `pixi config set --global` to configure pixi without prompts",
+            "`pixi setup` is interactive and needs a terminal"
+        );
+    }
+
+    let theme = ColorfulTheme::default();
+    let config_path = global_config_write_path();
+    let mut config = Config::load_global();
+    eprintln!(
+        "Welcome to pixi! The answers are stored in {}\n",
+        console::style(config_path.display()).bold()
+    );
+
+    configure_channels(&mut config, &theme)?;
+    configure_cache(&mut config, &theme)?;
+    configure_authentication(&theme)?;
+
+    config.save(&config_path)?;
+    eprintln!(
+        "{}Updated config at {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        config_path.display()
+    );
+
+    configure_shell(&theme)?;
+
+    if !args.no_verify && !verify_channels(&config).await {
+        miette::bail!(
+            help = "check your network connection and the credentials of the channels, e.g. with `pixi auth login`",
+            "not all default channels can be reached"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_channels() {
+        let channels =
+            parse_channels("conda-forge, bioconda https://prefix.dev/my-channel").unwrap();
+        assert_eq!(
+            channels.iter().map(|c| c.to_string()).collect_vec(),
+            vec!["conda-forge", "bioconda", "https://prefix.dev/my-channel"]
+        );
+        assert!(parse_channels("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_append_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".bashrc");
+        fs_err::write(&path, "export FOO=bar").unwrap();
+
+        let line = r#"eval "$(pixi completion --shell bash)""#;
+        assert!(append_line(&path, line).unwrap());
+        // The line is only added once
+        assert!(!append_line(&path, line).unwrap());
+        assert_eq!(
+            fs_err::read_to_string(&path).unwrap(),
+            format!("export FOO=bar\n{line}\n")
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use std::{collections::HashMap, io::Write};
+use std::{collections::HashMap, io::Write, path::Path};
 
 use clap::Parser;
+use fancy_display::FancyDisplay;
 use miette::IntoDiagnostic;
 use rattler_conda_types::Platform;
 use rattler_shell::{
@@ -11,9 +12,10 @@ use rattler_shell::{
 use crate::cli::cli_config::{PrefixUpdateConfig, ProjectConfig};
 use crate::lock_file::UpdateMode;
 use crate::{
-    activation::CurrentEnvVarBehavior, environment::get_update_lock_file_and_prefix,
-    project::virtual_packages::verify_current_platform_has_required_virtual_packages, prompt,
-    Project, UpdateLockFileOptions,
+    activation::{resolve_global_executable_conflicts, CurrentEnvVarBehavior},
+    environment::get_update_lock_file_and_prefix,
+    project::virtual_packages::verify_current_platform_has_required_virtual_packages,
+    prompt, Project, UpdateLockFileOptions,
 };
 use pixi_config::{ConfigCliActivation, ConfigCliPrompt};
 use pixi_manifest::EnvironmentName;
@@ -33,6 +35,12 @@ pub struct Args {
     #[arg(long, short)]
     environment: Option<String>,
 
+    /// The shell to start, detected from the parent process by default.
+    /// Options: [`bash`,  `zsh`,  `xonsh`,  `cmd`, `powershell`,  `fish`,
+    /// `nushell`]
+    #[arg(long, short)]
+    shell: Option<ShellEnum>,
+
     #[clap(flatten)]
     prompt_config: ConfigCliPrompt,
 
@@ -238,6 +246,26 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     verify_current_platform_has_required_virtual_packages(&environment).into_diagnostic()?;
 
+    // Starting the same shell again only stacks another activation on top of
+    // the current one.
+    if std::env::var("PIXI_IN_SHELL").is_ok() {
+        let same_project = std::env::var("PIXI_PROJECT_MANIFEST")
+            .is_ok_and(|path| Path::new(&path) == project.manifest_path());
+        let same_environment = std::env::var("PIXI_ENVIRONMENT_NAME")
+            .is_ok_and(|name| name == environment.name().as_str());
+        if same_project && same_environment {
+            eprintln!(
+                "{}The {} environment is already activated in this shell, use `exit` to deactivate it.",
+                console::style(console::Emoji("✔ ", "")).green(),
+                environment.name().fancy_display()
+            );
+            return Ok(());
+        }
+        tracing::warn!(
+            "starting a nested shell, `exit` returns to the pixi shell that is already active"
+        );
+    }
+
     let prompt_name = match environment.name() {
         EnvironmentName::Default => project.name().to_string(),
         EnvironmentName::Named(name) => format!("{}:{}", project.name(), name),
@@ -256,7 +284,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     .await?;
 
     // Get the environment variables we need to set activate the environment in the shell.
-    let env = project
+    let mut env = project
         .get_activated_environment_variables(
             &environment,
             CurrentEnvVarBehavior::Exclude,
@@ -264,14 +292,18 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             project.config().force_activate(),
             project.config().experimental_activation_cache_usage(),
         )
-        .await?;
+        .await?
+        .clone();
+    resolve_global_executable_conflicts(&environment, &mut env);
 
     tracing::debug!("Pixi environment activation:\n{:?}", env);
 
     // Start the shell as the last part of the activation script based on the default shell.
-    let interactive_shell: ShellEnum = ShellEnum::from_parent_process()
-        .or_else(ShellEnum::from_env)
-        .unwrap_or_default();
+    let interactive_shell: ShellEnum = args.shell.unwrap_or_else(|| {
+        ShellEnum::from_parent_process()
+            .or_else(ShellEnum::from_env)
+            .unwrap_or_default()
+    });
 
     tracing::info!("Starting shell: {:?}", interactive_shell);
 
@@ -291,9 +323,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     #[cfg(target_family = "windows")]
     let res = match interactive_shell {
-        ShellEnum::NuShell(nushell) => start_nu_shell(nushell, env, prompt).await,
-        ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, env, prompt),
-        ShellEnum::CmdExe(cmdexe) => start_cmdexe(cmdexe, env, prompt),
+        ShellEnum::NuShell(nushell) => start_nu_shell(nushell, &env, prompt).await,
+        ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, &env, prompt),
+        ShellEnum::CmdExe(cmdexe) => start_cmdexe(cmdexe, &env, prompt),
         _ => {
             miette::bail!("Unsupported shell: {:?}", interactive_shell);
         }
@@ -301,12 +333,12 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     #[cfg(target_family = "unix")]
     let res = match interactive_shell {
-        ShellEnum::NuShell(nushell) => start_nu_shell(nushell, env, prompt).await,
-        ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, env, prompt),
-        ShellEnum::Bash(bash) => start_unix_shell(bash, vec!["-l", "-i"], env, prompt).await,
-        ShellEnum::Zsh(zsh) => start_unix_shell(zsh, vec!["-l", "-i"], env, prompt).await,
-        ShellEnum::Fish(fish) => start_unix_shell(fish, vec![], env, prompt).await,
-        ShellEnum::Xonsh(xonsh) => start_unix_shell(xonsh, vec![], env, prompt).await,
+        ShellEnum::NuShell(nushell) => start_nu_shell(nushell, &env, prompt).await,
+        ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, &env, prompt),
+        ShellEnum::Bash(bash) => start_unix_shell(bash, vec!["-l", "-i"], &env, prompt).await,
+        ShellEnum::Zsh(zsh) => start_unix_shell(zsh, vec!["-l", "-i"], &env, prompt).await,
+        ShellEnum::Fish(fish) => start_unix_shell(fish, vec![], &env, prompt).await,
+        ShellEnum::Xonsh(xonsh) => start_unix_shell(xonsh, vec![], &env, prompt).await,
         _ => {
             miette::bail!("Unsupported shell: {:?}", interactive_shell)
         }
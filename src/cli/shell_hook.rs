@@ -1,4 +1,7 @@
-use std::{collections::HashMap, default::Default};
+use std::{
+    collections::{BTreeMap, HashMap},
+    default::Default,
+};
 
 use clap::Parser;
 use miette::IntoDiagnostic;
@@ -9,7 +12,6 @@ use rattler_shell::{
     shell::ShellEnum,
 };
 use serde::Serialize;
-use serde_json;
 
 use crate::activation::CurrentEnvVarBehavior;
 use crate::environment::get_update_lock_file_and_prefix;
@@ -44,7 +46,8 @@ pub struct Args {
     #[arg(long, short)]
     environment: Option<String>,
 
-    /// Emit the environment variables set by running the activation as JSON
+    /// Emit the environment variables changed by running the activation as
+    /// JSON
     #[clap(long, default_value = "false", conflicts_with = "shell")]
     json: bool,
 
@@ -54,7 +57,20 @@ pub struct Args {
 
 #[derive(Serialize)]
 struct ShellEnv<'a> {
-    environment_variables: &'a HashMap<String, String>,
+    environment_variables: BTreeMap<&'a str, &'a str>,
+}
+
+/// Returns the variables of the activation whose value differs from the
+/// current environment, sorted by name so the output is stable.
+fn environment_diff<'a>(
+    activation_variables: &'a HashMap<String, String>,
+    current_variables: &HashMap<String, String>,
+) -> BTreeMap<&'a str, &'a str> {
+    activation_variables
+        .iter()
+        .filter(|(key, value)| current_variables.get(*key) != Some(*value))
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect()
 }
 
 /// Generates the activation script.
@@ -89,14 +105,14 @@ async fn generate_activation_script(
     result.script.contents().into_diagnostic()
 }
 
-/// Generates a JSON object describing the changes to the shell environment when
+/// Prints a JSON object describing the changes to the shell environment when
 /// activating the provided pixi environment.
-async fn generate_environment_json(
+async fn print_environment_json(
     environment: &Environment<'_>,
     lock_file: &LockFile,
     force_activate: bool,
     experimental_cache: bool,
-) -> miette::Result<String> {
+) -> miette::Result<()> {
     let environment_variables = environment
         .project()
         .get_activated_environment_variables(
@@ -108,11 +124,12 @@ async fn generate_environment_json(
         )
         .await?;
 
+    let current_variables = std::env::vars().collect();
     let shell_env = ShellEnv {
-        environment_variables,
+        environment_variables: environment_diff(environment_variables, &current_variables),
     };
 
-    serde_json::to_string(&shell_env).into_diagnostic()
+    pixi_progress::print_json(&shell_env, false).into_diagnostic()
 }
 
 /// Prints the activation script to the stdout.
@@ -136,23 +153,21 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     )
     .await?;
 
-    let output = match args.json {
-        true => {
-            generate_environment_json(
-                &environment,
-                &lock_file_data.lock_file,
-                project.config().force_activate(),
-                project.config().experimental_activation_cache_usage(),
-            )
-            .await?
-        }
+    // Print the output - either a JSON object or a shell script
+    if args.json || pixi_progress::json_output() {
+        print_environment_json(
+            &environment,
+            &lock_file_data.lock_file,
+            project.config().force_activate(),
+            project.config().experimental_activation_cache_usage(),
+        )
+        .await?;
+    } else {
         // Skipping the activated environment caching for the script.
         // As it can still run scripts.
-        false => generate_activation_script(args.shell, &environment).await?,
-    };
-
-    // Print the output - either a JSON object or a shell script
-    println!("{}", output);
+        let output = generate_activation_script(args.shell, &environment).await?;
+        println!("{}", output);
+    }
 
     Ok(())
 }
@@ -215,4 +230,33 @@ mod tests {
         assert!(script.contains(&format!("$env.{path_var_name} = ")));
         assert!(script.contains("$env.CONDA_PREFIX = "));
     }
+
+    #[test]
+    fn test_environment_diff() {
+        let activation = HashMap::from([
+            (
+                String::from("CONDA_PREFIX"),
+                String::from("/project/.pixi/envs/default"),
+            ),
+            (
+                String::from("PATH"),
+                String::from("/project/.pixi/envs/default/bin:/usr/bin"),
+            ),
+            (String::from("PIXI_PROJECT_NAME"), String::from("project")),
+        ]);
+        let current = HashMap::from([
+            (String::from("PATH"), String::from("/usr/bin")),
+            (String::from("PIXI_PROJECT_NAME"), String::from("project")),
+            (String::from("HOME"), String::from("/home/user")),
+        ]);
+
+        let diff = environment_diff(&activation, &current);
+        assert_eq!(
+            diff.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("CONDA_PREFIX", "/project/.pixi/envs/default"),
+                ("PATH", "/project/.pixi/envs/default/bin:/usr/bin"),
+            ]
+        );
+    }
 }
@@ -90,6 +90,14 @@ pub struct AddArgs {
     /// Isolate the task from the shell environment, and only use the pixi environment to run the task
     #[arg(long)]
     pub clean_env: bool,
+
+    /// The number of times the task is retried when it exits with a non-zero exit code
+    #[arg(long)]
+    pub retries: Option<u32>,
+
+    /// The number of seconds to wait before the first retry, doubled for every subsequent retry
+    #[arg(long, requires = "retries")]
+    pub retry_backoff: Option<u64>,
 }
 
 /// Parse a single key-value pair
@@ -116,6 +124,10 @@ pub struct AliasArgs {
     #[arg(long, short)]
     pub platform: Option<Platform>,
 
+    /// The feature for which the alias should be added
+    #[arg(long, short)]
+    pub feature: Option<String>,
+
     /// The description of the alias task
     #[arg(long)]
     pub description: Option<String>,
@@ -173,6 +185,7 @@ impl From<AddArgs> for Task {
             && value.cwd.is_none()
             && value.env.is_empty()
             && description.is_none()
+            && value.retries.is_none()
         {
             Self::Plain(cmd_args)
         } else {
@@ -197,6 +210,9 @@ impl From<AddArgs> for Task {
                 env,
                 description,
                 clean_env,
+                retries: value.retries.unwrap_or_default(),
+                retry_backoff: value.retry_backoff,
+                sandbox: None,
             })
         }
     }
@@ -228,6 +244,27 @@ fn print_heading(value: &str) {
     eprintln!("{}\n{:-<2$}", bold.apply_to(value), "", value.len(),);
 }
 
+/// Formats the name of a task together with the feature and platform it is
+/// defined for, if the task doesn't come from the default feature or is
+/// platform specific.
+fn format_task_origin(env: &Environment, name: &TaskName) -> String {
+    let Some((feature, selector)) = env.task_origin(name, Some(env.best_platform())) else {
+        return name.fancy_display().to_string();
+    };
+
+    let origin = (!feature.is_default())
+        .then(|| feature.fancy_display().to_string())
+        .into_iter()
+        .chain(selector.map(|selector| console::style(selector).cyan().to_string()))
+        .join(", ");
+
+    if origin.is_empty() {
+        name.fancy_display().to_string()
+    } else {
+        format!("{} ({})", name.fancy_display(), origin)
+    }
+}
+
 fn list_tasks(
     task_map: HashMap<Environment, HashMap<TaskName, Task>>,
     summary: bool,
@@ -238,7 +275,7 @@ fn list_tasks(
             let formatted: String = tasks
                 .keys()
                 .sorted()
-                .map(|name| name.fancy_display())
+                .map(|name| format_task_origin(&env, name))
                 .join(", ");
             eprintln!("{}: {}", env.name().fancy_display().bold(), formatted);
         }
@@ -360,12 +397,12 @@ pub fn execute(args: Args) -> miette::Result<()> {
         Operation::Alias(args) => {
             let name = &args.alias;
             let task: Task = args.clone().into();
-            project.manifest.add_task(
-                name.clone(),
-                task.clone(),
-                args.platform,
-                &FeatureName::Default,
-            )?;
+            let feature = args
+                .feature
+                .map_or(FeatureName::Default, FeatureName::Named);
+            project
+                .manifest
+                .add_task(name.clone(), task.clone(), args.platform, &feature)?;
             project.save()?;
             eprintln!(
                 "{} Added alias `{}`: {}",
@@ -375,7 +412,7 @@ pub fn execute(args: Args) -> miette::Result<()> {
             );
         }
         Operation::List(args) => {
-            if args.json {
+            if args.json || pixi_progress::json_output() {
                 print_tasks_json(&project);
                 return Ok(());
             }
@@ -455,9 +492,7 @@ pub fn execute(args: Args) -> miette::Result<()> {
 fn print_tasks_json(project: &Project) {
     let env_feature_task_map: Vec<EnvTasks> = build_env_feature_task_map(project);
 
-    let json_string =
-        serde_json::to_string_pretty(&env_feature_task_map).expect("Failed to serialize tasks");
-    println!("{}", json_string);
+    pixi_progress::print_json(&env_feature_task_map, true).expect("Failed to serialize tasks");
 }
 
 fn build_env_feature_task_map(project: &Project) -> Vec<EnvTasks> {
@@ -535,6 +570,7 @@ pub struct TaskInfo {
     clean_env: bool,
     inputs: Option<Vec<String>>,
     outputs: Option<Vec<String>>,
+    retries: u32,
 }
 
 impl From<&Task> for TaskInfo {
@@ -552,6 +588,7 @@ impl From<&Task> for TaskInfo {
             outputs: task
                 .outputs()
                 .map(|outputs| outputs.iter().map(String::from).collect()),
+            retries: task.retries(),
         }
     }
 }
@@ -13,6 +13,7 @@ use pixi_manifest::FeaturesExt;
 use rattler_conda_types::Platform;
 use rattler_lock::LockedPackageRef;
 use regex::Regex;
+use serde::Serialize;
 
 use crate::{
     cli::cli_config::{PrefixUpdateConfig, ProjectConfig},
@@ -100,6 +101,17 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     let direct_deps = direct_dependencies(&environment, &platform, &dep_map);
 
+    if pixi_progress::json_output() {
+        let tree = if args.invert {
+            json_dependency_tree(&invert_dep_map(&dep_map), &direct_deps, &args.regex, true)?
+        } else {
+            json_dependency_tree(&dep_map, &direct_deps, &args.regex, false)?
+        };
+        pixi_progress::emit_json_result(&tree).into_diagnostic()?;
+        Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+        return Ok(());
+    }
+
     if !environment.is_default() {
         eprintln!("Environment: {}", environment.name().fancy_display());
     }
@@ -417,6 +429,106 @@ fn print_package(
     .wrap_err("Failed to write package information")
 }
 
+/// The dependency tree in the JSON output, as the packages at the top of the
+/// tree and all packages below them.
+#[derive(Serialize)]
+struct DependencyTreeJson<'a> {
+    roots: Vec<&'a str>,
+    packages: Vec<PackageJson<'a>>,
+}
+
+/// A package of the dependency tree in the JSON output.
+#[derive(Serialize)]
+struct PackageJson<'a> {
+    name: &'a str,
+    version: &'a str,
+    source: PackageSource,
+    /// Whether the package is a direct dependency of the environment.
+    direct: bool,
+    /// The packages below this package in the tree, the dependencies of the
+    /// package or, for an inverted tree, the packages that depend on it.
+    children: &'a [String],
+}
+
+/// Selects the packages of the dependency tree the same way as the printed
+/// tree, for the JSON output.
+fn json_dependency_tree<'a>(
+    dep_map: &'a HashMap<String, Package>,
+    direct_deps: &HashSet<String>,
+    regex: &Option<String>,
+    inverted: bool,
+) -> miette::Result<DependencyTreeJson<'a>> {
+    let regex = regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .into_diagnostic()
+        .wrap_err("Invalid regular expression")?;
+    let is_match = |name: &str| {
+        regex
+            .as_ref()
+            .map(|regex| regex.is_match(name))
+            .unwrap_or(true)
+    };
+
+    let mut roots = if inverted {
+        dep_map.keys().filter(|name| is_match(name)).collect_vec()
+    } else {
+        let roots = dep_map
+            .keys()
+            .filter(|name| direct_deps.contains(*name) && is_match(name))
+            .collect_vec();
+        if roots.is_empty() && regex.is_some() {
+            dep_map.keys().filter(|name| is_match(name)).collect_vec()
+        } else {
+            roots
+        }
+    };
+    if roots.is_empty() && regex.is_some() {
+        miette::bail!(if inverted {
+            "Nothing depends on the given regular expression"
+        } else {
+            "No dependencies matched the given regular expression"
+        });
+    }
+    roots.sort();
+
+    let children = |package: &'a Package| {
+        if inverted {
+            &package.needed_by
+        } else {
+            &package.dependencies
+        }
+    };
+    let mut visited = HashSet::new();
+    let mut queue = roots.clone();
+    while let Some(name) = queue.pop() {
+        if let Some(package) = dep_map.get(name) {
+            if visited.insert(name) {
+                queue.extend(children(package));
+            }
+        }
+    }
+
+    Ok(DependencyTreeJson {
+        roots: roots.into_iter().map(String::as_str).collect(),
+        packages: visited
+            .into_iter()
+            .sorted()
+            .map(|name| {
+                let package = &dep_map[name];
+                PackageJson {
+                    name,
+                    version: &package.version,
+                    source: package.source,
+                    direct: direct_deps.contains(name),
+                    children: children(package),
+                }
+            })
+            .collect(),
+    })
+}
+
 /// Extract the direct Conda and PyPI dependencies from the environment
 fn direct_dependencies(
     environment: &Environment<'_>,
@@ -452,7 +564,8 @@ fn direct_dependencies(
     project_dependency_names
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 enum PackageSource {
     Conda,
     Pypi,
@@ -43,6 +43,11 @@ pub struct Args {
     /// Output the changes in JSON format.
     #[clap(long)]
     pub json: bool,
+
+    /// Explain why each package was added, removed or changed, by showing the
+    /// packages that require it.
+    #[clap(long, conflicts_with = "json")]
+    pub explain: bool,
 }
 
 #[derive(Parser, Debug, Default)]
@@ -172,11 +177,10 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     let diff = LockFileDiff::from_lock_files(&loaded_lock_file, &updated_lock_file.lock_file);
 
     // Format as json?
-    if args.json {
+    if args.json || pixi_progress::json_output() {
         let diff = LockFileDiff::from_lock_files(&loaded_lock_file, &updated_lock_file.lock_file);
         let json_diff = LockFileJsonDiff::new(&project, diff);
-        let json = serde_json::to_string_pretty(&json_diff).expect("failed to convert to json");
-        println!("{}", json);
+        pixi_progress::print_json(&json_diff, true).expect("failed to convert to json");
     } else if diff.is_empty() {
         eprintln!(
             "{}Lock-file was already up-to-date",
@@ -186,6 +190,12 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         diff.print()
             .into_diagnostic()
             .context("failed to print lock-file diff")?;
+        if args.explain {
+            diff.explain(&project, &loaded_lock_file, &updated_lock_file.lock_file)
+                .print()
+                .into_diagnostic()
+                .context("failed to print the reasons of the changes")?;
+        }
     }
 
     Ok(())
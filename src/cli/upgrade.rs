@@ -84,10 +84,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     if let Some(update_deps) = update_deps {
         let diff = update_deps.lock_file_diff;
         // Format as json?
-        if args.json {
+        if args.json || pixi_progress::json_output() {
             let json_diff = LockFileJsonDiff::new(&project, diff);
-            let json = serde_json::to_string_pretty(&json_diff).expect("failed to convert to json");
-            println!("{}", json);
+            pixi_progress::print_json(&json_diff, true).expect("failed to convert to json");
         } else {
             diff.print()
                 .into_diagnostic()
@@ -0,0 +1,421 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_config::ConfigCli;
+use pixi_consts::consts;
+use rattler::package_cache::CacheKey;
+use rattler_conda_types::{PackageName, PrefixRecord, RepoDataRecord};
+use rattler_digest::{compute_file_digest, Sha256, Sha256Hash};
+use rattler_lock::LockFile;
+
+use crate::{
+    cli::cli_config::ProjectConfig,
+    environment::{get_update_lock_file_and_prefix, LockFileUsage},
+    lock_file::UpdateMode,
+    prefix::Prefix,
+    prefix_transaction::{
+        backup_packages, finish_backup, read_install_journal, remove_backup, restore_backup,
+    },
+    project::{Environment, HasProjectRef},
+    Project, UpdateLockFileOptions,
+};
+
+/// Verify the installed packages against the lock file
+///
+/// Checks that the installed conda packages are the packages of the lock file
+/// and that the files of every package still have the content they were
+/// installed with. PyPI packages are not verified.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: ProjectConfig,
+
+    /// The environment to verify, defaults to all installed environments.
+    #[arg(long, short)]
+    pub environment: Option<Vec<String>>,
+
+    /// Re-download and reinstall the packages that failed verification.
+    #[arg(long)]
+    pub fix: bool,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+/// A problem found while verifying an installed package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Problem {
+    /// The package of the lock file is not installed.
+    NotInstalled,
+    /// The installed package has a different hash than the locked package.
+    LockFileMismatch,
+    /// A file of the package is missing from the prefix.
+    MissingFile(PathBuf),
+    /// A file of the package has different content than when it was installed.
+    ModifiedFile(PathBuf),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::NotInstalled => write!(f, "is not installed"),
+            Problem::LockFileMismatch => write!(f, "does not match the package in the lock file"),
+            Problem::MissingFile(path) => write!(f, "is missing '{}'", path.display()),
+            Problem::ModifiedFile(path) => write!(f, "has a modified '{}'", path.display()),
+        }
+    }
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let project = Project::load_or_else_discover(args.project_config.manifest_path.as_deref())?
+        .with_cli_config(args.config);
+
+    let environments = match args.environment {
+        Some(names) => names
+            .into_iter()
+            .map(|name| project.environment_from_name_or_env_var(Some(name)))
+            .collect::<miette::Result<Vec<_>>>()?,
+        None => project
+            .environments()
+            .into_iter()
+            .filter(|env| env.dir().join(consts::CONDA_META_DIR).is_dir())
+            .collect(),
+    };
+    if environments.is_empty() {
+        miette::bail!(
+            help = "install an environment with `pixi install`",
+            "there are no installed environments to verify"
+        );
+    }
+
+    let lock_file = crate::lock_file::load_lock_file(&project).await?;
+
+    let mut failed_environments = Vec::new();
+    for environment in &environments {
+        if args.fix {
+            recover_interrupted_repair(&environment.dir()).await?;
+        }
+        let problems = verify_environment(environment, &lock_file).await?;
+
+        if problems.is_empty() {
+            eprintln!(
+                "{}The {} environment is intact.",
+                console::style(console::Emoji("✔ ", "")).green(),
+                environment.name().fancy_display()
+            );
+            continue;
+        }
+
+        eprintln!(
+            "{}The {} environment failed verification:",
+            console::style(console::Emoji("✘ ", "")).red(),
+            environment.name().fancy_display()
+        );
+        for (name, problems) in problems.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+            for problem in problems {
+                eprintln!(
+                    "    - {} {}",
+                    console::style(name.as_normalized()).bold(),
+                    problem
+                );
+            }
+        }
+
+        if args.fix {
+            fix_environment(environment, problems.keys()).await?;
+            eprintln!(
+                "{}Reinstalled {} packages of the {} environment.",
+                console::style(console::Emoji("✔ ", "")).green(),
+                problems.len(),
+                environment.name().fancy_display()
+            );
+        } else {
+            failed_environments.push(environment.name().clone());
+        }
+    }
+
+    Project::warn_on_discovered_from_env(args.project_config.manifest_path.as_deref());
+
+    if !failed_environments.is_empty() {
+        miette::bail!(
+            help = "run `pixi verify --fix` to reinstall the packages that failed verification",
+            "verification failed for {}",
+            failed_environments
+                .iter()
+                .map(|name| name.fancy_display())
+                .join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Verifies the installed packages of the environment against the packages of
+/// the lock file. Returns the problems per package.
+async fn verify_environment(
+    environment: &Environment<'_>,
+    lock_file: &LockFile,
+) -> miette::Result<HashMap<PackageName, Vec<Problem>>> {
+    let locked_records = lock_file
+        .environment(environment.name().as_str())
+        .and_then(|env| {
+            env.conda_repodata_records(environment.best_platform())
+                .transpose()
+        })
+        .transpose()
+        .into_diagnostic()?
+        .unwrap_or_default();
+
+    let prefix = Prefix::new(environment.dir());
+    let installed = prefix.find_installed_packages(None).await?;
+    verify_prefix(environment.dir(), locked_records, installed).await
+}
+
+/// Verifies the environment against the lock file and removes the installed
+/// packages that failed verification, so the next installation of the
+/// environment installs them again. Returns the names of the removed packages.
+pub(crate) async fn repair_environment(
+    environment: &Environment<'_>,
+    lock_file: &LockFile,
+) -> miette::Result<Vec<PackageName>> {
+    recover_interrupted_repair(&environment.dir()).await?;
+    let problems = verify_environment(environment, lock_file).await?;
+    let packages = problems
+        .into_iter()
+        .filter(|(_, problems)| !problems.contains(&Problem::NotInstalled))
+        .map(|(name, _)| name)
+        .sorted()
+        .collect_vec();
+    remove_packages(environment, &packages).await?;
+    Ok(packages)
+}
+
+/// Compares the installed packages with the locked packages and verifies the
+/// files of every installed package. Returns the problems per package.
+async fn verify_prefix(
+    prefix_root: PathBuf,
+    locked_records: Vec<RepoDataRecord>,
+    installed: Vec<PrefixRecord>,
+) -> miette::Result<HashMap<PackageName, Vec<Problem>>> {
+    // Hashing all files is IO bound, so do it on a blocking thread.
+    tokio::task::spawn_blocking(move || {
+        let mut installed = installed
+            .into_iter()
+            .map(|record| (record.repodata_record.package_record.name.clone(), record))
+            .collect::<HashMap<_, _>>();
+
+        let mut problems = HashMap::new();
+        for locked in locked_records {
+            let name = locked.package_record.name.clone();
+            let Some(record) = installed.remove(&name) else {
+                problems.insert(name, vec![Problem::NotInstalled]);
+                continue;
+            };
+
+            let mut package_problems = Vec::new();
+            if !hashes_match(&locked, &record.repodata_record) {
+                package_problems.push(Problem::LockFileMismatch);
+            }
+            package_problems.extend(verify_package_files(&prefix_root, &record));
+            if !package_problems.is_empty() {
+                problems.insert(name, package_problems);
+            }
+        }
+        problems
+    })
+    .await
+    .into_diagnostic()
+}
+
+/// Returns true if the hashes of the installed package match the hashes of
+/// the locked package. Hashes that are missing on either side are not compared.
+fn hashes_match(locked: &RepoDataRecord, installed: &RepoDataRecord) -> bool {
+    let locked = &locked.package_record;
+    let installed = &installed.package_record;
+    match (locked.sha256, installed.sha256) {
+        (Some(locked), Some(installed)) => locked == installed,
+        _ => match (locked.md5, installed.md5) {
+            (Some(locked), Some(installed)) => locked == installed,
+            _ => true,
+        },
+    }
+}
+
+/// Verifies the files of an installed package against the hashes recorded in
+/// its `paths.json` when it was linked.
+fn verify_package_files(prefix_root: &Path, record: &PrefixRecord) -> Vec<Problem> {
+    record
+        .paths_data
+        .paths
+        .iter()
+        .filter_map(|entry| {
+            // Files that contained a prefix placeholder are changed on install, only
+            // the hash after linking can be used for those.
+            let expected = entry.sha256_in_prefix.as_ref().or_else(|| {
+                entry
+                    .prefix_placeholder
+                    .is_none()
+                    .then_some(entry.sha256.as_ref())
+                    .flatten()
+            });
+            verify_file(prefix_root, &entry.relative_path, expected)
+        })
+        .collect()
+}
+
+/// Verifies that the file exists and, if a hash is known, that its content has
+/// the expected hash.
+fn verify_file(
+    prefix_root: &Path,
+    relative_path: &Path,
+    expected: Option<&Sha256Hash>,
+) -> Option<Problem> {
+    let path = prefix_root.join(relative_path);
+    if path.symlink_metadata().is_err() {
+        return Some(Problem::MissingFile(relative_path.to_path_buf()));
+    }
+    let expected = expected?;
+    match compute_file_digest::<Sha256>(&path) {
+        Ok(hash) if &hash == expected => None,
+        _ => Some(Problem::ModifiedFile(relative_path.to_path_buf())),
+    }
+}
+
+/// Removes the packages that failed verification from the prefix and the
+/// package cache, and installs the environment again from the lock file.
+///
+/// Installed files are hard linked to the package cache, so a corrupted file is
+/// usually also corrupted in the cache and the package has to be downloaded
+/// again.
+async fn fix_environment<'a>(
+    environment: &Environment<'_>,
+    packages: impl IntoIterator<Item = &'a PackageName>,
+) -> miette::Result<()> {
+    let packages = packages.into_iter().cloned().collect_vec();
+    remove_packages(environment, &packages).await?;
+
+    let result = get_update_lock_file_and_prefix(
+        environment,
+        UpdateMode::Revalidate,
+        UpdateLockFileOptions {
+            lock_file_usage: LockFileUsage::Frozen,
+            no_install: false,
+            max_concurrent_solves: environment.project().config().max_concurrent_solves(),
+        },
+    )
+    .await;
+    finish_repair(&environment.dir(), result).await?;
+    Ok(())
+}
+
+/// Removes the packages from the prefix and the package cache, without their
+/// record the installer considers a package as missing and links it again.
+///
+/// The files and records of the packages are backed up first, call
+/// [`finish_repair`] with the result of the reinstallation to either discard
+/// or restore the backup.
+async fn remove_packages(
+    environment: &Environment<'_>,
+    packages: &[PackageName],
+) -> miette::Result<()> {
+    if packages.is_empty() {
+        return Ok(());
+    }
+    let prefix = Prefix::new(environment.dir());
+    let package_cache_dir = environment.project().config().package_cache_dir()?;
+
+    let installed = prefix.find_installed_packages(None).await?;
+    let records = installed
+        .iter()
+        .filter(|record| packages.contains(&record.repodata_record.package_record.name))
+        .collect_vec();
+    backup_packages(
+        &environment.dir(),
+        &repair_backup_dir(&environment.dir()),
+        &installed,
+        &records,
+    )?;
+
+    for record in records {
+        let package_record = &record.repodata_record.package_record;
+        let cache_entry = package_cache_dir.join(CacheKey::from(package_record).to_string());
+        if cache_entry.is_dir() {
+            fs_err::remove_dir_all(&cache_entry).into_diagnostic()?;
+        }
+
+        let record_path = environment
+            .dir()
+            .join(consts::CONDA_META_DIR)
+            .join(record.file_name());
+        fs_err::remove_file(&record_path)
+            .into_diagnostic()
+            .with_context(|| format!("failed to remove '{}'", record_path.display()))?;
+    }
+    Ok(())
+}
+
+/// The directory in the `conda-meta` directory of the environment that holds
+/// the backup of a repair.
+fn repair_backup_dir(environment_dir: &Path) -> PathBuf {
+    environment_dir
+        .join(consts::CONDA_META_DIR)
+        .join(consts::REPAIR_BACKUP_DIR_NAME)
+}
+
+/// Handles a backup that is left over by an interrupted repair. If the
+/// reinstallation was interrupted as well the backup is restored, otherwise a
+/// later installation finished it and the backup is discarded.
+async fn recover_interrupted_repair(environment_dir: &Path) -> miette::Result<()> {
+    let backup_dir = repair_backup_dir(environment_dir);
+    if read_install_journal(environment_dir).is_some() {
+        restore_backup(environment_dir, &backup_dir).await
+    } else {
+        remove_backup(&backup_dir)
+    }
+}
+
+/// Finishes a repair with the result of reinstalling the removed packages. The
+/// backup is discarded when the reinstallation succeeded and restored when it
+/// failed, the result is returned either way.
+pub(crate) async fn finish_repair<T>(
+    environment_dir: &Path,
+    result: miette::Result<T>,
+) -> miette::Result<T> {
+    finish_backup(environment_dir, &repair_backup_dir(environment_dir), result).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = Path::new("bin/tool");
+        fs_err::create_dir_all(temp_dir.path().join("bin")).unwrap();
+        fs_err::write(temp_dir.path().join(path), "original").unwrap();
+        let hash = compute_file_digest::<Sha256>(temp_dir.path().join(path)).unwrap();
+
+        assert_eq!(verify_file(temp_dir.path(), path, Some(&hash)), None);
+        assert_eq!(verify_file(temp_dir.path(), path, None), None);
+
+        fs_err::write(temp_dir.path().join(path), "tampered").unwrap();
+        assert_eq!(
+            verify_file(temp_dir.path(), path, Some(&hash)),
+            Some(Problem::ModifiedFile(path.to_path_buf()))
+        );
+        // Without a hash only the existence of the file can be checked.
+        assert_eq!(verify_file(temp_dir.path(), path, None), None);
+
+        assert_eq!(
+            verify_file(temp_dir.path(), Path::new("bin/missing"), Some(&hash)),
+            Some(Problem::MissingFile(PathBuf::from("bin/missing")))
+        );
+    }
+}
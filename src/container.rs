@@ -0,0 +1,225 @@
+//! Runs tasks in a container for a platform that is not the current platform,
+//! e.g. a linux-only test suite on macOS.
+
+use std::{
+    hash::{Hash, Hasher},
+    io::{ErrorKind, IsTerminal},
+    path::Path,
+};
+
+use miette::IntoDiagnostic;
+use pixi_consts::consts;
+use rattler_conda_types::Platform;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::{project::Environment, Project};
+
+/// The directory the project is mounted at in the container.
+const CONTAINER_PROJECT_DIR: &str = "/workspace";
+
+/// Returns the container platform that corresponds with the conda platform.
+pub(crate) fn container_platform(platform: Platform) -> miette::Result<&'static str> {
+    match platform {
+        Platform::Linux64 => Ok("linux/amd64"),
+        Platform::LinuxAarch64 => Ok("linux/arm64"),
+        Platform::LinuxPpc64le => Ok("linux/ppc64le"),
+        Platform::LinuxS390X => Ok("linux/s390x"),
+        _ => miette::bail!(
+            help = "use one of the linux platforms of the project, e.g. `--platform linux-64`",
+            "cannot create a container for '{}', only linux platforms are supported",
+            platform
+        ),
+    }
+}
+
+/// The name of the volume that holds the `.pixi` directory of the project in
+/// the container. The environments of the host platform live in the `.pixi`
+/// directory of the project, so the container needs its own.
+fn volume_name(project_root: &Path, platform: Platform) -> String {
+    let mut hasher = Xxh3::new();
+    project_root.hash(&mut hasher);
+    format!("pixi-{:x}-{}", hasher.finish(), platform)
+}
+
+/// The arguments of `<engine> run` that run `task` in the environment of the
+/// project inside the container.
+#[allow(clippy::too_many_arguments)]
+fn container_run_args(
+    image: &str,
+    platform: Platform,
+    project_root: &Path,
+    manifest_path: &Path,
+    current_dir: &Path,
+    environment: &str,
+    task: &[String],
+    interactive: bool,
+) -> miette::Result<Vec<String>> {
+    // Paths outside of the project fall back to the project root.
+    let in_container = |path: &Path| match path
+        .strip_prefix(project_root)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+    {
+        Some(relative) => format!(
+            "{CONTAINER_PROJECT_DIR}/{}",
+            relative.to_string_lossy().replace('\\', "/")
+        ),
+        None => CONTAINER_PROJECT_DIR.to_string(),
+    };
+
+    let mut args = vec!["run".to_string(), "--rm".to_string(), "-i".to_string()];
+    if interactive {
+        args.push("-t".to_string());
+    }
+    args.extend([
+        "--platform".to_string(),
+        container_platform(platform)?.to_string(),
+        "-v".to_string(),
+        format!("{}:{CONTAINER_PROJECT_DIR}", project_root.display()),
+        "-v".to_string(),
+        format!(
+            "{}:{CONTAINER_PROJECT_DIR}/{}",
+            volume_name(project_root, platform),
+            consts::PIXI_DIR
+        ),
+        "-w".to_string(),
+        in_container(current_dir),
+        image.to_string(),
+        "pixi".to_string(),
+        "run".to_string(),
+        "--frozen".to_string(),
+        "--manifest-path".to_string(),
+        in_container(manifest_path),
+        "--environment".to_string(),
+        environment.to_string(),
+    ]);
+    args.extend(task.iter().cloned());
+    Ok(args)
+}
+
+/// Runs the task in a container of the configured image for the platform.
+/// The environment is installed in the container from the lock file, so the
+/// lock file has to be up-to-date. Returns the exit code of the task.
+pub(crate) async fn run_in_container(
+    project: &Project,
+    environment: &Environment<'_>,
+    platform: Platform,
+    task: &[String],
+) -> miette::Result<i32> {
+    let runner = project.config().container_runner();
+    let Some(image) = runner.image(platform) else {
+        miette::bail!(
+            help = format!(
+                "configure an image that contains pixi, e.g. `pixi config set container-runner.images '{{\"{platform}\": \"ghcr.io/prefix-dev/pixi:{}\"}}'`",
+                consts::PIXI_VERSION
+            ),
+            "there is no container image configured to run tasks for '{}'",
+            platform
+        );
+    };
+
+    let current_dir = std::env::current_dir().into_diagnostic()?;
+    let args = container_run_args(
+        image,
+        platform,
+        project.root(),
+        &project.manifest_path(),
+        &current_dir,
+        environment.name().as_str(),
+        task,
+        std::io::stdin().is_terminal(),
+    )?;
+
+    let engine = runner.engine().executable();
+    tracing::info!("running in container: {} {}", engine, args.join(" "));
+    let status = match tokio::process::Command::new(engine).args(&args).status().await {
+        Ok(status) => status,
+        Err(err) if err.kind() == ErrorKind::NotFound => miette::bail!(
+            help = "install it or configure another engine with `pixi config set container-runner.engine`",
+            "the container engine '{}' could not be found",
+            engine
+        ),
+        Err(err) => return Err(err).into_diagnostic(),
+    };
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_platform() {
+        assert_eq!(
+            container_platform(Platform::Linux64).unwrap(),
+            "linux/amd64"
+        );
+        assert!(container_platform(Platform::OsxArm64).is_err());
+        assert!(container_platform(Platform::Win64).is_err());
+    }
+
+    #[test]
+    fn test_container_run_args() {
+        let root = Path::new("/home/user/project");
+        let args = container_run_args(
+            "ghcr.io/prefix-dev/pixi",
+            Platform::Linux64,
+            root,
+            &root.join("pixi.toml"),
+            &root.join("tests"),
+            "test",
+            &["pytest".to_string(), "-x".to_string()],
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            args,
+            vec![
+                "run".to_string(),
+                "--rm".to_string(),
+                "-i".to_string(),
+                "--platform".to_string(),
+                "linux/amd64".to_string(),
+                "-v".to_string(),
+                "/home/user/project:/workspace".to_string(),
+                "-v".to_string(),
+                format!("{}:/workspace/.pixi", volume_name(root, Platform::Linux64)),
+                "-w".to_string(),
+                "/workspace/tests".to_string(),
+                "ghcr.io/prefix-dev/pixi".to_string(),
+                "pixi".to_string(),
+                "run".to_string(),
+                "--frozen".to_string(),
+                "--manifest-path".to_string(),
+                "/workspace/pixi.toml".to_string(),
+                "--environment".to_string(),
+                "test".to_string(),
+                "pytest".to_string(),
+                "-x".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_container_run_args_outside_project() {
+        let root = Path::new("/home/user/project");
+        let args = container_run_args(
+            "ghcr.io/prefix-dev/pixi",
+            Platform::LinuxAarch64,
+            root,
+            &root.join("pixi.toml"),
+            Path::new("/tmp"),
+            "default",
+            &["test".to_string()],
+            true,
+        )
+        .unwrap();
+
+        assert!(args.contains(&"-t".to_string()));
+        assert!(args.contains(&"linux/arm64".to_string()));
+        // The task runs in the project root when called from outside of the project.
+        let workdir = args.iter().position(|arg| arg == "-w").unwrap();
+        assert_eq!(args[workdir + 1], "/workspace");
+    }
+}
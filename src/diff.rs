@@ -1,5 +1,6 @@
 use std::{
     collections::HashSet,
+    fmt::{Display, Formatter},
     io::{stderr, Write},
 };
 
@@ -183,63 +184,23 @@ impl LockFileDiff {
             .sorted_by(|(a, _), (b, _)| a.cmp(b))
             .enumerate()
         {
-            // Find the changes that happened in all platforms.
             let changes_by_platform = environment
                 .into_iter()
                 .map(|(platform, packages)| {
                     let changes = Self::format_changes(packages)
                         .into_iter()
+                        .map(|(name, line)| (name.to_string(), line))
                         .collect::<HashSet<_>>();
                     (platform, changes)
                 })
                 .collect::<Vec<_>>();
 
-            // Find the changes that happened in all platforms.
-            let common_changes = changes_by_platform
-                .iter()
-                .fold(None, |acc, (_, changes)| match acc {
-                    None => Some(changes.clone()),
-                    Some(acc) => Some(acc.intersection(changes).cloned().collect()),
-                })
-                .unwrap_or_default();
-
             // Add a new line between environments
             if idx > 0 {
                 writeln!(writer, "\t\t\t",)?;
             }
 
-            writeln!(
-                writer,
-                "{}: {}\t\t\t",
-                console::style("Environment").underlined(),
-                consts::ENVIRONMENT_STYLE.apply_to(environment_name)
-            )?;
-
-            // Print the common changes.
-            for (_, line) in common_changes.iter().sorted_by_key(|(name, _)| name) {
-                writeln!(writer, "  {}", line)?;
-            }
-
-            // Print the per-platform changes.
-            for (platform, changes) in changes_by_platform {
-                let mut changes = changes
-                    .iter()
-                    .filter(|change| !common_changes.contains(change))
-                    .sorted_by_key(|(name, _)| name)
-                    .peekable();
-                if changes.peek().is_some() {
-                    writeln!(
-                        writer,
-                        "{}: {}:{}\t\t\t",
-                        console::style("Platform").underlined(),
-                        consts::ENVIRONMENT_STYLE.apply_to(environment_name),
-                        consts::PLATFORM_STYLE.apply_to(platform),
-                    )?;
-                    for (_, line) in changes {
-                        writeln!(writer, "  {}", line)?;
-                    }
-                }
-            }
+            write_environment_lines(&mut writer, environment_name, changes_by_platform)?;
         }
 
         writer.flush()?;
@@ -247,6 +208,60 @@ impl LockFileDiff {
         Ok(())
     }
 
+    /// Determines for every added, removed and changed package why it changed,
+    /// by following the dependencies of the packages in both lock-files.
+    pub(crate) fn explain(
+        &self,
+        project: &Project,
+        previous: &LockFile,
+        current: &LockFile,
+    ) -> LockFileExplanation {
+        let mut environment = IndexMap::new();
+        for (environment_name, environment_diff) in &self.environment {
+            let project_environment = project.environment(environment_name.as_str());
+            let mut platforms = IndexMap::new();
+            for (platform, packages_diff) in environment_diff {
+                let explicit: HashSet<String> = project_environment
+                    .as_ref()
+                    .map(|env| {
+                        env.combined_dependencies(Some(*platform))
+                            .names()
+                            .map(|name| name.as_normalized().to_string())
+                            .chain(
+                                env.pypi_dependencies(Some(*platform))
+                                    .names()
+                                    .map(|name| name.as_normalized().to_string()),
+                            )
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let previous_packages = previous
+                    .environment(environment_name)
+                    .and_then(|env| env.packages(*platform))
+                    .into_iter()
+                    .flatten()
+                    .collect_vec();
+                let current_packages = current
+                    .environment(environment_name)
+                    .and_then(|env| env.packages(*platform))
+                    .into_iter()
+                    .flatten()
+                    .collect_vec();
+                platforms.insert(
+                    *platform,
+                    explain_packages(
+                        packages_diff,
+                        &explicit,
+                        &previous_packages,
+                        &current_packages,
+                    ),
+                );
+            }
+            environment.insert(environment_name.clone(), platforms);
+        }
+        LockFileExplanation { environment }
+    }
+
     fn format_changes(packages: &PackagesDiff) -> Vec<(&str, String)> {
         enum Change<'i> {
             Added(&'i LockedPackage),
@@ -352,6 +367,285 @@ impl LockFileDiff {
     }
 }
 
+/// Writes the lines of an environment, the lines that are the same for all
+/// platforms are only written once.
+fn write_environment_lines(
+    writer: &mut impl Write,
+    environment_name: &str,
+    lines_by_platform: Vec<(&Platform, HashSet<(String, String)>)>,
+) -> std::io::Result<()> {
+    // Find the lines that are the same in all platforms.
+    let common_lines = lines_by_platform
+        .iter()
+        .fold(None, |acc, (_, lines)| match acc {
+            None => Some(lines.clone()),
+            Some(acc) => Some(acc.intersection(lines).cloned().collect()),
+        })
+        .unwrap_or_default();
+
+    writeln!(
+        writer,
+        "{}: {}\t\t\t",
+        console::style("Environment").underlined(),
+        consts::ENVIRONMENT_STYLE.apply_to(environment_name)
+    )?;
+
+    // Print the common lines.
+    for (_, line) in common_lines.iter().sorted_by_key(|(name, _)| name) {
+        writeln!(writer, "  {}", line)?;
+    }
+
+    // Print the per-platform lines.
+    for (platform, lines) in lines_by_platform {
+        let mut lines = lines
+            .iter()
+            .filter(|line| !common_lines.contains(line))
+            .sorted_by_key(|(name, _)| name)
+            .peekable();
+        if lines.peek().is_some() {
+            writeln!(
+                writer,
+                "{}: {}:{}\t\t\t",
+                console::style("Platform").underlined(),
+                consts::ENVIRONMENT_STYLE.apply_to(environment_name),
+                consts::PLATFORM_STYLE.apply_to(platform),
+            )?;
+            for (_, line) in lines {
+                writeln!(writer, "  {}", line)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The reason why a package was added, removed or changed by an update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeReason {
+    /// The package is a dependency in the manifest.
+    Explicit,
+
+    /// The package is a dependency of another package in the lock-file.
+    /// `changed` is true if that package was itself added or changed.
+    RequiredBy {
+        package: String,
+        version: String,
+        requirement: String,
+        changed: bool,
+    },
+
+    /// None of the packages that depend on the package changed, a newer
+    /// release satisfies the existing constraints.
+    NewRelease,
+
+    /// The package is no longer required, `previously_required_by` lists the
+    /// packages that depended on it before the update.
+    NoLongerRequired { previously_required_by: Vec<String> },
+}
+
+impl Display for ChangeReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeReason::Explicit => write!(f, "requested in the manifest"),
+            ChangeReason::RequiredBy {
+                package,
+                version,
+                requirement,
+                changed,
+            } => {
+                write!(f, "required by {package} {version} ({requirement})")?;
+                if *changed {
+                    write!(f, " which was updated")?;
+                }
+                Ok(())
+            }
+            ChangeReason::NewRelease => {
+                write!(f, "a newer release satisfies the existing constraints")
+            }
+            ChangeReason::NoLongerRequired {
+                previously_required_by,
+            } if previously_required_by.is_empty() => write!(f, "no longer required"),
+            ChangeReason::NoLongerRequired {
+                previously_required_by,
+            } => write!(
+                f,
+                "no longer required by {}",
+                previously_required_by.iter().format(", ")
+            ),
+        }
+    }
+}
+
+/// Explains why the packages of a [`LockFileDiff`] changed.
+pub struct LockFileExplanation {
+    pub environment: IndexMap<String, IndexMap<Platform, IndexMap<String, Vec<ChangeReason>>>>,
+}
+
+impl LockFileExplanation {
+    /// Prints the reasons of every package.
+    pub(crate) fn print(&self) -> std::io::Result<()> {
+        let mut writer = TabWriter::new(stderr());
+        writeln!(
+            writer,
+            "\n{}\t\t\t",
+            console::style("Why did the packages change?").bold()
+        )?;
+        for (environment_name, environment) in
+            self.environment.iter().sorted_by(|(a, _), (b, _)| a.cmp(b))
+        {
+            let lines_by_platform = environment
+                .iter()
+                .map(|(platform, packages)| {
+                    let lines = packages
+                        .iter()
+                        .filter(|(_, reasons)| !reasons.is_empty())
+                        .map(|(name, reasons)| {
+                            (
+                                name.clone(),
+                                format!(
+                                    "{}\t{}",
+                                    name,
+                                    console::style(reasons.iter().format("; ")).dim()
+                                ),
+                            )
+                        })
+                        .collect::<HashSet<_>>();
+                    (platform, lines)
+                })
+                .collect::<Vec<_>>();
+            write_environment_lines(&mut writer, environment_name, lines_by_platform)?;
+        }
+        writer.flush()
+    }
+}
+
+/// Returns the names of the packages a locked package depends on, together
+/// with the requirement as written in the package metadata.
+fn package_requirements(package: LockedPackageRef<'_>) -> Vec<(String, String)> {
+    match package {
+        LockedPackageRef::Conda(data) => data
+            .record()
+            .depends
+            .iter()
+            .map(|requirement| {
+                let name = requirement
+                    .split_once(' ')
+                    .map_or(requirement.as_str(), |(name, _)| name);
+                (name.to_string(), requirement.clone())
+            })
+            .collect(),
+        LockedPackageRef::Pypi(data, _) => data
+            .requires_dist
+            .iter()
+            .map(|requirement| (requirement.name.to_string(), requirement.to_string()))
+            .collect(),
+    }
+}
+
+/// Returns the version of a locked package.
+fn package_version(package: LockedPackageRef<'_>) -> String {
+    match package {
+        LockedPackageRef::Conda(data) => data.record().version.to_string(),
+        LockedPackageRef::Pypi(data, _) => data.version.to_string(),
+    }
+}
+
+/// Returns true if the version of the `current` package is higher than the
+/// version of the `previous` package.
+fn is_upgrade(previous: &LockedPackage, current: &LockedPackage) -> bool {
+    match (previous, current) {
+        (LockedPackage::Conda(previous), LockedPackage::Conda(current)) => {
+            previous.record().version.version() < current.record().version.version()
+        }
+        (LockedPackage::Pypi(previous, _), LockedPackage::Pypi(current, _)) => {
+            previous.version < current.version
+        }
+        _ => false,
+    }
+}
+
+/// Returns the packages that depend on the package with the given name,
+/// together with their requirement on it.
+fn dependents<'lock>(
+    packages: &[LockedPackageRef<'lock>],
+    name: &str,
+) -> Vec<(LockedPackageRef<'lock>, String)> {
+    packages
+        .iter()
+        .filter(|package| package.name() != name)
+        .flat_map(|&package| {
+            package_requirements(package)
+                .into_iter()
+                .filter(|(requirement_name, _)| requirement_name == name)
+                .map(move |(_, requirement)| (package, requirement))
+        })
+        .collect()
+}
+
+/// Determines the reasons of the changes in a single environment and platform.
+///
+/// `explicit` contains the names of the packages that are dependencies in the
+/// manifest.
+fn explain_packages(
+    diff: &PackagesDiff,
+    explicit: &HashSet<String>,
+    previous: &[LockedPackageRef<'_>],
+    current: &[LockedPackageRef<'_>],
+) -> IndexMap<String, Vec<ChangeReason>> {
+    let updated = diff
+        .added
+        .iter()
+        .chain(diff.changed.iter().map(|(_, current)| current))
+        .map(|package| package.name())
+        .collect::<HashSet<_>>();
+
+    let required_by = |name: &str| {
+        let mut reasons = Vec::new();
+        if explicit.contains(name) {
+            reasons.push(ChangeReason::Explicit);
+        }
+        for (package, requirement) in dependents(current, name) {
+            reasons.push(ChangeReason::RequiredBy {
+                package: package.name().to_string(),
+                version: package_version(package),
+                requirement,
+                changed: updated.contains(package.name()),
+            });
+        }
+        reasons
+    };
+
+    let mut result = IndexMap::new();
+    for package in &diff.added {
+        result.insert(package.name().to_string(), required_by(package.name()));
+    }
+    for (previous_package, current_package) in &diff.changed {
+        let mut reasons = required_by(current_package.name());
+        let caused_by_dependent = reasons
+            .iter()
+            .any(|reason| matches!(reason, ChangeReason::RequiredBy { changed: true, .. }));
+        if !caused_by_dependent && is_upgrade(previous_package, current_package) {
+            reasons.push(ChangeReason::NewRelease);
+        }
+        result.insert(current_package.name().to_string(), reasons);
+    }
+    for package in &diff.removed {
+        let previously_required_by = dependents(previous, package.name())
+            .into_iter()
+            .map(|(package, _)| format!("{} {}", package.name(), package_version(package)))
+            .unique()
+            .collect();
+        result.insert(
+            package.name().to_string(),
+            vec![ChangeReason::NoLongerRequired {
+                previously_required_by,
+            }],
+        );
+    }
+    result.sort_keys();
+    result
+}
+
 #[derive(Serialize, Clone)]
 pub struct JsonPackageDiff {
     name: String,
@@ -496,3 +790,91 @@ fn compute_json_diff(
     }
     (a, b)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use pixi_record::PixiRecord;
+    use rattler_conda_types::{PackageName, PackageRecord, RepoDataRecord, Version};
+    use rattler_lock::{CondaPackageData, LockFileBuilder};
+    use url::Url;
+
+    use super::*;
+
+    fn lock_file(packages: &[(&str, &str, &[&str])]) -> LockFile {
+        let mut builder = LockFileBuilder::new();
+        builder.set_channels("default", vec!["https://example.com/".to_string()]);
+        for (name, version, depends) in packages {
+            let mut package_record = PackageRecord::new(
+                PackageName::from_str(name).unwrap(),
+                Version::from_str(version).unwrap(),
+                "0".to_string(),
+            );
+            package_record.subdir = Platform::Linux64.to_string();
+            package_record.depends = depends.iter().map(|d| d.to_string()).collect();
+            let file_name = format!("{name}-{version}-0.conda");
+            let record = PixiRecord::Binary(RepoDataRecord {
+                package_record,
+                url: Url::parse(&format!("https://example.com/linux-64/{file_name}")).unwrap(),
+                file_name,
+                channel: None,
+            });
+            builder.add_conda_package("default", Platform::Linux64, CondaPackageData::from(record));
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_explain_packages() {
+        let previous = lock_file(&[
+            ("pandas", "2.1.0", &["numpy >=1.22", "legacy"]),
+            ("numpy", "1.26.0", &[]),
+            ("legacy", "1.0", &[]),
+            ("tzdata", "2024a", &[]),
+        ]);
+        let current = lock_file(&[
+            ("pandas", "2.2.0", &["numpy >=1.23", "python-dateutil"]),
+            ("numpy", "1.26.0", &[]),
+            ("python-dateutil", "2.9.0", &["tzdata"]),
+            ("tzdata", "2024b", &[]),
+        ]);
+
+        let diff = LockFileDiff::from_lock_files(&previous, &current);
+        let packages_diff = &diff.environment["default"][&Platform::Linux64];
+        let previous_packages = previous
+            .default_environment()
+            .unwrap()
+            .packages(Platform::Linux64)
+            .unwrap()
+            .collect_vec();
+        let current_packages = current
+            .default_environment()
+            .unwrap()
+            .packages(Platform::Linux64)
+            .unwrap()
+            .collect_vec();
+        let explicit = HashSet::from_iter([String::from("pandas")]);
+
+        let reasons = explain_packages(
+            packages_diff,
+            &explicit,
+            &previous_packages,
+            &current_packages,
+        );
+        let reasons = reasons
+            .iter()
+            .map(|(name, reasons)| format!("{name}: {}", reasons.iter().format("; ")))
+            .collect_vec();
+
+        assert_eq!(
+            reasons,
+            vec![
+                "legacy: no longer required by pandas 2.1.0",
+                "pandas: requested in the manifest; a newer release satisfies the existing constraints",
+                "python-dateutil: required by pandas 2.2.0 (python-dateutil) which was updated",
+                "tzdata: required by python-dateutil 2.9.0 (tzdata) which was updated",
+            ]
+        );
+    }
+}
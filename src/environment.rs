@@ -1,21 +1,29 @@
 use crate::{
     build::BuildReporter,
     install_pypi,
-    lock_file::{UpdateLockFileOptions, UpdateMode, UvResolutionContext},
+    lock_file::{records_without_hash, UpdateLockFileOptions, UpdateMode, UvResolutionContext},
     prefix::Prefix,
+    prefix_transaction::{
+        backup_replaced_packages, install_backup_dir, read_install_journal, remove_backup,
+        remove_install_journal, remove_partially_linked_packages, roll_back_installation,
+        write_install_journal, InstallJournal,
+    },
     project::{grouped_environment::GroupedEnvironment, Environment, HasProjectRef},
+    resumable_download::{get_or_fetch_resumable, RESUMABLE_DOWNLOAD_MIN_SIZE},
     rlimit::try_increase_rlimit_to_sensible,
+    shared_environments::installation_dir,
     Project,
 };
 use dialoguer::theme::ColorfulTheme;
 use fancy_display::FancyDisplay;
 use fs_err as fs;
-use futures::{stream, StreamExt, TryStreamExt};
+use futures::{future, stream, StreamExt, TryStreamExt};
 use indicatif::ProgressBar;
 use itertools::{Either, Itertools};
 use miette::{IntoDiagnostic, WrapErr};
 use parking_lot::Mutex;
 use pixi_build_frontend::CondaBuildReporter;
+use pixi_config::RunPostLinkScripts;
 use pixi_consts::consts;
 use pixi_manifest::{EnvironmentName, FeaturesExt, SystemRequirements};
 use pixi_progress::{await_in_progress, global_multi_progress};
@@ -51,8 +59,11 @@ use xxhash_rust::xxh3::Xxh3;
 /// prefix path is still valid. Errors when there is a file system error or the
 /// path does not align with the defined prefix. Returns false when the file is
 /// not present.
+///
+/// An environment that links to a shared environment is verified through the
+/// location the shared environment is installed in.
 pub async fn verify_prefix_location_unchanged(environment_dir: &Path) -> miette::Result<()> {
-    let prefix_file = environment_dir
+    let prefix_file = installation_dir(environment_dir)
         .join(consts::CONDA_META_DIR)
         .join(consts::PREFIX_FILE_NAME);
 
@@ -129,7 +140,7 @@ fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Resul
 
 /// Create the prefix location file.
 /// Give it the environment path to place it.
-fn create_prefix_location_file(environment_dir: &Path) -> miette::Result<()> {
+pub(crate) fn create_prefix_location_file(environment_dir: &Path) -> miette::Result<()> {
     let prefix_file_path = environment_dir
         .join(consts::CONDA_META_DIR)
         .join(consts::PREFIX_FILE_NAME);
@@ -207,6 +218,45 @@ impl LockedEnvironmentHash {
 
         LockedEnvironmentHash(format!("{:x}", hasher.finish()))
     }
+
+    /// Combines the hash of the locked packages with the platform and the
+    /// virtual packages that the system requirements of the environment
+    /// demand. Two installations with the same hash are interchangeable, which
+    /// makes it usable as a cache key in CI.
+    pub(crate) fn from_environment_and_system_requirements(
+        environment: rattler_lock::Environment,
+        platform: Platform,
+        virtual_packages: &[GenericVirtualPackage],
+    ) -> Self {
+        let mut hasher = Xxh3::new();
+
+        Self::from_environment(environment, platform)
+            .as_str()
+            .hash(&mut hasher);
+        platform.as_str().hash(&mut hasher);
+
+        let virtual_packages = virtual_packages
+            .iter()
+            .map(|package| {
+                format!(
+                    "{}={}={}",
+                    package.name.as_normalized(),
+                    package.version,
+                    package.build_string
+                )
+            })
+            .sorted();
+        for package in virtual_packages {
+            package.hash(&mut hasher);
+        }
+
+        LockedEnvironmentHash(format!("{:x}", hasher.finish()))
+    }
+
+    /// Returns the hash as a string.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 /// Information about the environment that was used to create the environment.
@@ -223,7 +273,20 @@ pub(crate) struct EnvironmentFile {
 }
 
 /// The path to the environment file in the `conda-meta` directory of the environment.
+///
+/// The file describes the project that uses the environment, so for an
+/// environment that links to a shared environment it is stored next to the
+/// link instead of in the shared environment.
 fn environment_file_path(environment_dir: &Path) -> PathBuf {
+    if environment_dir.is_symlink() {
+        if let Some(name) = environment_dir.file_name() {
+            return environment_dir.with_file_name(format!(
+                ".{}.{}",
+                name.to_string_lossy(),
+                consts::ENVIRONMENT_FILE_NAME
+            ));
+        }
+    }
     environment_dir
         .join(consts::CONDA_META_DIR)
         .join(consts::ENVIRONMENT_FILE_NAME)
@@ -270,6 +333,22 @@ pub(crate) fn read_environment_file(
 ) -> miette::Result<Option<EnvironmentFile>> {
     let path = environment_file_path(environment_dir);
 
+    // A shared environment is removed from the store when no project used it
+    // for a while, the file of the project is only valid while the shared
+    // environment is installed.
+    if environment_dir.is_symlink()
+        && !installation_dir(environment_dir)
+            .join(consts::CONDA_META_DIR)
+            .join(consts::PREFIX_FILE_NAME)
+            .is_file()
+    {
+        tracing::debug!(
+            "Shared environment of '{}' is not installed",
+            environment_dir.display()
+        );
+        return Ok(None);
+    }
+
     let contents = match fs_err::read_to_string(&path) {
         Ok(contents) => contents,
         Err(e) if e.kind() == ErrorKind::NotFound => {
@@ -705,9 +784,11 @@ impl CondaBuildReporter for CondaBuildProgress {
         // Actually show the progress
         let locked = self.build_progress.lock();
         let (identifier, pb) = locked.get(build_id).unwrap();
-        let template =
-            indicatif::ProgressStyle::with_template("    {spinner:.green} {msg} {elapsed}")
-                .unwrap();
+        let template = indicatif::ProgressStyle::with_template(&format!(
+            "    {{spinner:.{}}} {{msg}} {{elapsed}}",
+            pixi_progress::theme().accent_template_color("green")
+        ))
+        .unwrap();
         pb.set_style(template);
         pb.set_message(format!("building {identifier}"));
         pb.enable_steady_tick(Duration::from_millis(100));
@@ -740,7 +821,7 @@ pub async fn update_prefix_conda(
     prefix: &Prefix,
     package_cache: PackageCache,
     authenticated_client: ClientWithMiddleware,
-    installed_packages: Vec<PrefixRecord>,
+    mut installed_packages: Vec<PrefixRecord>,
     pixi_records: Vec<PixiRecord>,
     virtual_packages: Vec<GenericVirtualPackage>,
     channels: Vec<ChannelUrl>,
@@ -749,6 +830,7 @@ pub async fn update_prefix_conda(
     progress_bar_prefix: &str,
     io_concurrency_limit: Arc<Semaphore>,
     build_context: BuildContext,
+    run_post_link_scripts: RunPostLinkScripts,
 ) -> miette::Result<PythonStatus> {
     // Try to increase the rlimit to a sensible value for installation.
     try_increase_rlimit_to_sensible();
@@ -760,6 +842,18 @@ pub async fn update_prefix_conda(
             PixiRecord::Source(record) => Either::Right(record),
         });
 
+    // Packages without a hash cannot be verified after they have been downloaded.
+    let unverifiable = records_without_hash(&repodata_records);
+    if !unverifiable.is_empty() {
+        tracing::warn!(
+            "the following packages are locked without a hash, their integrity cannot be verified: {}",
+            unverifiable
+                .iter()
+                .map(|record| record.package_record.name.as_source())
+                .join(", ")
+        );
+    }
+
     let mut progress_reporter = None;
     let source_records_length = source_records.len();
     // Build conda packages out of the source records
@@ -797,6 +891,76 @@ pub async fn update_prefix_conda(
     // Extend the repodata records with the built packages
     repodata_records.append(&mut processed_source_packages);
 
+    // Keep a journal of the installation, if a previous installation of the
+    // same packages was interrupted only the remaining packages are installed.
+    // Otherwise the interrupted installation is rolled back first.
+    let journal = InstallJournal::new(&repodata_records);
+    let mut resuming = false;
+    if let Some(previous) = read_install_journal(prefix.root()) {
+        if previous == journal {
+            tracing::info!(
+                "resuming an interrupted installation of '{}', {} of {} packages are already installed",
+                prefix.root().display(),
+                journal.installed_count(&installed_packages),
+                journal.packages.len()
+            );
+            remove_partially_linked_packages(
+                prefix,
+                &journal,
+                &installed_packages,
+                &package_cache,
+                host_platform,
+            )
+            .await?;
+            resuming = true;
+        } else {
+            roll_back_installation(prefix, &previous, &package_cache, host_platform)
+                .await
+                .context("failed to roll back an interrupted installation")?;
+            installed_packages = prefix.find_installed_packages(None).await?;
+        }
+    }
+
+    // The backup of an interrupted installation holds the packages before that
+    // installation, so it is kept when resuming.
+    if !resuming {
+        backup_replaced_packages(prefix.root(), &journal, &installed_packages)?;
+    }
+    write_install_journal(prefix.root(), &journal)?;
+
+    // Large archives are downloaded before the installation with resumable
+    // downloads, so a download that broke off doesn't start over.
+    let large_packages = repodata_records
+        .iter()
+        .filter(|record| {
+            record
+                .package_record
+                .size
+                .is_some_and(|size| size >= RESUMABLE_DOWNLOAD_MIN_SIZE)
+                && !installed_packages
+                    .iter()
+                    .any(|installed| installed.repodata_record.file_name == record.file_name)
+        })
+        .collect_vec();
+    if !large_packages.is_empty() {
+        await_in_progress(
+            format!("{progress_bar_prefix}downloading large packages"),
+            |_| {
+                future::try_join_all(large_packages.into_iter().map(|record| {
+                    get_or_fetch_resumable(&package_cache, record, authenticated_client.clone())
+                }))
+            },
+        )
+        .await?;
+    }
+
+    if run_post_link_scripts.enabled() {
+        tracing::warn!(
+            "running the post-link scripts of the packages installed into '{}', these scripts can execute arbitrary code",
+            prefix.root().display()
+        );
+    }
+
     // Execute the operations that are returned by the solver.
     let result = await_in_progress(
         format!("{progress_bar_prefix}{progress_bar_message}",),
@@ -804,10 +968,10 @@ pub async fn update_prefix_conda(
             Installer::new()
                 .with_download_client(authenticated_client)
                 .with_io_concurrency_semaphore(io_concurrency_limit)
-                .with_execute_link_scripts(false)
+                .with_execute_link_scripts(run_post_link_scripts.enabled())
                 .with_installed_packages(installed_packages)
                 .with_target_platform(host_platform)
-                .with_package_cache(package_cache)
+                .with_package_cache(package_cache.clone())
                 .with_reporter(
                     IndicatifReporter::builder()
                         .with_multi_progress(global_multi_progress())
@@ -824,7 +988,20 @@ pub async fn update_prefix_conda(
                 .into_diagnostic()
         },
     )
-    .await?;
+    .await;
+
+    // Roll back a failed installation, so the prefix is left as it was.
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => {
+            roll_back_installation(prefix, &journal, &package_cache, host_platform)
+                .await
+                .context("failed to roll back the installation")?;
+            return Err(err);
+        }
+    };
+    remove_backup(&install_backup_dir(prefix.root()))?;
+    remove_install_journal(prefix.root())?;
 
     // Mark the location of the prefix
     create_prefix_location_file(prefix.root())?;
@@ -838,3 +1015,42 @@ pub type PerEnvironment<'p, T> = HashMap<Environment<'p>, T>;
 pub type PerGroup<'p, T> = HashMap<GroupedEnvironment<'p>, T>;
 pub type PerEnvironmentAndPlatform<'p, T> = PerEnvironment<'p, HashMap<Platform, T>>;
 pub type PerGroupAndPlatform<'p, T> = PerGroup<'p, HashMap<Platform, T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_hash_with_system_requirements() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/mock-projects/test-project-export/pixi.lock");
+        let lock_file = rattler_lock::LockFile::from_path(&path).unwrap();
+        let cuda = GenericVirtualPackage {
+            name: "__cuda".parse().unwrap(),
+            version: "12.0".parse().unwrap(),
+            build_string: "0".to_string(),
+        };
+        let glibc = GenericVirtualPackage {
+            name: "__glibc".parse().unwrap(),
+            version: "2.28".parse().unwrap(),
+            build_string: "0".to_string(),
+        };
+
+        let hash = |platform, virtual_packages: &[GenericVirtualPackage]| {
+            LockedEnvironmentHash::from_environment_and_system_requirements(
+                lock_file.default_environment().unwrap(),
+                platform,
+                virtual_packages,
+            )
+        };
+
+        let reference = hash(Platform::Linux64, &[glibc.clone(), cuda.clone()]);
+        // The order of the virtual packages doesn't matter.
+        assert_eq!(
+            reference,
+            hash(Platform::Linux64, &[cuda.clone(), glibc.clone()])
+        );
+        assert_ne!(reference, hash(Platform::Linux64, &[glibc.clone()]));
+        assert_ne!(reference, hash(Platform::OsxArm64, &[glibc, cuda]));
+    }
+}
@@ -0,0 +1,149 @@
+//! Renders the files of the `[generate]` table of the manifest, e.g. compiler
+//! wrapper configs or `.cmake` toolchain files that contain the paths and the
+//! versions of an installed environment.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+use miette::{Context, IntoDiagnostic};
+use minijinja::{context, UndefinedBehavior, Value};
+use rattler_lock::LockedPackageRef;
+
+use crate::{prefix::Prefix, project::Environment};
+
+/// Renders the templates of the project for the environment and writes the
+/// outputs that changed.
+pub(crate) fn generate_files(
+    environment: &Environment<'_>,
+    prefix: &Prefix,
+    locked_environment: rattler_lock::Environment<'_>,
+) -> miette::Result<()> {
+    let project = environment.project();
+    let templates = project.generated_files();
+    if templates.is_empty() {
+        return Ok(());
+    }
+
+    let platform = environment.best_platform();
+    let packages = locked_environment
+        .packages(platform)
+        .into_iter()
+        .flatten()
+        .map(|package| match package {
+            LockedPackageRef::Conda(data) => (
+                data.record().name.as_normalized().to_string(),
+                data.record().version.to_string(),
+            ),
+            LockedPackageRef::Pypi(data, _) => (data.name.to_string(), data.version.to_string()),
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let context = context! {
+        project => context! {
+            name => project.name(),
+            version => project.version().as_ref().map(|version| version.to_string()),
+            root => project.root(),
+        },
+        environment => context! {
+            name => environment.name().as_str(),
+            prefix => prefix.root(),
+            platform => platform.as_str(),
+        },
+        packages,
+    };
+
+    for (output, content) in render_files(project.root(), templates, context)? {
+        write_if_changed(&output, &content)?;
+    }
+    Ok(())
+}
+
+/// Renders the templates, relative to `root`, and returns the paths of the
+/// outputs with their content. The output paths are templates as well.
+fn render_files(
+    root: &Path,
+    templates: &IndexMap<PathBuf, PathBuf>,
+    context: Value,
+) -> miette::Result<Vec<(PathBuf, String)>> {
+    let mut env = minijinja::Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_keep_trailing_newline(true);
+
+    templates
+        .iter()
+        .map(|(template, output)| {
+            let source = fs_err::read_to_string(root.join(template)).into_diagnostic()?;
+            let content = env
+                .render_str(&source, &context)
+                .into_diagnostic()
+                .with_context(|| format!("failed to render '{}'", template.display()))?;
+            let output = env
+                .render_str(&output.to_string_lossy(), &context)
+                .into_diagnostic()
+                .with_context(|| {
+                    format!(
+                        "failed to render the output path of '{}'",
+                        template.display()
+                    )
+                })?;
+            Ok((root.join(output), content))
+        })
+        .collect()
+}
+
+/// Writes the content to the path, unless the file already has that content,
+/// so that tools that watch the file are not triggered for nothing.
+fn write_if_changed(path: &Path, content: &str) -> miette::Result<()> {
+    if fs_err::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent).into_diagnostic()?;
+    }
+    tracing::info!("generating {}", path.display());
+    fs_err::write(path, content).into_diagnostic()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_files() {
+        let root = tempfile::tempdir().unwrap();
+        fs_err::write(
+            root.path().join("toolchain.cmake.j2"),
+            "set(CMAKE_PREFIX_PATH \"{{ environment.prefix }}\")\n# cmake {{ packages.cmake }}\n",
+        )
+        .unwrap();
+
+        let templates = IndexMap::from([(
+            PathBuf::from("toolchain.cmake.j2"),
+            PathBuf::from("build/{{ environment.name }}/toolchain.cmake"),
+        )]);
+        let context = context! {
+            environment => context! { name => "test", prefix => "/project/.pixi/envs/test" },
+            packages => context! { cmake => "3.30.5" },
+        };
+
+        let files = render_files(root.path(), &templates, context.clone()).unwrap();
+        assert_eq!(
+            files,
+            vec![(
+                root.path().join("build/test/toolchain.cmake"),
+                "set(CMAKE_PREFIX_PATH \"/project/.pixi/envs/test\")\n# cmake 3.30.5\n".to_string()
+            )]
+        );
+
+        // Variables that are not defined are an error instead of an empty string.
+        fs_err::write(
+            root.path().join("toolchain.cmake.j2"),
+            "{{ packages.ninja }}",
+        )
+        .unwrap();
+        assert!(render_files(root.path(), &templates, context).is_err());
+    }
+}
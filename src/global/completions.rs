@@ -0,0 +1,244 @@
+//! Shell completions that are shipped by the packages of global environments.
+//! They are linked into `~/.pixi/completions/<shell>`, so that a single line
+//! in the startup file of the shell loads them, see
+//! `pixi global shell-completion`.
+
+use std::path::{Path, PathBuf};
+
+use ahash::HashSet;
+use fs_err as fs;
+use miette::IntoDiagnostic;
+use pixi_config::pixi_home;
+
+use super::EnvDir;
+
+/// The shells for which the completions of global environments are linked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+
+    /// The directory, relative to the prefix, in which packages install their
+    /// completions for this shell.
+    fn prefix_dir(&self) -> &'static Path {
+        Path::new(match self {
+            Shell::Bash => "share/bash-completion/completions",
+            Shell::Zsh => "share/zsh/site-functions",
+            Shell::Fish => "share/fish/vendor_completions.d",
+        })
+    }
+
+    /// Returns the name of the command that a completion file belongs to.
+    fn command_name<'a>(&self, file_name: &'a str) -> Option<&'a str> {
+        match self {
+            Shell::Bash => Some(file_name),
+            Shell::Zsh => file_name.strip_prefix('_'),
+            Shell::Fish => file_name.strip_suffix(".fish"),
+        }
+    }
+
+    /// Returns the line that loads the completions in `completions_dir` in
+    /// this shell.
+    pub(crate) fn hook(&self, completions_dir: &CompletionsDir) -> String {
+        let dir = completions_dir.shell_dir(*self);
+        match self {
+            Shell::Bash => format!(
+                r#"for file in "{}"/*; do [ -f "$file" ] && . "$file"; done"#,
+                dir.display()
+            ),
+            Shell::Zsh => format!(r#"fpath=("{}" $fpath)"#, dir.display()),
+            Shell::Fish => format!(r#"set -p fish_complete_path "{}""#, dir.display()),
+        }
+    }
+}
+
+/// Global completions directory, default to `$HOME/.pixi/completions`
+#[derive(Debug, Clone)]
+pub struct CompletionsDir(PathBuf);
+
+impl CompletionsDir {
+    /// Create the completions directory from path
+    #[cfg(test)]
+    pub fn new(root: PathBuf) -> Self {
+        Self(root.join("completions"))
+    }
+
+    /// Create the completions directory from environment variables
+    pub(crate) fn from_env() -> miette::Result<Self> {
+        pixi_home()
+            .map(|path| Self(path.join("completions")))
+            .ok_or_else(|| miette::miette!("Couldn't determine global completions directory"))
+    }
+
+    /// Returns the directory with the completions of the shell
+    fn shell_dir(&self, shell: Shell) -> PathBuf {
+        self.0.join(shell.as_str())
+    }
+
+    /// Links the completions of the environment, as returned by
+    /// [`find_completions`], and removes the links to completions of the
+    /// environment that are no longer exposed or installed.
+    ///
+    /// The completions are symlinked on Unix and copied on Windows.
+    pub(crate) fn sync(
+        &self,
+        env_dir: &EnvDir,
+        completions: &[(Shell, PathBuf)],
+    ) -> miette::Result<()> {
+        let targets: HashSet<PathBuf> = completions
+            .iter()
+            .map(|(_, relative_path)| env_dir.path().join(relative_path))
+            .collect();
+
+        for shell in Shell::ALL {
+            self.prune(shell, env_dir, &targets)?;
+        }
+
+        for (shell, relative_path) in completions {
+            let Some(file_name) = relative_path.file_name() else {
+                continue;
+            };
+            let shell_dir = self.shell_dir(*shell);
+            fs::create_dir_all(&shell_dir).into_diagnostic()?;
+            link(
+                &env_dir.path().join(relative_path),
+                &shell_dir.join(file_name),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Removes the links that point to a file that doesn't exist anymore, or
+    /// to a completion of the environment that isn't in `targets`.
+    fn prune(
+        &self,
+        shell: Shell,
+        env_dir: &EnvDir,
+        targets: &HashSet<PathBuf>,
+    ) -> miette::Result<()> {
+        let shell_dir = self.shell_dir(shell);
+        if !shell_dir.is_dir() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(&shell_dir).into_diagnostic()? {
+            let path = entry.into_diagnostic()?.path();
+            let Ok(target) = fs::read_link(&path) else {
+                continue;
+            };
+            let dangling = !path.exists();
+            let stale = target.starts_with(env_dir.path()) && !targets.contains(&target);
+            if dangling || stale {
+                tracing::debug!("Removing completion {}", path.display());
+                fs::remove_file(&path).into_diagnostic()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the completion files, relative to the prefix, that belong to the
+/// exposed executables.
+pub(crate) fn find_completions<'a>(
+    files: impl IntoIterator<Item = &'a PathBuf>,
+    exposed: &HashSet<&str>,
+) -> Vec<(Shell, PathBuf)> {
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let shell = Shell::ALL
+                .into_iter()
+                .find(|shell| path.parent() == Some(shell.prefix_dir()))?;
+            let file_name = path.file_name()?.to_str()?;
+            shell
+                .command_name(file_name)
+                .filter(|name| exposed.contains(name))?;
+            Some((shell, path.clone()))
+        })
+        .collect()
+}
+
+/// Links `destination` to `source`, replacing what was at `destination`.
+fn link(source: &Path, destination: &Path) -> miette::Result<()> {
+    if fs::read_link(destination).is_ok_and(|target| target == source) {
+        return Ok(());
+    }
+    if destination.symlink_metadata().is_ok() {
+        fs::remove_file(destination).into_diagnostic()?;
+    }
+    tracing::debug!(
+        "Linking completion {} to {}",
+        destination.display(),
+        source.display()
+    );
+    #[cfg(unix)]
+    fs::os::unix::fs::symlink(source, destination).into_diagnostic()?;
+    #[cfg(windows)]
+    fs::copy(source, destination).into_diagnostic()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_completions() {
+        let files = [
+            "bin/rg",
+            "share/bash-completion/completions/rg",
+            "share/zsh/site-functions/_rg",
+            "share/fish/vendor_completions.d/rg.fish",
+            "share/fish/vendor_completions.d/other.fish",
+            "share/zsh/site-functions/rg",
+        ]
+        .map(PathBuf::from);
+        let exposed = HashSet::from_iter(["rg"]);
+
+        assert_eq!(
+            find_completions(&files, &exposed),
+            vec![
+                (Shell::Bash, files[1].clone()),
+                (Shell::Zsh, files[2].clone()),
+                (Shell::Fish, files[3].clone()),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_completions() {
+        let tmp_home_dir = tempfile::tempdir().unwrap();
+        let completions_dir = CompletionsDir::new(tmp_home_dir.path().to_path_buf());
+        let env_dir = EnvDir::from_path(tmp_home_dir.path().join("envs/ripgrep"));
+
+        let completion = PathBuf::from("share/zsh/site-functions/_rg");
+        fs::create_dir_all(env_dir.path().join(completion.parent().unwrap())).unwrap();
+        fs::write(env_dir.path().join(&completion), "#compdef rg").unwrap();
+
+        let completions = vec![(Shell::Zsh, completion)];
+        completions_dir.sync(&env_dir, &completions).unwrap();
+        let linked = completions_dir.shell_dir(Shell::Zsh).join("_rg");
+        assert_eq!(fs::read_to_string(&linked).unwrap(), "#compdef rg");
+
+        // Syncing again doesn't change anything
+        completions_dir.sync(&env_dir, &completions).unwrap();
+        assert!(linked.exists());
+
+        // The link is removed once the executable isn't exposed anymore
+        completions_dir.sync(&env_dir, &[]).unwrap();
+        assert!(linked.symlink_metadata().is_err());
+    }
+}
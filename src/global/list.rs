@@ -1,4 +1,4 @@
-use std::io::stdout;
+use std::{io::stdout, path::Path};
 
 use fancy_display::FancyDisplay;
 use human_bytes::human_bytes;
@@ -6,7 +6,7 @@ use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use pixi_consts::consts;
 use pixi_spec::PixiSpec;
-use rattler_conda_types::{PackageName, PackageRecord, PrefixRecord, Version};
+use rattler_conda_types::{PackageName, PackageRecord, Platform, PrefixRecord, Version};
 use serde::Serialize;
 use std::io::Write;
 
@@ -142,6 +142,7 @@ pub async fn list_environment(
     environment_name: &EnvironmentName,
     sort_by: GlobalSortBy,
     regex: Option<String>,
+    json: bool,
 ) -> miette::Result<()> {
     let env = project
         .environments()
@@ -199,6 +200,16 @@ pub async fn list_environment(
             packages_to_output.sort_by(|a, b| a.name.cmp(&b.name));
         }
     }
+
+    if json {
+        let env_dir = project.env_root.path().join(environment_name.as_str());
+        let mut output =
+            EnvironmentToOutput::new(project, environment_name, env, &records, &env_dir);
+        output.packages = Some(packages_to_output);
+        pixi_progress::print_json(&output, true).into_diagnostic()?;
+        return Ok(());
+    }
+
     println!("{}", output_message);
     print_package_table(packages_to_output).into_diagnostic()?;
     println!();
@@ -207,13 +218,13 @@ pub async fn list_environment(
     Ok(())
 }
 
-/// List all environments in the global environment
-pub async fn list_global_environments(
+/// Returns the environments of the manifest that have dependencies, sorted by
+/// name and filtered by the regex on their name and by `envs`.
+fn filter_environments(
     project: &Project,
     envs: Option<Vec<EnvironmentName>>,
-    envs_changes: Option<&EnvChanges>,
     regex: Option<String>,
-) -> miette::Result<()> {
+) -> miette::Result<IndexMap<EnvironmentName, ParsedEnvironment>> {
     let mut project_envs = project.environments().clone();
     project_envs.sort_by(|a, _, b, _| a.to_string().cmp(&b.to_string()));
 
@@ -238,6 +249,18 @@ pub async fn list_global_environments(
         project_envs.retain(|env_name, _| envs.contains(env_name));
     }
 
+    Ok(project_envs)
+}
+
+/// List all environments in the global environment
+pub async fn list_global_environments(
+    project: &Project,
+    envs: Option<Vec<EnvironmentName>>,
+    envs_changes: Option<&EnvChanges>,
+    regex: Option<String>,
+) -> miette::Result<()> {
+    let project_envs = filter_environments(project, envs, regex)?;
+
     let mut message = String::new();
 
     let len = project_envs.len();
@@ -278,19 +301,27 @@ pub async fn list_global_environments(
             }) {
                 // output the environment name and version
                 message.push_str(&format!(
-                    " {}: {} {}",
+                    " {}: {}",
                     env_name.fancy_display(),
                     console::style(env_package.repodata_record.package_record.version.clone())
                         .blue(),
-                    state
                 ));
             } else {
-                message.push_str(&format!(" {} {}", env_name.fancy_display(), state));
+                message.push_str(&format!(" {}", env_name.fancy_display()));
             }
         } else {
-            message.push_str(&format!(" {} {}", env_name.fancy_display(), state));
+            message.push_str(&format!(" {}", env_name.fancy_display()));
         }
 
+        // Write the disk usage and the state of the environment
+        if let Some(size) = environment_size(&env_dir) {
+            message.push_str(&format!(
+                " {}",
+                console::style(format!("[{}]", human_bytes(size as f64))).dim()
+            ));
+        }
+        message.push_str(&format!(" {}", state));
+
         // Write dependencies
         if let Some(dep_message) = format_dependencies(
             env_name.as_str(),
@@ -324,6 +355,28 @@ pub async fn list_global_environments(
     Ok(())
 }
 
+/// Print all environments in the global environment as JSON
+pub async fn list_global_environments_json(
+    project: &Project,
+    regex: Option<String>,
+) -> miette::Result<()> {
+    let mut output = Vec::new();
+    for (env_name, env) in filter_environments(project, None, regex)? {
+        let env_dir = project.env_root.path().join(env_name.as_str());
+        let records = find_package_records(&env_dir.join(consts::CONDA_META_DIR)).await?;
+        output.push(EnvironmentToOutput::new(
+            project, &env_name, &env, &records, &env_dir,
+        ));
+    }
+    pixi_progress::print_json(&output, true).into_diagnostic()
+}
+
+/// Returns the size of all the files in the environment directory, or `None`
+/// if it can't be determined, e.g. because the environment is not installed.
+fn environment_size(env_dir: &Path) -> Option<u64> {
+    fs_extra::dir::get_size(env_dir).ok()
+}
+
 /// Display a dependency in a human-readable format.
 fn display_dependency(name: &PackageName, version: Option<Version>) -> String {
     if let Some(version) = version {
@@ -387,3 +440,72 @@ impl PackageToOutput {
         }
     }
 }
+
+#[derive(Serialize)]
+struct EnvironmentToOutput {
+    name: EnvironmentName,
+    /// The platform the environment is installed for, the current platform
+    /// unless it was installed with `--platform`
+    platform: Platform,
+    dependencies: Vec<DependencyToOutput>,
+    exposed: Vec<ExposedToOutput>,
+    size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    packages: Option<Vec<PackageToOutput>>,
+}
+
+#[derive(Serialize)]
+struct DependencyToOutput {
+    name: PackageName,
+    version: Option<Version>,
+}
+
+#[derive(Serialize)]
+struct ExposedToOutput {
+    name: String,
+    executable: String,
+    /// Whether the executable exists in the binary directory
+    installed: bool,
+}
+
+impl EnvironmentToOutput {
+    fn new(
+        project: &Project,
+        env_name: &EnvironmentName,
+        env: &ParsedEnvironment,
+        records: &[PrefixRecord],
+        env_dir: &Path,
+    ) -> Self {
+        let dependencies = env
+            .dependencies()
+            .keys()
+            .map(|name| DependencyToOutput {
+                name: name.clone(),
+                version: records
+                    .iter()
+                    .find(|rec| rec.repodata_record.package_record.name == *name)
+                    .map(|rec| rec.repodata_record.package_record.version.version().clone()),
+            })
+            .collect();
+        let exposed = env
+            .exposed()
+            .iter()
+            .map(|mapping| ExposedToOutput {
+                name: mapping.exposed_name().to_string(),
+                executable: mapping.executable_relname().to_string(),
+                installed: project
+                    .bin_dir
+                    .executable_trampoline_path(mapping.exposed_name())
+                    .is_file(),
+            })
+            .collect();
+        Self {
+            name: env_name.clone(),
+            platform: env.platform().unwrap_or_else(Platform::current),
+            dependencies,
+            exposed,
+            size_bytes: environment_size(env_dir),
+            packages: None,
+        }
+    }
+}
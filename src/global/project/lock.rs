@@ -0,0 +1,148 @@
+//! The lock-files of the global environments. They are stored next to the
+//! global manifest, so that `pixi global sync` installs exactly the same
+//! packages, e.g. on another machine, instead of solving the environments
+//! again.
+
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexSet;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use rattler_conda_types::{Channel, MatchSpec, Platform, RepoDataRecord};
+use rattler_lock::{CondaPackageData, LockFile, LockFileBuilder};
+
+use super::EnvironmentName;
+use crate::global::install::local_environment_matches_spec;
+
+/// The directory next to the global manifest that contains the lock-files.
+const LOCKS_DIR: &str = "locks";
+
+/// Returns the path of the lock-file of the environment.
+pub(crate) fn lock_file_path(root: &Path, env_name: &EnvironmentName) -> PathBuf {
+    root.join(LOCKS_DIR).join(format!("{}.lock", env_name))
+}
+
+/// Writes the packages of the environment to its lock-file.
+pub(crate) fn write_lock_file(
+    path: &Path,
+    channels: &[Channel],
+    platform: Platform,
+    records: Vec<RepoDataRecord>,
+) -> miette::Result<()> {
+    let mut builder = LockFileBuilder::new();
+    builder.set_channels(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        channels
+            .iter()
+            .map(|channel| channel.base_url.to_string())
+            .collect_vec(),
+    );
+    for record in records {
+        builder.add_conda_package(
+            consts::DEFAULT_ENVIRONMENT_NAME,
+            platform,
+            CondaPackageData::from(record),
+        );
+    }
+
+    let contents = builder
+        .finish()
+        .render_to_string()
+        .into_diagnostic()
+        .context("failed to render the lock-file")?;
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent).into_diagnostic()?;
+    }
+    pixi_manifest::utils::write_atomic(path, contents)
+        .into_diagnostic()
+        .context("failed to write lock-file to disk")
+}
+
+/// Returns the locked packages of the environment, or `None` if the
+/// environment isn't locked or the lock-file doesn't match the channels,
+/// platform and specs of the manifest anymore.
+pub(crate) fn read_lock_file(
+    path: &Path,
+    channels: &[Channel],
+    platform: Platform,
+    specs: &IndexSet<MatchSpec>,
+) -> Option<Vec<RepoDataRecord>> {
+    if !path.is_file() {
+        return None;
+    }
+    let lock_file = LockFile::from_path(path)
+        .inspect_err(|err| tracing::warn!("Couldn't read {}: {err}", path.display()))
+        .ok()?;
+    let environment = lock_file.default_environment()?;
+
+    let locked_channels = environment
+        .channels()
+        .iter()
+        .map(|channel| channel.url.as_str())
+        .collect_vec();
+    let channels = channels
+        .iter()
+        .map(|channel| channel.base_url.to_string())
+        .collect_vec();
+    if locked_channels != channels {
+        tracing::debug!("The channels of {} are outdated", path.display());
+        return None;
+    }
+
+    let records = environment.conda_repodata_records(platform).ok()??;
+    if !local_environment_matches_spec(records.clone(), specs, Some(platform)) {
+        tracing::debug!("The packages of {} are outdated", path.display());
+        return None;
+    }
+    Some(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rattler_conda_types::{ChannelConfig, ParseStrictness};
+
+    use super::*;
+
+    #[test]
+    fn test_lock_file_roundtrip() {
+        let records = LockFile::from_str(include_str!("../test_data/lockfiles/ripgrep.lock"))
+            .unwrap()
+            .default_environment()
+            .unwrap()
+            .conda_repodata_records(Platform::Linux64)
+            .unwrap()
+            .unwrap();
+        let channels = vec![Channel::from_str(
+            "conda-forge",
+            &ChannelConfig::default_with_root_dir(PathBuf::from("/")),
+        )
+        .unwrap()];
+        let specs =
+            IndexSet::from([
+                MatchSpec::from_str("ripgrep=14.1.0", ParseStrictness::Strict).unwrap(),
+            ]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_file_path(dir.path(), &EnvironmentName::from_str("ripgrep").unwrap());
+        assert!(read_lock_file(&path, &channels, Platform::Linux64, &specs).is_none());
+
+        write_lock_file(&path, &channels, Platform::Linux64, records.clone()).unwrap();
+        assert_eq!(
+            read_lock_file(&path, &channels, Platform::Linux64, &specs)
+                .unwrap()
+                .len(),
+            records.len()
+        );
+
+        // The lock-file is outdated when the platform or the specs change
+        assert!(read_lock_file(&path, &channels, Platform::Osx64, &specs).is_none());
+        let specs =
+            IndexSet::from([
+                MatchSpec::from_str("ripgrep=14.0.0", ParseStrictness::Strict).unwrap(),
+            ]);
+        assert!(read_lock_file(&path, &channels, Platform::Linux64, &specs).is_none());
+    }
+}
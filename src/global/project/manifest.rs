@@ -103,6 +103,36 @@ impl Manifest {
         Ok(())
     }
 
+    /// Adds the environments of another manifest, replacing the environments
+    /// with the same name. Returns the names of the imported environments.
+    pub fn import(&mut self, other: &Manifest) -> miette::Result<Vec<EnvironmentName>> {
+        if other.parsed.envs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let other_envs = other.document.get_nested_table("envs")?;
+        let envs = self.document.get_or_insert_nested_table("envs")?;
+        for (env_name, environment) in &other.parsed.envs {
+            let item = other_envs.get(env_name.as_str()).ok_or_else(|| {
+                miette::miette!("Environment {} doesn't exist.", env_name.fancy_display())
+            })?;
+
+            // Update self.document
+            envs.insert(env_name.as_str(), item.clone());
+
+            // Update self.parsed
+            self.parsed
+                .envs
+                .insert(env_name.clone(), environment.clone());
+
+            tracing::debug!(
+                "Imported environment {} into toml document",
+                env_name.fancy_display()
+            );
+        }
+        Ok(other.parsed.envs.keys().cloned().collect())
+    }
+
     /// Removes a specific environment from the manifest
     pub fn remove_environment(&mut self, env_name: &EnvironmentName) -> miette::Result<()> {
         // Update self.parsed
@@ -197,6 +227,9 @@ impl Manifest {
             .get_or_insert_nested_table(&format!("envs.{env_name}.dependencies"))?
             .remove(name.as_normalized());
 
+        // A removed dependency is no longer injected either
+        self.remove_injected_package(env_name, &name)?;
+
         tracing::debug!(
             "Removed dependency {} to toml document for environment {}",
             console::style(name.as_normalized()).green(),
@@ -205,6 +238,86 @@ impl Manifest {
         Ok(name)
     }
 
+    /// Marks a dependency as injected, so that its executables are not
+    /// exposed automatically
+    pub fn add_injected_package(
+        &mut self,
+        env_name: &EnvironmentName,
+        package_name: &PackageName,
+    ) -> miette::Result<()> {
+        // Update self.parsed
+        let inserted = self
+            .parsed
+            .envs
+            .get_mut(env_name)
+            .ok_or_else(|| {
+                miette::miette!("Environment {} doesn't exist.", env_name.fancy_display())
+            })?
+            .injected
+            .insert(package_name.clone());
+        if !inserted {
+            return Ok(());
+        }
+
+        // Update self.document
+        self.document
+            .get_or_insert_toml_array_mut(&format!("envs.{env_name}"), "injected")?
+            .push(package_name.as_normalized());
+
+        tracing::debug!(
+            "Marked dependency {} as injected in toml document for environment {}",
+            package_name.as_normalized(),
+            env_name.fancy_display()
+        );
+        Ok(())
+    }
+
+    /// Marks a dependency as a primary package of the environment again
+    pub fn remove_injected_package(
+        &mut self,
+        env_name: &EnvironmentName,
+        package_name: &PackageName,
+    ) -> miette::Result<()> {
+        // Update self.parsed
+        let removed = self
+            .parsed
+            .envs
+            .get_mut(env_name)
+            .ok_or_else(|| {
+                miette::miette!("Environment {} doesn't exist.", env_name.fancy_display())
+            })?
+            .injected
+            .shift_remove(package_name);
+        if !removed {
+            return Ok(());
+        }
+
+        // Update self.document
+        let env_table = self
+            .document
+            .get_or_insert_nested_table(&format!("envs.{env_name}"))?;
+        let now_empty = match env_table
+            .get_mut("injected")
+            .and_then(|item| item.as_array_mut())
+        {
+            Some(injected) => {
+                injected.retain(|value| value.as_str() != Some(package_name.as_normalized()));
+                injected.is_empty()
+            }
+            None => false,
+        };
+        if now_empty {
+            env_table.remove("injected");
+        }
+
+        tracing::debug!(
+            "Marked dependency {} as primary in toml document for environment {}",
+            package_name.as_normalized(),
+            env_name.fancy_display()
+        );
+        Ok(())
+    }
+
     /// Sets the platform of a specific environment in the manifest
     pub fn set_platform(
         &mut self,
@@ -808,6 +921,73 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_import() {
+        let path = Path::new("pixi-global.toml");
+        let mut manifest = Manifest::from_str(
+            path,
+            r#"
+[envs.python]
+channels = ["conda-forge"]
+dependencies = { python = "3.11.*" }
+exposed = { python = "python" }
+
+# Faster than grep
+[envs.ripgrep]
+channels = ["conda-forge"]
+dependencies = { ripgrep = "*" }
+"#,
+        )
+        .unwrap();
+        let other = Manifest::from_str(
+            path,
+            r#"
+[envs.python]
+channels = ["conda-forge"]
+dependencies = { python = "==3.12.7" }
+exposed = { python3 = "python" }
+
+[envs.gh]
+channels = ["conda-forge"]
+dependencies = { gh = "==2.60.1" }
+"#,
+        )
+        .unwrap();
+
+        let imported = manifest.import(&other).unwrap();
+        assert_eq!(
+            imported.iter().map(|env| env.as_str()).collect_vec(),
+            vec!["python", "gh"]
+        );
+
+        // Check parsed
+        assert_eq!(
+            manifest
+                .parsed
+                .envs
+                .keys()
+                .map(|env| env.as_str())
+                .collect_vec(),
+            vec!["python", "ripgrep", "gh"]
+        );
+        let python = EnvironmentName::from_str("python").unwrap();
+        assert_eq!(
+            manifest.parsed.envs[&python]
+                .exposed
+                .iter()
+                .map(|mapping| mapping.exposed_name().to_string())
+                .collect_vec(),
+            vec!["python3"]
+        );
+
+        // Check document
+        let document = manifest.document.to_string();
+        assert!(document.contains("# Faster than grep"));
+        assert!(document.contains(r#"python = "==3.12.7""#));
+        assert!(document.contains(r#"gh = "==2.60.1""#));
+        assert!(!document.contains(r#"python = "3.11.*""#));
+    }
+
     #[test]
     fn test_add_dependency() {
         let mut manifest = Manifest::default();
@@ -1047,4 +1227,35 @@ dependencies = { "python" = "*", pytest = "*"}
 
         assert_snapshot!(manifest.document.to_string());
     }
+
+    #[test]
+    fn test_injected_package() {
+        let env_name = EnvironmentName::from_str("ipython").unwrap();
+        let numpy = PackageName::from_str("numpy").unwrap();
+
+        let mut manifest = Manifest::from_str(
+            Path::new("global.toml"),
+            r#"
+[envs.ipython]
+channels = ["conda-forge"]
+dependencies = { ipython = "*", numpy = "*" }
+"#,
+        )
+        .unwrap();
+
+        // Mark the dependency as injected
+        manifest.add_injected_package(&env_name, &numpy).unwrap();
+        manifest.add_injected_package(&env_name, &numpy).unwrap();
+        assert!(manifest.parsed.envs[&env_name].is_injected(&numpy));
+        assert!(manifest
+            .document
+            .to_string()
+            .contains(r#"injected = ["numpy"]"#));
+
+        // Removing the dependency also removes it from the injected packages
+        let match_spec = MatchSpec::from_str("numpy", ParseStrictness::Strict).unwrap();
+        manifest.remove_dependency(&env_name, &match_spec).unwrap();
+        assert!(!manifest.parsed.envs[&env_name].is_injected(&numpy));
+        assert!(!manifest.document.to_string().contains("injected"));
+    }
 }
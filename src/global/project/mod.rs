@@ -29,7 +29,8 @@ use rattler::{
     package_cache::PackageCache,
 };
 use rattler_conda_types::{
-    ChannelConfig, GenericVirtualPackage, MatchSpec, PackageName, Platform, PrefixRecord,
+    version_spec::EqualityOperator, Channel, ChannelConfig, GenericVirtualPackage, MatchSpec,
+    PackageName, Platform, PrefixRecord, RepoDataRecord, VersionSpec,
 };
 use rattler_lock::Matches;
 use rattler_repodata_gateway::Gateway;
@@ -41,6 +42,7 @@ use toml_edit::DocumentMut;
 use self::trampoline::{Configuration, ConfigurationParseError, Trampoline};
 use super::{
     common::{get_install_changes, EnvironmentUpdate},
+    completions::{find_completions, CompletionsDir},
     install::find_binary_by_name,
     trampoline::{self, GlobalExecutable},
     BinDir, EnvRoot, StateChange, StateChanges,
@@ -56,12 +58,14 @@ use crate::{
         project::environment::environment_specs_in_sync,
         EnvDir,
     },
+    lock_file::SolveError,
     prefix::{Executable, Prefix},
     repodata::Repodata,
     rlimit::try_increase_rlimit_to_sensible,
 };
 
 mod environment;
+mod lock;
 mod manifest;
 mod parsed_manifest;
 
@@ -101,6 +105,24 @@ impl Debug for Project {
     }
 }
 
+/// The packages of a global environment as solved by
+/// [`Project::solve_environment`].
+#[derive(Debug)]
+pub(crate) struct SolvedEnvironment {
+    records: Vec<RepoDataRecord>,
+    dependencies: Vec<PackageName>,
+    channels: Vec<Channel>,
+    platform: Platform,
+}
+
+/// The channels, platform and specs that a global environment is solved for.
+struct EnvironmentRequirements {
+    channels: Vec<Channel>,
+    platform: Platform,
+    match_specs: Vec<MatchSpec>,
+    dependencies: Vec<PackageName>,
+}
+
 /// Intermediate struct to store all the binaries that are exposed.
 #[derive(Debug)]
 struct ExposedData {
@@ -430,6 +452,59 @@ impl Project {
         self
     }
 
+    /// Returns a copy of the manifest in which the conda dependencies are
+    /// pinned to the installed versions. The dependencies of environments that
+    /// are not installed are left as they are.
+    pub(crate) async fn pinned_manifest(&self) -> miette::Result<Manifest> {
+        let mut manifest = self.manifest.clone();
+        let channel_config = self.config.global_channel_config();
+        for (env_name, environment) in self.environments() {
+            let conda_meta = self
+                .env_root
+                .path()
+                .join(env_name.as_str())
+                .join(consts::CONDA_META_DIR);
+            let Ok(records) = find_package_records(&conda_meta).await else {
+                tracing::warn!(
+                    "Environment {} is not installed, its dependencies are not pinned",
+                    env_name.fancy_display()
+                );
+                continue;
+            };
+
+            for (name, spec) in environment.dependencies() {
+                let Some(record) = records
+                    .iter()
+                    .find(|record| record.repodata_record.package_record.name == *name)
+                else {
+                    continue;
+                };
+                let Some(mut nameless_spec) = spec
+                    .clone()
+                    .try_into_nameless_match_spec(channel_config)
+                    .into_diagnostic()?
+                else {
+                    continue;
+                };
+                nameless_spec.version = Some(VersionSpec::Exact(
+                    EqualityOperator::Equals,
+                    record
+                        .repodata_record
+                        .package_record
+                        .version
+                        .version()
+                        .clone(),
+                ));
+                manifest.add_dependency(
+                    env_name,
+                    &MatchSpec::from_nameless(nameless_spec, Some(name.clone())),
+                    channel_config,
+                )?;
+            }
+        }
+        Ok(manifest)
+    }
+
     /// Returns the environments in this project.
     pub(crate) fn environments(&self) -> &IndexMap<EnvironmentName, ParsedEnvironment> {
         &self.manifest.parsed.envs
@@ -468,10 +543,88 @@ impl Project {
         &self.config
     }
 
+    /// Installs the packages of the lock-file of the environment, or solves
+    /// the environment if the lock-file doesn't match the manifest anymore.
     pub(crate) async fn install_environment(
         &self,
         env_name: &EnvironmentName,
     ) -> miette::Result<EnvironmentUpdate> {
+        let solved_environment = match self.locked_environment(env_name)? {
+            Some(locked_environment) => locked_environment,
+            None => self.solve_environment(env_name).await?,
+        };
+        self.install_solved_environment(env_name, solved_environment)
+            .await
+    }
+
+    /// Returns the path of the lock-file of the environment.
+    pub(crate) fn lock_file_path(&self, env_name: &EnvironmentName) -> PathBuf {
+        lock::lock_file_path(&self.root, env_name)
+    }
+
+    /// Writes the lock-file of the environment from its installed packages.
+    async fn lock_installed_environment(&self, env_name: &EnvironmentName) -> miette::Result<()> {
+        let requirements = self.environment_requirements(env_name)?;
+        let records = self
+            .environment_prefix(env_name)
+            .await?
+            .find_installed_packages(None)
+            .await?
+            .into_iter()
+            .map(|record| record.repodata_record)
+            .collect();
+        lock::write_lock_file(
+            &self.lock_file_path(env_name),
+            &requirements.channels,
+            requirements.platform,
+            records,
+        )
+    }
+
+    /// Removes the lock-file of the environment, if it exists.
+    async fn remove_lock_file(&self, env_name: &EnvironmentName) -> miette::Result<()> {
+        let lock_file_path = self.lock_file_path(env_name);
+        if lock_file_path.is_file() {
+            tokio_fs::remove_file(&lock_file_path)
+                .await
+                .into_diagnostic()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the packages of the lock-file of the environment, or `None` if
+    /// the environment isn't locked or the lock-file is outdated.
+    fn locked_environment(
+        &self,
+        env_name: &EnvironmentName,
+    ) -> miette::Result<Option<SolvedEnvironment>> {
+        let requirements = self.environment_requirements(env_name)?;
+        let specs = requirements.match_specs.into_iter().collect();
+        let Some(records) = lock::read_lock_file(
+            &self.lock_file_path(env_name),
+            &requirements.channels,
+            requirements.platform,
+            &specs,
+        ) else {
+            return Ok(None);
+        };
+        tracing::debug!(
+            "Using the lock-file of environment {}",
+            env_name.fancy_display()
+        );
+        Ok(Some(SolvedEnvironment {
+            records,
+            dependencies: requirements.dependencies,
+            channels: requirements.channels,
+            platform: requirements.platform,
+        }))
+    }
+
+    /// Returns the channels, platform and specs of the environment.
+    fn environment_requirements(
+        &self,
+        env_name: &EnvironmentName,
+    ) -> miette::Result<EnvironmentRequirements> {
         let environment = self
             .environment(env_name)
             .ok_or_else(|| miette::miette!("Environment {} not found", env_name.fancy_display()))?;
@@ -508,6 +661,26 @@ impl Project {
             })
             .collect::<miette::Result<(Vec<MatchSpec>, Vec<PackageName>)>>()?;
 
+        Ok(EnvironmentRequirements {
+            channels,
+            platform,
+            match_specs,
+            dependencies: dependencies_names,
+        })
+    }
+
+    /// Solves the environment with the current repodata of its channels.
+    pub(crate) async fn solve_environment(
+        &self,
+        env_name: &EnvironmentName,
+    ) -> miette::Result<SolvedEnvironment> {
+        let EnvironmentRequirements {
+            channels,
+            platform,
+            match_specs,
+            dependencies,
+        } = self.environment_requirements(env_name)?;
+
         let repodata = await_in_progress(
             format!(
                 "Querying repodata for environment: {} ",
@@ -515,7 +688,11 @@ impl Project {
             ),
             |_| async {
                 self.repodata_gateway()
-                    .query(channels, [platform, Platform::NoArch], match_specs.clone())
+                    .query(
+                        channels.clone(),
+                        [platform, Platform::NoArch],
+                        match_specs.clone(),
+                    )
                     .recursive(true)
                     .await
                     .into_diagnostic()
@@ -550,7 +727,7 @@ impl Project {
                     })
                 },
             )
-            .into_diagnostic()
+            .map_err(SolveError::new)
             .wrap_err_with(|| {
                 miette::miette!(
                     "Failed to determine virtual packages for environment {}",
@@ -561,11 +738,64 @@ impl Project {
         .await
         .into_diagnostic()??;
 
+        Ok(SolvedEnvironment {
+            records: solved_records,
+            dependencies,
+            channels,
+            platform,
+        })
+    }
+
+    /// Returns true if exactly the packages of the solved environment are
+    /// installed, in which case installing it would not change anything.
+    pub(crate) async fn is_solved_environment_installed(
+        &self,
+        env_name: &EnvironmentName,
+        solved_environment: &SolvedEnvironment,
+    ) -> miette::Result<bool> {
+        let prefix = self.environment_prefix(env_name).await?;
+        let installed = prefix.find_installed_packages(None).await?;
+        let installed_urls = installed
+            .iter()
+            .map(|record| &record.repodata_record.url)
+            .collect::<HashSet<_>>();
+        let solved_urls = solved_environment
+            .records
+            .iter()
+            .map(|record| &record.url)
+            .collect::<HashSet<_>>();
+        Ok(installed_urls == solved_urls)
+    }
+
+    /// Installs the packages of the solved environment into the environment.
+    pub(crate) async fn install_solved_environment(
+        &self,
+        env_name: &EnvironmentName,
+        solved_environment: SolvedEnvironment,
+    ) -> miette::Result<EnvironmentUpdate> {
+        let SolvedEnvironment {
+            records: solved_records,
+            dependencies: dependencies_names,
+            channels,
+            platform,
+        } = solved_environment;
+        let locked_records = solved_records.clone();
+
         try_increase_rlimit_to_sensible();
 
         // Install the environment
-        let package_cache = PackageCache::new(pixi_config::get_cache_dir()?.join("pkgs"));
+        let package_cache = PackageCache::new(self.config().package_cache_dir()?);
         let prefix = self.environment_prefix(env_name).await?;
+        let run_post_link_scripts = self
+            .environment(env_name)
+            .and_then(ParsedEnvironment::run_post_link_scripts)
+            .unwrap_or_else(|| self.config().run_post_link_scripts());
+        if run_post_link_scripts.enabled() {
+            tracing::warn!(
+                "running the post-link scripts of the packages in {}, these scripts can execute arbitrary code",
+                env_name.fancy_display()
+            );
+        }
         let result = await_in_progress(
             format!(
                 "Creating virtual environment for {}",
@@ -575,7 +805,7 @@ impl Project {
                 Installer::new()
                     .with_download_client(self.authenticated_client().clone())
                     .with_io_concurrency_limit(100)
-                    .with_execute_link_scripts(false)
+                    .with_execute_link_scripts(run_post_link_scripts.enabled())
                     .with_package_cache(package_cache)
                     .with_target_platform(platform)
                     .with_reporter(
@@ -594,6 +824,13 @@ impl Project {
 
         let install_changes = get_install_changes(result.transaction);
 
+        lock::write_lock_file(
+            &self.lock_file_path(env_name),
+            &channels,
+            platform,
+            locked_records,
+        )?;
+
         Ok(EnvironmentUpdate::new(install_changes, dependencies_names))
     }
 
@@ -613,6 +850,7 @@ impl Project {
         tokio_fs::remove_dir_all(env_dir.path())
             .await
             .into_diagnostic()?;
+        self.remove_lock_file(env_name).await?;
 
         // Get all removable binaries related to the environment
         let (to_remove, _to_add) =
@@ -673,7 +911,8 @@ impl Project {
         for package_name in &package_names {
             let prefix = self.environment_prefix(env_name).await?;
             let prefix_package = prefix.find_designated_package(package_name).await?;
-            let mut package_executables = prefix.find_executables(&[prefix_package]);
+            let mut package_executables = prefix
+                .find_executables_in(&[prefix_package], parsed_env.executable_paths(package_name));
 
             // Sometimes the package don't ship executables on their own.
             // We need to search for it in different packages.
@@ -699,6 +938,8 @@ impl Project {
     /// * If the use chose to expose only a subset of binaries, we will remove
     ///   the binaries that are not anymore present in the environment and will
     ///   not expose the new ones
+    ///
+    /// Executables of injected packages are never exposed automatically.
     pub async fn sync_exposed_names(
         &mut self,
         env_name: &EnvironmentName,
@@ -729,6 +970,13 @@ impl Project {
             })
             .collect_vec();
 
+        // Injected packages only live next to the primary packages, their
+        // executables are not candidates for auto-exposure
+        let env_executables = env_executables
+            .into_iter()
+            .filter(|(package_name, _)| !environment.is_injected(package_name))
+            .collect_vec();
+
         // Removed the removable exposed names from the manifest
         for exposed_name in &to_remove {
             self.manifest.remove_exposed_name(env_name, exposed_name)?;
@@ -913,6 +1161,13 @@ impl Project {
 
         state_changes |= create_executable_trampolines(&script_mapping, &prefix, env_name).await?;
 
+        // Link the completions of the exposed executables
+        let completions = find_completions(
+            prefix_records.iter().flat_map(|record| &record.files),
+            &exposed,
+        );
+        CompletionsDir::from_env()?.sync(&env_dir, &completions)?;
+
         Ok(state_changes)
     }
 
@@ -924,11 +1179,28 @@ impl Project {
         removed_packages: Option<Vec<PackageName>>,
     ) -> miette::Result<StateChanges> {
         let mut state_changes = StateChanges::new_with_env(env_name.clone());
-        if self.environment_in_sync(env_name).await? {
+
+        // The installed packages have to match the lock-file, if it's up to date
+        let locked_environment = self.locked_environment(env_name)?;
+        let in_sync = self.environment_in_sync(env_name).await?
+            && match &locked_environment {
+                Some(locked_environment) => {
+                    self.is_solved_environment_installed(env_name, locked_environment)
+                        .await?
+                }
+                None => true,
+            };
+
+        if in_sync {
             tracing::debug!(
                 "Environment {} specs already up to date with global manifest",
                 env_name.fancy_display()
             );
+
+            // Lock the installed packages if the lock-file is missing or outdated
+            if locked_environment.is_none() {
+                self.lock_installed_environment(env_name).await?;
+            }
         } else {
             tracing::debug!(
                 "Environment {} specs not up to date with global manifest",
@@ -1007,6 +1279,7 @@ impl Project {
                     tokio_fs::remove_dir_all(&env_path)
                         .await
                         .into_diagnostic()?;
+                    self.remove_lock_file(&env_name).await?;
                     // Get all removable binaries related to the environment
                     let (to_remove, _to_add) = get_expose_scripts_sync_status(
                         &self.bin_dir,
@@ -1026,6 +1299,44 @@ impl Project {
         Ok(state_changes)
     }
 
+    /// Remove the exposed executables that point into an environment that no
+    /// longer exists, e.g. because its directory was deleted by hand.
+    ///
+    /// The environment of an executable is found from the executable it
+    /// points to, which is stored in the configuration of the trampoline.
+    pub(crate) async fn prune_orphaned_exposed(&self) -> miette::Result<StateChanges> {
+        let mut state_changes = StateChanges::default();
+        for exposed in self.bin_dir.executables().await? {
+            let Ok(executable) = exposed.executable().await else {
+                continue;
+            };
+            let Some(Ok(env_name)) = executable
+                .strip_prefix(self.env_root.path())
+                .ok()
+                .and_then(|relative| relative.components().next())
+                .and_then(|component| component.as_os_str().to_str())
+                .map(EnvironmentName::from_str)
+            else {
+                continue;
+            };
+
+            let env_dir = self.env_root.path().join(env_name.as_str());
+            if !env_dir.join(consts::CONDA_META_DIR).exists() {
+                tracing::debug!(
+                    "Removing {} which points into the removed environment {}",
+                    exposed.path().display(),
+                    env_name.fancy_display()
+                );
+                exposed.remove().await?;
+                state_changes.insert_change(
+                    &env_name,
+                    StateChange::RemovedExposed(exposed.exposed_name()),
+                );
+            }
+        }
+        Ok(state_changes)
+    }
+
     // Figure which packages have been added
     pub async fn added_packages(
         &self,
@@ -1052,11 +1363,7 @@ impl Repodata for Project {
     /// Returns the [`Gateway`] used by this project.
     fn repodata_gateway(&self) -> &Gateway {
         self.repodata_gateway.get_or_init(|| {
-            Self::repodata_gateway_init(
-                self.authenticated_client().clone(),
-                self.config().clone().into(),
-                self.config().max_concurrent_downloads(),
-            )
+            Self::repodata_gateway_init(self.authenticated_client().clone(), self.config())
         })
     }
 }
@@ -1300,6 +1607,64 @@ mod tests {
         assert_eq!(remaining_dirs, vec!["env1", "env3", "non-conda-env-dir"]);
     }
 
+    #[tokio::test]
+    async fn test_prune_orphaned_exposed() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let project = Project::from_str(
+            &PathBuf::from("dummy"),
+            r#"
+            [envs.kept]
+            channels = ["conda-forge"]
+            [envs.kept.dependencies]
+            python = "*"
+            [envs.kept.exposed]
+            python = "python"
+            "#,
+            EnvRoot::new(tempdir.path().to_path_buf()).unwrap(),
+            BinDir::new(tempdir.path().to_path_buf()).unwrap(),
+        )
+        .unwrap();
+
+        // Only the `kept` environment is installed
+        tokio_fs::create_dir_all(
+            project
+                .env_root
+                .path()
+                .join("kept")
+                .join(consts::CONDA_META_DIR),
+        )
+        .await
+        .unwrap();
+
+        let mut trampolines = Vec::new();
+        for (env, exposed) in [("kept", "python"), ("deleted", "rg")] {
+            let trampoline = Trampoline::new(
+                ExposedName::from_str(exposed).unwrap(),
+                project.bin_dir.path().to_path_buf(),
+                Configuration::new(
+                    project.env_root.path().join(env).join("bin").join(exposed),
+                    project.env_root.path().join(env).join("bin"),
+                    None,
+                ),
+            );
+            trampoline.save().await.unwrap();
+            trampolines.push(trampoline);
+        }
+
+        let state_changes = project.prune_orphaned_exposed().await.unwrap();
+        assert_eq!(
+            state_changes.changes(),
+            std::collections::HashMap::from([(
+                "deleted".parse().unwrap(),
+                vec![StateChange::RemovedExposed(
+                    ExposedName::from_str("rg").unwrap()
+                )]
+            )])
+        );
+        assert!(trampolines[0].path().exists());
+        assert!(!trampolines[1].path().exists());
+    }
+
     #[test]
     fn test_convert_repodata_to_exposed_data() {
         let temp_dir = tempdir().unwrap();
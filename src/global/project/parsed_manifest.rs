@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use super::environment::EnvironmentName;
@@ -12,6 +12,7 @@ use fancy_display::FancyDisplay;
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use miette::{Context, Diagnostic, IntoDiagnostic, LabeledSpan, NamedSource, Report};
+use pixi_config::RunPostLinkScripts;
 use pixi_consts::consts;
 use pixi_manifest::utils::package_map::UniquePackageMap;
 use pixi_manifest::PrioritizedChannel;
@@ -265,6 +266,19 @@ pub(crate) struct ParsedEnvironment {
         serialize_with = "serialize_expose_mappings"
     )]
     pub(crate) exposed: IndexSet<Mapping>,
+    /// Dependencies that were injected into the environment next to the
+    /// primary packages, their executables are never exposed automatically.
+    #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
+    pub(crate) injected: IndexSet<PackageName>,
+    /// Paths relative to the environment, per package, below which the
+    /// executables of the package are found when they are not installed in
+    /// the default binary folders.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub(crate) executable_paths: IndexMap<PackageName, Vec<PathBuf>>,
+    /// Whether the post-link scripts of the packages are executed, overrides
+    /// the `run-post-link-scripts` configuration for this environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) run_post_link_scripts: Option<RunPostLinkScripts>,
 }
 
 impl ParsedEnvironment {
@@ -294,6 +308,27 @@ impl ParsedEnvironment {
     pub(crate) fn exposed(&self) -> &IndexSet<Mapping> {
         &self.exposed
     }
+
+    /// Returns whether the package was injected into the environment, instead
+    /// of being one of its primary packages.
+    pub(crate) fn is_injected(&self, package_name: &PackageName) -> bool {
+        self.injected.contains(package_name)
+    }
+
+    /// Returns the additional paths that contain executables of the given
+    /// package.
+    pub(crate) fn executable_paths(&self, package_name: &PackageName) -> &[PathBuf] {
+        self.executable_paths
+            .get(package_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the post-link scripts of the packages are executed,
+    /// `None` means the configuration decides.
+    pub(crate) fn run_post_link_scripts(&self) -> Option<RunPostLinkScripts> {
+        self.run_post_link_scripts
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, PartialOrd, Ord)]
@@ -365,8 +400,11 @@ pub struct ParseExposedKeyError {}
 #[cfg(test)]
 mod tests {
     use insta::assert_snapshot;
+    use std::{path::PathBuf, str::FromStr};
 
-    use super::ParsedManifest;
+    use rattler_conda_types::PackageName;
+
+    use super::{EnvironmentName, ParsedManifest};
 
     #[test]
     fn test_invalid_key() {
@@ -482,4 +520,71 @@ mod tests {
         "#;
         let _manifest = ParsedManifest::from_toml_str(contents).unwrap();
     }
+
+    #[test]
+    fn test_executable_paths() {
+        let contents = r#"
+        [envs.java]
+        channels = ["conda-forge"]
+        dependencies = { openjdk = "*" }
+        executable-paths = { openjdk = ["lib/jvm/bin"] }
+        "#;
+        let manifest = ParsedManifest::from_toml_str(contents).unwrap();
+        let environment = manifest
+            .envs
+            .get(&EnvironmentName::from_str("java").unwrap())
+            .unwrap();
+        assert_eq!(
+            environment.executable_paths(&PackageName::from_str("openjdk").unwrap()),
+            [PathBuf::from("lib/jvm/bin")]
+        );
+        assert!(environment
+            .executable_paths(&PackageName::from_str("python").unwrap())
+            .is_empty());
+        assert_eq!(environment.run_post_link_scripts(), None);
+    }
+
+    #[test]
+    fn test_injected() {
+        let contents = r#"
+        [envs.ipython]
+        channels = ["conda-forge"]
+        dependencies = { ipython = "*", numpy = "*" }
+        injected = ["numpy"]
+        "#;
+        let manifest = ParsedManifest::from_toml_str(contents).unwrap();
+        let environment = manifest
+            .envs
+            .get(&EnvironmentName::from_str("ipython").unwrap())
+            .unwrap();
+        assert!(environment.is_injected(&PackageName::from_str("numpy").unwrap()));
+        assert!(!environment.is_injected(&PackageName::from_str("ipython").unwrap()));
+    }
+
+    #[test]
+    fn test_run_post_link_scripts() {
+        let contents = r#"
+        [envs.compilers]
+        channels = ["conda-forge"]
+        dependencies = { gcc = "*" }
+        run-post-link-scripts = "insecure"
+        "#;
+        let manifest = ParsedManifest::from_toml_str(contents).unwrap();
+        let environment = manifest
+            .envs
+            .get(&EnvironmentName::from_str("compilers").unwrap())
+            .unwrap();
+        assert_eq!(
+            environment.run_post_link_scripts(),
+            Some(RunPostLinkScripts::Insecure)
+        );
+
+        let contents = r#"
+        [envs.compilers]
+        channels = ["conda-forge"]
+        dependencies = { gcc = "*" }
+        run-post-link-scripts = true
+        "#;
+        assert!(ParsedManifest::from_toml_str(contents).is_err());
+    }
 }
@@ -13,14 +13,12 @@ use pypi_modifiers::pypi_tags::{get_pypi_tags, is_python_record};
 use rattler_conda_types::Platform;
 use rattler_lock::{PypiIndexes, PypiPackageData, PypiPackageEnvironmentData};
 use utils::elapsed;
-use uv_auth::store_credentials_from_url;
 use uv_client::{Connectivity, FlatIndexClient, RegistryClientBuilder};
 use uv_configuration::{ConfigSettings, Constraints, IndexStrategy, LowerBound};
 use uv_dispatch::{BuildDispatch, SharedState};
 use uv_distribution::{DistributionDatabase, RegistryWheelIndex};
 use uv_distribution_types::{DependencyMetadata, IndexLocations, Name};
 use uv_git::GitResolver;
-use uv_install_wheel::linker::LinkMode;
 use uv_installer::{Preparer, SitePackages, UninstallError};
 use uv_python::{Interpreter, PythonEnvironment};
 use uv_resolver::{FlatIndex, InMemoryIndex};
@@ -76,6 +74,10 @@ pub async fn update_python_distributions(
         .unwrap_or_else(|| Ok(IndexLocations::default()))
         .into_diagnostic()?;
 
+    // Before hitting the network let's make sure the credentials are available to
+    // uv
+    uv_context.store_index_credentials(&index_locations);
+
     let registry_client = Arc::new(
         RegistryClientBuilder::new(uv_context.cache.clone())
             .client(uv_context.client.clone())
@@ -142,7 +144,7 @@ pub async fn update_python_distributions(
         IndexStrategy::default(),
         &config_settings,
         build_isolation,
-        LinkMode::default(),
+        uv_context.link_mode,
         &uv_context.build_options,
         &uv_context.hash_strategy,
         None,
@@ -317,13 +319,6 @@ pub async fn update_python_distributions(
             uv_context.concurrency.downloads,
         );
 
-        // Before hitting the network let's make sure the credentials are available to
-        // uv
-        for url in index_locations.indexes().map(|index| index.url()) {
-            let success = store_credentials_from_url(url);
-            tracing::debug!("Stored credentials for {}: {}", url, success);
-        }
-
         let preparer = Preparer::new(
             &uv_context.cache,
             &tags,
@@ -469,7 +464,7 @@ pub async fn update_python_distributions(
     if !all_dists.is_empty() {
         let start = std::time::Instant::now();
         uv_installer::Installer::new(&venv)
-            .with_link_mode(LinkMode::default())
+            .with_link_mode(uv_context.link_mode)
             .with_installer_name(Some(consts::PIXI_UV_INSTALLER.to_string()))
             .with_reporter(UvReporter::new(options))
             .install(all_dists.clone())
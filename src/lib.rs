@@ -1,14 +1,19 @@
 pub mod activation;
 pub mod cli;
+mod container;
 mod diff;
 pub mod environment;
+mod generate;
 mod global;
 mod install_pypi;
 pub mod lock_file;
 mod prefix;
+mod prefix_transaction;
 mod project;
 mod prompt;
 pub(crate) mod repodata;
+mod resumable_download;
+mod shared_environments;
 pub mod task;
 
 mod uv_reporter;
@@ -0,0 +1,245 @@
+//! Flags dependencies that make the environments of a lock-file unusually
+//! heavy, with a suggestion to keep the environments lean.
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
+
+use human_bytes::human_bytes;
+use itertools::Itertools;
+use rattler_conda_types::Platform;
+use rattler_lock::{LockFile, LockedPackageRef};
+
+/// Conda packages that are larger than this are flagged as heavy.
+const HEAVY_PACKAGE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Groups of conda packages that provide the same functionality, an
+/// environment only needs one package of each group.
+const DUPLICATE_FUNCTIONALITY: &[(&str, &[&str])] = &[
+    ("BLAS", &["libopenblas", "mkl", "blis", "libflexiblas"]),
+    ("JPEG", &["libjpeg-turbo", "jpeg"]),
+    ("Qt", &["qt-main", "qt6-main"]),
+];
+
+/// Something in a locked environment that makes it heavier than it needs to be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Finding {
+    /// A conda package that is larger than [`HEAVY_PACKAGE_SIZE`].
+    HeavyPackage { package: String, size: u64 },
+    /// Multiple packages that provide the same functionality.
+    DuplicateFunctionality {
+        functionality: &'static str,
+        packages: Vec<String>,
+    },
+    /// A project that is installed both as a conda and as a PyPI package.
+    CondaAndPypi { package: String },
+}
+
+impl Finding {
+    /// Returns a suggestion to resolve the finding.
+    pub(crate) fn suggestion(&self) -> String {
+        match self {
+            Finding::HeavyPackage { .. } => {
+                "check whether a smaller variant of the package is available, e.g. a `-base` or `-minimal` package".to_string()
+            }
+            Finding::DuplicateFunctionality { packages, .. } => format!(
+                "add a dependency on one of {} to select a single implementation",
+                packages.iter().map(|package| format!("`{package}`")).join(", ")
+            ),
+            Finding::CondaAndPypi { package } => format!(
+                "remove `{package}` from the pypi-dependencies, the conda package already provides it"
+            ),
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::HeavyPackage { package, size } => {
+                write!(f, "{} is {}", package, human_bytes(*size as f64))
+            }
+            Finding::DuplicateFunctionality {
+                functionality,
+                packages,
+            } => write!(
+                f,
+                "{} provide the same {} functionality",
+                packages.iter().join(" and "),
+                functionality
+            ),
+            Finding::CondaAndPypi { package } => {
+                write!(
+                    f,
+                    "{} is installed both as a conda and a PyPI package",
+                    package
+                )
+            }
+        }
+    }
+}
+
+/// The findings of all the environments of a lock-file.
+#[derive(Debug, Default)]
+pub(crate) struct EnvironmentAnalysis {
+    pub findings: Vec<(String, Platform, Finding)>,
+}
+
+impl EnvironmentAnalysis {
+    /// Analyzes the packages of all environments and platforms of the lock-file.
+    pub(crate) fn from_lock_file(lock_file: &LockFile) -> Self {
+        let mut analysis = Self::default();
+        for (env_name, environment) in lock_file.environments() {
+            for platform in environment.platforms().sorted_by_key(|p| p.as_str()) {
+                let packages = environment
+                    .packages(platform)
+                    .into_iter()
+                    .flatten()
+                    .collect_vec();
+                analysis.findings.extend(
+                    analyze_packages(&packages)
+                        .into_iter()
+                        .map(|finding| (env_name.to_string(), platform, finding)),
+                );
+            }
+        }
+        analysis
+    }
+
+    /// Prints the findings with their suggestion as warnings to stderr.
+    pub(crate) fn print(&self) {
+        if self.findings.is_empty() {
+            eprintln!(
+                "{}No unusually heavy dependencies found",
+                console::style(console::Emoji("✔ ", "")).green()
+            );
+            return;
+        }
+
+        for ((environment, platform), findings) in &self
+            .findings
+            .iter()
+            .chunk_by(|(environment, platform, _)| (environment, *platform))
+        {
+            eprintln!(
+                "{}The {} environment ({}) could be leaner:",
+                console::style(console::Emoji("⚠️ ", "")).yellow(),
+                console::style(environment).bold(),
+                platform
+            );
+            for (_, _, finding) in findings {
+                eprintln!("  - {}", finding);
+                eprintln!("    {}", console::style(finding.suggestion()).dim());
+            }
+        }
+    }
+}
+
+/// Returns the findings for the locked packages of a single platform.
+fn analyze_packages(packages: &[LockedPackageRef<'_>]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut conda_packages = HashMap::new();
+    for package in packages {
+        if let LockedPackageRef::Conda(data) = package {
+            let record = data.record();
+            conda_packages.insert(record.name.as_normalized().to_string(), record.size);
+        }
+    }
+
+    for (name, size) in conda_packages.iter().sorted() {
+        if let Some(size) = size.filter(|size| *size > HEAVY_PACKAGE_SIZE) {
+            findings.push(Finding::HeavyPackage {
+                package: name.clone(),
+                size,
+            });
+        }
+    }
+
+    for (functionality, group) in DUPLICATE_FUNCTIONALITY {
+        let installed = group
+            .iter()
+            .filter(|name| conda_packages.contains_key(**name))
+            .map(|name| name.to_string())
+            .collect_vec();
+        if installed.len() > 1 {
+            findings.push(Finding::DuplicateFunctionality {
+                functionality: *functionality,
+                packages: installed,
+            });
+        }
+    }
+
+    for package in packages {
+        if let LockedPackageRef::Pypi(data, _) = package {
+            if conda_packages.contains_key(data.name.as_ref()) {
+                findings.push(Finding::CondaAndPypi {
+                    package: data.name.to_string(),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const LOCK_FILE: &str = r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    indexes:
+    - https://pypi.org/simple
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/libopenblas-0.3.28-0.conda
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/mkl-2024.2.2-0.conda
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/numpy-2.1.3-0.conda
+      - pypi: https://files.pythonhosted.org/packages/numpy-2.1.3-cp312-none-any.whl
+packages:
+- conda: https://conda.anaconda.org/conda-forge/linux-64/libopenblas-0.3.28-0.conda
+  size: 5000000
+- conda: https://conda.anaconda.org/conda-forge/linux-64/mkl-2024.2.2-0.conda
+  size: 130000000
+- conda: https://conda.anaconda.org/conda-forge/linux-64/numpy-2.1.3-0.conda
+  size: 8000000
+- pypi: https://files.pythonhosted.org/packages/numpy-2.1.3-cp312-none-any.whl
+  name: numpy
+  version: 2.1.3
+"#;
+
+    #[test]
+    fn test_environment_analysis() {
+        let lock_file = LockFile::from_str(LOCK_FILE).unwrap();
+        let analysis = EnvironmentAnalysis::from_lock_file(&lock_file);
+
+        let findings = analysis
+            .findings
+            .into_iter()
+            .map(|(_, _, finding)| finding)
+            .collect_vec();
+        assert_eq!(
+            findings,
+            vec![
+                Finding::HeavyPackage {
+                    package: "mkl".to_string(),
+                    size: 130000000
+                },
+                Finding::DuplicateFunctionality {
+                    functionality: "BLAS",
+                    packages: vec!["libopenblas".to_string(), "mkl".to_string()]
+                },
+                Finding::CondaAndPypi {
+                    package: "numpy".to_string()
+                },
+            ]
+        );
+    }
+}
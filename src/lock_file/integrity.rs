@@ -0,0 +1,73 @@
+//! Checks that the packages in the lock-file can be verified when they are
+//! installed.
+//!
+//! The hashes that are stored in the lock-file are used by the installer to
+//! verify the archives that are downloaded from the channels. Packages that are
+//! locked without a hash cannot be verified, and could therefore silently change
+//! between installs.
+
+use rattler_conda_types::RepoDataRecord;
+
+/// Returns the records that are downloaded from a remote location but don't
+/// have a `sha256` or `md5` hash that can be used to verify the integrity of the
+/// downloaded archive.
+pub(crate) fn records_without_hash<'a>(
+    records: impl IntoIterator<Item = &'a RepoDataRecord>,
+) -> Vec<&'a RepoDataRecord> {
+    records
+        .into_iter()
+        .filter(|record| record.url.scheme() != "file")
+        .filter(|record| {
+            record.package_record.sha256.is_none() && record.package_record.md5.is_none()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rattler_conda_types::{PackageName, PackageRecord, Version};
+    use rattler_digest::{parse_digest_from_hex, Sha256};
+    use url::Url;
+
+    use super::*;
+
+    fn record(name: &str, url: &str, sha256: Option<&str>) -> RepoDataRecord {
+        let mut package_record = PackageRecord::new(
+            PackageName::from_str(name).unwrap(),
+            Version::from_str("1.0").unwrap(),
+            "0".to_string(),
+        );
+        package_record.sha256 = sha256.and_then(parse_digest_from_hex::<Sha256>);
+        RepoDataRecord {
+            package_record,
+            file_name: format!("{name}-1.0-0.conda"),
+            url: Url::parse(url).unwrap(),
+            channel: None,
+        }
+    }
+
+    #[test]
+    fn test_records_without_hash() {
+        let records = [
+            record(
+                "hashed",
+                "https://conda.anaconda.org/conda-forge/noarch/hashed-1.0-0.conda",
+                Some("1154fceeb5c4ee9bb97d245713ac21eb1910237c724d2b7103747215663273c2"),
+            ),
+            record(
+                "unhashed",
+                "https://conda.anaconda.org/conda-forge/noarch/unhashed-1.0-0.conda",
+                None,
+            ),
+            record("local", "file:///channel/noarch/local-1.0-0.conda", None),
+        ];
+
+        let missing = records_without_hash(&records)
+            .into_iter()
+            .map(|record| record.package_record.name.as_normalized())
+            .collect::<Vec<_>>();
+        assert_eq!(missing, vec!["unhashed"]);
+    }
+}
@@ -1,28 +1,39 @@
+mod analysis;
+mod integrity;
 mod outdated;
 mod package_identifier;
 mod records_by_name;
 mod reporter;
+mod reproducibility;
 mod resolve;
 mod satisfiability;
 mod update;
 mod utils;
 
 use crate::Project;
+pub(crate) use analysis::EnvironmentAnalysis;
+pub(crate) use integrity::records_without_hash;
 use miette::{IntoDiagnostic, WrapErr};
 pub(crate) use package_identifier::PypiPackageIdentifier;
 use pixi_record::PixiRecord;
 use rattler_lock::{LockFile, ParseCondaLockError, PypiPackageData, PypiPackageEnvironmentData};
 pub(crate) use records_by_name::{PixiRecordsByName, PypiRecordsByName};
+pub(crate) use reproducibility::{set_source_date_epoch, ReproducibilityReport};
 pub(crate) use resolve::{
-    conda::resolve_conda, pypi::resolve_pypi, uv_resolution_context::UvResolutionContext,
+    conda::{resolve_conda, ChannelOverlays},
+    pypi::resolve_pypi,
+    uv_resolution_context::UvResolutionContext,
+    SolveError,
 };
 pub use satisfiability::{
     verify_environment_satisfiability, verify_platform_satisfiability, EnvironmentUnsat,
     PlatformUnsat,
 };
-pub(crate) use update::{LockFileDerivedData, UpdateContext};
+pub(crate) use update::{LockFileDerivedData, LockFileOutOfDateError, UpdateContext};
 pub use update::{UpdateLockFileOptions, UpdateMode};
-pub(crate) use utils::filter_lock_file;
+pub(crate) use utils::{
+    add_locked_packages, copy_lock_file_environment, filter_lock_file, relative_paths,
+};
 
 /// A list of conda packages that are locked for a specific platform.
 pub type LockedCondaPackages = Vec<PixiRecord>;
@@ -38,6 +49,7 @@ pub type PypiRecord = (PypiPackageData, PypiPackageEnvironmentData);
 /// could be found.
 pub async fn load_lock_file(project: &Project) -> miette::Result<LockFile> {
     let lock_file_path = project.lock_file_path();
+    let project_root = project.root().to_path_buf();
     if lock_file_path.is_file() {
         // Spawn a background task because loading the file might be IO bound.
         tokio::task::spawn_blocking(move || {
@@ -58,6 +70,7 @@ pub async fn load_lock_file(project: &Project) -> miette::Result<LockFile> {
                         lock_file_path.display()
                     )
                 })
+                .map(|lock_file| utils::resolve_relative_paths(&lock_file, &project_root))
         })
             .await
             .unwrap_or_else(|e| Err(e).into_diagnostic())
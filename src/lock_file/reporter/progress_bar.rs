@@ -13,6 +13,8 @@ use crate::{build::BuildMetadataReporter, project::grouped_environment::GroupedE
 #[derive(Clone)]
 pub(crate) struct SolveProgressBar {
     pub pb: ProgressBar,
+    /// The environment and platform that are solved, for the JSON output.
+    name_and_platform: String,
 }
 
 impl SolveProgressBar {
@@ -29,11 +31,15 @@ impl SolveProgressBar {
 
         pb.set_style(indicatif::ProgressStyle::with_template("    {prefix:20!} ..").unwrap());
         pb.enable_steady_tick(Duration::from_millis(100));
-        pb.set_prefix(name_and_platform);
-        Self { pb }
+        pb.set_prefix(name_and_platform.clone());
+        Self {
+            pb,
+            name_and_platform,
+        }
     }
 
     pub(crate) fn start(&self) {
+        pixi_progress::emit_json_progress(&format!("solving {}", self.name_and_platform), false);
         self.pb.reset_elapsed();
         self.reset_style()
     }
@@ -99,6 +105,7 @@ impl SolveProgressBar {
             .unwrap(),
         );
         self.pb.finish_and_clear();
+        pixi_progress::emit_json_progress(&format!("solving {}", self.name_and_platform), true);
     }
 
     pub(crate) fn purl_amend_reporter(self: &Arc<Self>) -> Arc<dyn Reporter> {
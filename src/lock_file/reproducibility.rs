@@ -0,0 +1,231 @@
+//! Checks that the environments of a lock-file can be reproduced exactly.
+//!
+//! An environment is only reproducible if every package is downloaded as a
+//! prebuilt archive that can be verified with a hash from the lock-file.
+//! Packages that are built while they are installed, or that are installed in
+//! editable mode, can differ between installs even though the lock-file didn't
+//! change.
+
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use rattler_conda_types::Platform;
+use rattler_lock::{CondaPackageData, LockFile, LockedPackageRef};
+
+/// The value of `SOURCE_DATE_EPOCH` for builds in reproducible mode when it
+/// isn't set already: 1980-01-01, the earliest timestamp a zip archive, and
+/// thus a wheel, can store.
+const DEFAULT_SOURCE_DATE_EPOCH: &str = "315532800";
+
+/// Sets `SOURCE_DATE_EPOCH` when it isn't set already, build backends use it
+/// instead of the current time for the timestamps in the built artifacts.
+pub(crate) fn set_source_date_epoch() {
+    if std::env::var_os("SOURCE_DATE_EPOCH").is_none() {
+        std::env::set_var("SOURCE_DATE_EPOCH", DEFAULT_SOURCE_DATE_EPOCH);
+    }
+}
+
+/// A reason why a locked package could differ between installs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Nondeterminism {
+    /// The package has no hash to verify the downloaded archive with.
+    MissingHash,
+    /// The package is built from source when it is installed.
+    SourceBuild,
+    /// The package is installed in editable mode from a local directory.
+    Editable,
+}
+
+impl Display for Nondeterminism {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Nondeterminism::MissingHash => write!(f, "is not pinned by a hash"),
+            Nondeterminism::SourceBuild => write!(f, "is built from source on install"),
+            Nondeterminism::Editable => write!(f, "is installed in editable mode"),
+        }
+    }
+}
+
+/// A locked package that could differ between installs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Violation {
+    pub environment: String,
+    pub platform: Platform,
+    pub package: String,
+    pub reason: Nondeterminism,
+}
+
+/// The result of checking all the packages of a lock-file.
+#[derive(Debug, Default)]
+pub(crate) struct ReproducibilityReport {
+    /// The number of locked packages that were checked.
+    pub checked: usize,
+    /// The packages that could differ between installs.
+    pub violations: Vec<Violation>,
+}
+
+impl ReproducibilityReport {
+    /// Checks the packages of the given environments of the lock-file, or of
+    /// all environments if `environments` is `None`.
+    pub(crate) fn from_lock_file(lock_file: &LockFile, environments: Option<&[String]>) -> Self {
+        let mut report = Self::default();
+        for (env_name, environment) in lock_file.environments() {
+            if environments.is_some_and(|names| !names.iter().any(|name| name == env_name)) {
+                continue;
+            }
+            for platform in environment.platforms().sorted_by_key(|p| p.as_str()) {
+                for package in environment.packages(platform).into_iter().flatten() {
+                    report.checked += 1;
+                    let (name, reasons) = check_package(package);
+                    report
+                        .violations
+                        .extend(reasons.into_iter().map(|reason| Violation {
+                            environment: env_name.to_string(),
+                            platform,
+                            package: name.clone(),
+                            reason,
+                        }));
+                }
+            }
+        }
+        report
+    }
+
+    /// Returns true if all the checked packages are reproducible.
+    pub(crate) fn is_compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Prints the report to stderr.
+    pub(crate) fn print(&self) {
+        if self.is_compliant() {
+            eprintln!(
+                "{}All {} locked packages are reproducible.",
+                console::style(console::Emoji("✔ ", "")).green(),
+                self.checked
+            );
+            return;
+        }
+
+        eprintln!(
+            "{}{} of {} locked packages are not reproducible:",
+            console::style(console::Emoji("✘ ", "")).red(),
+            self.violations
+                .iter()
+                .map(|violation| (
+                    &violation.environment,
+                    violation.platform,
+                    &violation.package
+                ))
+                .unique()
+                .count(),
+            self.checked
+        );
+        for ((environment, platform), violations) in &self
+            .violations
+            .iter()
+            .chunk_by(|violation| (&violation.environment, violation.platform))
+        {
+            eprintln!("  {} ({})", console::style(environment).bold(), platform);
+            for violation in violations {
+                eprintln!("    - {} {}", violation.package, violation.reason);
+            }
+        }
+    }
+}
+
+/// Returns the name of the package and the reasons why it could differ between
+/// installs.
+fn check_package(package: LockedPackageRef<'_>) -> (String, Vec<Nondeterminism>) {
+    let mut reasons = Vec::new();
+    let name = match package {
+        LockedPackageRef::Conda(CondaPackageData::Binary(data)) => {
+            let record = &data.package_record;
+            if record.sha256.is_none() && record.md5.is_none() {
+                reasons.push(Nondeterminism::MissingHash);
+            }
+            record.name.as_source().to_string()
+        }
+        LockedPackageRef::Conda(CondaPackageData::Source(data)) => {
+            reasons.push(Nondeterminism::SourceBuild);
+            data.package_record.name.as_source().to_string()
+        }
+        LockedPackageRef::Pypi(data, _) => {
+            if data.hash.is_none() {
+                reasons.push(Nondeterminism::MissingHash);
+            }
+            if !data.location.to_string().ends_with(".whl") {
+                reasons.push(Nondeterminism::SourceBuild);
+            }
+            if data.editable {
+                reasons.push(Nondeterminism::Editable);
+            }
+            data.name.to_string()
+        }
+    };
+    (name, reasons)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const LOCK_FILE: &str = r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    indexes:
+    - https://pypi.org/simple
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/noarch/hashed-1.0-0.conda
+      - conda: https://conda.anaconda.org/conda-forge/noarch/unhashed-1.0-0.conda
+      - pypi: https://files.pythonhosted.org/packages/wheel-1.0-py3-none-any.whl
+      - pypi: ./local
+packages:
+- conda: https://conda.anaconda.org/conda-forge/noarch/hashed-1.0-0.conda
+  sha256: 1154fceeb5c4ee9bb97d245713ac21eb1910237c724d2b7103747215663273c2
+  md5: 0a2ce8f1a5d1a5b0e5a5b1c5d1a5b0e5
+- conda: https://conda.anaconda.org/conda-forge/noarch/unhashed-1.0-0.conda
+- pypi: https://files.pythonhosted.org/packages/wheel-1.0-py3-none-any.whl
+  name: wheel
+  version: '1.0'
+  sha256: 1154fceeb5c4ee9bb97d245713ac21eb1910237c724d2b7103747215663273c2
+- pypi: ./local
+  name: local
+  version: 0.1.0
+  editable: true
+"#;
+
+    #[test]
+    fn test_reproducibility_report() {
+        let lock_file = LockFile::from_str(LOCK_FILE).unwrap();
+        let report = ReproducibilityReport::from_lock_file(&lock_file, None);
+
+        assert_eq!(report.checked, 4);
+        assert!(!report.is_compliant());
+        let violations = report
+            .violations
+            .iter()
+            .map(|violation| (violation.package.as_str(), violation.reason))
+            .collect_vec();
+        assert_eq!(
+            violations,
+            vec![
+                ("unhashed", Nondeterminism::MissingHash),
+                ("local", Nondeterminism::MissingHash),
+                ("local", Nondeterminism::SourceBuild),
+                ("local", Nondeterminism::Editable),
+            ]
+        );
+
+        let report =
+            ReproducibilityReport::from_lock_file(&lock_file, Some(&["other".to_string()]));
+        assert_eq!(report.checked, 0);
+        assert!(report.is_compliant());
+    }
+}
@@ -1,12 +1,19 @@
+use std::collections::HashSet;
+
 use ahash::HashMap;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
-use miette::IntoDiagnostic;
+use pixi_manifest::SolveStrategy;
 use pixi_record::{PixiRecord, SourceRecord};
-use rattler_conda_types::{GenericVirtualPackage, MatchSpec, RepoDataRecord};
+use rattler_conda_types::{
+    ChannelConfig, GenericVirtualPackage, MatchSpec, Matches, NamedChannelOrUrl, PackageName,
+    ParseChannelError, RepoDataRecord,
+};
 use rattler_repodata_gateway::RepoData;
 use rattler_solve::{resolvo, ChannelPriority, SolverImpl};
 use url::Url;
 
+use super::SolveError;
 use crate::{
     build::{SourceCheckout, SourceMetadata},
     lock_file::LockedCondaPackages,
@@ -22,6 +29,9 @@ pub async fn resolve_conda(
     available_repodata: Vec<RepoData>,
     available_source_packages: Vec<SourceMetadata>,
     channel_priority: ChannelPriority,
+    channel_overlays: ChannelOverlays,
+    exclude_newer: Option<DateTime<Utc>>,
+    solve_strategy: SolveStrategy,
 ) -> miette::Result<LockedCondaPackages> {
     tokio::task::spawn_blocking(move || {
         // Combine the repodata from the source packages and from registry channels.
@@ -51,21 +61,43 @@ pub async fn resolve_conda(
                 .map(|(_, record)| record)
                 .collect_vec(),
         );
-        for repo_data in &available_repodata {
-            solvable_records.push(repo_data.iter().collect_vec());
+        for (channel_idx, repo_data) in available_repodata.iter().enumerate() {
+            solvable_records.push(
+                repo_data
+                    .iter()
+                    .filter(|record| {
+                        channel_overlays.is_available(channel_idx, &record.package_record.name)
+                    })
+                    .collect_vec(),
+            );
         }
 
+        // Keep what is needed to explain a failed solve.
+        let requested_specs = specs.clone();
+        let available_virtual_packages = virtual_packages.clone();
+        let candidates = solvable_records.iter().flatten().copied().collect_vec();
+
         // Construct a solver task that we can start solving.
         let task = rattler_solve::SolverTask {
             specs,
             locked_packages,
             virtual_packages,
             channel_priority,
+            exclude_newer,
+            strategy: solve_strategy.into(),
             ..rattler_solve::SolverTask::from_iter(solvable_records)
         };
 
         // Solve the task
-        let solved = resolvo::Solver.solve(task).into_diagnostic()?;
+        let solved = resolvo::Solver.solve(task).map_err(|err| {
+            let help = match &err {
+                rattler_solve::SolveError::Unsolvable(_) => {
+                    explain_unsolvable(&requested_specs, &candidates, &available_virtual_packages)
+                }
+                _ => None,
+            };
+            SolveError::new(err).with_help(help)
+        })?;
 
         Ok(solved
             .into_iter()
@@ -84,6 +116,168 @@ pub async fn resolve_conda(
     })
 }
 
+/// Determines which packages are used from which channel, based on the
+/// `channel-overlays` of the manifest.
+///
+/// An overlay channel shadows the packages it lists: those packages are only
+/// taken from the overlay, and nothing else is taken from the overlay.
+#[derive(Debug, Default, Clone)]
+pub struct ChannelOverlays {
+    /// For every channel, in the order of the repodata, the packages it
+    /// overlays or `None` if the channel is not an overlay.
+    overlays: Vec<Option<(NamedChannelOrUrl, HashSet<PackageName>)>>,
+}
+
+impl ChannelOverlays {
+    /// Determines the overlays of the given channels, the order of the channels
+    /// must match the order of the repodata passed to the solver.
+    pub fn new(
+        channels: &[NamedChannelOrUrl],
+        channel_overlays: &std::collections::HashMap<NamedChannelOrUrl, Vec<PackageName>>,
+        channel_config: &ChannelConfig,
+    ) -> Result<Self, ParseChannelError> {
+        if channel_overlays.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let overlays_by_url = channel_overlays
+            .iter()
+            .map(|(channel, packages)| {
+                let url = channel.clone().into_base_url(channel_config)?;
+                Ok((url, (channel.clone(), packages.iter().cloned().collect())))
+            })
+            .collect::<Result<HashMap<_, _>, ParseChannelError>>()?;
+
+        let overlays = channels
+            .iter()
+            .map(|channel| {
+                let url = channel.clone().into_base_url(channel_config)?;
+                Ok(overlays_by_url.get(&url).cloned())
+            })
+            .collect::<Result<_, ParseChannelError>>()?;
+
+        Ok(Self { overlays })
+    }
+
+    /// Returns true if the package can be used from the channel at the given
+    /// index.
+    fn is_available(&self, channel_idx: usize, name: &PackageName) -> bool {
+        match self.overlays.get(channel_idx) {
+            Some(Some((_, packages))) => packages.contains(name),
+            _ => !self
+                .overlays
+                .iter()
+                .flatten()
+                .any(|(_, packages)| packages.contains(name)),
+        }
+    }
+
+    /// Warns about overlaid packages for which the other channels contain a
+    /// newer version than the overlay, this usually means the fork has to be
+    /// rebuilt.
+    pub fn warn_outdated(&self, available_repodata: &[RepoData]) {
+        for (overlay_idx, (channel, packages)) in self
+            .overlays
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, overlay)| Some((idx, overlay.as_ref()?)))
+        {
+            for name in packages {
+                // The highest version of the package in the overlay or in the other channels.
+                let highest_version = |in_overlay: bool| {
+                    available_repodata
+                        .iter()
+                        .enumerate()
+                        .filter(|(idx, _)| (*idx == overlay_idx) == in_overlay)
+                        .flat_map(|(_, repo_data)| repo_data.iter())
+                        .filter(|record| &record.package_record.name == name)
+                        .map(|record| record.package_record.version.version())
+                        .max()
+                };
+                if let (Some(overlay_version), Some(base_version)) =
+                    (highest_version(true), highest_version(false))
+                {
+                    if overlay_version < base_version {
+                        tracing::warn!(
+                            "the overlay channel '{}' provides {} {}, but {} is available in the other channels",
+                            channel,
+                            name.as_source(),
+                            overlay_version,
+                            base_version
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Explains the requested specs for which the solver has no candidate at all:
+/// packages that none of the channels provide for the platform, versions that
+/// the channels don't have and virtual packages the system doesn't provide.
+/// Conflicts between the candidates are already explained by the solver.
+fn explain_unsolvable(
+    specs: &[MatchSpec],
+    candidates: &[&RepoDataRecord],
+    virtual_packages: &[GenericVirtualPackage],
+) -> Option<String> {
+    let mut hints = Vec::new();
+    for spec in specs {
+        let Some(name) = &spec.name else {
+            continue;
+        };
+
+        if name.as_normalized().starts_with("__") {
+            if !virtual_packages.iter().any(|package| &package.name == name) {
+                hints.push(format!(
+                    "the virtual package '{}' is not available on this system, add it to the `system-requirements` if the environment targets such systems",
+                    name.as_source()
+                ));
+            }
+            continue;
+        }
+
+        let records = candidates
+            .iter()
+            .filter(|record| &record.package_record.name == name)
+            .collect_vec();
+        if records.is_empty() {
+            hints.push(format!(
+                "none of the channels provide '{}' for this platform",
+                name.as_source()
+            ));
+            continue;
+        }
+        if records.iter().any(|record| spec.matches(**record)) {
+            continue;
+        }
+
+        let versions = records
+            .iter()
+            .map(|record| &record.package_record.version)
+            .unique()
+            .sorted()
+            .rev()
+            .take(5)
+            .join(", ");
+        let channels = records
+            .iter()
+            .filter_map(|record| record.channel.as_deref())
+            .unique()
+            .join(", ");
+        hints.push(format!(
+            "no package matches '{spec}', the available versions are {versions}{}",
+            if channels.is_empty() {
+                String::new()
+            } else {
+                format!(" (from {channels})")
+            }
+        ));
+    }
+
+    (!hints.is_empty()).then(|| hints.join("\n"))
+}
+
 fn unique_url(checkout: &SourceCheckout, source: &SourceRecord) -> Url {
     let mut url = Url::from_directory_path(&checkout.path)
         .expect("expected source checkout to be a valid url");
@@ -97,3 +291,78 @@ fn unique_url(checkout: &SourceCheckout, source: &SourceRecord) -> Url {
 
     url
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_channel_overlays() {
+        let channel_config = ChannelConfig::default_with_root_dir(std::env::current_dir().unwrap());
+        let fork = NamedChannelOrUrl::from_str("https://example.com/fork").unwrap();
+        let conda_forge = NamedChannelOrUrl::from_str("conda-forge").unwrap();
+        let openssl = PackageName::from_str("openssl").unwrap();
+        let python = PackageName::from_str("python").unwrap();
+
+        let overlays = ChannelOverlays::new(
+            &[fork.clone(), conda_forge],
+            &std::collections::HashMap::from_iter([(fork, vec![openssl.clone()])]),
+            &channel_config,
+        )
+        .unwrap();
+
+        // The overlaid package is only taken from the overlay.
+        assert!(overlays.is_available(0, &openssl));
+        assert!(!overlays.is_available(1, &openssl));
+
+        // Other packages are never taken from the overlay.
+        assert!(!overlays.is_available(0, &python));
+        assert!(overlays.is_available(1, &python));
+    }
+
+    #[test]
+    fn test_explain_unsolvable() {
+        let record = |name: &str, version: &str| RepoDataRecord {
+            package_record: rattler_conda_types::PackageRecord::new(
+                PackageName::from_str(name).unwrap(),
+                rattler_conda_types::Version::from_str(version).unwrap(),
+                "0".to_string(),
+            ),
+            file_name: format!("{name}-{version}-0.conda"),
+            url: Url::parse(&format!(
+                "https://conda.anaconda.org/conda-forge/linux-64/{name}-{version}-0.conda"
+            ))
+            .unwrap(),
+            channel: Some("https://conda.anaconda.org/conda-forge/".to_string()),
+        };
+        let records = [
+            record("numpy", "1.26.4"),
+            record("numpy", "2.0.0"),
+            record("python", "3.12.0"),
+        ];
+        let candidates = records.iter().collect_vec();
+        let spec = |spec: &str| {
+            MatchSpec::from_str(spec, rattler_conda_types::ParseStrictness::Lenient).unwrap()
+        };
+
+        // Specs with candidates are left to the solver.
+        assert_eq!(
+            explain_unsolvable(&[spec("numpy"), spec("python")], &candidates, &[]),
+            None
+        );
+
+        assert_eq!(
+            explain_unsolvable(
+                &[spec("numpy>=3"), spec("nmupy"), spec("__cuda")],
+                &candidates,
+                &[]
+            )
+            .unwrap(),
+            "no package matches 'numpy >=3', the available versions are 2.0.0, 1.26.4 (from https://conda.anaconda.org/conda-forge/)\n\
+             none of the channels provide 'nmupy' for this platform\n\
+             the virtual package '__cuda' is not available on this system, add it to the `system-requirements` if the environment targets such systems"
+        );
+    }
+}
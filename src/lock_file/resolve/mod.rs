@@ -6,3 +6,45 @@ pub(crate) mod conda;
 pub(crate) mod pypi;
 mod resolver_provider;
 pub(crate) mod uv_resolution_context;
+
+use std::fmt::{Display, Formatter};
+
+use miette::Diagnostic;
+
+/// The requirements of an environment can't be satisfied. Pixi exits with
+/// [`crate::cli::ExitCode::SolveFailed`] when a command fails because of it.
+#[derive(Debug, Diagnostic)]
+#[diagnostic(code(pixi::solve_failed))]
+pub struct SolveError {
+    error: Box<dyn std::error::Error + Send + Sync + 'static>,
+    /// Explains why the solver could not find a solution, in terms of the
+    /// requested specs and the available packages.
+    #[help]
+    help: Option<String>,
+}
+
+impl SolveError {
+    pub(crate) fn new(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self {
+            error: Box::new(err),
+            help: None,
+        }
+    }
+
+    pub(crate) fn with_help(mut self, help: Option<String>) -> Self {
+        self.help = help;
+        self
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
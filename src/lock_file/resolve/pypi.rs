@@ -39,7 +39,6 @@ use uv_distribution_types::{
     IndexUrl, InstalledDist, InstalledRegistryDist, Name, Resolution, ResolvedDist, SourceDist,
 };
 use uv_git::GitResolver;
-use uv_install_wheel::linker::LinkMode;
 use uv_pypi_types::{Conflicts, HashAlgorithm, HashDigest, RequirementSource};
 use uv_python::{Interpreter, PythonEnvironment};
 use uv_requirements::LookaheadResolver;
@@ -52,7 +51,7 @@ use uv_types::EmptyInstalledPackages;
 use crate::{
     lock_file::{
         records_by_name::HasNameVersion, resolve::resolver_provider::CondaResolverProvider,
-        LockedPypiPackages, PypiPackageIdentifier, PypiRecord, UvResolutionContext,
+        LockedPypiPackages, PypiPackageIdentifier, PypiRecord, SolveError, UvResolutionContext,
     },
     uv_reporter::{UvReporter, UvReporterOptions},
 };
@@ -279,6 +278,8 @@ pub async fn resolve_pypi(
 
     let index_locations =
         pypi_options_to_index_locations(pypi_options, project_root).into_diagnostic()?;
+    context.store_index_credentials(&index_locations);
+    let context = context.with_build_options(pypi_options)?;
 
     // TODO: create a cached registry client per index_url set?
     let index_strategy = to_index_strategy(pypi_options.index_strategy.as_ref());
@@ -334,6 +335,7 @@ pub async fn resolve_pypi(
     let dependency_metadata = DependencyMetadata::default();
     let options = Options {
         index_strategy,
+        exclude_newer: context.exclude_newer,
         ..Options::default()
     };
     let git_resolver = GitResolver::default();
@@ -358,7 +360,7 @@ pub async fn resolve_pypi(
         IndexStrategy::default(),
         &config_settings,
         build_isolation,
-        LinkMode::default(),
+        context.link_mode,
         &context.build_options,
         &context.hash_strategy,
         None,
@@ -500,7 +502,7 @@ pub async fn resolve_pypi(
     ))
     .resolve()
     .await
-    .into_diagnostic()
+    .map_err(SolveError::new)
     .context("failed to resolve pypi dependencies")?;
     let resolution = Resolution::from(resolution);
 
@@ -161,6 +161,9 @@ pub enum PlatformUnsat {
     #[error("missing purls")]
     MissingPurls,
 
+    #[error("the pypi name of '{0}' is overridden by the manifest, but not in the lock-file")]
+    PypiNameOverrideNotLocked(String),
+
     #[error("corrupted lock-file entry for '{0}'")]
     CorruptedEntry(String, ParseLockFileError),
 
@@ -498,6 +501,28 @@ pub async fn verify_platform_satisfiability(
         }
     }
 
+    // The purls of the locked conda packages have to reflect the pypi names that
+    // are overridden in the manifest.
+    if environment.has_pypi_dependencies() {
+        let pypi_name_mapping = &environment.project().manifest().workspace.pypi_name_mapping;
+        for record in pixi_records.iter().filter_map(PixiRecord::as_binary) {
+            let Some(pypi_name) = pypi_name_mapping.get(&record.package_record.name) else {
+                continue;
+            };
+            let is_overridden = record.package_record.purls.as_ref().is_some_and(|purls| {
+                !purls.is_empty()
+                    && purls
+                        .iter()
+                        .all(|purl| purl.name() == pypi_name.as_normalized().as_ref())
+            });
+            if !is_overridden {
+                return Err(Box::new(PlatformUnsat::PypiNameOverrideNotLocked(
+                    record.package_record.name.as_source().to_string(),
+                )));
+            }
+        }
+    }
+
     // Create a lookup table from package name to package record. Returns an error
     // if we find a duplicate entry for a record
     let pixi_records_by_name = match PixiRecordsByName::from_unique_iter(pixi_records) {
@@ -1484,6 +1509,74 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_local_package_satisfiability() {
+        let project_root = tempfile::tempdir().unwrap();
+        let manifest_path = project_root.path().join("pixi.toml");
+        fs_err::write(
+            &manifest_path,
+            r#"
+        [project]
+        name = "local-package"
+        channels = ["conda-forge"]
+        platforms = ["linux-64"]
+
+        [dependencies]
+        foo = "*"
+        "#,
+        )
+        .unwrap();
+        let archive = project_root.path().join("dist").join("foo-1.0-0.conda");
+        fs_err::create_dir_all(archive.parent().unwrap()).unwrap();
+        fs_err::write(&archive, b"not really a package").unwrap();
+        let project = Project::from_path(&manifest_path).unwrap();
+
+        // Lock the local package and write the lock-file like `pixi lock` does.
+        let mut package_record = rattler_conda_types::PackageRecord::new(
+            "foo".parse().unwrap(),
+            rattler_conda_types::Version::from_str("1.0").unwrap(),
+            "0".to_string(),
+        );
+        package_record.subdir = Platform::Linux64.to_string();
+        let record = PixiRecord::Binary(rattler_conda_types::RepoDataRecord {
+            package_record,
+            url: url::Url::from_file_path(&archive).unwrap(),
+            file_name: "foo-1.0-0.conda".to_string(),
+            channel: None,
+        });
+        let mut builder = LockFile::builder();
+        builder.set_channels(
+            "default",
+            vec!["https://conda.anaconda.org/conda-forge/".to_string()],
+        );
+        crate::lock_file::add_locked_packages(
+            &mut builder,
+            "default",
+            Platform::Linux64,
+            [record],
+            Vec::new(),
+        );
+        let lock_file = crate::lock_file::relative_paths(&builder.finish(), project.root());
+        fs_err::write(
+            project.lock_file_path(),
+            lock_file.render_to_string().unwrap(),
+        )
+        .unwrap();
+        assert!(fs_err::read_to_string(project.lock_file_path())
+            .unwrap()
+            .contains("conda: ./dist/foo-1.0-0.conda"));
+
+        // Loading the lock-file again must result in a satisfiable environment.
+        let lock_file = crate::load_lock_file(&project).await.unwrap();
+        match verify_lockfile_satisfiability(&project, &lock_file)
+            .await
+            .into_diagnostic()
+        {
+            Ok(()) => {}
+            Err(e) => panic!("{e:?}"),
+        }
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_example_satisfiability(#[files("examples/*/p*.toml")] manifest_path: PathBuf) {
@@ -9,6 +9,7 @@ use std::{
 };
 
 use barrier_cell::BarrierCell;
+use chrono::{DateTime, Utc};
 use fancy_display::FancyDisplay;
 use futures::{stream::FuturesUnordered, FutureExt, StreamExt, TryFutureExt, TryStreamExt};
 use indexmap::{IndexMap, IndexSet};
@@ -17,9 +18,10 @@ use itertools::{Either, Itertools};
 use miette::{Diagnostic, IntoDiagnostic, LabeledSpan, MietteDiagnostic, Report, WrapErr};
 use pixi_build_frontend::ToolContext;
 use pixi_consts::consts;
-use pixi_manifest::{EnvironmentName, FeaturesExt, HasFeaturesIter};
+use pixi_manifest::{EnvironmentName, FeaturesExt, HasFeaturesIter, SolveStrategy};
 use pixi_progress::global_multi_progress;
 use pixi_record::{ParseLockFileError, PixiRecord};
+use pixi_utils::PrefixGuard;
 use pixi_uv_conversions::{
     to_extra_name, to_marker_environment, to_normalize, to_uv_extra_name, to_uv_normalize,
     ConversionError,
@@ -38,8 +40,8 @@ use tracing::Instrument;
 use uv_normalize::ExtraName;
 
 use super::{
-    outdated::OutdatedEnvironments, utils::IoConcurrencyLimit, PixiRecordsByName,
-    PypiRecordsByName, UvResolutionContext,
+    add_locked_packages, outdated::OutdatedEnvironments, relative_paths, utils::IoConcurrencyLimit,
+    ChannelOverlays, PixiRecordsByName, PypiRecordsByName, UvResolutionContext,
 };
 use crate::{
     activation::CurrentEnvVarBehavior,
@@ -49,6 +51,7 @@ use crate::{
         LockedEnvironmentHash, PerEnvironmentAndPlatform, PerGroup, PerGroupAndPlatform,
         PythonStatus,
     },
+    generate::generate_files,
     load_lock_file,
     lock_file::{
         self,
@@ -59,9 +62,11 @@ use crate::{
     prefix::Prefix,
     project::{
         grouped_environment::{GroupedEnvironment, GroupedEnvironmentName},
+        history::{self, HistoryEntry},
         Environment, HasProjectRef,
     },
     repodata::Repodata,
+    shared_environments::{self, SharedEnvironments},
     Project,
 };
 
@@ -83,6 +88,17 @@ impl Project {
     }
 }
 
+/// The lock-file is not up-to-date with the project, and it may not be
+/// updated. Pixi exits with [`crate::cli::ExitCode::LockFileOutOfDate`] when a
+/// command fails because of it.
+#[derive(Debug, Error, Diagnostic)]
+#[error("lock-file not up-to-date with the project")]
+#[diagnostic(
+    code(pixi::lock_file_out_of_date),
+    help("run `pixi lock` to update the lock-file")
+)]
+pub struct LockFileOutOfDateError;
+
 #[derive(Debug, Error, Diagnostic)]
 enum UpdateError {
     #[error("the lockfile is not up-to-date with requested environment: '{}'", .0.fancy_display())]
@@ -153,12 +169,28 @@ pub enum UpdateMode {
 
 impl<'p> LockFileDerivedData<'p> {
     /// Write the lock-file to disk.
+    ///
+    /// If the contents of the lock-file changed, the change is recorded in the
+    /// history of the project.
     pub(crate) fn write_to_disk(&self) -> miette::Result<()> {
         let lock_file_path = self.project.lock_file_path();
-        self.lock_file
-            .to_path(&lock_file_path)
+        let hash_before = history::file_hash(&lock_file_path);
+        // Replace the lock-file atomically, so an interrupted write never leaves
+        // a truncated lock-file behind.
+        let contents = relative_paths(&self.lock_file, self.project.root())
+            .render_to_string()
             .into_diagnostic()
-            .context("failed to write lock-file to disk")
+            .context("failed to render the lock-file")?;
+        pixi_manifest::utils::write_atomic(&lock_file_path, contents)
+            .into_diagnostic()
+            .context("failed to write lock-file to disk")?;
+
+        let hash_after = history::file_hash(&lock_file_path);
+        if hash_before != hash_after {
+            self.project
+                .record_history(&HistoryEntry::lock_file(hash_before, hash_after));
+        }
+        Ok(())
     }
 
     fn locked_environment_hash(
@@ -184,15 +216,45 @@ impl<'p> LockFileDerivedData<'p> {
         // Check if the prefix is already up-to-date by validating the hash with the
         // environment file
         let hash = self.locked_environment_hash(environment)?;
+
+        // Lock the shared environment for the duration of the update, other
+        // projects that share it wait until it is installed.
+        let mut shared_guard = self.link_shared_environment(environment, &hash)?;
+        let _shared_write_guard = shared_guard
+            .as_mut()
+            .map(PrefixGuard::write)
+            .transpose()
+            .into_diagnostic()?;
+
         if update_mode == UpdateMode::QuickValidate {
             if let Some(prefix) = self.cached_prefix(environment, &hash) {
                 return prefix;
             }
         }
 
+        let hash_before = read_environment_file(&environment.dir())
+            .ok()
+            .flatten()
+            .map(|file| file.environment_lock_file_hash);
+
         // Get the up-to-date prefix
         let prefix = self.update_prefix(environment).await?;
 
+        // Render the files that are generated from the environment.
+        if let Some(locked_environment) = self.lock_file.environment(environment.name().as_str()) {
+            generate_files(environment, &prefix, locked_environment)?;
+        }
+
+        // Record the installation in the history of the project when the
+        // environment changed.
+        if hash_before.as_ref() != Some(&hash) {
+            self.project.record_history(&HistoryEntry::environment(
+                environment.name().as_str(),
+                hash_before.map(|hash| hash.as_str().to_string()),
+                Some(hash.as_str().to_string()),
+            ));
+        }
+
         // Save an environment file to the environment directory after the update.
         // Avoiding writing the cache away before the update is done.
         write_environment_file(
@@ -208,6 +270,31 @@ impl<'p> LockFileDerivedData<'p> {
         Ok(prefix)
     }
 
+    /// Links the environment to the shared environment with the same locked
+    /// packages, if shared environments are enabled. Returns the guard of the
+    /// shared environment.
+    fn link_shared_environment(
+        &self,
+        environment: &Environment<'p>,
+        hash: &LockedEnvironmentHash,
+    ) -> miette::Result<Option<PrefixGuard>> {
+        let Some(store) = SharedEnvironments::from_config(self.project.config())? else {
+            return Ok(None);
+        };
+        let locked_environment = self
+            .lock_file
+            .environment(environment.name().as_str())
+            .ok_or_else(|| UpdateError::LockFileMissingEnv(environment.name().clone()))?;
+        if !SharedEnvironments::is_shareable(locked_environment, environment.best_platform()) {
+            tracing::info!(
+                "the environment '{}' contains packages from a path, it is installed in the project instead of being shared",
+                environment.name().fancy_display()
+            );
+            return Ok(None);
+        }
+        store.link(&environment.dir(), hash).map(Some)
+    }
+
     fn cached_prefix(
         &mut self,
         environment: &Environment<'p>,
@@ -289,7 +376,9 @@ impl<'p> LockFileDerivedData<'p> {
             )
             .await?;
 
-        let non_isolated_packages = environment.pypi_options().no_build_isolation;
+        let pypi_options = environment.pypi_options();
+        let uv_context = uv_context.with_build_options(&pypi_options)?;
+        let non_isolated_packages = pypi_options.no_build_isolation;
         // Update the prefix with Pypi records
         environment::update_prefix_pypi(
             environment.name(),
@@ -378,7 +467,7 @@ impl<'p> LockFileDerivedData<'p> {
             return Ok((prefix.clone(), python_status.clone()));
         }
 
-        let prefix = Prefix::new(environment.dir());
+        let prefix = Prefix::new(shared_environments::installation_dir(&environment.dir()));
         let platform = environment.best_platform();
 
         // Determine the currently installed packages.
@@ -429,6 +518,7 @@ impl<'p> LockFileDerivedData<'p> {
             "",
             self.io_concurrency_limit.clone().into(),
             self.build_context.clone(),
+            self.project.config().run_post_link_scripts(),
         )
         .await?;
 
@@ -513,6 +603,12 @@ pub struct UpdateContext<'p> {
 
     /// Whether it is allowed to instantiate any prefix.
     no_install: bool,
+
+    /// Packages published after this moment are not used when solving.
+    exclude_newer: Option<DateTime<Utc>>,
+
+    /// The solve strategy that overrides the one of the manifest.
+    solve_strategy: Option<SolveStrategy>,
 }
 
 impl<'p> UpdateContext<'p> {
@@ -685,8 +781,7 @@ pub async fn update_lock_file(
     options: UpdateLockFileOptions,
 ) -> miette::Result<LockFileDerivedData<'_>> {
     let lock_file = load_lock_file(project).await?;
-    let package_cache =
-        PackageCache::new(pixi_config::get_cache_dir()?.join(consts::CONDA_PACKAGE_CACHE_DIR));
+    let package_cache = PackageCache::new(project.config().package_cache_dir()?);
     let glob_hash_cache = GlobHashCache::default();
 
     // should we check the lock-file in the first place?
@@ -733,7 +828,7 @@ pub async fn update_lock_file(
     // If the lock-file is out of date, but we're not allowed to update it, we
     // should exit.
     if !options.lock_file_usage.allows_lock_file_updates() {
-        miette::bail!("lock-file not up-to-date with the project");
+        return Err(LockFileOutOfDateError.into());
     }
 
     // Construct an update context and perform the actual update.
@@ -784,6 +879,12 @@ pub struct UpdateContextBuilder<'p> {
 
     /// A cache for computing input hashes
     glob_hash_cache: Option<GlobHashCache>,
+
+    /// Packages published after this moment are not used when solving.
+    exclude_newer: Option<DateTime<Utc>>,
+
+    /// The solve strategy that overrides the one of the manifest.
+    solve_strategy: Option<SolveStrategy>,
 }
 
 impl<'p> UpdateContextBuilder<'p> {
@@ -810,6 +911,25 @@ impl<'p> UpdateContextBuilder<'p> {
         Self { no_install, ..self }
     }
 
+    /// Only use packages that were published before the given moment when
+    /// solving, to reconstruct the environments of that time. Packages without
+    /// a timestamp are always used.
+    pub(crate) fn with_exclude_newer(self, exclude_newer: Option<DateTime<Utc>>) -> Self {
+        Self {
+            exclude_newer,
+            ..self
+        }
+    }
+
+    /// Solve all environments with the given strategy instead of the one of
+    /// the manifest.
+    pub(crate) fn with_solve_strategy(self, solve_strategy: Option<SolveStrategy>) -> Self {
+        Self {
+            solve_strategy,
+            ..self
+        }
+    }
+
     /// Sets the current lock-file that should be used to determine the
     /// previously locked packages.
     pub(crate) fn with_lock_file(self, lock_file: LockFile) -> Self {
@@ -842,9 +962,7 @@ impl<'p> UpdateContextBuilder<'p> {
         let project = self.project;
         let package_cache = match self.package_cache {
             Some(package_cache) => package_cache,
-            None => PackageCache::new(
-                pixi_config::get_cache_dir()?.join(consts::CONDA_PACKAGE_CACHE_DIR),
-            ),
+            None => PackageCache::new(project.config().package_cache_dir()?),
         };
         let lock_file = self.lock_file;
         let glob_hash_cache = self.glob_hash_cache.unwrap_or_default();
@@ -1046,6 +1164,8 @@ impl<'p> UpdateContextBuilder<'p> {
             glob_hash_cache,
 
             no_install: self.no_install,
+            exclude_newer: self.exclude_newer,
+            solve_strategy: self.solve_strategy,
         })
     }
 }
@@ -1062,6 +1182,8 @@ impl<'p> UpdateContext<'p> {
             max_concurrent_solves: project.config().max_concurrent_solves(),
             io_concurrency_limit: None,
             glob_hash_cache: None,
+            exclude_newer: None,
+            solve_strategy: None,
         }
     }
 
@@ -1105,13 +1227,24 @@ impl<'p> UpdateContext<'p> {
             // Determine the source of the solve information
             let source = GroupedEnvironment::from(environment.clone());
 
-            // Determine the channel priority, if no channel priority is set we use the
-            // default.
+            // Determine the channel priority, if no channel priority is set in the
+            // manifest we use the configured one or the default.
             let channel_priority = source
                 .channel_priority()
                 .into_diagnostic()?
+                .or(project.config().channel_priority())
                 .unwrap_or_default();
 
+            // Determine the solve strategy, the one that is passed explicitly
+            // overrides the one of the manifest.
+            let solve_strategy = match self.solve_strategy {
+                Some(solve_strategy) => solve_strategy,
+                None => source
+                    .solve_strategy()
+                    .into_diagnostic()?
+                    .unwrap_or_default(),
+            };
+
             for platform in ordered_platforms {
                 // Is there an existing pending task to solve the group?
                 if self
@@ -1140,6 +1273,8 @@ impl<'p> UpdateContext<'p> {
                     self.conda_solve_semaphore.clone(),
                     project.authenticated_client().clone(),
                     channel_priority,
+                    self.exclude_newer,
+                    solve_strategy,
                     self.build_context.clone(),
                 )
                 .boxed_local();
@@ -1277,7 +1412,10 @@ impl<'p> UpdateContext<'p> {
             // Get the uv context
             let uv_context = match uv_context.as_ref() {
                 None => uv_context
-                    .insert(UvResolutionContext::from_project(project)?)
+                    .insert(
+                        UvResolutionContext::from_project(project)?
+                            .with_exclude_newer(self.exclude_newer)?,
+                    )
                     .clone(),
                 Some(context) => context.clone(),
             };
@@ -1531,23 +1669,26 @@ impl<'p> UpdateContext<'p> {
             builder.set_channels(&environment_name, channels);
 
             let mut has_pypi_records = false;
-            for platform in environment.platforms() {
-                if let Some(records) = self.take_latest_repodata_records(&environment, platform) {
-                    for record in records.into_inner() {
-                        builder.add_conda_package(&environment_name, platform, record.into());
-                    }
-                }
-                if let Some(records) = self.take_latest_pypi_records(&environment, platform) {
-                    for (pkg_data, pkg_env_data) in records.into_inner() {
-                        builder.add_pypi_package(
-                            &environment_name,
-                            platform,
-                            pkg_data,
-                            pkg_env_data,
-                        );
-                        has_pypi_records = true;
-                    }
-                }
+            for platform in environment
+                .platforms()
+                .into_iter()
+                .sorted_by_key(|platform| platform.as_str())
+            {
+                let conda_records = self
+                    .take_latest_repodata_records(&environment, platform)
+                    .map(|records| records.into_inner())
+                    .unwrap_or_default();
+                let pypi_records = self
+                    .take_latest_pypi_records(&environment, platform)
+                    .map(|records| records.into_inner())
+                    .unwrap_or_default();
+                has_pypi_records |= add_locked_packages(
+                    &mut builder,
+                    &environment_name,
+                    platform,
+                    conda_records,
+                    pypi_records,
+                );
             }
 
             // Store the indexes that were used to solve the environment. But only if there
@@ -1682,6 +1823,8 @@ async fn spawn_solve_conda_environment_task(
     concurrency_semaphore: Arc<Semaphore>,
     client: ClientWithMiddleware,
     channel_priority: ChannelPriority,
+    exclude_newer: Option<DateTime<Utc>>,
+    solve_strategy: SolveStrategy,
     build_context: BuildContext,
 ) -> miette::Result<TaskResult> {
     // Get the dependencies for this platform
@@ -1701,18 +1844,31 @@ async fn spawn_solve_conda_environment_task(
 
     // Whether we should use custom mapping location
     let pypi_name_mapping_location = group.project().pypi_name_mapping_source()?.clone();
+    let pypi_name_overrides = group
+        .project()
+        .manifest()
+        .workspace
+        .pypi_name_mapping
+        .clone();
 
     // Get the channel configuration
     let channel_config = group.project().channel_config();
 
+    // Determine which packages are shadowed by overlay channels
+    let channel_overlays = ChannelOverlays::new(
+        &channels,
+        &group
+            .project()
+            .manifest()
+            .workspace
+            .workspace
+            .channel_overlays,
+        &channel_config,
+    )
+    .into_diagnostic()?;
+
     tokio::spawn(
         async move {
-            // Acquire a permit before we are allowed to solve the environment.
-            let _permit = concurrency_semaphore
-                .acquire()
-                .await
-                .expect("the semaphore is never closed");
-
             let pb = Arc::new(SolveProgressBar::new(
                 global_multi_progress().add(ProgressBar::hidden()),
                 platform,
@@ -1745,6 +1901,23 @@ async fn spawn_solve_conda_environment_task(
                 .collect::<Result<Vec<_>, _>>()
                 .into_diagnostic()?;
 
+            // A dependency that requests a specific channel can only be taken from
+            // one of the channels of the environment.
+            for spec in &match_specs {
+                let Some(channel) = &spec.channel else {
+                    continue;
+                };
+                if !channel_urls.contains(&channel.base_url) {
+                    miette::bail!(
+                        help = "add the channel to the `channels` of the project or of a feature of the environment",
+                        "the dependency '{}' requests the channel '{}', which is not one of the channels of {}",
+                        spec.name.as_ref().map_or("", |name| name.as_source()),
+                        channel.canonical_name(),
+                        group_name.fancy_display()
+                    );
+                }
+            }
+
             let mut metadata_progress = None;
             let mut source_match_specs = Vec::new();
             let source_futures = FuturesUnordered::new();
@@ -1819,6 +1992,7 @@ async fn spawn_solve_conda_environment_task(
                 "fetched {total_records} records in {:?}",
                 fetch_repodata_start.elapsed()
             );
+            channel_overlays.warn_outdated(&available_packages);
 
             // Solve conda packages
             pb.reset_style();
@@ -1847,6 +2021,14 @@ async fn spawn_solve_conda_environment_task(
                 })
                 .collect();
 
+            // Acquire a permit before we are allowed to solve the environment. The
+            // repodata is fetched through the shared gateway without a permit, so the
+            // records of all environments and platforms are loaded concurrently and
+            // only the solves themselves are limited.
+            let permit = concurrency_semaphore
+                .acquire()
+                .await
+                .expect("the semaphore is never closed");
             let mut records = lock_file::resolve_conda(
                 all_specs,
                 virtual_packages,
@@ -1854,6 +2036,9 @@ async fn spawn_solve_conda_environment_task(
                 available_packages,
                 source_repodata,
                 channel_priority,
+                channel_overlays,
+                exclude_newer,
+                solve_strategy,
             )
             .await
             .with_context(|| {
@@ -1863,6 +2048,7 @@ async fn spawn_solve_conda_environment_task(
                     consts::PLATFORM_STYLE.apply_to(platform)
                 )
             })?;
+            drop(permit);
 
             // Add purl's for the conda packages that are also available as pypi packages if
             // we need them.
@@ -1875,6 +2061,12 @@ async fn spawn_solve_conda_environment_task(
                     Some(pb.purl_amend_reporter()),
                 )
                 .await?;
+                pypi_mapping::override_pypi_purls(
+                    pypi_name_overrides
+                        .iter()
+                        .map(|(name, pypi_name)| (name, pypi_name.as_normalized())),
+                    records.iter_mut().filter_map(PixiRecord::as_binary_mut),
+                );
             }
 
             // Turn the records into a map by name
@@ -2128,6 +2320,18 @@ async fn spawn_solve_pypi_task(
         None,
     )
     .await?;
+    pypi_mapping::override_pypi_purls(
+        environment
+            .project()
+            .manifest()
+            .workspace
+            .pypi_name_mapping
+            .iter()
+            .map(|(name, pypi_name)| (name, pypi_name.as_normalized())),
+        pixi_records
+            .iter_mut()
+            .filter_map(PixiRecord::as_binary_mut),
+    );
 
     let pypi_options = environment.pypi_options();
     // let (pypi_packages, duration) = tokio::spawn(
@@ -2215,6 +2419,7 @@ async fn spawn_create_prefix_task(
     let group_name = group.name().clone();
     let prefix = group.prefix();
     let client = group.project().authenticated_client().clone();
+    let run_post_link_scripts = group.project().config().run_post_link_scripts();
     let channels = group
         .channel_urls(&group.project().channel_config())
         .into_diagnostic()?;
@@ -2264,6 +2469,7 @@ async fn spawn_create_prefix_task(
                 "  ",
                 io_concurrency_limit.into(),
                 build_context,
+                run_post_link_scripts,
             )
             .await?;
             let end = Instant::now();
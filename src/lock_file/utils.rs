@@ -1,10 +1,21 @@
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use itertools::Itertools;
 use pixi_manifest::FeaturesExt;
+use pixi_record::PixiRecord;
 use rattler_conda_types::Platform;
-use rattler_lock::{LockFile, LockFileBuilder, LockedPackageRef};
+use rattler_digest::Sha256;
+use rattler_lock::{
+    CondaPackageData, LockFile, LockFileBuilder, LockedPackage, LockedPackageRef, UrlOrPath,
+};
 use tokio::sync::Semaphore;
+use typed_path::Utf8TypedPathBuf;
+use url::Url;
 
+use super::PypiRecord;
 use crate::{
     project::{grouped_environment::GroupedEnvironment, Environment},
     Project,
@@ -68,3 +79,346 @@ pub fn filter_lock_file<
 
     builder.finish()
 }
+
+/// Constructs a new lock-file where the environment `from` is copied to `to`.
+/// If `rename` is set the environment `from` is removed.
+pub(crate) fn copy_lock_file_environment(
+    lock_file: &LockFile,
+    from: &str,
+    to: &str,
+    rename: bool,
+) -> LockFile {
+    let mut builder = LockFileBuilder::new();
+
+    for (environment_name, environment) in lock_file.environments() {
+        let names = match environment_name {
+            name if name == from && rename => vec![to],
+            name if name == from => vec![from, to],
+            name => vec![name],
+        };
+
+        for name in names {
+            builder.set_channels(name, environment.channels().to_vec());
+            if let Some(indexes) = environment.pypi_indexes() {
+                builder.set_pypi_indexes(name, indexes.clone());
+            }
+            for (platform, packages) in environment.packages_by_platform() {
+                for package in packages {
+                    builder.add_package(name, platform, package.into());
+                }
+            }
+        }
+    }
+
+    builder.finish()
+}
+
+/// Adds the locked packages of an environment for a single platform to the
+/// builder.
+///
+/// The packages are added in a canonical order, independent of the order in
+/// which they were solved, such that the lock-file only changes where the
+/// locked packages change. Returns `true` if any pypi packages were added.
+pub(crate) fn add_locked_packages(
+    builder: &mut LockFileBuilder,
+    environment: &str,
+    platform: Platform,
+    conda_records: impl IntoIterator<Item = PixiRecord>,
+    pypi_records: impl IntoIterator<Item = PypiRecord>,
+) -> bool {
+    let conda_records = conda_records.into_iter().sorted_by_cached_key(|record| {
+        let location = match record {
+            PixiRecord::Binary(record) => record.url.to_string(),
+            PixiRecord::Source(record) => record.source.to_string(),
+        };
+        (record.package_record().name.clone(), location)
+    });
+    for record in conda_records {
+        builder.add_conda_package(environment, platform, locked_conda_package(record));
+    }
+
+    let mut has_pypi_records = false;
+    let pypi_records = pypi_records
+        .into_iter()
+        .sorted_by_cached_key(|(pkg_data, _)| {
+            (pkg_data.name.clone(), pkg_data.location.to_string())
+        });
+    for (pkg_data, pkg_env_data) in pypi_records {
+        builder.add_pypi_package(environment, platform, pkg_data, pkg_env_data);
+        has_pypi_records = true;
+    }
+    has_pypi_records
+}
+
+/// The prefix of paths in the lock-file that are relative to the project root.
+const RELATIVE_BASE: &str = "./";
+
+/// Converts a conda record into its lock-file representation.
+///
+/// The hash of local binary packages is recorded so that a modified archive is
+/// detected.
+fn locked_conda_package(record: PixiRecord) -> CondaPackageData {
+    let mut package = CondaPackageData::from(record);
+    if let CondaPackageData::Binary(binary) = &mut package {
+        if binary.package_record.sha256.is_none() {
+            if let Some(path) = local_package_path(&binary.location) {
+                binary.package_record.sha256 =
+                    rattler_digest::compute_file_digest::<Sha256>(&path).ok();
+            }
+        }
+    }
+    package
+}
+
+/// Returns the path of a binary package that is stored on the local
+/// filesystem.
+fn local_package_path(location: &UrlOrPath) -> Option<PathBuf> {
+    match location {
+        UrlOrPath::Url(url) if url.scheme() == "file" => url.to_file_path().ok(),
+        _ => None,
+    }
+}
+
+/// Constructs a new lock-file where `f` is applied to every conda package.
+fn map_conda_packages(
+    lock_file: &LockFile,
+    mut f: impl FnMut(CondaPackageData) -> CondaPackageData,
+) -> LockFile {
+    let mut builder = LockFileBuilder::new();
+
+    for (environment_name, environment) in lock_file.environments() {
+        builder.set_channels(environment_name, environment.channels().to_vec());
+        if let Some(indexes) = environment.pypi_indexes() {
+            builder.set_pypi_indexes(environment_name, indexes.clone());
+        }
+        for (platform, packages) in environment.packages_by_platform() {
+            for package in packages {
+                let package = match package {
+                    LockedPackageRef::Conda(conda) => LockedPackage::Conda(f(conda.clone())),
+                    package => package.into(),
+                };
+                builder.add_package(environment_name, platform, package);
+            }
+        }
+    }
+
+    builder.finish()
+}
+
+/// Returns the lock-file as it is stored on disk.
+///
+/// Local binary packages inside the project are stored with a path relative to
+/// the project root, such that the lock-file stays valid when the project is
+/// moved or checked out on another machine. This is the inverse of
+/// [`resolve_relative_paths`].
+pub(crate) fn relative_paths(lock_file: &LockFile, project_root: &Path) -> LockFile {
+    map_conda_packages(lock_file, |mut package| {
+        if let CondaPackageData::Binary(binary) = &mut package {
+            let relative = local_package_path(&binary.location)
+                .and_then(|path| path.strip_prefix(project_root).ok().map(Path::to_path_buf));
+            if let Some(relative) = relative {
+                // Always use forward slashes so the lock-file is the same on every platform.
+                let relative = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .join("/");
+                binary.location =
+                    UrlOrPath::Path(Utf8TypedPathBuf::from(format!("{RELATIVE_BASE}{relative}")));
+            }
+        }
+        package
+    })
+}
+
+/// Resolves the paths of local binary packages that are stored relative to
+/// the project root, see [`relative_paths`].
+///
+/// This is applied when the lock-file is loaded, such that all code that reads
+/// the lock-file only ever sees absolute locations.
+pub(crate) fn resolve_relative_paths(lock_file: &LockFile, project_root: &Path) -> LockFile {
+    map_conda_packages(lock_file, |mut package| {
+        if let CondaPackageData::Binary(binary) = &mut package {
+            if let UrlOrPath::Path(path) = &binary.location {
+                let native_path = Path::new(path.as_str());
+                if !native_path.is_absolute() {
+                    if let Ok(url) = Url::from_file_path(project_root.join(native_path)) {
+                        binary.location = UrlOrPath::Url(url);
+                    }
+                }
+            }
+        }
+        package
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, str::FromStr};
+
+    use rattler_conda_types::{PackageName, PackageRecord, RepoDataRecord, Version};
+    use url::Url;
+
+    use super::*;
+
+    fn record(name: &str, version: &str, platform: Platform) -> PixiRecord {
+        let mut package_record = PackageRecord::new(
+            PackageName::from_str(name).unwrap(),
+            Version::from_str(version).unwrap(),
+            "0".to_string(),
+        );
+        package_record.subdir = platform.to_string();
+        let file_name = format!("{name}-{version}-0.conda");
+        PixiRecord::Binary(RepoDataRecord {
+            package_record,
+            url: Url::parse(&format!("https://example.com/{platform}/{file_name}")).unwrap(),
+            file_name,
+            channel: None,
+        })
+    }
+
+    /// Renders a lock-file that contains the given packages for each platform,
+    /// adding the platforms and packages in the given order.
+    fn render(packages: &[(Platform, Vec<(&str, &str)>)]) -> String {
+        let mut builder = LockFile::builder();
+        builder.set_channels("default", vec!["https://example.com/".to_string()]);
+        for (platform, records) in packages {
+            add_locked_packages(
+                &mut builder,
+                "default",
+                *platform,
+                records
+                    .iter()
+                    .map(|(name, version)| record(name, version, *platform)),
+                Vec::new(),
+            );
+        }
+        builder.finish().render_to_string().unwrap()
+    }
+
+    /// Returns the lines that are only present in `a` and not in `b`.
+    fn removed_lines<'a>(a: &'a str, b: &str) -> Vec<&'a str> {
+        let mut remaining: HashMap<&str, usize> = b.lines().counts();
+        a.lines()
+            .filter(|line| match remaining.get_mut(line) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lock_file_order_is_canonical() {
+        let lock_file = render(&[
+            (Platform::Linux64, vec![("foo", "1.0"), ("bar", "1.0")]),
+            (Platform::Osx64, vec![("foo", "1.0"), ("baz", "1.0")]),
+        ]);
+        let reordered = render(&[
+            (Platform::Osx64, vec![("baz", "1.0"), ("foo", "1.0")]),
+            (Platform::Linux64, vec![("bar", "1.0"), ("foo", "1.0")]),
+        ]);
+        assert_eq!(lock_file, reordered);
+    }
+
+    #[test]
+    fn test_lock_file_diff_is_minimal() {
+        let before = render(&[
+            (Platform::Linux64, vec![("bar", "1.0"), ("foo", "1.0")]),
+            (Platform::Osx64, vec![("bar", "1.0"), ("foo", "1.0")]),
+        ]);
+        let after = render(&[
+            (Platform::Osx64, vec![("foo", "1.0"), ("bar", "1.0")]),
+            (Platform::Linux64, vec![("foo", "1.0"), ("bar", "2.0")]),
+        ]);
+
+        // Only the lines that describe the updated package should change.
+        let removed = removed_lines(&before, &after);
+        let added = removed_lines(&after, &before);
+        assert!(!removed.is_empty());
+        assert!(
+            removed.iter().all(|line| line.contains("1.0")),
+            "unexpected removed lines: {removed:#?}"
+        );
+        assert!(
+            added.iter().all(|line| line.contains("2.0")),
+            "unexpected added lines: {added:#?}"
+        );
+
+        // The packages of the unrelated platform should not be reordered.
+        let osx_lines = |lock_file: &str| {
+            lock_file
+                .lines()
+                .filter(|line| line.contains("osx-64"))
+                .map(str::to_owned)
+                .collect_vec()
+        };
+        assert_eq!(osx_lines(&before), osx_lines(&after));
+    }
+
+    #[test]
+    fn test_copy_lock_file_environment() {
+        let lock_file =
+            LockFile::from_str(&render(&[(Platform::Linux64, vec![("foo", "1.0")])])).unwrap();
+
+        let copied = copy_lock_file_environment(&lock_file, "default", "other", false);
+        assert!(copied.environment("default").is_some());
+        let other = copied.environment("other").unwrap();
+        assert_eq!(
+            other.channels(),
+            lock_file.default_environment().unwrap().channels()
+        );
+        assert_eq!(other.packages(Platform::Linux64).unwrap().count(), 1);
+
+        let renamed = copy_lock_file_environment(&lock_file, "default", "other", true);
+        assert!(renamed.environment("default").is_none());
+        assert!(renamed.environment("other").is_some());
+    }
+
+    #[test]
+    fn test_local_package_is_locked_relative_to_project() {
+        let project_root = tempfile::tempdir().unwrap();
+        let archive = project_root.path().join("dist").join("foo-1.0-0.conda");
+        fs_err::create_dir_all(archive.parent().unwrap()).unwrap();
+        fs_err::write(&archive, b"not really a package").unwrap();
+
+        let mut local_record = record("foo", "1.0", Platform::Linux64);
+        if let PixiRecord::Binary(record) = &mut local_record {
+            record.url = Url::from_file_path(&archive).unwrap();
+        }
+
+        let mut builder = LockFile::builder();
+        builder.set_channels("default", vec!["https://example.com/".to_string()]);
+        add_locked_packages(
+            &mut builder,
+            "default",
+            Platform::Linux64,
+            [local_record],
+            Vec::new(),
+        );
+        let lock_file = builder.finish();
+
+        let on_disk = relative_paths(&lock_file, project_root.path());
+        let package = on_disk
+            .default_environment()
+            .and_then(|env| env.conda_packages(Platform::Linux64)?.next().cloned())
+            .unwrap();
+        let CondaPackageData::Binary(binary) = &package else {
+            panic!("expected a binary package");
+        };
+        assert_eq!(binary.location.to_string(), "./dist/foo-1.0-0.conda");
+        assert!(binary.package_record.sha256.is_some());
+
+        let loaded = LockFile::from_str(&on_disk.render_to_string().unwrap()).unwrap();
+        let resolved = resolve_relative_paths(&loaded, project_root.path());
+        let package = resolved
+            .default_environment()
+            .and_then(|env| env.conda_packages(Platform::Linux64)?.next().cloned())
+            .unwrap();
+        assert_eq!(
+            package.location(),
+            &UrlOrPath::Url(Url::from_file_path(&archive).unwrap())
+        );
+    }
+}
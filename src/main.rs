@@ -1,7 +1,7 @@
 #[tokio::main]
 pub async fn main() {
     if let Err(err) = pixi::cli::execute().await {
-        eprintln!("{err:?}");
-        std::process::exit(1);
+        pixi::cli::report_error(&err);
+        std::process::exit(pixi::cli::ExitCode::from_error(&err) as i32);
     }
 }
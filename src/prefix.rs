@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ffi::OsStr,
     path::{Path, PathBuf},
 };
@@ -8,13 +8,31 @@ use futures::{stream::FuturesUnordered, StreamExt};
 use itertools::Itertools;
 use miette::{Context, IntoDiagnostic};
 use pixi_utils::{is_binary_folder, strip_executable_extension};
-use rattler_conda_types::{PackageName, Platform, PrefixRecord};
+use rattler_conda_types::{prefix_record::PathType, PackageName, Platform, PrefixRecord};
 use rattler_shell::{
     activation::{ActivationVariables, Activator},
     shell::ShellEnum,
 };
 use tokio::task::JoinHandle;
 
+/// Returns the python entry points that were created when the package was
+/// linked, according to its `paths.json`. These are executables regardless of
+/// the folder they are installed in.
+fn entry_points(record: &PrefixRecord) -> HashSet<&Path> {
+    record
+        .paths_data
+        .paths
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry.path_type,
+                PathType::UnixPythonEntryPoint | PathType::WindowsPythonEntryPointExe
+            )
+        })
+        .map(|entry| entry.relative_path.as_path())
+        .collect()
+}
+
 /// Points to a directory that serves as a Conda prefix.
 #[derive(Debug, Clone)]
 pub struct Prefix {
@@ -112,13 +130,32 @@ impl Prefix {
     /// Processes prefix records (that you can get by using `find_installed_packages`)
     /// to filter and collect executable files.
     pub fn find_executables(&self, prefix_packages: &[PrefixRecord]) -> Vec<Executable> {
+        self.find_executables_in(prefix_packages, &[])
+    }
+
+    /// Like [`Self::find_executables`], but also collects the executable files
+    /// below the given paths relative to the prefix. This is used for packages
+    /// that install their executables outside of the binary folders.
+    pub fn find_executables_in(
+        &self,
+        prefix_packages: &[PrefixRecord],
+        extra_paths: &[PathBuf],
+    ) -> Vec<Executable> {
         let executables = prefix_packages
             .iter()
             .flat_map(|record| {
+                let entry_points = entry_points(record);
                 record
                     .files
                     .iter()
-                    .filter(|relative_path| self.is_executable(relative_path))
+                    .filter(move |relative_path| {
+                        self.is_executable(relative_path)
+                            || ((entry_points.contains(relative_path.as_path())
+                                || extra_paths
+                                    .iter()
+                                    .any(|extra_path| relative_path.starts_with(extra_path)))
+                                && is_executable::is_executable(self.root().join(relative_path)))
+                    })
                     .filter_map(|path| {
                         path.iter().last().and_then(OsStr::to_str).map(|name| {
                             Executable::new(
@@ -0,0 +1,603 @@
+//! Transactions of the changes to the conda packages of a prefix.
+//!
+//! Before the packages of a prefix are changed, the packages that are removed
+//! or replaced are backed up and an [`InstallJournal`] is written to the
+//! `conda-meta` directory. When the installation fails the prefix is rolled
+//! back to the backup. When it is interrupted, the journal is still there on
+//! the next installation, which either continues the installation or rolls it
+//! back if the packages changed since.
+
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use rattler::{install::PythonInfo, package_cache::PackageCache};
+use rattler_conda_types::{
+    package::PathsJson, PackageRecord, Platform, PrefixRecord, RepoDataRecord,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prefix::Prefix;
+
+/// A journal of an installation that is in progress.
+///
+/// The journal is written before the packages of a prefix are installed and
+/// removed once the installation finished. Every package that has been linked
+/// has a record in the `conda-meta` folder, so if the journal still exists on
+/// the next installation, that installation was interrupted and only the
+/// remaining packages have to be installed.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct InstallJournal {
+    /// The packages that are being installed, sorted by file name.
+    pub(crate) packages: Vec<RepoDataRecord>,
+}
+
+impl InstallJournal {
+    /// Creates a journal for installing the given records.
+    pub(crate) fn new(records: &[RepoDataRecord]) -> Self {
+        Self {
+            packages: records
+                .iter()
+                .sorted_by(|a, b| a.file_name.cmp(&b.file_name))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns the number of packages of the journal that are already
+    /// installed in the prefix.
+    pub(crate) fn installed_count(&self, installed_packages: &[PrefixRecord]) -> usize {
+        installed_packages
+            .iter()
+            .filter(|record| self.contains(&record.repodata_record))
+            .count()
+    }
+
+    /// Returns true if the journal installs the same package, the installer
+    /// leaves such a package untouched.
+    fn contains(&self, record: &RepoDataRecord) -> bool {
+        self.packages
+            .binary_search_by(|package| package.file_name.cmp(&record.file_name))
+            .is_ok_and(|idx| {
+                same_content(&self.packages[idx].package_record, &record.package_record)
+            })
+    }
+}
+
+/// Returns true if the records describe the same package, by their hashes if
+/// both have them.
+fn same_content(a: &PackageRecord, b: &PackageRecord) -> bool {
+    match (a.sha256, b.sha256) {
+        (Some(a), Some(b)) => a == b,
+        _ => match (a.md5, b.md5) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.name == b.name && a.version == b.version && a.build == b.build,
+        },
+    }
+}
+
+/// The path to the installation journal in the `conda-meta` directory of the
+/// environment.
+fn install_journal_path(environment_dir: &Path) -> PathBuf {
+    environment_dir
+        .join(consts::CONDA_META_DIR)
+        .join(consts::INSTALL_JOURNAL_FILE_NAME)
+}
+
+/// Reads the journal of an interrupted installation, if there is one.
+pub(crate) fn read_install_journal(environment_dir: &Path) -> Option<InstallJournal> {
+    let contents = fs_err::read_to_string(install_journal_path(environment_dir)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Writes the journal before the installation starts.
+pub(crate) fn write_install_journal(
+    environment_dir: &Path,
+    journal: &InstallJournal,
+) -> miette::Result<()> {
+    let path = install_journal_path(environment_dir);
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent).into_diagnostic()?;
+    }
+    let contents = serde_json::to_string(journal).into_diagnostic()?;
+    pixi_manifest::utils::write_atomic(&path, contents).into_diagnostic()
+}
+
+/// Removes the journal once the installation finished.
+pub(crate) fn remove_install_journal(environment_dir: &Path) -> miette::Result<()> {
+    remove_file_if_exists(&install_journal_path(environment_dir)).into_diagnostic()
+}
+
+/// The directory in the `conda-meta` directory of the environment that holds
+/// the backup of an installation.
+pub(crate) fn install_backup_dir(environment_dir: &Path) -> PathBuf {
+    environment_dir
+        .join(consts::CONDA_META_DIR)
+        .join(consts::INSTALL_BACKUP_DIR_NAME)
+}
+
+/// Backs up the installed packages that the installation of the journal
+/// removes or replaces.
+pub(crate) fn backup_replaced_packages(
+    environment_dir: &Path,
+    journal: &InstallJournal,
+    installed_packages: &[PrefixRecord],
+) -> miette::Result<()> {
+    // Noarch python packages are linked again when python is replaced.
+    let python_replaced = installed_packages.iter().any(|record| {
+        record.repodata_record.package_record.name.as_normalized() == "python"
+            && !journal.contains(&record.repodata_record)
+    });
+    let replaced = installed_packages
+        .iter()
+        .filter(|record| {
+            !journal.contains(&record.repodata_record)
+                || (python_replaced && record.repodata_record.package_record.noarch.is_python())
+        })
+        .collect_vec();
+    backup_packages(
+        environment_dir,
+        &install_backup_dir(environment_dir),
+        installed_packages,
+        &replaced,
+    )
+}
+
+/// Removes the files of the packages of the journal that have no record in the
+/// prefix. The installer writes the record of a package after all its files
+/// are linked, so these packages were partially linked when the installation
+/// stopped. Their files are looked up in the package cache, a package that is
+/// not in the cache was never linked.
+pub(crate) async fn remove_partially_linked_packages(
+    prefix: &Prefix,
+    journal: &InstallJournal,
+    installed_packages: &[PrefixRecord],
+    package_cache: &PackageCache,
+    platform: Platform,
+) -> miette::Result<()> {
+    let installed = installed_packages
+        .iter()
+        .map(|record| record.repodata_record.file_name.as_str())
+        .collect::<HashSet<_>>();
+    // Files of installed packages are kept, even if a package of the journal
+    // would have replaced them.
+    let installed_files = installed_packages
+        .iter()
+        .flat_map(|record| record.files.iter().map(PathBuf::as_path))
+        .collect::<HashSet<_>>();
+
+    // The files of noarch python packages are linked into the site-packages of
+    // the python that is installed.
+    let python_info = journal
+        .packages
+        .iter()
+        .map(|record| &record.package_record)
+        .chain(
+            installed_packages
+                .iter()
+                .map(|record| &record.repodata_record.package_record),
+        )
+        .find(|record| record.name.as_normalized() == "python")
+        .map(|record| PythonInfo::from_python_record(record, platform))
+        .transpose()
+        .into_diagnostic()?;
+
+    for record in journal
+        .packages
+        .iter()
+        .filter(|record| !installed.contains(record.file_name.as_str()))
+    {
+        let Ok(cache_lock) = package_cache
+            .get_or_fetch(
+                &record.package_record,
+                |_| async { Err(std::io::Error::from(ErrorKind::NotFound)) },
+                None,
+            )
+            .await
+        else {
+            continue;
+        };
+        let paths = PathsJson::from_package_directory_with_deprecated_fallback(cache_lock.path())
+            .into_diagnostic()?;
+
+        tracing::debug!(
+            "removing the files of the partially linked package '{}'",
+            record.file_name
+        );
+        for entry in &paths.paths {
+            let relative_path = match &python_info {
+                Some(python_info) if record.package_record.noarch.is_python() => {
+                    python_info.get_python_noarch_target_path(&entry.relative_path)
+                }
+                _ => Cow::Borrowed(entry.relative_path.as_path()),
+            };
+            if installed_files.contains(relative_path.as_ref()) {
+                continue;
+            }
+            remove_file_if_exists(&prefix.root().join(relative_path)).into_diagnostic()?;
+        }
+    }
+    Ok(())
+}
+
+/// Rolls the prefix back to the state before the installation of the journal
+/// and removes the journal.
+pub(crate) async fn roll_back_installation(
+    prefix: &Prefix,
+    journal: &InstallJournal,
+    package_cache: &PackageCache,
+    platform: Platform,
+) -> miette::Result<()> {
+    tracing::info!(
+        "rolling back the installation of '{}'",
+        prefix.root().display()
+    );
+    let installed_packages = prefix.find_installed_packages(None).await?;
+    remove_partially_linked_packages(
+        prefix,
+        journal,
+        &installed_packages,
+        package_cache,
+        platform,
+    )
+    .await?;
+    restore_backup(prefix.root(), &install_backup_dir(prefix.root())).await?;
+    remove_install_journal(prefix.root())
+}
+
+/// The journal of a backup, stored in the backup directory.
+///
+/// The files and `conda-meta` records of the packages that are removed or
+/// replaced are copied to the backup directory before the prefix is changed.
+/// Restoring the backup brings the prefix back to the packages that were
+/// installed before.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct BackupJournal {
+    /// The file names of the packages that were installed before the change.
+    installed: Vec<String>,
+    /// The file names of the packages that are backed up.
+    packages: Vec<String>,
+    /// The backed up paths, relative to the root of the prefix.
+    paths: Vec<PathBuf>,
+}
+
+/// The path to the journal in the backup directory.
+pub(crate) fn backup_journal_path(backup_dir: &Path) -> PathBuf {
+    backup_dir.join("journal.json")
+}
+
+/// Copies the files and the `conda-meta` records of the packages to the
+/// backup directory and writes the journal of the backup.
+pub(crate) fn backup_packages(
+    environment_dir: &Path,
+    backup_dir: &Path,
+    installed_packages: &[PrefixRecord],
+    records: &[&PrefixRecord],
+) -> miette::Result<()> {
+    remove_backup(backup_dir)?;
+
+    let mut journal = BackupJournal {
+        installed: installed_packages
+            .iter()
+            .map(|record| record.repodata_record.file_name.clone())
+            .collect(),
+        ..BackupJournal::default()
+    };
+    for record in records {
+        journal
+            .packages
+            .push(record.repodata_record.file_name.clone());
+
+        let record_path = Path::new(consts::CONDA_META_DIR).join(record.file_name());
+        for path in record.files.iter().chain([&record_path]) {
+            let source = environment_dir.join(path);
+            // Missing files can't be restored.
+            if fs_err::symlink_metadata(&source).is_err() {
+                continue;
+            }
+            copy_path(&source, &backup_dir.join("files").join(path))
+                .into_diagnostic()
+                .with_context(|| format!("failed to back up '{}'", source.display()))?;
+            journal.paths.push(path.clone());
+        }
+    }
+
+    // The journal is written last, a backup without a journal is incomplete and
+    // is never restored.
+    fs_err::create_dir_all(backup_dir).into_diagnostic()?;
+    let contents = serde_json::to_string(&journal).into_diagnostic()?;
+    pixi_manifest::utils::write_atomic(backup_journal_path(backup_dir), contents).into_diagnostic()
+}
+
+/// Restores the backup, if there is one. The packages that were installed
+/// since the backup was made and the backed up packages are removed, and the
+/// backed up files and records are copied back to the prefix.
+pub(crate) async fn restore_backup(
+    environment_dir: &Path,
+    backup_dir: &Path,
+) -> miette::Result<()> {
+    let Some(journal) = fs_err::read_to_string(backup_journal_path(backup_dir))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<BackupJournal>(&contents).ok())
+    else {
+        return remove_backup(backup_dir);
+    };
+
+    tracing::info!(
+        "restoring the backup of the packages in '{}'",
+        environment_dir.display()
+    );
+
+    for record in Prefix::new(environment_dir)
+        .find_installed_packages(None)
+        .await?
+    {
+        let file_name = &record.repodata_record.file_name;
+        if journal.installed.contains(file_name) && !journal.packages.contains(file_name) {
+            continue;
+        }
+        for path in &record.files {
+            remove_file_if_exists(&environment_dir.join(path)).into_diagnostic()?;
+        }
+        let record_path = environment_dir
+            .join(consts::CONDA_META_DIR)
+            .join(record.file_name());
+        fs_err::remove_file(&record_path).into_diagnostic()?;
+    }
+
+    for path in &journal.paths {
+        let target = environment_dir.join(path);
+        copy_path(&backup_dir.join("files").join(path), &target)
+            .into_diagnostic()
+            .with_context(|| format!("failed to restore '{}'", target.display()))?;
+    }
+
+    remove_backup(backup_dir)
+}
+
+/// Removes the backup directory.
+pub(crate) fn remove_backup(backup_dir: &Path) -> miette::Result<()> {
+    match fs_err::remove_dir_all(backup_dir) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e).into_diagnostic(),
+        _ => Ok(()),
+    }
+}
+
+/// Finishes a change of the prefix that was backed up with the result of the
+/// change. The backup is discarded when the change succeeded and restored
+/// when it failed, the result is returned either way.
+pub(crate) async fn finish_backup<T>(
+    environment_dir: &Path,
+    backup_dir: &Path,
+    result: miette::Result<T>,
+) -> miette::Result<T> {
+    match result {
+        Ok(value) => {
+            remove_backup(backup_dir)?;
+            Ok(value)
+        }
+        Err(err) => {
+            restore_backup(environment_dir, backup_dir)
+                .await
+                .context("failed to roll back the changes to the environment")?;
+            Err(err)
+        }
+    }
+}
+
+/// Removes a file, a file that doesn't exist is not an error.
+fn remove_file_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs_err::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Copies a file to the target, replacing the file at the target instead of
+/// writing through it, because installed files are hard linked to the package
+/// cache. Symlinks are copied as symlinks.
+fn copy_path(source: &Path, target: &Path) -> std::io::Result<()> {
+    if let Some(parent) = target.parent() {
+        fs_err::create_dir_all(parent)?;
+    }
+    remove_file_if_exists(target)?;
+
+    #[cfg(unix)]
+    if fs_err::symlink_metadata(source)?.file_type().is_symlink() {
+        return fs_err::os::unix::fs::symlink(fs_err::read_link(source)?, target);
+    }
+    fs_err::copy(source, target).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use rattler_conda_types::{PackageRecord, VersionWithSource};
+    use std::str::FromStr;
+    use url::Url;
+
+    use super::*;
+
+    /// Writes the `conda-meta` record of a package with the given files to the
+    /// prefix.
+    fn write_record(prefix: &Path, name: &str, version: &str, files: &[&str]) -> PrefixRecord {
+        let package_record = PackageRecord::new(
+            name.parse().unwrap(),
+            VersionWithSource::from_str(version).unwrap(),
+            "0".to_string(),
+        );
+        let repodata_record = RepoDataRecord {
+            package_record,
+            file_name: format!("{name}-{version}-0.conda"),
+            url: Url::from_str("https://doesnt_matter").unwrap(),
+            channel: None,
+        };
+        let record = PrefixRecord::from_repodata_record(
+            repodata_record,
+            None,
+            None,
+            files.iter().map(PathBuf::from).collect(),
+            Default::default(),
+            None,
+        );
+        let conda_meta = prefix.join(consts::CONDA_META_DIR);
+        fs_err::create_dir_all(&conda_meta).unwrap();
+        fs_err::write(
+            conda_meta.join(record.file_name()),
+            serde_json::to_string(&record).unwrap(),
+        )
+        .unwrap();
+        record
+    }
+
+    #[tokio::test]
+    async fn test_backup_restored_on_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let prefix = temp_dir.path().join("env");
+        let cache = temp_dir.path().join("cache");
+        fs_err::create_dir_all(prefix.join("bin")).unwrap();
+        fs_err::create_dir_all(&cache).unwrap();
+        fs_err::write(prefix.join("bin/tool"), "broken").unwrap();
+        let record = write_record(&prefix, "tool", "1.0", &["bin/tool"]);
+        let record_path = prefix.join(consts::CONDA_META_DIR).join(record.file_name());
+
+        // Back up and remove the package like a repair does.
+        let backup_dir = prefix.join("backup");
+        backup_packages(&prefix, &backup_dir, &[record.clone()], &[&record]).unwrap();
+        fs_err::remove_file(&record_path).unwrap();
+
+        // The reinstallation links another version from the package cache and
+        // then fails.
+        fs_err::write(cache.join("tool"), "reinstalled").unwrap();
+        fs_err::remove_file(prefix.join("bin/tool")).unwrap();
+        fs_err::hard_link(cache.join("tool"), prefix.join("bin/tool")).unwrap();
+        let reinstalled = write_record(&prefix, "tool", "2.0", &["bin/tool"]);
+        let err = finish_backup::<()>(
+            &prefix,
+            &backup_dir,
+            Err(miette::miette!("injected failure")),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "injected failure");
+
+        // The prefix is back in the state before the repair, without writing
+        // through the hard link to the package cache.
+        assert_eq!(
+            fs_err::read_to_string(prefix.join("bin/tool")).unwrap(),
+            "broken"
+        );
+        assert!(record_path.is_file());
+        assert!(!prefix
+            .join(consts::CONDA_META_DIR)
+            .join(reinstalled.file_name())
+            .exists());
+        assert_eq!(
+            fs_err::read_to_string(cache.join("tool")).unwrap(),
+            "reinstalled"
+        );
+        assert!(!backup_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_backup_discarded_on_success() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let prefix = temp_dir.path();
+        fs_err::create_dir_all(prefix.join("bin")).unwrap();
+        fs_err::write(prefix.join("bin/tool"), "broken").unwrap();
+        let record = write_record(prefix, "tool", "1.0", &["bin/tool", "bin/missing"]);
+
+        let backup_dir = prefix.join("backup");
+        backup_packages(prefix, &backup_dir, &[record.clone()], &[&record]).unwrap();
+        assert!(backup_journal_path(&backup_dir).is_file());
+
+        fs_err::write(prefix.join("bin/tool"), "reinstalled").unwrap();
+        finish_backup(prefix, &backup_dir, Ok(())).await.unwrap();
+        assert_eq!(
+            fs_err::read_to_string(prefix.join("bin/tool")).unwrap(),
+            "reinstalled"
+        );
+        assert!(!backup_dir.exists());
+
+        // Restoring without a backup does nothing.
+        restore_backup(prefix, &backup_dir).await.unwrap();
+        assert_eq!(
+            fs_err::read_to_string(prefix.join("bin/tool")).unwrap(),
+            "reinstalled"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_rolled_back() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let prefix = temp_dir.path();
+        fs_err::create_dir_all(prefix.join("bin")).unwrap();
+        fs_err::write(prefix.join("bin/kept"), "kept").unwrap();
+        fs_err::write(prefix.join("bin/tool"), "1.0").unwrap();
+        let kept = write_record(prefix, "kept", "1.0", &["bin/kept"]);
+        let old = write_record(prefix, "tool", "1.0", &["bin/tool"]);
+        let installed = vec![kept.clone(), old.clone()];
+
+        // Only the package that is replaced is backed up.
+        let new_record = |record: &PrefixRecord, version: &str| RepoDataRecord {
+            file_name: record.repodata_record.file_name.replace("1.0", version),
+            ..record.repodata_record.clone()
+        };
+        let journal = InstallJournal::new(&[kept.repodata_record.clone(), new_record(&old, "2.0")]);
+        assert_eq!(journal.installed_count(&installed), 1);
+        backup_replaced_packages(prefix, &journal, &installed).unwrap();
+        let backup_dir = install_backup_dir(prefix);
+        assert!(backup_dir.join("files/bin/tool").is_file());
+        assert!(!backup_dir.join("files/bin/kept").exists());
+
+        // The installation replaces the package, adds another one and fails.
+        let conda_meta = prefix.join(consts::CONDA_META_DIR);
+        fs_err::remove_file(conda_meta.join(old.file_name())).unwrap();
+        fs_err::write(prefix.join("bin/tool"), "2.0").unwrap();
+        write_record(prefix, "tool", "2.0", &["bin/tool"]);
+        fs_err::write(prefix.join("bin/added"), "added").unwrap();
+        write_record(prefix, "added", "1.0", &["bin/added"]);
+        restore_backup(prefix, &backup_dir).await.unwrap();
+
+        let mut file_names = Prefix::new(prefix)
+            .find_installed_packages(None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.repodata_record.file_name)
+            .collect_vec();
+        file_names.sort();
+        assert_eq!(file_names, vec!["kept-1.0-0.conda", "tool-1.0-0.conda"]);
+        assert_eq!(
+            fs_err::read_to_string(prefix.join("bin/tool")).unwrap(),
+            "1.0"
+        );
+        assert_eq!(
+            fs_err::read_to_string(prefix.join("bin/kept")).unwrap(),
+            "kept"
+        );
+        assert!(!prefix.join("bin/added").exists());
+    }
+
+    #[test]
+    fn test_install_journal() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(read_install_journal(temp_dir.path()).is_none());
+
+        let record = write_record(temp_dir.path(), "tool", "1.0", &[]);
+        let journal = InstallJournal::new(&[record.repodata_record]);
+        write_install_journal(temp_dir.path(), &journal).unwrap();
+        assert_eq!(read_install_journal(temp_dir.path()), Some(journal));
+
+        remove_install_journal(temp_dir.path()).unwrap();
+        assert!(read_install_journal(temp_dir.path()).is_none());
+
+        // Removing a journal that doesn't exist is not an error.
+        remove_install_journal(temp_dir.path()).unwrap();
+    }
+}
@@ -10,7 +10,7 @@ use itertools::Either;
 use pixi_consts::consts;
 use pixi_manifest::{
     self as manifest, EnvironmentName, Feature, FeatureName, FeaturesExt, HasFeaturesIter,
-    HasManifestRef, Manifest, SystemRequirements, Task, TaskName,
+    HasManifestRef, Manifest, SystemRequirements, TargetSelector, Task, TaskName,
 };
 use rattler_conda_types::{Arch, Platform};
 
@@ -247,6 +247,30 @@ impl<'p> Environment<'p> {
             .collect()
     }
 
+    /// Returns the feature and the target selector that define the task with
+    /// the given `name` for the specified `platform`. A selector of `None`
+    /// means the task is defined for all platforms.
+    ///
+    /// Returns `None` if the task is not available for the platform.
+    pub(crate) fn task_origin(
+        &self,
+        name: &TaskName,
+        platform: Option<Platform>,
+    ) -> Option<(&'p FeatureName, Option<&'p TargetSelector>)> {
+        self.features().find_map(|feature| {
+            let target = feature
+                .targets
+                .resolve(platform)
+                .find(|target| target.tasks.contains_key(name))?;
+            let selector = feature
+                .targets
+                .iter()
+                .find(|(candidate, _)| std::ptr::eq(*candidate, target))
+                .and_then(|(_, selector)| selector);
+            Some((&feature.name, selector))
+        })
+    }
+
     /// Returns the system requirements for this environment.
     ///
     /// The system requirements of the environment are the union of the system
@@ -475,6 +499,55 @@ mod tests {
         assert!(task.contains(&"foo".into()));
     }
 
+    #[test]
+    fn test_task_origin() {
+        let manifest = Project::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [project]
+        name = "foobar"
+        channels = []
+        platforms = ["linux-64", "osx-64"]
+
+        [tasks]
+        foo = "echo default"
+
+        [target.linux-64.tasks]
+        foo = "echo linux"
+
+        [feature.test.tasks]
+        bar = "echo bar"
+
+        [environments]
+        test = ["test"]
+        "#,
+        )
+        .unwrap();
+
+        let default_env = manifest.default_environment();
+        assert_eq!(
+            default_env.task_origin(&"foo".into(), Some(Platform::Linux64)),
+            Some((
+                &FeatureName::Default,
+                Some(&TargetSelector::Platform(Platform::Linux64))
+            ))
+        );
+        assert_eq!(
+            default_env.task_origin(&"foo".into(), Some(Platform::Osx64)),
+            Some((&FeatureName::Default, None))
+        );
+        assert_eq!(
+            default_env.task_origin(&"bar".into(), Some(Platform::Osx64)),
+            None
+        );
+
+        let test_env = manifest.environment("test").unwrap();
+        assert_eq!(
+            test_env.task_origin(&"bar".into(), Some(Platform::Linux64)),
+            Some((&FeatureName::Named("test".to_string()), None))
+        );
+    }
+
     fn format_dependencies(dependencies: CondaDependencies) -> String {
         dependencies
             .into_specs()
@@ -600,6 +673,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_activation_env_list() {
+        let manifest = Project::from_str(
+            Path::new("pixi.toml"),
+            r#"
+            [project]
+            name = "foobar"
+            channels = []
+            platforms = ["linux-64", "osx-64"]
+
+            [activation.env]
+            SEARCH_PATH = ["${PIXI_PROJECT_ROOT}/data", "${SEARCH_PATH}"]
+            "#,
+        )
+        .unwrap();
+
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(
+            manifest.default_environment().activation_env(None),
+            indexmap! {
+                "SEARCH_PATH".to_string() => format!("${{PIXI_PROJECT_ROOT}}/data{separator}${{SEARCH_PATH}}"),
+            }
+        );
+    }
+
     #[test]
     fn test_channel_feature_priority() {
         let manifest = Project::from_str(
@@ -750,6 +848,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_channel_priority_combination() {
+        let manifest = Project::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [project]
+        name = "foobar"
+        channels = ["conda-forge"]
+        platforms = ["linux-64"]
+        channel-priority = "disabled"
+
+        [feature.strict]
+        channel-priority = "strict"
+
+        [feature.disabled]
+        channel-priority = "disabled"
+
+        [environments]
+        disabled = ["disabled"]
+        strict = ["strict"]
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.default_environment().channel_priority().unwrap(),
+            Some(rattler_solve::ChannelPriority::Disabled)
+        );
+        assert_eq!(
+            manifest
+                .environment("disabled")
+                .unwrap()
+                .channel_priority()
+                .unwrap(),
+            Some(rattler_solve::ChannelPriority::Disabled)
+        );
+        assert!(manifest
+            .environment("strict")
+            .unwrap()
+            .channel_priority()
+            .is_err());
+    }
+
     #[test]
     fn test_pypi_options_per_environment() {
         let manifest = Project::from_str(
@@ -0,0 +1,310 @@
+//! Keeps an audit trail of the mutations of a project.
+//!
+//! Every time the manifest or the lock-file of a project changes, or an
+//! environment is installed or removed, a [`HistoryEntry`] is appended to
+//! [`consts::HISTORY_FILE`] in the `.pixi` directory. Each line of the file
+//! contains a single json record which makes it easy to process the history
+//! with other tools.
+
+use std::{
+    fmt::{Display, Formatter},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use rattler_digest::Sha256;
+use serde::{Deserialize, Serialize};
+
+use super::Project;
+
+/// The part of the project that was changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryChange {
+    /// The lock-file was changed, the hashes are the sha256 of the lock-file.
+    LockFile,
+
+    /// The manifest was changed, the hashes are the sha256 of the manifest.
+    Manifest,
+
+    /// An environment was installed, updated or removed, the hashes identify
+    /// the locked packages the environment was installed from.
+    Environment,
+}
+
+impl Display for HistoryChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryChange::LockFile => write!(f, "lock-file"),
+            HistoryChange::Manifest => write!(f, "manifest"),
+            HistoryChange::Environment => write!(f, "environment"),
+        }
+    }
+}
+
+/// A single record in the history of a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The time at which the change was made.
+    pub timestamp: DateTime<Utc>,
+
+    /// The command that caused the change.
+    pub command: String,
+
+    /// The user that executed the command, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// The part of the project that was changed.
+    pub change: HistoryChange,
+
+    /// The name of the environment that was changed, for environment changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+
+    /// The hash of the changed part before the change, or `None` if it did not
+    /// exist.
+    pub hash_before: Option<String>,
+
+    /// The hash of the changed part after the change, or `None` if it was
+    /// removed.
+    pub hash_after: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Constructs a new entry for the currently executing command.
+    fn new(change: HistoryChange, hash_before: Option<String>, hash_after: Option<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            command: current_command(),
+            user: current_user(),
+            change,
+            environment: None,
+            hash_before,
+            hash_after,
+        }
+    }
+
+    /// Constructs an entry for a change of the lock-file.
+    pub fn lock_file(hash_before: Option<String>, hash_after: Option<String>) -> Self {
+        Self::new(HistoryChange::LockFile, hash_before, hash_after)
+    }
+
+    /// Constructs an entry for a change of the manifest.
+    pub fn manifest(hash_before: Option<String>, hash_after: Option<String>) -> Self {
+        Self::new(HistoryChange::Manifest, hash_before, hash_after)
+    }
+
+    /// Constructs an entry for an environment that was installed or removed.
+    pub fn environment(
+        name: impl Into<String>,
+        hash_before: Option<String>,
+        hash_after: Option<String>,
+    ) -> Self {
+        Self {
+            environment: Some(name.into()),
+            ..Self::new(HistoryChange::Environment, hash_before, hash_after)
+        }
+    }
+}
+
+impl Project {
+    /// Returns the path to the history file of the project.
+    pub(crate) fn history_file_path(&self) -> std::path::PathBuf {
+        self.pixi_dir().join(consts::HISTORY_FILE)
+    }
+
+    /// Appends an entry to the history file of the project.
+    pub(crate) fn append_history(&self, entry: &HistoryEntry) -> miette::Result<()> {
+        let path = self.history_file_path();
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        let line = serde_json::to_string(entry).into_diagnostic()?;
+        let mut file = fs_err::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .into_diagnostic()?;
+        writeln!(file, "{line}")
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to write to '{}'", path.display()))
+    }
+
+    /// Records a change in the history of the project. Failing to record the
+    /// change is not fatal, only a warning is emitted.
+    pub(crate) fn record_history(&self, entry: &HistoryEntry) {
+        if let Err(err) = self.append_history(entry) {
+            tracing::warn!(
+                "failed to record the {} change in the history: {err}",
+                entry.change
+            );
+        }
+    }
+
+    /// Reads all the entries from the history file of the project, oldest
+    /// first. Returns an empty list if there is no history yet.
+    ///
+    /// Lines that can't be parsed are skipped with a warning, such that a
+    /// single corrupted line does not hide the rest of the history.
+    pub(crate) fn history(&self) -> miette::Result<Vec<HistoryEntry>> {
+        let path = self.history_file_path();
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let file = fs_err::File::open(&path).into_diagnostic()?;
+        let mut entries = Vec::new();
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line
+                .into_diagnostic()
+                .wrap_err_with(|| format!("failed to read '{}'", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(err) => tracing::warn!(
+                    "skipping line {} of '{}' because it could not be parsed: {err}",
+                    idx + 1,
+                    path.display()
+                ),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Computes the sha256 hash of the file at the given path that is stored in
+/// the history, or `None` if the file does not exist.
+pub(crate) fn file_hash(path: &Path) -> Option<String> {
+    rattler_digest::compute_file_digest::<Sha256>(path)
+        .ok()
+        .map(|digest| format!("{digest:x}"))
+}
+
+/// Returns the command line of the current process.
+fn current_command() -> String {
+    let mut args = std::env::args();
+    let program = args
+        .next()
+        .and_then(|program| {
+            Path::new(&program)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| String::from("pixi"));
+    std::iter::once(program).chain(args).join(" ")
+}
+
+/// Returns the name of the user executing the current process.
+fn current_user() -> Option<String> {
+    ["USER", "USERNAME"]
+        .into_iter()
+        .find_map(|key| std::env::var(key).ok().filter(|user| !user.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = Project::from_str(
+            &dir.path().join("pixi.toml"),
+            r#"
+        [project]
+        name = "foo"
+        channels = []
+        platforms = []
+        "#,
+        )
+        .unwrap();
+
+        assert!(project.history().unwrap().is_empty());
+
+        project
+            .append_history(&HistoryEntry::lock_file(None, Some("a".to_string())))
+            .unwrap();
+        project
+            .append_history(&HistoryEntry::lock_file(
+                Some("a".to_string()),
+                Some("b".to_string()),
+            ))
+            .unwrap();
+        project
+            .append_history(&HistoryEntry::environment(
+                "default",
+                None,
+                Some("c".to_string()),
+            ))
+            .unwrap();
+
+        let history = project.history().unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].change, HistoryChange::LockFile);
+        assert_eq!(history[0].hash_before, None);
+        assert_eq!(history[0].hash_after.as_deref(), Some("a"));
+        assert_eq!(history[1].hash_before.as_deref(), Some("a"));
+        assert_eq!(history[1].hash_after.as_deref(), Some("b"));
+        assert_eq!(history[2].change, HistoryChange::Environment);
+        assert_eq!(history[2].environment.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn test_history_skips_malformed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = Project::from_str(
+            &dir.path().join("pixi.toml"),
+            r#"
+        [project]
+        name = "foo"
+        channels = []
+        platforms = []
+        "#,
+        )
+        .unwrap();
+
+        fs_err::create_dir_all(project.pixi_dir()).unwrap();
+        fs_err::write(
+            project.history_file_path(),
+            r#"{"timestamp":"2024-01-01T00:00:00Z","command":"pixi add foo","change":"lock-file","hash_before":null,"hash_after":"a"}
+this is not json
+"#,
+        )
+        .unwrap();
+        project
+            .append_history(&HistoryEntry::manifest(
+                Some("b".to_string()),
+                Some("c".to_string()),
+            ))
+            .unwrap();
+
+        let history = project.history().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].change, HistoryChange::LockFile);
+        assert_eq!(history[0].hash_after.as_deref(), Some("a"));
+        assert_eq!(history[1].change, HistoryChange::Manifest);
+    }
+
+    #[test]
+    fn test_file_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pixi.lock");
+        assert_eq!(file_hash(&path), None);
+
+        fs_err::write(&path, "version: 6").unwrap();
+        let hash = file_hash(&path).unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(file_hash(&path), Some(hash.clone()));
+
+        fs_err::write(&path, "version: 5").unwrap();
+        assert_ne!(file_hash(&path), Some(hash));
+    }
+}
@@ -0,0 +1,276 @@
+//! A project can list other projects in its subdirectories as the `members` of
+//! its workspace. Members are regular projects, but commands like
+//! `pixi run --workspace` operate on all of them. The members are ordered by
+//! the path dependencies between them, so that a member comes after the
+//! members it depends on.
+
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use miette::{Diagnostic, WrapErr};
+use thiserror::Error;
+
+use super::{find_manifest_in_dir, resolve_manifest_path, Discovered, Project};
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("the workspace members {} depend on each other", .0.iter().format(", "))]
+#[diagnostic(help("remove one of the path dependencies between them"))]
+pub struct CyclicMembersError(Vec<String>);
+
+impl Project {
+    /// Loads the project like [`Project::load_or_else_discover`], but returns
+    /// the root of the workspace if the project is a member of one.
+    pub fn load_workspace_or_else_discover(manifest_path: Option<&Path>) -> miette::Result<Self> {
+        let project = Self::load_or_else_discover(manifest_path)?;
+        Ok(project.workspace_root()?.unwrap_or(project))
+    }
+
+    /// Returns the project in one of the parent directories that lists this
+    /// project as one of its `members`, if any.
+    ///
+    /// The search stops at a discovery boundary, like the discovery of the
+    /// manifest.
+    pub(crate) fn workspace_root(&self) -> miette::Result<Option<Project>> {
+        // A manifest that is a discovery boundary is never part of a workspace
+        // in one of the parent directories.
+        if self.manifest.workspace.discovery_boundary {
+            return Ok(None);
+        }
+        let Some(parent) = self.root().parent() else {
+            return Ok(None);
+        };
+        let root = canonicalize(self.root());
+        for dir in parent.ancestors() {
+            match find_manifest_in_dir(dir) {
+                Discovered::Manifest(path) => {
+                    let project = Project::from_path(&path)?;
+                    if project.member_roots().contains(&root) {
+                        return Ok(Some(project));
+                    }
+                    if project.manifest.workspace.discovery_boundary {
+                        break;
+                    }
+                }
+                Discovered::Boundary(_) => break,
+                Discovered::None => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// Loads the `members` of the workspace of this project, a member comes
+    /// after the members it depends on.
+    pub(crate) fn workspace_members(&self) -> miette::Result<Vec<Project>> {
+        let members = self
+            .manifest
+            .workspace
+            .workspace
+            .members
+            .iter()
+            .map(|member| {
+                let manifest_path = resolve_manifest_path(&self.root().join(member))
+                    .wrap_err_with(|| {
+                        format!("failed to load the workspace member '{}'", member.display())
+                    })?;
+                Project::from_path(&manifest_path)
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        // A member depends on the members that one of its path dependencies
+        // points to.
+        let roots = members
+            .iter()
+            .map(|member| canonicalize(member.root()))
+            .collect_vec();
+        let dependencies = members
+            .iter()
+            .map(|member| {
+                member
+                    .path_dependencies()
+                    .into_iter()
+                    .filter_map(|path| roots.iter().position(|root| *root == path))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let order = topological_order(&dependencies).map_err(|cycle| {
+            CyclicMembersError(
+                cycle
+                    .into_iter()
+                    .map(|index| members[index].name().to_string())
+                    .collect(),
+            )
+        })?;
+        let mut members = members.into_iter().map(Some).collect_vec();
+        Ok(order
+            .into_iter()
+            .filter_map(|index| members[index].take())
+            .collect())
+    }
+
+    /// Returns the canonical paths of the `members` of the workspace.
+    fn member_roots(&self) -> Vec<PathBuf> {
+        self.manifest
+            .workspace
+            .workspace
+            .members
+            .iter()
+            .map(|member| canonicalize(&self.root().join(member)))
+            .collect()
+    }
+
+    /// Returns the canonical paths that the dependencies of the project,
+    /// including those of its package, point to.
+    fn path_dependencies(&self) -> Vec<PathBuf> {
+        let workspace_dependencies = self
+            .manifest
+            .workspace
+            .features
+            .values()
+            .flat_map(|feature| feature.targets.iter())
+            .flat_map(|(target, _)| target.dependencies.values());
+        let package_dependencies = self
+            .manifest
+            .package
+            .iter()
+            .flat_map(|package| package.targets.iter())
+            .flat_map(|(target, _)| target.dependencies.values());
+
+        workspace_dependencies
+            .chain(package_dependencies)
+            .flat_map(|dependencies| dependencies.values())
+            .filter_map(|spec| spec.as_path())
+            .filter_map(|spec| spec.resolve(self.root()).ok())
+            .map(|path| canonicalize(&path))
+            .unique()
+            .collect()
+    }
+}
+
+/// Returns the path with symlinks resolved, or the path itself if it doesn't
+/// exist.
+fn canonicalize(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Orders the nodes so that every node comes after its dependencies, nodes
+/// that don't depend on each other keep their order.
+///
+/// Returns the nodes of a cycle if the nodes depend on each other.
+fn topological_order(dependencies: &[Vec<usize>]) -> Result<Vec<usize>, Vec<usize>> {
+    let mut order = Vec::with_capacity(dependencies.len());
+    let mut visiting = Vec::new();
+    for node in 0..dependencies.len() {
+        visit(node, dependencies, &mut visiting, &mut order)?;
+    }
+    return Ok(order);
+
+    fn visit(
+        node: usize,
+        dependencies: &[Vec<usize>],
+        visiting: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), Vec<usize>> {
+        if order.contains(&node) {
+            return Ok(());
+        }
+        if let Some(start) = visiting.iter().position(|visited| *visited == node) {
+            return Err(visiting[start..].to_vec());
+        }
+
+        visiting.push(node);
+        for dependency in &dependencies[node] {
+            visit(*dependency, dependencies, visiting, order)?;
+        }
+        visiting.pop();
+
+        order.push(node);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pixi_consts::consts;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_topological_order() {
+        assert_eq!(
+            topological_order(&[vec![], vec![], vec![]]),
+            Ok(vec![0, 1, 2])
+        );
+        assert_eq!(
+            topological_order(&[vec![2], vec![], vec![1]]),
+            Ok(vec![1, 2, 0])
+        );
+        assert_eq!(
+            topological_order(&[vec![], vec![2], vec![3], vec![1]]),
+            Err(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_workspace_root() {
+        // Create a file structure like:
+        // root
+        // ├── packages
+        // │   ├── core
+        // │   │   └── pixi.toml
+        // │   └── other
+        // │       └── pixi.toml
+        // └── pixi.toml
+        //
+        // Where only `core` is a member of the workspace of the root.
+        let dir = tempdir().unwrap();
+        let manifest = |name: &str, members: &str| {
+            format!(
+                r#"
+                [workspace]
+                name = "{name}"
+                channels = []
+                platforms = []
+                members = [{members}]
+                "#
+            )
+        };
+        for (path, contents) in [
+            ("", manifest("root", r#""packages/core""#)),
+            ("packages/core", manifest("core", "")),
+            ("packages/other", manifest("other", "")),
+        ] {
+            let path = dir.path().join(path);
+            fs_err::create_dir_all(&path).unwrap();
+            fs_err::write(path.join(consts::PROJECT_MANIFEST), contents).unwrap();
+        }
+
+        let root = Project::from_path(&dir.path().join(consts::PROJECT_MANIFEST)).unwrap();
+        assert!(root.workspace_root().unwrap().is_none());
+        let members = root.workspace_members().unwrap();
+        assert_eq!(
+            members.iter().map(|member| member.name()).collect_vec(),
+            vec!["core"]
+        );
+
+        let core = Project::from_path(&dir.path().join("packages/core/pixi.toml")).unwrap();
+        assert_eq!(core.workspace_root().unwrap().unwrap().name(), "root");
+
+        let other = Project::from_path(&dir.path().join("packages/other/pixi.toml")).unwrap();
+        assert!(other.workspace_root().unwrap().is_none());
+
+        // A member that is a discovery boundary does not look for its workspace.
+        let core_manifest = dir
+            .path()
+            .join("packages/core")
+            .join(consts::PROJECT_MANIFEST);
+        fs_err::write(
+            &core_manifest,
+            format!("discovery-boundary = true\n{}", manifest("core", "")),
+        )
+        .unwrap();
+        let core = Project::from_path(&core_manifest).unwrap();
+        assert!(core.workspace_root().unwrap().is_none());
+    }
+}
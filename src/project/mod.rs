@@ -2,6 +2,8 @@ mod environment;
 pub mod errors;
 pub mod grouped_environment;
 mod has_project_ref;
+pub mod history;
+mod members;
 mod repodata;
 mod solve_group;
 pub mod virtual_packages;
@@ -20,9 +22,10 @@ use std::{
 
 use async_once_cell::OnceCell as AsyncCell;
 pub use environment::Environment;
+use fancy_display::FancyDisplay;
 use grouped_environment::GroupedEnvironment;
 pub use has_project_ref::HasProjectRef;
-use indexmap::{Equivalent, IndexMap};
+use indexmap::{Equivalent, IndexMap, IndexSet};
 use itertools::Itertools;
 use miette::IntoDiagnostic;
 use once_cell::sync::OnceCell;
@@ -37,7 +40,9 @@ use pixi_manifest::{
 };
 use pixi_utils::reqwest::build_reqwest_clients;
 use pypi_mapping::{ChannelName, CustomMapping, MappingLocation, MappingSource};
-use rattler_conda_types::{Channel, ChannelConfig, MatchSpec, PackageName, Platform, Version};
+use rattler_conda_types::{
+    Channel, ChannelConfig, MatchSpec, Matches, PackageName, Platform, Version,
+};
 use rattler_lock::{LockFile, LockedPackageRef};
 use rattler_repodata_gateway::Gateway;
 use reqwest_middleware::ClientWithMiddleware;
@@ -52,6 +57,7 @@ use crate::{
     environment::LockFileUsage,
     load_lock_file,
     lock_file::{filter_lock_file, LockFileDerivedData, UpdateContext, UpdateMode},
+    repodata::Repodata,
 };
 
 static CUSTOM_TARGET_DIR_WARN: OnceCell<()> = OnceCell::new();
@@ -204,9 +210,9 @@ impl Project {
     /// environment. This will also set the current working directory to the
     /// project root.
     pub(crate) fn discover() -> miette::Result<Self> {
-        let project_toml = find_project_manifest(std::env::current_dir().into_diagnostic()?);
+        let discovered = discover_manifest(&std::env::current_dir().into_diagnostic()?);
 
-        if let Some(project_toml) = project_toml {
+        if let Discovered::Manifest(project_toml) = discovered {
             if std::env::var("PIXI_IN_SHELL").is_ok() {
                 if let Ok(env_manifest_path) = std::env::var("PIXI_PROJECT_MANIFEST") {
                     if env_manifest_path != project_toml.to_string_lossy() {
@@ -222,7 +228,18 @@ impl Project {
         }
 
         if let Ok(env_manifest_path) = std::env::var("PIXI_PROJECT_MANIFEST") {
-            return Self::from_path(Path::new(env_manifest_path.as_str()));
+            return Self::from_path(&resolve_manifest_path(Path::new(
+                env_manifest_path.as_str(),
+            ))?);
+        }
+
+        if let Discovered::Boundary(boundary) = discovered {
+            miette::bail!(
+                "could not find {} or {} which is configured to use pixi, the search stopped at the discovery boundary '{}'",
+                consts::PROJECT_MANIFEST,
+                consts::PYPROJECT_MANIFEST,
+                boundary.display()
+            );
         }
 
         miette::bail!(
@@ -239,10 +256,11 @@ impl Project {
     }
 
     /// Loads a project manifest file or discovers it in the current directory
-    /// or any of the parent
+    /// or any of the parent. The manifest path may also point to the directory
+    /// that contains the manifest.
     pub fn load_or_else_discover(manifest_path: Option<&Path>) -> miette::Result<Self> {
         let project = match manifest_path {
-            Some(path) => Project::from_path(path)?,
+            Some(path) => Project::from_path(&resolve_manifest_path(path)?)?,
             None => Project::discover()?,
         };
         Ok(project)
@@ -277,6 +295,13 @@ impl Project {
         self
     }
 
+    /// Ignores the configured mirrors, so that every package is downloaded from
+    /// the channel it is locked with.
+    pub(crate) fn without_mirrors(mut self) -> Self {
+        self.config.mirrors.clear();
+        self
+    }
+
     /// Returns the name of the project
     pub fn name(&self) -> &str {
         &self.manifest.workspace.workspace.name
@@ -287,6 +312,24 @@ impl Project {
         &self.manifest.workspace.workspace.version
     }
 
+    /// Returns the directory with the scripts of the project that is added to
+    /// the `PATH` of the activated environments, if any.
+    pub(crate) fn script_dir(&self) -> Option<PathBuf> {
+        self.manifest
+            .workspace
+            .workspace
+            .script_dir
+            .as_ref()
+            .map(|dir| self.root().join(dir))
+    }
+
+    /// Returns the files that are generated from templates when an environment
+    /// is installed, mapping the template to the output path (both relative
+    /// to the project root).
+    pub(crate) fn generated_files(&self) -> &IndexMap<PathBuf, PathBuf> {
+        &self.manifest.workspace.generate
+    }
+
     /// Returns the description of the project
     pub(crate) fn description(&self) -> &Option<String> {
         &self.manifest.workspace.workspace.description
@@ -304,7 +347,7 @@ impl Project {
 
     /// Create the detached-environments path for this project if it is set in
     /// the config
-    fn detached_environments_path(&self) -> Option<PathBuf> {
+    pub(crate) fn detached_environments_path(&self) -> Option<PathBuf> {
         if let Ok(Some(detached_environments_path)) = self.config().detached_environments().path() {
             Some(detached_environments_path.join(format!(
                 "{}-{}",
@@ -334,6 +377,7 @@ impl Project {
         // If the detached-environments path is set, use it instead of the default
         // directory.
         if let Some(detached_environments_path) = self.detached_environments_path() {
+            write_project_marker(&detached_environments_path, &self.manifest_path());
             let detached_environments_path =
                 detached_environments_path.join(consts::ENVIRONMENTS_DIR);
             let _ = CUSTOM_TARGET_DIR_WARN.get_or_init(|| {
@@ -392,9 +436,16 @@ impl Project {
         self.root.join(consts::PROJECT_LOCK_FILE)
     }
 
-    /// Save back changes
+    /// Save back changes, the change is recorded in the history of the
+    /// project.
     pub(crate) fn save(&mut self) -> miette::Result<()> {
-        self.manifest.save()
+        let hash_before = history::file_hash(&self.manifest_path());
+        self.manifest.save()?;
+        let hash_after = history::file_hash(&self.manifest_path());
+        if hash_before != hash_after {
+            self.record_history(&history::HistoryEntry::manifest(hash_before, hash_after));
+        }
+        Ok(())
     }
 
     /// Returns the default environment of the project.
@@ -427,7 +478,15 @@ impl Project {
         &self,
         name: Option<String>,
     ) -> miette::Result<Environment> {
-        let environment_name = EnvironmentName::from_arg_or_env_var(name).into_diagnostic()?;
+        // The environment of an outer `pixi shell` or `pixi run` only applies to
+        // the project that activated it.
+        let outer_project = std::env::var("PIXI_PROJECT_MANIFEST")
+            .is_ok_and(|path| Path::new(&path) == self.manifest_path());
+        let environment_name = if name.is_none() && !outer_project {
+            EnvironmentName::Default
+        } else {
+            EnvironmentName::from_arg_or_env_var(name).into_diagnostic()?
+        };
         self.environment(&environment_name)
             .ok_or_else(|| miette::miette!("unknown environment '{environment_name}'"))
     }
@@ -548,8 +607,18 @@ impl Project {
         }
     }
 
+    /// Returns the folder of the task cache, which is either located in the
+    /// `.pixi` directory or in a project specific folder of the configured
+    /// `cache-dirs.tasks` directory.
     pub(crate) fn task_cache_folder(&self) -> PathBuf {
-        self.pixi_dir().join(consts::TASK_CACHE_DIR)
+        match self.config().task_cache_dir() {
+            Some(task_cache_dir) => task_cache_dir.join(format!(
+                "{}-{}",
+                self.name(),
+                xxh3_64(self.root.to_string_lossy().as_bytes())
+            )),
+            None => self.pixi_dir().join(consts::TASK_CACHE_DIR),
+        }
     }
 
     pub(crate) fn activation_env_cache_folder(&self) -> PathBuf {
@@ -659,10 +728,12 @@ impl Project {
     /// Update the manifest with the given package specs, and upgrade the
     /// packages if possible
     ///
-    /// 1. Modify the manifest with the given package specs, if no version is
+    /// 1. Check that the conda packages exist for the platforms of the
+    ///    environments that are modified
+    /// 2. Modify the manifest with the given package specs, if no version is
     ///    given, use `no-pin` strategy
-    /// 2. Update the lock file
-    /// 3. Given packages without version restrictions will get a semver
+    /// 3. Update the lock file
+    /// 4. Given packages without version restrictions will get a semver
     ///    restriction
     #[allow(clippy::too_many_arguments)]
     pub async fn update_dependencies(
@@ -675,6 +746,14 @@ impl Project {
         editable: bool,
         dry_run: bool,
     ) -> Result<Option<UpdateDeps>, miette::Error> {
+        self.validate_spec_channels(&match_specs, feature_name)?;
+
+        // Fetching the repodata is only allowed if the lock-file may be updated.
+        if prefix_update_config.lock_file_usage() == LockFileUsage::Update {
+            self.validate_conda_specs(&match_specs, feature_name, platforms)
+                .await?;
+        }
+
         let mut conda_specs_to_add_constraints_for = IndexMap::new();
         let mut pypi_specs_to_add_constraints_for = IndexMap::new();
         let mut conda_packages = HashSet::new();
@@ -754,8 +833,8 @@ impl Project {
             .collect_vec();
         let unlocked_lock_file = self.unlock_packages(
             &original_lock_file,
-            conda_packages,
-            pypi_packages,
+            conda_packages.clone(),
+            pypi_packages.clone(),
             affect_environment_and_platforms
                 .iter()
                 .map(|(e, p)| (e.as_str(), *p))
@@ -779,6 +858,13 @@ impl Project {
             .update()
             .await?;
 
+        let solved_versions = Self::solved_versions(
+            &lock_file,
+            &affect_environment_and_platforms,
+            &conda_packages,
+            &pypi_packages,
+        );
+
         let mut implicit_constraints = HashMap::new();
         if !conda_specs_to_add_constraints_for.is_empty() {
             let conda_constraints = self.update_conda_specs_from_lock_file(
@@ -837,10 +923,179 @@ impl Project {
 
         Ok(Some(UpdateDeps {
             implicit_constraints,
+            solved_versions,
             lock_file_diff,
         }))
     }
 
+    /// Checks that the channel requested by a spec, e.g. `conda-forge::numpy`,
+    /// is one of the channels of every environment that contains the feature.
+    /// Otherwise the environment could never be solved.
+    fn validate_spec_channels(
+        &self,
+        match_specs: &MatchSpecs,
+        feature_name: &FeatureName,
+    ) -> miette::Result<()> {
+        let channel_config = self.channel_config();
+        for environment in self
+            .environments()
+            .into_iter()
+            .filter(|e| e.features().any(|f| f.name == *feature_name))
+        {
+            let channel_urls = environment
+                .channel_urls(&channel_config)
+                .into_diagnostic()?;
+            for (name, (spec, _)) in match_specs {
+                let Some(channel) = &spec.channel else {
+                    continue;
+                };
+                if !channel_urls.contains(&channel.base_url) {
+                    miette::bail!(
+                        help = "add the channel to the `channels` of the project or of a feature of the environment",
+                        "the dependency '{}' requests the channel '{}', which is not one of the channels of {}",
+                        name.as_source(),
+                        channel.canonical_name(),
+                        environment.name().fancy_display()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the channels of every environment that contains the feature
+    /// have a package matching each of the specs, for each platform of the
+    /// environment that is modified. This way a typo or a package that is not
+    /// available for one of the platforms is reported before the manifest is
+    /// modified. Virtual packages are not checked.
+    async fn validate_conda_specs(
+        &self,
+        match_specs: &MatchSpecs,
+        feature_name: &FeatureName,
+        platforms: &[Platform],
+    ) -> miette::Result<()> {
+        let specs = match_specs
+            .iter()
+            .filter(|(name, _)| !name.as_normalized().starts_with("__"))
+            .map(|(_, (spec, _))| spec.clone())
+            .collect_vec();
+        if specs.is_empty() {
+            return Ok(());
+        }
+
+        let channel_config = self.channel_config();
+        let mut missing: IndexMap<String, IndexSet<Platform>> = IndexMap::new();
+        for environment in self
+            .environments()
+            .into_iter()
+            .filter(|e| e.features().any(|f| f.name == *feature_name))
+        {
+            let environment_platforms = environment
+                .platforms()
+                .into_iter()
+                .filter(|platform| platforms.is_empty() || platforms.contains(platform))
+                .sorted_by_key(|platform| platform.as_str())
+                .collect_vec();
+            if environment_platforms.is_empty() {
+                continue;
+            }
+            let channels = environment
+                .channels()
+                .into_iter()
+                .map(|channel| channel.clone().into_channel(&channel_config))
+                .collect::<Result<Vec<_>, _>>()
+                .into_diagnostic()?;
+
+            let repodata = self
+                .repodata_gateway()
+                .query(
+                    channels,
+                    environment_platforms
+                        .iter()
+                        .copied()
+                        .chain([Platform::NoArch]),
+                    specs.clone(),
+                )
+                .await
+                .into_diagnostic()?;
+            let records = repodata.iter().flat_map(|r| r.iter()).collect_vec();
+
+            for spec in &specs {
+                for platform in &environment_platforms {
+                    let available = records.iter().any(|record| {
+                        let subdir = record.package_record.subdir.as_str();
+                        (subdir == platform.as_str() || subdir == Platform::NoArch.as_str())
+                            && spec.matches(*record)
+                    });
+                    if !available {
+                        missing
+                            .entry(spec.to_string())
+                            .or_default()
+                            .insert(*platform);
+                    }
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+        miette::bail!(
+            help = "check the spelling of the package and the channels of the project",
+            "no package matches {}",
+            missing
+                .iter()
+                .map(|(spec, platforms)| format!(
+                    "'{}' for {}",
+                    spec,
+                    platforms.iter().format(", ")
+                ))
+                .format(", ")
+        )
+    }
+
+    /// Returns the versions of the added packages in the updated lock-file,
+    /// by the normalized name of the package. A package can be solved to a
+    /// different version for each environment and platform.
+    fn solved_versions(
+        lock_file: &LockFile,
+        affect_environment_and_platforms: &[(String, Platform)],
+        conda_packages: &HashSet<PackageName>,
+        pypi_packages: &HashSet<pep508_rs::PackageName>,
+    ) -> HashMap<String, String> {
+        let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+        for (env, platform) in affect_environment_and_platforms {
+            let Some(packages) = lock_file
+                .environment(env)
+                .and_then(|env| env.packages(*platform))
+            else {
+                continue;
+            };
+            for package in packages {
+                let (name, version) = match package {
+                    LockedPackageRef::Conda(package)
+                        if conda_packages.contains(&package.record().name) =>
+                    {
+                        (
+                            package.record().name.as_normalized().to_string(),
+                            package.record().version.to_string(),
+                        )
+                    }
+                    LockedPackageRef::Pypi(package, _) if pypi_packages.contains(&package.name) => {
+                        (package.name.to_string(), package.version.to_string())
+                    }
+                    _ => continue,
+                };
+                versions.entry(name).or_default().push(version);
+            }
+        }
+
+        versions
+            .into_iter()
+            .map(|(name, versions)| (name, versions.into_iter().unique().join(", ")))
+            .collect()
+    }
+
     /// Constructs a new lock-file where some of the constraints have been
     /// removed.
     fn unlock_packages(
@@ -1018,6 +1273,8 @@ impl Project {
 
 pub struct UpdateDeps {
     pub implicit_constraints: HashMap<String, String>,
+    /// The versions the added packages were solved to, by normalized name.
+    pub solved_versions: HashMap<String, String>,
     pub lock_file_diff: LockFileDiff,
 }
 
@@ -1030,31 +1287,139 @@ impl<'source> HasManifestRef<'source> for &'source Project {
 /// Iterates over the current directory and all its parent directories and
 /// returns the manifest path in the first directory path that contains the
 /// [`consts::PROJECT_MANIFEST`] or [`consts::PYPROJECT_MANIFEST`].
+///
+/// The nearest manifest always wins, this makes sure that a project nested in
+/// a subdirectory of another project is not resolved to the outer project.
+/// Returns `None` if a discovery boundary is reached first, see
+/// [`discover_manifest`].
 pub(crate) fn find_project_manifest(current_dir: PathBuf) -> Option<PathBuf> {
-    let manifests = [consts::PROJECT_MANIFEST, consts::PYPROJECT_MANIFEST];
+    match discover_manifest(&current_dir) {
+        Discovered::Manifest(path) => Some(path),
+        Discovered::Boundary(_) | Discovered::None => None,
+    }
+}
 
-    for dir in current_dir.ancestors() {
-        for manifest in &manifests {
-            let path = dir.join(manifest);
-            if !path.is_file() {
-                continue;
-            }
+/// The result of searching a directory or its parents for a manifest.
+#[derive(Debug, PartialEq, Eq)]
+enum Discovered {
+    /// The path of the nearest manifest.
+    Manifest(PathBuf),
+    /// The path of the manifest that marks a discovery boundary, the search
+    /// did not continue in its parent directories.
+    Boundary(PathBuf),
+    /// No manifest was found.
+    None,
+}
 
-            match *manifest {
-                consts::PROJECT_MANIFEST => return Some(path),
-                consts::PYPROJECT_MANIFEST => {
-                    if let Ok(content) = fs_err::read_to_string(&path) {
-                        if content.contains("[tool.pixi") {
-                            return Some(path);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
+/// Searches the given directory and its parents for the nearest manifest.
+///
+/// The search stops at a manifest that sets `discovery-boundary = true`
+/// without being a project, see [`find_manifest_in_dir`]. This allows
+/// subprojects in a repository to not resolve to the manifest at the root of
+/// the repository.
+fn discover_manifest(current_dir: &Path) -> Discovered {
+    current_dir
+        .ancestors()
+        .find_map(|dir| match find_manifest_in_dir(dir) {
+            Discovered::None => None,
+            discovered => Some(discovered),
+        })
+        .unwrap_or(Discovered::None)
+}
+
+/// Returns the manifest in the given directory, if any.
+///
+/// A [`consts::PROJECT_MANIFEST`] takes precedence over a
+/// [`consts::PYPROJECT_MANIFEST`], which is only considered if it contains
+/// pixi configuration. A manifest that only sets `discovery-boundary = true`
+/// is not a project, but marks the directory as a discovery boundary.
+fn find_manifest_in_dir(dir: &Path) -> Discovered {
+    let path = dir.join(consts::PROJECT_MANIFEST);
+    if path.is_file() {
+        let is_marker = fs_err::read_to_string(&path)
+            .ok()
+            .and_then(|content| content.parse::<toml_edit::DocumentMut>().ok())
+            .is_some_and(|document| is_boundary_marker(document.as_table()));
+        return if is_marker {
+            Discovered::Boundary(path)
+        } else {
+            Discovered::Manifest(path)
+        };
+    }
+
+    let path = dir.join(consts::PYPROJECT_MANIFEST);
+    let Ok(content) = fs_err::read_to_string(&path) else {
+        return Discovered::None;
+    };
+    if !content.contains("[tool.pixi") {
+        return Discovered::None;
+    }
+    let Ok(document) = content.parse::<toml_edit::DocumentMut>() else {
+        // Let loading the manifest report the error.
+        return Discovered::Manifest(path);
+    };
+    let is_marker = document
+        .get("tool")
+        .and_then(|tool| tool.get("pixi"))
+        .and_then(|pixi| pixi.as_table_like())
+        .is_some_and(is_boundary_marker);
+    if is_marker {
+        Discovered::Boundary(path)
+    } else {
+        Discovered::Manifest(path)
+    }
+}
+
+/// Returns true if the pixi configuration consists of nothing but
+/// `discovery-boundary = true`.
+fn is_boundary_marker(pixi: &dyn toml_edit::TableLike) -> bool {
+    pixi.get("discovery-boundary")
+        .and_then(|boundary| boundary.as_bool())
+        .unwrap_or(false)
+        && pixi
+            .iter()
+            .all(|(key, _)| matches!(key, "discovery-boundary" | "$schema"))
+}
+
+/// Resolves a user provided manifest path. If the path points to a directory
+/// the manifest in that directory is used, this allows selecting a specific
+/// project in a repository with multiple projects.
+fn resolve_manifest_path(path: &Path) -> miette::Result<PathBuf> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+
+    match find_manifest_in_dir(path) {
+        Discovered::Manifest(path) => Ok(path),
+        Discovered::Boundary(_) | Discovered::None => Err(miette::miette!(
+            "could not find {} or {} which is configured to use pixi in '{}'",
+            consts::PROJECT_MANIFEST,
+            consts::PYPROJECT_MANIFEST,
+            path.display()
+        )),
     }
+}
 
-    None
+/// Write the path of the manifest to a marker file in the detached
+/// environments directory of the project, so that the directory can be linked
+/// back to the project, e.g. to clean up the environments of deleted projects.
+fn write_project_marker(detached_dir: &Path, manifest_path: &Path) {
+    let marker = detached_dir.join(consts::DETACHED_PROJECT_MARKER);
+    if marker.is_file() {
+        return;
+    }
+    fs_extra::dir::create_all(detached_dir, false)
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to create directory '{}': {}",
+                detached_dir.display(),
+                e
+            )
+        })
+        .ok();
+    fs_err::write(&marker, manifest_path.to_string_lossy().as_bytes())
+        .map_err(|e| tracing::error!("Failed to write project marker: {}", e))
+        .ok();
 }
 
 /// Create a symlink from the directory to the custom target directory
@@ -1461,6 +1826,20 @@ mod tests {
         insta::assert_snapshot!(project.pypi_name_mapping_source().unwrap_err());
     }
 
+    #[test]
+    fn test_write_project_marker() {
+        let dir = tempdir().unwrap();
+        let detached_dir = dir.path().join("project-1234");
+        let manifest_path = dir.path().join("project").join(consts::PROJECT_MANIFEST);
+
+        write_project_marker(&detached_dir, &manifest_path);
+        let marker = detached_dir.join(consts::DETACHED_PROJECT_MARKER);
+        assert_eq!(
+            fs_err::read_to_string(&marker).unwrap(),
+            manifest_path.to_string_lossy()
+        );
+    }
+
     #[test]
     fn test_find_project_manifest_in_current_dir() {
         for manifest in &[consts::PROJECT_MANIFEST, consts::PYPROJECT_MANIFEST] {
@@ -1551,4 +1930,110 @@ mod tests {
             Some(manifest_path_root)
         );
     }
+
+    #[test]
+    fn test_find_manifest_stops_at_boundary() {
+        // Create a file structure like:
+        // root
+        // ├── sub
+        // │   ├── pyproject.toml (discovery boundary)
+        // │   └── nested
+        // │       └── pixi.toml
+        // └── pixi.toml
+        let dir = tempdir().unwrap();
+        let sub_dir = dir.path().join("sub");
+        let nested_dir = sub_dir.join("nested");
+        fs_err::create_dir_all(&nested_dir).unwrap();
+
+        let manifest_path_root = dir.path().join(consts::PROJECT_MANIFEST);
+        fs_err::write(&manifest_path_root, "[project]\n").unwrap();
+        let boundary_path = sub_dir.join(consts::PYPROJECT_MANIFEST);
+        fs_err::write(
+            &boundary_path,
+            "[project]\nname = \"sub\"\n\n[tool.pixi]\ndiscovery-boundary = true\n",
+        )
+        .unwrap();
+        let manifest_path_nested = nested_dir.join(consts::PROJECT_MANIFEST);
+        fs_err::write(&manifest_path_nested, "[project]\n").unwrap();
+
+        // The nearest manifest still wins below the boundary
+        assert_eq!(
+            discover_manifest(&nested_dir),
+            Discovered::Manifest(manifest_path_nested)
+        );
+
+        // The search does not continue above the boundary
+        assert_eq!(
+            discover_manifest(&sub_dir),
+            Discovered::Boundary(boundary_path)
+        );
+        assert_eq!(find_project_manifest(sub_dir.clone()), None);
+        assert!(resolve_manifest_path(&sub_dir).is_err());
+
+        // A pyproject.toml with other pixi configuration is a regular manifest
+        let project_path = sub_dir.join(consts::PYPROJECT_MANIFEST);
+        fs_err::write(
+            &project_path,
+            "[project]\nname = \"sub\"\n\n[tool.pixi]\ndiscovery-boundary = true\n\n[tool.pixi.project]\nchannels = []\nplatforms = []\n",
+        )
+        .unwrap();
+        assert_eq!(
+            discover_manifest(&sub_dir),
+            Discovered::Manifest(project_path)
+        );
+
+        // The manifest is a project that also marks the boundary
+        let project = Project::from_path(&project_path).unwrap();
+        assert!(project.manifest.workspace.discovery_boundary);
+
+        // A pixi.toml can mark a boundary as well
+        fs_err::remove_file(&project_path).unwrap();
+        let boundary_path = sub_dir.join(consts::PROJECT_MANIFEST);
+        fs_err::write(&boundary_path, "discovery-boundary = true\n").unwrap();
+        assert_eq!(
+            discover_manifest(&sub_dir),
+            Discovered::Boundary(boundary_path.clone())
+        );
+
+        // Without the boundary the root manifest is found
+        fs_err::remove_file(&boundary_path).unwrap();
+        assert_eq!(find_project_manifest(sub_dir), Some(manifest_path_root));
+    }
+
+    #[test]
+    fn test_resolve_manifest_path_from_dir() {
+        let dir = tempdir().unwrap();
+        let child_dir = dir.path().join("child");
+        let manifest_path_root = dir.path().join(consts::PROJECT_MANIFEST);
+        let manifest_path_child = child_dir.join(consts::PYPROJECT_MANIFEST);
+
+        let mut file = File::create(&manifest_path_root).unwrap();
+        writeln!(file, "[project]").unwrap();
+
+        fs_err::create_dir_all(&child_dir).unwrap();
+        let mut file = File::create(&manifest_path_child).unwrap();
+        writeln!(file, "[project]").unwrap();
+        writeln!(file, "[tool.pixi.project]").unwrap();
+
+        // A manifest file is used as is
+        assert_eq!(
+            resolve_manifest_path(&manifest_path_root).unwrap(),
+            manifest_path_root
+        );
+
+        // A directory resolves to the manifest it contains
+        assert_eq!(
+            resolve_manifest_path(dir.path()).unwrap(),
+            manifest_path_root
+        );
+        assert_eq!(
+            resolve_manifest_path(&child_dir).unwrap(),
+            manifest_path_child
+        );
+
+        // A directory without a manifest is an error, even if a parent has one
+        let empty_dir = dir.path().join("empty");
+        fs_err::create_dir_all(&empty_dir).unwrap();
+        assert!(resolve_manifest_path(&empty_dir).is_err());
+    }
 }
@@ -98,6 +98,10 @@ pub enum VerifyCurrentPlatformError {
     },
 
     #[error("The current system has a mismatching virtual package. The project requires '{required}' to be at least version '{required_version}' but the system has version '{local_version}'")]
+    #[diagnostic(help(
+        "lower the requirement in the `system-requirements` of the environment, or override the detected version with the `CONDA_OVERRIDE_{}` environment variable",
+        .required.trim_start_matches('_').to_uppercase()
+    ))]
     MismatchingVersion {
         required: String,
         required_version: Box<Version>,
@@ -105,6 +109,10 @@ pub enum VerifyCurrentPlatformError {
     },
 
     #[error("The platform you are running on should at least have the virtual package {required} on version {required_version}, build_string: {required_build_string}")]
+    #[diagnostic(help(
+        "the virtual package was not detected on this machine, e.g. '__cuda' requires a CUDA driver. It can be overridden with the `CONDA_OVERRIDE_{}` environment variable",
+        .required.trim_start_matches('_').to_uppercase()
+    ))]
     MissingVirtualPackage {
         required: String,
         required_version: Box<Version>,
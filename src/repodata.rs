@@ -1,21 +1,26 @@
+use pixi_config::Config;
 use rattler::package_cache::PackageCache;
-use rattler_repodata_gateway::{ChannelConfig, Gateway};
+use rattler_repodata_gateway::Gateway;
 use std::path::PathBuf;
 
 pub(crate) trait Repodata {
     /// Initialized the [`Gateway`]
     fn repodata_gateway_init(
         authenticated_client: reqwest_middleware::ClientWithMiddleware,
-        channel_config: ChannelConfig,
-        max_concurrent_requests: usize,
+        config: &Config,
     ) -> Gateway {
-        // Determine the cache directory and fall back to sane defaults otherwise.
-        let cache_dir = pixi_config::get_cache_dir().unwrap_or_else(|e| {
-            tracing::error!("failed to determine repodata cache directory: {e}");
+        // Determine the cache directories and fall back to sane defaults otherwise.
+        let fallback_dir = |e: miette::Report| {
+            tracing::error!("failed to determine cache directory: {e}");
             std::env::current_dir().unwrap_or_else(|_| PathBuf::from("./"))
+        };
+        let repodata_cache_dir = config.repodata_cache_dir().unwrap_or_else(|e| {
+            fallback_dir(e).join(pixi_consts::consts::CONDA_REPODATA_CACHE_DIR)
         });
-
-        let package_cache = PackageCache::new(cache_dir.join("pkgs"));
+        let package_cache = PackageCache::new(config.package_cache_dir().unwrap_or_else(|e| {
+            fallback_dir(e).join(pixi_consts::consts::CONDA_PACKAGE_CACHE_DIR)
+        }));
+        let max_concurrent_requests = config.max_concurrent_downloads();
 
         tracing::info!(
             "repodata gateway: using max '{}' concurrent network requests",
@@ -25,9 +30,9 @@ pub(crate) trait Repodata {
         // Construct the gateway
         Gateway::builder()
             .with_client(authenticated_client)
-            .with_cache_dir(cache_dir.join(pixi_consts::consts::CONDA_REPODATA_CACHE_DIR))
+            .with_cache_dir(repodata_cache_dir)
             .with_package_cache(package_cache)
-            .with_channel_config(channel_config)
+            .with_channel_config(config.into())
             .with_max_concurrent_requests(max_concurrent_requests)
             .finish()
     }
@@ -0,0 +1,233 @@
+//! Downloads of package archives into the package cache that continue where
+//! an earlier download stopped.
+//!
+//! The archive is downloaded to the `partial` directory of the package cache
+//! and only extracted into the cache once it is complete. When a download
+//! fails halfway, the partially downloaded archive is kept and the next
+//! download requests the remaining bytes with an HTTP range request.
+
+use std::{
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use miette::{Context, IntoDiagnostic};
+use rattler::package_cache::{CacheLock, PackageCache};
+use rattler_conda_types::RepoDataRecord;
+use rattler_digest::{compute_file_digest, Sha256};
+use reqwest::{header, StatusCode};
+use reqwest_middleware::ClientWithMiddleware;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+/// Archives from this size on are downloaded ahead of the installation with a
+/// resumable download, smaller archives are downloaded by the installer.
+pub(crate) const RESUMABLE_DOWNLOAD_MIN_SIZE: u64 = 16 * 1024 * 1024;
+
+/// How often a download that broke off is resumed before giving up. The
+/// partially downloaded archive is kept, so a later download resumes it again.
+const MAX_RESUME_ATTEMPTS: usize = 3;
+
+/// The directory of the package cache in which archives are downloaded.
+const PARTIAL_DIR: &str = "partial";
+
+/// Fetches the package into the package cache, or returns the cached package.
+/// Packages that are not fetched over HTTP are fetched by the package cache
+/// itself.
+pub(crate) async fn get_or_fetch_resumable(
+    package_cache: &PackageCache,
+    record: &RepoDataRecord,
+    client: ClientWithMiddleware,
+) -> miette::Result<CacheLock> {
+    if !matches!(record.url.scheme(), "http" | "https") {
+        return package_cache
+            .get_or_fetch_from_url(&record.package_record, record.url.clone(), client, None)
+            .await
+            .into_diagnostic()
+            .with_context(|| format!("failed to fetch '{}'", record.url));
+    }
+
+    let url = record.url.clone();
+    let file_name = record.file_name.clone();
+    let sha256 = record.package_record.sha256;
+    package_cache
+        .get_or_fetch(
+            &record.package_record,
+            move |destination| {
+                let client = client.clone();
+                let url = url.clone();
+                let file_name = file_name.clone();
+                async move {
+                    let archive = partial_archive_path(&destination, &file_name)?;
+                    download_resumable(&client, &url, &archive).await?;
+
+                    // A partial archive of another file or a changed file on the server
+                    // results in a corrupt archive, which is downloaded again next time.
+                    if let Some(expected) = sha256 {
+                        let hash = compute_file_digest::<Sha256>(&archive)?;
+                        if hash != expected {
+                            fs_err::remove_file(&archive)?;
+                            return Err(io::Error::new(
+                                ErrorKind::InvalidData,
+                                format!("the downloaded archive of '{url}' has an unexpected hash"),
+                            ));
+                        }
+                    }
+
+                    let extract_archive = archive.clone();
+                    tokio::task::spawn_blocking(move || {
+                        rattler_package_streaming::fs::extract(&extract_archive, &destination)
+                    })
+                    .await?
+                    .map_err(io::Error::other)?;
+                    fs_err::remove_file(&archive)
+                }
+            },
+            None,
+        )
+        .await
+        .into_diagnostic()
+        .with_context(|| format!("failed to fetch '{}'", record.url))
+}
+
+/// The path to which the archive is downloaded, next to the directory of the
+/// package in the package cache.
+fn partial_archive_path(destination: &Path, file_name: &str) -> io::Result<PathBuf> {
+    let partial_dir = destination
+        .parent()
+        .ok_or_else(|| io::Error::from(ErrorKind::NotFound))?
+        .join(PARTIAL_DIR);
+    fs_err::create_dir_all(&partial_dir)?;
+    Ok(partial_dir.join(file_name))
+}
+
+/// Downloads the url to the path, continuing after the bytes that are
+/// already in the file.
+async fn download_resumable(
+    client: &ClientWithMiddleware,
+    url: &Url,
+    path: &Path,
+) -> io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match download_remaining(client, url, path).await {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt < MAX_RESUME_ATTEMPTS => {
+                attempt += 1;
+                tracing::debug!("resuming the download of '{url}' after: {err}");
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Requests the bytes of the url after the ones that are already in the file
+/// and appends them. Starts over if the server doesn't support range requests.
+async fn download_remaining(
+    client: &ClientWithMiddleware,
+    url: &Url,
+    path: &Path,
+) -> io::Result<()> {
+    let offset = match fs_err::tokio::metadata(path).await {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == ErrorKind::NotFound => 0,
+        Err(e) => return Err(e),
+    };
+
+    let mut request = client.get(url.clone());
+    if offset > 0 {
+        request = request.header(header::RANGE, format!("bytes={offset}-"));
+    }
+    let mut response = request.send().await.map_err(io::Error::other)?;
+
+    // The file is already complete.
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+    response = response.error_for_status().map_err(io::Error::other)?;
+
+    let mut file = if offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT {
+        fs_err::tokio::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .await?
+    } else {
+        fs_err::tokio::File::create(path).await?
+    };
+    while let Some(chunk) = response.chunk().await.map_err(io::Error::other)? {
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+
+    use super::*;
+
+    const CONTENT: &[u8] = b"the content of a package archive";
+
+    /// Serves the content on a local port, honoring range requests like a
+    /// package server. Returns the url of the content.
+    fn serve(content: &'static [u8]) -> Url {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/package.conda",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut offset = None;
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap().to_ascii_lowercase();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(range) = line.strip_prefix("range: bytes=") {
+                        offset = Some(range.trim_end_matches('-').parse::<usize>().unwrap());
+                    }
+                }
+                let (status, body) = match offset {
+                    Some(offset) if offset >= content.len() => {
+                        ("416 Range Not Satisfiable", &[][..])
+                    }
+                    Some(offset) => ("206 Partial Content", &content[offset..]),
+                    None => ("200 OK", content),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(body).unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file() {
+        let url = serve(CONTENT);
+        let client = ClientWithMiddleware::from(reqwest::Client::new());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("package.conda");
+
+        // Only the remaining bytes are appended to a partial download.
+        fs_err::write(&path, &CONTENT[..10]).unwrap();
+        download_resumable(&client, &url, &path).await.unwrap();
+        assert_eq!(fs_err::read(&path).unwrap(), CONTENT);
+
+        // A complete download is left as it is.
+        download_resumable(&client, &url, &path).await.unwrap();
+        assert_eq!(fs_err::read(&path).unwrap(), CONTENT);
+
+        // A new download starts at the beginning.
+        fs_err::remove_file(&path).unwrap();
+        download_resumable(&client, &url, &path).await.unwrap();
+        assert_eq!(fs_err::read(&path).unwrap(), CONTENT);
+    }
+}
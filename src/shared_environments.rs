@@ -0,0 +1,210 @@
+//! Environments with the same locked packages can be shared between projects.
+//!
+//! When `shared-environments` is enabled, an environment is installed into a
+//! store in the cache directory, under the hash of its locked packages, and the
+//! environment directory of the project is a symlink to that entry of the
+//! store. Entries that have not been used for a while are removed.
+
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use miette::IntoDiagnostic;
+use pixi_config::Config;
+use pixi_consts::consts;
+use pixi_utils::{mark_prefix_used, remove_unused_prefixes, PrefixGuard, UNUSED_PREFIX_MAX_AGE};
+use rattler_conda_types::Platform;
+use rattler_lock::UrlOrPath;
+
+use crate::environment::LockedEnvironmentHash;
+
+/// The store of environments that are shared between projects.
+pub(crate) struct SharedEnvironments {
+    root: PathBuf,
+}
+
+impl SharedEnvironments {
+    /// Returns the store if shared environments are enabled in the config.
+    pub(crate) fn from_config(config: &Config) -> miette::Result<Option<Self>> {
+        if !config.shared_environments() {
+            return Ok(None);
+        }
+        if cfg!(windows) {
+            tracing::warn!("shared environments require symlinks, which are not supported on Windows, installing the environment in the project instead");
+            return Ok(None);
+        }
+        Ok(Some(Self::new(
+            pixi_config::get_cache_dir()?.join(consts::SHARED_ENVS_DIR),
+        )))
+    }
+
+    fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Returns true if the locked environment can be shared with other
+    /// projects. Packages from a path, like source packages or editable
+    /// installs, are relative to the project, so an environment with such a
+    /// package can't be shared.
+    pub(crate) fn is_shareable(environment: rattler_lock::Environment, platform: Platform) -> bool {
+        environment.packages(platform).map_or(true, |mut packages| {
+            packages.all(|package| matches!(package.location(), UrlOrPath::Url(_)))
+        })
+    }
+
+    /// Returns the entry of the store for an environment with the given hash.
+    fn entry(&self, hash: &LockedEnvironmentHash) -> PathBuf {
+        self.root.join(hash.as_str())
+    }
+
+    /// Links the environment directory of a project to the entry of the store
+    /// for the given hash, and records that the entry is used.
+    ///
+    /// Returns a guard of the entry, which should be locked while the
+    /// environment is installed.
+    pub(crate) fn link(
+        &self,
+        environment_dir: &Path,
+        hash: &LockedEnvironmentHash,
+    ) -> miette::Result<PrefixGuard> {
+        // Entries that have not been used for a while are removed.
+        remove_unused_prefixes(&self.root, UNUSED_PREFIX_MAX_AGE, SystemTime::now());
+
+        let entry = self.entry(hash);
+        let guard = PrefixGuard::new(&entry).into_diagnostic()?;
+        mark_prefix_used(&entry);
+
+        if fs_err::read_link(environment_dir).ok().as_deref() == Some(entry.as_path()) {
+            return Ok(guard);
+        }
+
+        // The environment directory was installed in the project, or links to the
+        // entry of a different hash.
+        match environment_dir.symlink_metadata() {
+            Ok(metadata) if metadata.is_dir() => {
+                tracing::info!(
+                    "replacing '{}' with the shared environment '{}'",
+                    environment_dir.display(),
+                    entry.display()
+                );
+                fs_err::remove_dir_all(environment_dir).into_diagnostic()?;
+            }
+            Ok(_) => fs_err::remove_file(environment_dir).into_diagnostic()?,
+            Err(_) => {}
+        }
+        if let Some(parent) = environment_dir.parent() {
+            fs_err::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        #[cfg(not(windows))]
+        fs_err::os::unix::fs::symlink(&entry, environment_dir).into_diagnostic()?;
+        tracing::info!(
+            "linked '{}' to the shared environment '{}'",
+            environment_dir.display(),
+            entry.display()
+        );
+        Ok(guard)
+    }
+}
+
+/// Returns the directory the environment is installed in. That is the entry of
+/// the store if the environment directory links to it, otherwise the
+/// environment directory itself.
+///
+/// Files in an environment can contain the path of the environment, so a shared
+/// environment has to be installed through the path of the entry instead of the
+/// path of the project that installed it.
+pub(crate) fn installation_dir(environment_dir: &Path) -> PathBuf {
+    if environment_dir.is_symlink() {
+        if let Ok(target) = dunce::canonicalize(environment_dir) {
+            return target;
+        }
+    }
+    environment_dir.to_path_buf()
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use crate::environment::{
+        create_prefix_location_file, read_environment_file, verify_prefix_location_unchanged,
+        write_environment_file, EnvironmentFile,
+    };
+
+    fn locked_hash(hash: &str) -> LockedEnvironmentHash {
+        serde_json::from_value(serde_json::Value::String(hash.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_link() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = SharedEnvironments::new(temp_dir.path().join("store"));
+        let environment_dir = temp_dir.path().join(".pixi/envs/default");
+
+        // An environment that was installed in the project is replaced.
+        fs_err::create_dir_all(environment_dir.join(consts::CONDA_META_DIR)).unwrap();
+        let hash = locked_hash("first");
+        store.link(&environment_dir, &hash).unwrap();
+        let first = store.entry(&hash);
+        assert_eq!(fs_err::read_link(&environment_dir).unwrap(), first);
+        assert_eq!(
+            installation_dir(&environment_dir),
+            dunce::canonicalize(&first).unwrap()
+        );
+
+        // A different hash links to a different entry.
+        let hash = locked_hash("second");
+        store.link(&environment_dir, &hash).unwrap();
+        let second = store.entry(&hash);
+        assert_eq!(fs_err::read_link(&environment_dir).unwrap(), second);
+
+        // Both entries are in use, so they are kept.
+        assert!(first.is_dir());
+        assert!(second.is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_linked_environment_metadata() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = SharedEnvironments::new(temp_dir.path().join("store"));
+        let environment_dir = temp_dir.path().join(".pixi/envs/default");
+        store
+            .link(&environment_dir, &locked_hash("shared"))
+            .unwrap();
+
+        // Not installed yet, so the environment file is ignored.
+        let environment_file = || EnvironmentFile {
+            manifest_path: temp_dir.path().join("pixi.toml"),
+            environment_name: "default".to_string(),
+            pixi_version: consts::PIXI_VERSION.to_string(),
+            environment_lock_file_hash: locked_hash("shared"),
+        };
+        write_environment_file(&environment_dir, environment_file()).unwrap();
+        assert!(read_environment_file(&environment_dir).unwrap().is_none());
+
+        // The environment is installed through the entry of the store.
+        let installation_dir = installation_dir(&environment_dir);
+        fs_err::create_dir_all(installation_dir.join(consts::CONDA_META_DIR)).unwrap();
+        create_prefix_location_file(&installation_dir).unwrap();
+        verify_prefix_location_unchanged(&environment_dir)
+            .await
+            .unwrap();
+
+        // The environment file of the project is not stored in the shared
+        // environment.
+        let path = write_environment_file(&environment_dir, environment_file()).unwrap();
+        assert!(!path.starts_with(&installation_dir));
+        assert!(!installation_dir
+            .join(consts::CONDA_META_DIR)
+            .join(consts::ENVIRONMENT_FILE_NAME)
+            .exists());
+        assert_eq!(
+            read_environment_file(&environment_dir)
+                .unwrap()
+                .unwrap()
+                .manifest_path,
+            temp_dir.path().join("pixi.toml")
+        );
+    }
+}
@@ -13,27 +13,45 @@ pub struct MissingTaskError {
     pub task_name: TaskName,
 }
 
-// TODO: We should make this error much better
 #[derive(Debug, Error)]
 pub struct AmbiguousTaskError {
     pub task_name: TaskName,
+    /// The task that depends on the ambiguous task, if it wasn't requested
+    /// directly.
+    pub depended_on_by: Option<TaskName>,
     pub environments: Vec<EnvironmentName>,
 }
 
 impl Display for AmbiguousTaskError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "the task '{}' is ambiguous", &self.task_name)
+        match &self.depended_on_by {
+            Some(depended_on_by) => write!(
+                f,
+                "the task '{}', that '{}' depends on, is ambiguous because it is defined in multiple environments",
+                self.task_name.fancy_display(),
+                depended_on_by.fancy_display()
+            ),
+            None => write!(
+                f,
+                "the task '{}' is ambiguous because it is defined in multiple environments",
+                self.task_name.fancy_display()
+            ),
+        }
     }
 }
 
 impl Diagnostic for AmbiguousTaskError {
     fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         Some(Box::new(format!(
-            "These environments provide the task '{task_name}': {}\n\nSpecify the '--environment' flag to run the task in a specific environment, e.g:.\n\n\t{} run --environment {} {task_name}",
-            self.environments.iter().map(|env| env.as_str()).format(", "),
-            env!("CARGO_PKG_NAME"),
-            self.environments.first().expect("there should be at least two environment"),
-            task_name=&self.task_name
+            "specify the environment to run the task in with the '--environment' flag:\n\n{}",
+            self.environments
+                .iter()
+                .format_with("\n", |env, f| f(&format_args!(
+                    "\t{} run --environment {} {}",
+                    env!("CARGO_PKG_NAME"),
+                    env.as_str(),
+                    self.task_name
+                )))
         )))
     }
 }
@@ -2,19 +2,25 @@ use std::{
     borrow::Cow,
     collections::HashMap,
     fmt::{Display, Formatter},
+    io::Write,
     path::PathBuf,
+    sync::LazyLock,
 };
 
 use deno_task_shell::{
-    execute_with_pipes, parser::SequentialList, pipe, ShellPipeWriter, ShellState,
+    execute_with_pipes, parser::SequentialList, pipe, ShellPipeReader, ShellPipeWriter, ShellState,
 };
 use itertools::Itertools;
 use miette::{Context, Diagnostic, IntoDiagnostic};
 use rattler_lock::LockFile;
+use regex::{NoExpand, Regex};
 use thiserror::Error;
 use tokio::task::JoinHandle;
 
-use super::task_hash::{InputHashesError, TaskCache, TaskHash};
+use super::{
+    sandbox::{sandboxed_command, SandboxError},
+    task_hash::{InputHashesError, TaskCache, TaskHash},
+};
 use crate::{
     lock_file::LockFileDerivedData,
     project::Environment,
@@ -30,6 +36,22 @@ use crate::project::HasProjectRef;
 use pixi_manifest::{Task, TaskName};
 use pixi_progress::await_in_progress;
 
+/// Matches the `{{ args }}` placeholder in a task command that is replaced by
+/// the additional arguments passed on the command line.
+static ARGS_PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*args\s*\}\}").expect("Failed to compile regex"));
+
+/// Replaces all `{{ args }}` placeholders in the command with the given
+/// arguments. Returns `None` if the command does not contain a placeholder, in
+/// which case the arguments should be appended to the command instead.
+pub(crate) fn substitute_args_placeholder(command: &str, args: &str) -> Option<String> {
+    ARGS_PLACEHOLDER.is_match(command).then(|| {
+        ARGS_PLACEHOLDER
+            .replace_all(command, NoExpand(args))
+            .into_owned()
+    })
+}
+
 /// Runs task in project.
 #[derive(Default, Debug)]
 pub struct RunOutput {
@@ -58,6 +80,10 @@ pub enum TaskExecutionError {
 
     #[error(transparent)]
     FailedToParseShellScript(#[from] FailedToParseShellScript),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Sandbox(#[from] SandboxError),
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -118,45 +144,57 @@ impl<'p> ExecutableTask<'p> {
     }
 
     /// Returns the task as script
-    fn as_script(&self) -> Option<String> {
+    fn as_script(&self) -> Result<Option<String>, SandboxError> {
         // Convert the task into an executable string
-        let task = self.task.as_single_command()?;
+        let Some(task) = self.task.as_single_command() else {
+            return Ok(None);
+        };
 
         // Get the export specific environment variables
         let export = get_export_specific_task_env(self.task.as_ref());
 
-        // Append the command line arguments verbatim
+        // Pass the command line arguments verbatim, either in place of the `{{ args }}`
+        // placeholder or appended to the command.
         let cli_args = self
             .additional_args
             .iter()
-            .format_with(" ", |arg, f| f(&format_args!("'{}'", arg)));
+            .format_with(" ", |arg, f| f(&format_args!("'{}'", arg)))
+            .to_string();
+        let command = substitute_args_placeholder(&task, &cli_args)
+            .unwrap_or_else(|| format!("{task} {cli_args}"));
+
+        // Run the command in the sandbox of the task, the exported variables are
+        // inherited by the sandboxed process.
+        let command = match self.task.sandbox() {
+            Some(sandbox) => sandboxed_command(sandbox, &command, self.project.root())?,
+            None => command,
+        };
 
         // Skip the export if it's empty, to avoid newlines
         let full_script = if export.is_empty() {
-            format!("{task} {cli_args}")
+            command
         } else {
-            format!("{export}\n{task} {cli_args}")
+            format!("{export}\n{command}")
         };
 
-        Some(full_script)
+        Ok(Some(full_script))
     }
 
     /// Returns a [`SequentialList`] which can be executed by deno task shell.
     /// Returns `None` if the command is not executable like in the case of
     /// an alias.
-    pub(crate) fn as_deno_script(
-        &self,
-    ) -> Result<Option<SequentialList>, FailedToParseShellScript> {
-        if let Some(full_script) = self.as_script() {
+    pub(crate) fn as_deno_script(&self) -> Result<Option<SequentialList>, TaskExecutionError> {
+        if let Some(full_script) = self.as_script()? {
             tracing::debug!("Parsing shell script: {}", full_script);
 
             // Parse the shell command
-            deno_task_shell::parser::parse(full_script.trim())
-                .map_err(|e| FailedToParseShellScript {
+            let script = deno_task_shell::parser::parse(full_script.trim()).map_err(|e| {
+                FailedToParseShellScript {
                     script: full_script,
                     error: e.to_string(),
-                })
-                .map(Some)
+                }
+            })?;
+            Ok(Some(script))
         } else {
             Ok(None)
         }
@@ -188,6 +226,10 @@ impl<'p> ExecutableTask<'p> {
     pub(crate) fn full_command(&self) -> Option<String> {
         let mut cmd = self.task.as_single_command()?.to_string();
 
+        if let Some(cmd) = substitute_args_placeholder(&cmd, &self.additional_args.join(" ")) {
+            return Some(cmd);
+        }
+
         if !self.additional_args.is_empty() {
             cmd.push(' ');
             cmd.push_str(&self.additional_args.join(" "));
@@ -232,6 +274,40 @@ impl<'p> ExecutableTask<'p> {
         })
     }
 
+    /// Executes the task and forwards its output line by line to the stdout and
+    /// stderr of this process, prefixing every line with `prefix`. This makes
+    /// it possible to tell apart the output of tasks that run concurrently.
+    ///
+    /// Returns the exit code of the task.
+    pub async fn execute_with_prefixed_output(
+        &self,
+        command_env: &HashMap<String, String>,
+        prefix: &str,
+    ) -> Result<i32, TaskExecutionError> {
+        let Some(script) = self.as_deno_script()? else {
+            return Ok(0);
+        };
+        let cwd = self.working_directory()?;
+
+        // Concurrent tasks can't share the stdin of this process.
+        let (stdin, stdin_writer) = pipe();
+        drop(stdin_writer);
+
+        let (stdout_reader, stdout) = pipe();
+        let (stderr_reader, stderr) = pipe();
+        let stdout_handle = forward_prefixed_lines(stdout_reader, prefix, std::io::stdout());
+        let stderr_handle = forward_prefixed_lines(stderr_reader, prefix, std::io::stderr());
+
+        let state = ShellState::new(command_env.clone(), &cwd, Default::default());
+        let code = execute_with_pipes(script, state, stdin, stdout, stderr).await;
+
+        // Wait until all the output has been forwarded.
+        let _ = stdout_handle.await;
+        let _ = stderr_handle.await;
+
+        Ok(code)
+    }
+
     /// We store the hashes of the inputs and the outputs of the task in a file
     /// in the cache. The current name is something like
     /// `run_environment-task_name.json`.
@@ -305,6 +381,11 @@ struct ExecutableTaskConsoleDisplay<'p, 't> {
 impl<'p, 't> Display for ExecutableTaskConsoleDisplay<'p, 't> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let command = self.task.task.as_single_command();
+        if let Some(command) = command.as_deref().and_then(|command| {
+            substitute_args_placeholder(command, &self.task.additional_args.join(" "))
+        }) {
+            return write!(f, "{}", consts::TASK_STYLE.apply_to(command).bold());
+        }
         write!(
             f,
             "{}",
@@ -329,6 +410,70 @@ fn get_output_writer_and_handle() -> (ShellPipeWriter, JoinHandle<String>) {
     (writer, handle)
 }
 
+/// Forwards everything read from `reader` to `output` on a separate thread,
+/// prefixing every line with `prefix`.
+fn forward_prefixed_lines<W: Write + Send + 'static>(
+    reader: ShellPipeReader,
+    prefix: &str,
+    output: W,
+) -> JoinHandle<()> {
+    let mut writer = PrefixedLineWriter {
+        prefix: prefix.to_string(),
+        output,
+        buffer: Vec::new(),
+    };
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = reader.pipe_to(&mut writer).and_then(|_| writer.finish()) {
+            tracing::debug!("failed to forward task output: {err}");
+        }
+    })
+}
+
+/// A writer that writes complete lines to the underlying output, each prefixed
+/// with a fixed string. Writing whole lines at once makes sure the output of
+/// different writers is not interleaved within a line.
+struct PrefixedLineWriter<W> {
+    prefix: String,
+    output: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> PrefixedLineWriter<W> {
+    /// Writes a single line, including its line ending, to the output.
+    fn write_line(&mut self, line: &[u8]) -> std::io::Result<()> {
+        let mut prefixed = Vec::with_capacity(self.prefix.len() + 1 + line.len());
+        prefixed.extend_from_slice(self.prefix.as_bytes());
+        prefixed.push(b' ');
+        prefixed.extend_from_slice(line);
+        self.output.write_all(&prefixed)
+    }
+
+    /// Writes out the last line if it did not end with a newline.
+    fn finish(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            let mut line = std::mem::take(&mut self.buffer);
+            line.push(b'\n');
+            self.write_line(&line)?;
+        }
+        self.output.flush()
+    }
+}
+
+impl<W: Write> Write for PrefixedLineWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            self.write_line(&line)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
 /// Task specific environment variables.
 fn get_export_specific_task_env(task: &Task) -> String {
     // Append the environment variables if they don't exist
@@ -458,10 +603,58 @@ mod tests {
             additional_args: vec![],
         };
 
-        let script = executable_task.as_script().unwrap();
+        let script = executable_task.as_script().unwrap().unwrap();
         assert_eq!(script, "export \"FOO=bar\";\n\ntest ");
     }
 
+    #[test]
+    fn test_prefixed_line_writer() {
+        let mut writer = PrefixedLineWriter {
+            prefix: String::from("[build]"),
+            output: Vec::new(),
+            buffer: Vec::new(),
+        };
+        write!(writer, "first line\nsecond").unwrap();
+        write!(writer, " line\nlast").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.output).unwrap(),
+            "[build] first line\n[build] second line\n[build] last\n"
+        );
+    }
+
+    #[test]
+    fn test_as_script_with_args_placeholder() {
+        let file_contents = r#"
+            [tasks]
+            test = "pytest {{ args }} --verbose"
+            "#;
+        let manifest = Manifest::from_str(
+            Path::new("pixi.toml"),
+            format!("{PROJECT_BOILERPLATE}\n{file_contents}").as_str(),
+        )
+        .unwrap();
+
+        let project = Project::from_manifest(manifest);
+
+        let task = project
+            .default_environment()
+            .task(&TaskName::from("test"), None)
+            .unwrap();
+
+        let executable_task = ExecutableTask {
+            project: &project,
+            name: Some("test".into()),
+            task: Cow::Borrowed(task),
+            run_environment: project.default_environment(),
+            additional_args: vec!["-k".to_string(), "some test".to_string()],
+        };
+
+        let script = executable_task.as_script().unwrap().unwrap();
+        assert_eq!(script, "pytest '-k' 'some test' --verbose");
+    }
+
     #[tokio::test]
     async fn test_get_task_env() {
         let file_contents = r#"
@@ -66,95 +66,110 @@ impl FileHashes {
         root: &Path,
         filters: impl IntoIterator<Item = impl AsRef<str>>,
     ) -> Result<Self, FileHashesError> {
-        // If the root is not a directory or does not exist, return an empty map.
-        if !root.is_dir() {
-            return Ok(Self::default());
-        }
+        let files = walk_files(root, filters, compute_file_hash).await?;
+        Ok(Self { files })
+    }
+}
 
-        // Construct the custom filter
-        let mut ignore_builder = OverrideBuilder::new(root);
-        for ignore_line in filters {
-            let path = root.join(ignore_line.as_ref());
-            let mut pat = if ignore_line.as_ref().ends_with('/') {
-                format!("{}**", ignore_line.as_ref())
-            } else if path.exists() && path.is_dir() {
-                format!("{}/**", ignore_line.as_ref())
-            } else {
-                ignore_line.as_ref().to_owned()
-            };
+/// Applies `f` to all files in `root` that match the specified set of filters
+/// and returns the results by the path of the file relative to `root`.
+///
+/// The filters follow the same rules as for [`FileHashes::from_files`]. The
+/// traversal and the calls to `f` are parallelized over all available CPU
+/// cores.
+pub(crate) async fn walk_files<T: Send + 'static>(
+    root: &Path,
+    filters: impl IntoIterator<Item = impl AsRef<str>>,
+    f: fn(&Path) -> Result<T, FileHashesError>,
+) -> Result<HashMap<PathBuf, T>, FileHashesError> {
+    // If the root is not a directory or does not exist, return an empty map.
+    if !root.is_dir() {
+        return Ok(HashMap::default());
+    }
 
-            if pat.starts_with('!') && !pat.starts_with("!/") {
-                // make sure there is a `/` at the 2nd place so that the pattern reads
-                // `!/**/lib.rs` instead of `!**/lib.rs`
-                pat.insert(1, '/');
-            } else {
-                // Same for the others, make sure they start in the right folder
-                if !pat.starts_with('/') {
-                    pat.insert(0, '/');
-                }
+    // Construct the custom filter
+    let mut ignore_builder = OverrideBuilder::new(root);
+    for ignore_line in filters {
+        let path = root.join(ignore_line.as_ref());
+        let mut pat = if ignore_line.as_ref().ends_with('/') {
+            format!("{}**", ignore_line.as_ref())
+        } else if path.exists() && path.is_dir() {
+            format!("{}/**", ignore_line.as_ref())
+        } else {
+            ignore_line.as_ref().to_owned()
+        };
+
+        if pat.starts_with('!') && !pat.starts_with("!/") {
+            // make sure there is a `/` at the 2nd place so that the pattern reads
+            // `!/**/lib.rs` instead of `!**/lib.rs`
+            pat.insert(1, '/');
+        } else {
+            // Same for the others, make sure they start in the right folder
+            if !pat.starts_with('/') {
+                pat.insert(0, '/');
             }
-            ignore_builder.add(&pat)?;
         }
+        ignore_builder.add(&pat)?;
+    }
 
-        let filter = ignore_builder.build()?;
+    let filter = ignore_builder.build()?;
 
-        // Spawn a thread that will collect the results from a channel.
-        let (tx, rx) = crossbeam_channel::bounded(100);
-        let collect_handle =
-            tokio::task::spawn_blocking(move || rx.iter().collect::<Result<HashMap<_, _>, _>>());
+    // Spawn a thread that will collect the results from a channel.
+    let (tx, rx) = crossbeam_channel::bounded(100);
+    let collect_handle =
+        tokio::task::spawn_blocking(move || rx.iter().collect::<Result<HashMap<_, _>, _>>());
 
-        // Iterate over all entries in parallel and send them over a channel to the collection thread.
-        let collect_root = root.to_owned();
-        WalkBuilder::new(root)
-            .overrides(filter)
-            .hidden(false)
-            .git_ignore(false)
-            .git_global(false)
-            .git_exclude(false)
-            // Turn this back off as it can cause issues with symlinks:
-            // https://github.com/prefix-dev/pixi/issues/2196
-            // TODO: The current idea is to completely reimplement this without the `ignore` crate.
-            // .follow_links(true)
-            .build_parallel()
-            .run(|| {
-                let tx = tx.clone();
-                let collect_root = collect_root.clone();
-                Box::new(move |entry| {
-                    let result = match entry {
-                        Ok(entry) if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) => {
-                            return ignore::WalkState::Continue;
-                        }
-                        Ok(entry) => compute_file_hash(entry.path()).map(|hash| {
-                            let path = entry
-                                .path()
-                                .strip_prefix(&collect_root)
-                                .expect("path is not prefixed by the root");
-                            tracing::info!("Added hash for file: {:?}", path);
-                            (path.to_owned(), hash)
-                        }),
-                        Err(e) => Err(FileHashesError::from(e)),
-                    };
-                    match (result.is_err(), tx.send(result)) {
-                        (true, _) => ignore::WalkState::Quit,
-                        (_, Err(_)) => ignore::WalkState::Quit,
-                        _ => ignore::WalkState::Continue,
+    // Iterate over all entries in parallel and send them over a channel to the collection thread.
+    let collect_root = root.to_owned();
+    WalkBuilder::new(root)
+        .overrides(filter)
+        .hidden(false)
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        // Turn this back off as it can cause issues with symlinks:
+        // https://github.com/prefix-dev/pixi/issues/2196
+        // TODO: The current idea is to completely reimplement this without the `ignore` crate.
+        // .follow_links(true)
+        .build_parallel()
+        .run(|| {
+            let tx = tx.clone();
+            let collect_root = collect_root.clone();
+            Box::new(move |entry| {
+                let result = match entry {
+                    Ok(entry) if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) => {
+                        return ignore::WalkState::Continue;
                     }
-                })
-            });
+                    Ok(entry) => f(entry.path()).map(|value| {
+                        let path = entry
+                            .path()
+                            .strip_prefix(&collect_root)
+                            .expect("path is not prefixed by the root");
+                        tracing::info!("Added file: {:?}", path);
+                        (path.to_owned(), value)
+                    }),
+                    Err(e) => Err(FileHashesError::from(e)),
+                };
+                match (result.is_err(), tx.send(result)) {
+                    (true, _) => ignore::WalkState::Quit,
+                    (_, Err(_)) => ignore::WalkState::Quit,
+                    _ => ignore::WalkState::Continue,
+                }
+            })
+        });
 
-        // Drop the local handle to the channel. This will close the channel which in turn will
-        // cause the collection thread to finish which allows us to join without deadlocking.
-        drop(tx);
-        match collect_handle.await.map_err(JoinError::try_into_panic) {
-            Ok(files) => Ok(Self { files: files? }),
-            Err(Ok(panic)) => std::panic::resume_unwind(panic),
-            Err(Err(_)) => panic!("the task was cancelled"),
-        }
+    // Drop the local handle to the channel. This will close the channel which in turn will
+    // cause the collection thread to finish which allows us to join without deadlocking.
+    drop(tx);
+    match collect_handle.await.map_err(JoinError::try_into_panic) {
+        Ok(files) => files,
+        Err(Ok(panic)) => std::panic::resume_unwind(panic),
+        Err(Err(_)) => panic!("the task was cancelled"),
     }
 }
 
 /// Computes the xxh3 hash of a file.
-fn compute_file_hash(path: &Path) -> Result<String, FileHashesError> {
+pub(crate) fn compute_file_hash(path: &Path) -> Result<String, FileHashesError> {
     let mut file =
         BufReader::new(File::open(path).map_err(|e| FileHashesError::IoError(path.to_owned(), e))?);
     let mut hasher = Box::new(Xxh3::new());
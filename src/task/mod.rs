@@ -1,12 +1,17 @@
 mod error;
 mod executable_task;
 mod file_hashes;
+mod process_tree;
+mod sandbox;
 mod task_environment;
 mod task_graph;
 mod task_hash;
+mod watcher;
 
 pub use file_hashes::{FileHashes, FileHashesError};
 pub use pixi_manifest::{Task, TaskName};
+pub use process_tree::terminate_child_processes;
+pub use sandbox::SandboxError;
 pub use task_hash::{ComputationHash, EnvironmentHash, InputHashes, TaskHash};
 
 pub use executable_task::{
@@ -18,3 +23,4 @@ pub use task_environment::{
     TaskDisambiguation,
 };
 pub use task_graph::{TaskGraph, TaskGraphError, TaskId, TaskNode};
+pub use watcher::FileWatcher;
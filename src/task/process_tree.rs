@@ -0,0 +1,86 @@
+//! Terminates the processes that were spawned while executing tasks.
+//!
+//! Tasks are executed in-process by `deno_task_shell`, which does not stop the
+//! processes it spawned when the execution is cancelled. When `pixi run --watch`
+//! restarts the tasks these processes have to be stopped explicitly, otherwise
+//! a long-running process like a development server keeps running and holds on
+//! to its resources.
+
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, ProcessStatus, ProcessesToUpdate, Signal, System};
+
+/// The time the processes get to exit after they were asked to terminate,
+/// before they are killed.
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The interval at which is checked whether the processes have exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Terminates all processes that descend from this process and waits until
+/// they have exited.
+///
+/// The processes are first asked to terminate, processes that are still
+/// running after [`TERMINATE_TIMEOUT`] are killed.
+pub async fn terminate_child_processes() {
+    let Ok(current) = sysinfo::get_current_pid() else {
+        return;
+    };
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    let children = descendants(&system, current);
+    if children.is_empty() {
+        return;
+    }
+    tracing::debug!("terminating {} child processes", children.len());
+    for pid in &children {
+        if let Some(process) = system.process(*pid) {
+            // Not all platforms support signals, kill the process right away there.
+            if process.kill_with(Signal::Term).is_none() {
+                process.kill();
+            }
+        }
+    }
+
+    let mut deadline = Instant::now() + TERMINATE_TIMEOUT;
+    let mut killed = false;
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        system.refresh_processes(ProcessesToUpdate::Some(&children), true);
+        let mut running = children
+            .iter()
+            .filter_map(|pid| system.process(*pid))
+            .filter(|process| process.status() != ProcessStatus::Zombie)
+            .peekable();
+        if running.peek().is_none() {
+            return;
+        }
+        if Instant::now() >= deadline {
+            if killed {
+                tracing::warn!("child processes did not exit after they were killed");
+                return;
+            }
+            running.for_each(|process| {
+                process.kill();
+            });
+            killed = true;
+            deadline = Instant::now() + TERMINATE_TIMEOUT;
+        }
+    }
+}
+
+/// Returns all processes that directly or indirectly descend from `parent`.
+fn descendants(system: &System, parent: Pid) -> Vec<Pid> {
+    let mut result = Vec::new();
+    let mut stack = vec![parent];
+    while let Some(pid) = stack.pop() {
+        for (child, process) in system.processes() {
+            if process.parent() == Some(pid) && process.status() != ProcessStatus::Zombie {
+                result.push(*child);
+                stack.push(*child);
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,255 @@
+//! Executes task commands in a sandbox using the primitives of the operating
+//! system.
+//!
+//! On Linux the network is disabled by running the command in a new network
+//! namespace with `unshare`, filesystem restrictions are implemented with
+//! `bwrap` (bubblewrap). On macOS both are implemented with a `sandbox-exec`
+//! profile. Inside the sandbox the command is executed by `pixi` itself with
+//! the same shell that is used for tasks that are not sandboxed.
+
+use std::path::Path;
+
+use itertools::Itertools;
+use miette::Diagnostic;
+use pixi_manifest::task::Sandbox;
+use thiserror::Error;
+
+/// The hidden `pixi` subcommand that runs a script with the task shell.
+pub(crate) const RUN_SCRIPT_COMMAND: &str = "run-script";
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum SandboxError {
+    #[error("the '{0}' sandbox is not supported on this platform")]
+    #[diagnostic(help("sandboxed tasks are only supported on Linux and macOS"))]
+    Unsupported(Sandbox),
+
+    #[error("the '{sandbox}' sandbox requires unprivileged user namespaces, but {reason}")]
+    #[diagnostic(help(
+        "allow unprivileged user namespaces on this machine or remove the sandbox from the task"
+    ))]
+    UserNamespacesDisabled { sandbox: Sandbox, reason: String },
+
+    #[error("failed to determine the path of the pixi executable")]
+    CurrentExe(#[source] std::io::Error),
+}
+
+/// Wraps the command such that it is executed in the given sandbox. Writing to
+/// the `project_root` is always allowed.
+pub(crate) fn sandboxed_command(
+    sandbox: Sandbox,
+    command: &str,
+    project_root: &Path,
+) -> Result<String, SandboxError> {
+    let launcher = sandbox_launcher(sandbox, project_root)?;
+    let pixi = std::env::current_exe().map_err(SandboxError::CurrentExe)?;
+    Ok(launcher
+        .iter()
+        .map(String::as_str)
+        .chain([
+            pixi.to_string_lossy().as_ref(),
+            RUN_SCRIPT_COMMAND,
+            "--",
+            command,
+        ])
+        .map(single_quote)
+        .join(" "))
+}
+
+/// Returns the reason why unprivileged user namespaces cannot be created, or
+/// `None` if they are available. `proc_sys` is the path of `/proc/sys`.
+#[cfg(any(target_os = "linux", test))]
+fn user_namespaces_unavailable(proc_sys: &Path) -> Option<String> {
+    let read = |path: &str| {
+        fs_err::read_to_string(proc_sys.join(path))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+
+    if read("user/max_user_namespaces").as_deref() == Some("0") {
+        return Some("`user.max_user_namespaces` is set to 0".to_string());
+    }
+    if read("kernel/unprivileged_userns_clone").as_deref() == Some("0") {
+        return Some("`kernel.unprivileged_userns_clone` is set to 0".to_string());
+    }
+    if read("kernel/apparmor_restrict_unprivileged_userns").as_deref() == Some("1") {
+        return Some(
+            "AppArmor restricts them (`kernel.apparmor_restrict_unprivileged_userns` is set to 1)"
+                .to_string(),
+        );
+    }
+    None
+}
+
+/// Returns the arguments that launch a command in the sandbox.
+#[cfg(target_os = "linux")]
+fn sandbox_launcher(sandbox: Sandbox, project_root: &Path) -> Result<Vec<String>, SandboxError> {
+    // Both `unshare --user` and an unprivileged `bwrap` create a user namespace.
+    if let Some(reason) = user_namespaces_unavailable(Path::new("/proc/sys")) {
+        return Err(SandboxError::UserNamespacesDisabled { sandbox, reason });
+    }
+
+    if !sandbox.restricts_filesystem() {
+        return Ok(["unshare", "--user", "--map-root-user", "--net", "--"]
+            .map(String::from)
+            .to_vec());
+    }
+
+    let root = project_root.to_string_lossy().into_owned();
+    let temp_dir = std::env::temp_dir().to_string_lossy().into_owned();
+    let mut args = vec![
+        "bwrap".to_string(),
+        "--ro-bind".to_string(),
+        "/".to_string(),
+        "/".to_string(),
+        "--dev-bind".to_string(),
+        "/dev".to_string(),
+        "/dev".to_string(),
+        "--proc".to_string(),
+        "/proc".to_string(),
+        "--bind".to_string(),
+        temp_dir.clone(),
+        temp_dir,
+        "--bind".to_string(),
+        root.clone(),
+        root,
+    ];
+    if sandbox.denies_network() {
+        args.push("--unshare-net".to_string());
+    }
+    args.push("--".to_string());
+    Ok(args)
+}
+
+/// Returns the arguments that launch a command in the sandbox.
+#[cfg(target_os = "macos")]
+fn sandbox_launcher(sandbox: Sandbox, project_root: &Path) -> Result<Vec<String>, SandboxError> {
+    Ok(vec![
+        "sandbox-exec".to_string(),
+        "-p".to_string(),
+        macos_sandbox_profile(sandbox, project_root),
+    ])
+}
+
+/// Returns the arguments that launch a command in the sandbox.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn sandbox_launcher(sandbox: Sandbox, _project_root: &Path) -> Result<Vec<String>, SandboxError> {
+    Err(SandboxError::Unsupported(sandbox))
+}
+
+/// Constructs a `sandbox-exec` profile that implements the restrictions of the
+/// sandbox.
+#[cfg(any(target_os = "macos", test))]
+fn macos_sandbox_profile(sandbox: Sandbox, project_root: &Path) -> String {
+    let mut profile = String::from("(version 1)\n(allow default)\n");
+    if sandbox.denies_network() {
+        profile.push_str("(deny network*)\n");
+    }
+    if sandbox.restricts_filesystem() {
+        let writable = [
+            project_root.to_string_lossy().into_owned(),
+            std::env::temp_dir().to_string_lossy().into_owned(),
+            "/private/tmp".to_string(),
+            "/private/var/folders".to_string(),
+            "/dev".to_string(),
+        ];
+        profile.push_str("(deny file-write*)\n(allow file-write*");
+        for path in writable {
+            profile.push_str(&format!(" (subpath {:?})", path));
+        }
+        profile.push_str(")\n");
+    }
+    profile
+}
+
+/// Quotes an argument with single quotes so it is passed verbatim.
+fn single_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r#"'"'"'"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_quote() {
+        assert_eq!(single_quote("echo hello"), "'echo hello'");
+        assert_eq!(single_quote("it's"), r#"'it'"'"'s'"#);
+    }
+
+    #[test]
+    fn test_macos_sandbox_profile() {
+        let root = Path::new("/home/user/project");
+
+        let profile = macos_sandbox_profile(Sandbox::NetworkOff, root);
+        assert!(profile.contains("(deny network*)"));
+        assert!(!profile.contains("(deny file-write*)"));
+
+        let profile = macos_sandbox_profile(Sandbox::RestrictedFs, root);
+        assert!(!profile.contains("(deny network*)"));
+        assert!(profile.contains("(deny file-write*)"));
+        assert!(profile.contains(r#"(subpath "/home/user/project")"#));
+
+        let profile = macos_sandbox_profile(Sandbox::Strict, root);
+        assert!(profile.contains("(deny network*)"));
+        assert!(profile.contains("(deny file-write*)"));
+    }
+
+    #[test]
+    fn test_user_namespaces_unavailable() {
+        let proc_sys = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(proc_sys.path().join("user")).unwrap();
+        fs_err::create_dir_all(proc_sys.path().join("kernel")).unwrap();
+        assert_eq!(user_namespaces_unavailable(proc_sys.path()), None);
+
+        fs_err::write(
+            proc_sys
+                .path()
+                .join("kernel/apparmor_restrict_unprivileged_userns"),
+            "1\n",
+        )
+        .unwrap();
+        assert!(user_namespaces_unavailable(proc_sys.path())
+            .unwrap()
+            .contains("AppArmor"));
+
+        fs_err::write(proc_sys.path().join("user/max_user_namespaces"), "0\n").unwrap();
+        assert!(user_namespaces_unavailable(proc_sys.path())
+            .unwrap()
+            .contains("max_user_namespaces"));
+
+        fs_err::write(proc_sys.path().join("user/max_user_namespaces"), "63704\n").unwrap();
+        fs_err::write(
+            proc_sys
+                .path()
+                .join("kernel/apparmor_restrict_unprivileged_userns"),
+            "0\n",
+        )
+        .unwrap();
+        assert_eq!(user_namespaces_unavailable(proc_sys.path()), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "requires unprivileged user namespaces"]
+    fn test_sandboxed_command() {
+        if let Some(reason) = user_namespaces_unavailable(Path::new("/proc/sys")) {
+            panic!("user namespaces are unavailable: {reason}");
+        }
+        let root = Path::new("/home/user/project");
+        let pixi = single_quote(&std::env::current_exe().unwrap().to_string_lossy());
+
+        // The command is executed by pixi, not by `sh`
+        let command = sandboxed_command(Sandbox::NetworkOff, "curl $URL", root).unwrap();
+        assert_eq!(
+            command,
+            format!("'unshare' '--user' '--map-root-user' '--net' '--' {pixi} 'run-script' '--' 'curl $URL'")
+        );
+
+        let command = sandboxed_command(Sandbox::Strict, "make", root).unwrap();
+        assert!(command.starts_with("'bwrap' '--ro-bind' '/' '/'"));
+        assert!(command.contains("'--bind' '/home/user/project' '/home/user/project'"));
+        assert!(command.ends_with(&format!(
+            "'--unshare-net' '--' {pixi} 'run-script' '--' 'make'"
+        )));
+    }
+}
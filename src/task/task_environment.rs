@@ -62,6 +62,7 @@ impl<'p> From<AmbiguousTask<'p>> for AmbiguousTaskError {
     fn from(value: AmbiguousTask<'p>) -> Self {
         Self {
             task_name: value.task_name,
+            depended_on_by: value.depended_on_by.map(|(name, _)| name),
             environments: value
                 .environments
                 .into_iter()
@@ -368,6 +369,12 @@ mod tests {
         let result = search.find_task("bla".into(), FindTaskSource::CmdArgs);
         // Ambiguous task because it is the same name and code but it is defined in
         // different environments
-        assert!(matches!(result, Err(FindTaskError::AmbiguousTask(_))));
+        let Err(FindTaskError::AmbiguousTask(err)) = result else {
+            panic!("expected an ambiguous task");
+        };
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            "specify the environment to run the task in with the '--environment' flag:\n\n\tpixi run --environment default bla\n\tpixi run --environment other bla"
+        );
     }
 }
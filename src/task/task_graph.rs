@@ -83,6 +83,13 @@ impl<'p> TaskNode<'p> {
     pub(crate) fn full_command(&self) -> Option<String> {
         let mut cmd = self.task.as_single_command()?.to_string();
 
+        if let Some(cmd) = super::executable_task::substitute_args_placeholder(
+            &cmd,
+            &self.format_additional_args().to_string(),
+        ) {
+            return Some(cmd);
+        }
+
         if !self.additional_args.is_empty() {
             // Pass each additional argument varbatim by wrapping it in single quotes
             cmd.push_str(&format!(" {}", self.format_additional_args()));
@@ -165,11 +172,19 @@ impl<'p> TaskGraph<'p> {
                         Some(explicit_env) if task_env.is_default() => explicit_env,
                         _ => task_env,
                     };
+                    let name = args.remove(0);
+
+                    // Allow separating the arguments that are passed to the task with `--`,
+                    // e.g. `pixi run test -- -k some_test`.
+                    if args.first().map(String::as_str) == Some("--") {
+                        args.remove(0);
+                    }
+
                     return Self::from_root(
                         project,
                         search_envs,
                         TaskNode {
-                            name: Some(args.remove(0).into()),
+                            name: Some(name.into()),
                             task: Cow::Borrowed(task),
                             run_environment: run_env,
                             additional_args: args,
@@ -405,6 +420,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_additional_args() {
+        let project = r#"
+        [project]
+        name = "pixi"
+        channels = ["conda-forge"]
+        platforms = ["linux-64", "osx-64", "win-64", "osx-arm64"]
+        [tasks]
+        test = "pytest"
+        template = "pytest {{ args }} --verbose"
+    "#;
+        assert_eq!(
+            commands_in_order(project, &["test", "--", "-k", "some_test"], None, None),
+            vec!["pytest '-k' 'some_test'"]
+        );
+        assert_eq!(
+            commands_in_order(project, &["template", "-k", "some_test"], None, None),
+            vec!["pytest '-k' 'some_test' --verbose"]
+        );
+        assert_eq!(
+            commands_in_order(project, &["template"], None, None),
+            vec!["pytest  --verbose"]
+        );
+    }
+
     #[test]
     fn test_cycle_ordered_commands() {
         assert_eq!(
@@ -54,13 +54,19 @@ impl EnvironmentHash {
             value.hash(&mut hasher);
         }
 
-        // Hash the activation scripts
+        // Hash the activation scripts, including their contents so that editing a
+        // script invalidates the hash.
         let activation_scripts =
             run_environment.activation_scripts(Some(run_environment.best_platform()));
         for script in activation_scripts {
+            let contents = fs_err::read(run_environment.project().root().join(&script)).ok();
             script.hash(&mut hasher);
+            contents.hash(&mut hasher);
         }
 
+        // Hash the script directory that is added to the `PATH`
+        run_environment.project().script_dir().hash(&mut hasher);
+
         // Hash the environment variables
         let project_activation_env =
             run_environment.activation_env(Some(run_environment.best_platform()));
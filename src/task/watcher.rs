@@ -0,0 +1,233 @@
+//! Implements the file watching used by `pixi run --watch`.
+//!
+//! The watcher periodically lists all files that match a set of globs and
+//! compares their modification time and size with the previous snapshot. Only
+//! the files for which these changed are hashed again, such that a change to
+//! the timestamp alone is not reported and polling stays cheap for large
+//! inputs. Because editors and build tools often write multiple files in quick
+//! succession, a change is only reported after the files have not changed for
+//! the debounce duration.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use super::{
+    file_hashes::{compute_file_hash, walk_files},
+    FileHashesError,
+};
+
+/// The interval at which the files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The duration the files have to be stable before a change is reported.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(300);
+
+/// The modification time and size of a file, used to cheaply determine whether
+/// the file might have changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn from_path(path: &Path) -> Result<Self, FileHashesError> {
+        let metadata =
+            std::fs::metadata(path).map_err(|e| FileHashesError::IoError(path.to_owned(), e))?;
+        Ok(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// The state of a single watched file.
+#[derive(Debug, Clone)]
+struct FileState {
+    stamp: FileStamp,
+    hash: String,
+}
+
+/// The state of all watched files.
+type Snapshot = HashMap<PathBuf, FileState>;
+
+/// Watches the files matching a set of globs for changes.
+#[derive(Debug)]
+pub struct FileWatcher {
+    root: PathBuf,
+    globs: Vec<String>,
+    poll_interval: Duration,
+    debounce: Duration,
+    snapshot: Snapshot,
+}
+
+impl FileWatcher {
+    /// Constructs a new watcher for the files in `root` that match the `globs`.
+    /// The globs follow the same rules as the `inputs` of a task.
+    ///
+    /// The current state of the files is used as the initial snapshot.
+    pub async fn new(
+        root: &Path,
+        globs: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self, FileHashesError> {
+        let mut watcher = Self {
+            root: root.to_path_buf(),
+            globs: globs.into_iter().map(Into::into).collect(),
+            poll_interval: POLL_INTERVAL,
+            debounce: DEBOUNCE_DURATION,
+            snapshot: Snapshot::new(),
+        };
+        watcher.snapshot = watcher.compute_snapshot(&Snapshot::new()).await?;
+        Ok(watcher)
+    }
+
+    /// Sets the interval at which the files are checked for changes.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Sets the duration the files have to be stable before a change is
+    /// reported.
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        Self { debounce, ..self }
+    }
+
+    /// Returns the globs that are watched.
+    pub fn globs(&self) -> &[String] {
+        &self.globs
+    }
+
+    /// Waits until the watched files have changed compared to the last
+    /// snapshot and have settled. The new state becomes the snapshot that
+    /// subsequent calls compare against.
+    ///
+    /// This function is cancel safe, dropping the future keeps the previous
+    /// snapshot.
+    pub async fn changed(&mut self) -> Result<(), FileHashesError> {
+        // Wait for the first change.
+        let mut current = loop {
+            tokio::time::sleep(self.poll_interval).await;
+            let current = self.compute_snapshot(&self.snapshot).await?;
+            if !same_contents(&current, &self.snapshot) {
+                break current;
+            }
+            // Remember the new timestamps to avoid hashing the files again.
+            self.snapshot = current;
+        };
+
+        // Wait until the files are no longer changing.
+        loop {
+            tokio::time::sleep(self.debounce).await;
+            let next = self.compute_snapshot(&current).await?;
+            let settled = same_contents(&next, &current);
+            current = next;
+            if settled {
+                break;
+            }
+        }
+
+        self.snapshot = current;
+        Ok(())
+    }
+
+    /// Determines the state of all watched files. The hash of a file is only
+    /// computed if its modification time or size differs from the `previous`
+    /// snapshot.
+    async fn compute_snapshot(&self, previous: &Snapshot) -> Result<Snapshot, FileHashesError> {
+        let stamps = walk_files(&self.root, self.globs.iter(), FileStamp::from_path).await?;
+
+        let mut snapshot = Snapshot::with_capacity(stamps.len());
+        let mut changed = Vec::new();
+        for (path, stamp) in stamps {
+            match previous.get(&path) {
+                Some(state) if state.stamp == stamp => {
+                    snapshot.insert(path, state.clone());
+                }
+                _ => changed.push((path, stamp)),
+            }
+        }
+
+        if !changed.is_empty() {
+            let root = self.root.clone();
+            let hashed = tokio::task::spawn_blocking(move || {
+                changed
+                    .into_iter()
+                    .map(|(path, stamp)| {
+                        let hash = compute_file_hash(&root.join(&path))?;
+                        Ok((path, FileState { stamp, hash }))
+                    })
+                    .collect::<Result<Vec<_>, FileHashesError>>()
+            })
+            .await
+            .map_err(|e| FileHashesError::IoError(self.root.clone(), e.into()))??;
+            snapshot.extend(hashed);
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Returns true if both snapshots contain the same files with the same
+/// contents.
+fn same_contents(a: &Snapshot, b: &Snapshot) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(path, state)| b.get(path).is_some_and(|other| other.hash == state.hash))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fs_err::{create_dir, write};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn detects_changes() {
+        let target_dir = tempdir().unwrap();
+        create_dir(target_dir.path().join("src")).unwrap();
+        write(target_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        write(target_dir.path().join("README.md"), "# readme").unwrap();
+
+        let mut watcher = FileWatcher::new(target_dir.path(), ["src/"])
+            .await
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10))
+            .with_debounce(Duration::from_millis(10));
+
+        // Changing a file that is not watched should not trigger the watcher.
+        write(target_dir.path().join("README.md"), "# changed").unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), watcher.changed())
+                .await
+                .is_err()
+        );
+
+        // Writing the same contents to a watched file should not trigger the watcher.
+        write(target_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), watcher.changed())
+                .await
+                .is_err()
+        );
+
+        // Changing a watched file should trigger the watcher.
+        write(target_dir.path().join("src/main.rs"), "fn main() { }").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), watcher.changed())
+            .await
+            .expect("the change should be detected")
+            .unwrap();
+
+        // Adding a watched file should trigger the watcher.
+        write(target_dir.path().join("src/lib.rs"), "").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), watcher.changed())
+            .await
+            .expect("the new file should be detected")
+            .unwrap();
+    }
+}
@@ -624,6 +624,7 @@ version = {MANIFEST_VERSION}
 [envs.dummy-a]
 channels = ["{dummy_channel_1}"]
 dependencies = {{ dummy-a = "*", dummy-b = "*", dummy-c = "*" }}
+injected = ["dummy-b", "dummy-c"]
 exposed = {{ dummy-a = "dummy-a", dummy-aa = "dummy-aa" }}
 """
     actual_manifest = manifest.read_text()
@@ -670,6 +671,7 @@ version = {MANIFEST_VERSION}
 [envs.dummy]
 channels = ["{dummy_channel_1}"]
 dependencies = {{ dummy-a = "*", dummy-b = "*" }}
+injected = ["dummy-b"]
 exposed = {{ dummy-a = "dummy-a", dummy-aa = "dummy-aa" }}
 """
     actual_manifest = manifest.read_text()
@@ -716,6 +718,7 @@ version = {MANIFEST_VERSION}
 [envs.dummy]
 channels = ["{dummy_channel_1}"]
 dependencies = {{ dummy-a = "*", dummy-b = "*" }}
+injected = ["dummy-b"]
 exposed = {{ dummy-b = "dummy-b" }}
 """
     actual_manifest = manifest.read_text()
@@ -1773,6 +1776,53 @@ def test_add(pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str) -> None
     assert dummy_b.is_file()
 
 
+
+def test_inject(pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str) -> None:
+    env = {"PIXI_HOME": str(tmp_pixi_workspace)}
+    manifests = tmp_pixi_workspace.joinpath("manifests")
+    manifest = manifests.joinpath("pixi-global.toml")
+
+    # Can't inject package into environment that doesn't exist
+    verify_cli_command(
+        [pixi, "global", "inject", "--environment", "dummy-a", "dummy-b"],
+        ExitCode.FAILURE,
+        env=env,
+        stderr_contains="Environment dummy-a doesn't exist",
+    )
+
+    verify_cli_command(
+        [pixi, "global", "install", "--channel", dummy_channel_1, "dummy-a"],
+        env=env,
+    )
+    dummy_a = tmp_pixi_workspace / "bin" / exec_extension("dummy-a")
+    assert dummy_a.is_file()
+
+    verify_cli_command(
+        [pixi, "global", "inject", "--environment", "dummy-a", "dummy-b"],
+        env=env,
+        stderr_contains="Added package dummy-b",
+    )
+    # Make sure it doesn't expose a binary from this package
+    dummy_b = tmp_pixi_workspace / "bin" / exec_extension("dummy-b")
+    assert not dummy_b.is_file()
+
+    expected_manifest = f"""\
+version = {MANIFEST_VERSION}
+
+[envs.dummy-a]
+channels = ["{dummy_channel_1}"]
+dependencies = {{ dummy-a = "*", dummy-b = "*" }}
+exposed = {{ dummy-a = "dummy-a", dummy-aa = "dummy-aa" }}
+injected = ["dummy-b"]
+"""
+    assert manifest.read_text() == expected_manifest
+
+    # Injected packages are also not exposed when the environment is updated
+    verify_cli_command([pixi, "global", "update", "dummy-a"], env=env)
+    assert dummy_a.is_file()
+    assert not dummy_b.is_file()
+
+
 def test_remove_dependency(pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str) -> None:
     env = {"PIXI_HOME": str(tmp_pixi_workspace)}
 
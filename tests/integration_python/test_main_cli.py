@@ -444,6 +444,43 @@ def test_upgrade_json_output(
     assert data["environment"]["default"]
 
 
+def test_upgrade_json_events(
+    pixi: Path, tmp_pixi_workspace: Path, multiple_versions_channel_1: str
+) -> None:
+    manifest_path = tmp_pixi_workspace / "pixi.toml"
+
+    # Create a new project
+    verify_cli_command([pixi, "init", "--channel", multiple_versions_channel_1, tmp_pixi_workspace])
+
+    # Add package pinned to version 0.1.0
+    verify_cli_command([pixi, "add", "--manifest-path", manifest_path, "package==0.1.0"])
+
+    # Every line is an event and the result is the same as the --json output
+    result = verify_cli_command(
+        [pixi, "upgrade", "--manifest-path", manifest_path, "--json-events"],
+    )
+    events = [json.loads(line) for line in result.stdout.splitlines()]
+    assert any(event["event"] == "progress-started" for event in events)
+    data = next(event["data"] for event in events if event["event"] == "result")
+    assert data["environment"]["default"]
+
+    # Errors are reported as an event as well
+    result = verify_cli_command(
+        [
+            pixi,
+            "upgrade",
+            "--manifest-path",
+            manifest_path,
+            "--json-events",
+            "--feature",
+            "unknown",
+        ],
+        ExitCode.FAILURE,
+    )
+    events = [json.loads(line) for line in result.stdout.splitlines()]
+    assert events[-1]["event"] == "error"
+
+
 def test_upgrade_dryrun(
     pixi: Path, tmp_pixi_workspace: Path, multiple_versions_channel_1: str
 ) -> None:
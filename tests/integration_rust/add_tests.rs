@@ -5,11 +5,13 @@ use crate::common::{
     package_database::{Package, PackageDatabase},
     LockFileExt, PixiControl,
 };
+use indexmap::IndexMap;
 use pixi::{DependencyType, Project};
+use pixi_config::PinningStrategy;
 use pixi_consts::consts;
 use pixi_manifest::pypi::VersionOrStar;
-use pixi_manifest::{pypi::PyPiPackageName, FeaturesExt, PyPiRequirement, SpecType};
-use rattler_conda_types::{PackageName, Platform};
+use pixi_manifest::{pypi::PyPiPackageName, FeatureName, FeaturesExt, PyPiRequirement, SpecType};
+use rattler_conda_types::{MatchSpec, PackageName, ParseStrictness, Platform};
 use tempfile::TempDir;
 
 /// Test add functionality for different types of packages.
@@ -80,7 +82,12 @@ async fn add_functionality() {
 async fn add_with_channel() {
     let pixi = PixiControl::new().unwrap();
 
-    pixi.init().no_fast_prefix_overwrite(true).await.unwrap();
+    pixi.init()
+        .no_fast_prefix_overwrite(true)
+        .with_channel("conda-forge")
+        .with_channel("https://prefix.dev/conda-forge")
+        .await
+        .unwrap();
 
     pixi.add("conda-forge::py_rattler")
         .without_lockfile_update()
@@ -113,6 +120,172 @@ async fn add_with_channel() {
     );
 }
 
+/// Test that adding a package from a channel that is not used by the project
+/// fails before the manifest is modified
+#[tokio::test]
+async fn add_with_unknown_channel() {
+    let pixi = PixiControl::new().unwrap();
+
+    pixi.init().with_channel("conda-forge").await.unwrap();
+    let manifest_before = fs_err::read_to_string(pixi.manifest_path()).unwrap();
+
+    let err = pixi
+        .add("bioconda::samtools")
+        .without_lockfile_update()
+        .await
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("the dependency 'samtools' requests the channel"));
+    assert!(message.contains("which is not one of the channels"));
+
+    assert_eq!(
+        fs_err::read_to_string(pixi.manifest_path()).unwrap(),
+        manifest_before
+    );
+}
+
+/// Test that adding a package that is not in the channels fails before the
+/// manifest is modified
+#[tokio::test]
+async fn add_missing_package() {
+    let mut package_database = PackageDatabase::default();
+    package_database.add_package(Package::build("rattler", "1").finish());
+    let local_channel = package_database.into_channel().await.unwrap();
+
+    let pixi = PixiControl::new().unwrap();
+    pixi.init().with_channel(local_channel.url()).await.unwrap();
+    let manifest_before = fs_err::read_to_string(pixi.manifest_path()).unwrap();
+
+    let err = pixi.add("ratler").await.unwrap_err();
+    assert!(err.to_string().contains(&format!(
+        "no package matches 'ratler' for {}",
+        Platform::current()
+    )));
+
+    // A version that doesn't exist is also reported
+    let err = pixi.add("rattler>=2").await.unwrap_err();
+    assert!(err.to_string().contains("no package matches 'rattler >=2'"));
+
+    assert_eq!(
+        fs_err::read_to_string(pixi.manifest_path()).unwrap(),
+        manifest_before
+    );
+}
+
+/// Test that adding a package that is only available for some of the
+/// platforms of the project reports the platforms that lack it
+#[tokio::test]
+async fn add_package_missing_on_platform() {
+    let mut package_database = PackageDatabase::default();
+    package_database.add_package(
+        Package::build("rattler", "1")
+            .with_subdir(Platform::Linux64)
+            .finish(),
+    );
+    package_database.add_package(
+        Package::build("other", "1")
+            .with_subdir(Platform::OsxArm64)
+            .finish(),
+    );
+    let local_channel = package_database.into_channel().await.unwrap();
+
+    let pixi = PixiControl::new().unwrap();
+    pixi.init_with_platforms(vec![
+        Platform::Linux64.to_string(),
+        Platform::OsxArm64.to_string(),
+    ])
+    .with_channel(local_channel.url())
+    .await
+    .unwrap();
+    let manifest_before = fs_err::read_to_string(pixi.manifest_path()).unwrap();
+
+    let err = pixi.add("rattler").await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("no package matches 'rattler' for osx-arm64"));
+    assert_eq!(
+        fs_err::read_to_string(pixi.manifest_path()).unwrap(),
+        manifest_before
+    );
+
+    // Adding it for the platform that has it works
+    pixi.add("rattler")
+        .set_platforms(&[Platform::Linux64])
+        .await
+        .unwrap();
+    let lock = pixi.lock_file().await.unwrap();
+    assert!(lock.contains_match_spec(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        Platform::Linux64,
+        "rattler==1"
+    ));
+}
+
+/// Test that the packages are not checked against the channels when the
+/// lock-file is not updated
+#[tokio::test]
+async fn add_frozen_skips_package_check() {
+    let package_database = PackageDatabase::default();
+    let local_channel = package_database.into_channel().await.unwrap();
+
+    let pixi = PixiControl::new().unwrap();
+    pixi.init().with_channel(local_channel.url()).await.unwrap();
+
+    pixi.add("rattler").with_frozen().await.unwrap();
+    pixi.add("ratler").without_lockfile_update().await.unwrap();
+
+    let project = pixi.project().unwrap();
+    let dependencies = project
+        .manifest()
+        .default_feature()
+        .dependencies(SpecType::Run, None)
+        .unwrap_or_default();
+    assert!(dependencies.contains_key(&PackageName::from_str("rattler").unwrap()));
+    assert!(dependencies.contains_key(&PackageName::from_str("ratler").unwrap()));
+}
+
+/// Test that the versions the added packages were solved to are reported
+#[tokio::test]
+async fn add_reports_solved_version() {
+    let mut package_database = PackageDatabase::default();
+    package_database.add_package(Package::build("rattler", "1").finish());
+    package_database.add_package(Package::build("rattler", "2.1").finish());
+    let local_channel = package_database.into_channel().await.unwrap();
+
+    let pixi = PixiControl::new().unwrap();
+    pixi.init().with_channel(local_channel.url()).await.unwrap();
+
+    let prefix_update_config = pixi.add("rattler").args.prefix_update_config;
+    let mut project = pixi.project().unwrap();
+    let update_deps = project
+        .update_dependencies(
+            IndexMap::from([(
+                PackageName::from_str("rattler").unwrap(),
+                (
+                    MatchSpec::from_str("rattler", ParseStrictness::Strict).unwrap(),
+                    SpecType::Run,
+                ),
+            )]),
+            IndexMap::new(),
+            &prefix_update_config,
+            &FeatureName::Default,
+            &[],
+            false,
+            false,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        update_deps
+            .solved_versions
+            .get("rattler")
+            .map(String::as_str),
+        Some("2.1")
+    );
+}
+
 /// Test that we get the union of all packages in the lockfile for the run,
 /// build and host
 #[tokio::test]
@@ -646,3 +819,44 @@ async fn add_dependency_pinning_strategy() {
     // Package should be automatically pinned to a major version
     assert_eq!(bar_spec, r#"">=1,<2""#);
 }
+
+#[tokio::test]
+async fn add_dependency_pinning_strategy_override() {
+    // Create a channel with two packages
+    let mut package_database = PackageDatabase::default();
+    package_database.add_package(Package::build("foo", "1.2.3").finish());
+    package_database.add_package(Package::build("bar", "1.2.3").finish());
+
+    let local_channel = package_database.into_channel().await.unwrap();
+
+    // Initialize a new pixi project using the above channel
+    let pixi = PixiControl::new().unwrap();
+    pixi.init().with_channel(local_channel.url()).await.unwrap();
+
+    // Add the packages with a pinning strategy that differs from the config
+    pixi.add("foo")
+        .with_pinning_strategy(PinningStrategy::Minor)
+        .await
+        .unwrap();
+    pixi.add("bar")
+        .with_pinning_strategy(PinningStrategy::ExactVersion)
+        .await
+        .unwrap();
+
+    let project = pixi.project().unwrap();
+    let dependencies = project
+        .manifest()
+        .default_feature()
+        .dependencies(SpecType::Run, None)
+        .unwrap_or_default();
+    let spec = |name: &str| {
+        dependencies
+            .get(name)
+            .cloned()
+            .unwrap()
+            .to_toml_value()
+            .to_string()
+    };
+    assert_eq!(spec("foo"), r#"">=1.2.3,<1.3""#);
+    assert_eq!(spec("bar"), r#""==1.2.3""#);
+}
@@ -40,6 +40,7 @@ use pixi::{
     task::TaskName,
     DependencyType,
 };
+use pixi_config::PinningStrategy;
 use pixi_manifest::{EnvironmentName, FeatureName, SpecType};
 use rattler_conda_types::{NamedChannelOrUrl, Platform, RepoDataRecord};
 use url::Url;
@@ -199,6 +200,16 @@ impl AddBuilder {
         self.args.dependency_config.feature = FeatureName::Named(feature.to_string());
         self
     }
+
+    pub fn with_frozen(mut self) -> Self {
+        self.args.prefix_update_config.lock_file_usage.frozen = true;
+        self
+    }
+
+    pub fn with_pinning_strategy(mut self, pinning_strategy: PinningStrategy) -> Self {
+        self.args.pinning_strategy = Some(pinning_strategy);
+        self
+    }
 }
 
 impl HasDependencyConfig for AddBuilder {
@@ -246,6 +257,13 @@ pub struct RemoveBuilder {
     pub args: remove::Args,
 }
 
+impl RemoveBuilder {
+    pub fn with_feature(mut self, feature: impl ToString) -> Self {
+        self.args.dependency_config.feature = FeatureName::Named(feature.to_string());
+        self
+    }
+}
+
 impl HasDependencyConfig for RemoveBuilder {
     fn dependency_config(&mut self) -> &mut DependencyConfig {
         &mut self.args.dependency_config
@@ -413,6 +431,13 @@ impl InstallBuilder {
         self.args.lock_file_usage.frozen = true;
         self
     }
+    pub fn with_environment(mut self, env: impl ToString) -> Self {
+        self.args
+            .environment
+            .get_or_insert_with(Vec::new)
+            .push(env.to_string());
+        self
+    }
 }
 
 impl IntoFuture for InstallBuilder {
@@ -297,6 +297,7 @@ impl PixiControl {
                 format: None,
                 pyproject_toml: false,
                 scm: Some(GitAttributes::Github),
+                template: None,
             },
         }
     }
@@ -315,6 +316,7 @@ impl PixiControl {
                 format: None,
                 pyproject_toml: false,
                 scm: Some(GitAttributes::Github),
+                template: None,
             },
         }
     }
@@ -342,6 +344,8 @@ impl PixiControl {
                     revalidate: false,
                 },
                 editable: false,
+                json: false,
+                pinning_strategy: None,
             },
         }
     }
@@ -528,6 +532,9 @@ impl PixiControl {
                 },
                 config: Default::default(),
                 all: false,
+                reproducible: false,
+                repair: false,
+                json: false,
             },
         }
     }
@@ -545,6 +552,7 @@ impl PixiControl {
                 dry_run: false,
                 specs: Default::default(),
                 json: false,
+                explain: false,
             },
         }
     }
@@ -599,6 +607,8 @@ impl TasksControl<'_> {
                 env: Default::default(),
                 description: None,
                 clean_env: false,
+                retries: None,
+                retry_backoff: None,
             },
         }
     }
@@ -630,6 +640,7 @@ impl TasksControl<'_> {
                 platform,
                 alias: name,
                 depends_on: vec![],
+                feature: None,
                 description: None,
             },
         }
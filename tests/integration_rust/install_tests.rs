@@ -761,3 +761,60 @@ async fn test_ensure_gitignore_file_creation() {
         ".pixi/.gitignore file does not contain the expected content"
     );
 }
+
+/// Removing a dependency from a feature that is only used by a non-default
+/// environment should also uninstall the orphaned package from the prefix of
+/// that environment.
+#[tokio::test]
+#[cfg_attr(not(feature = "slow_integration_tests"), ignore)]
+async fn remove_uninstalls_from_non_default_environment() {
+    let platform = Platform::current();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+    [project]
+    name = "test-remove-non-default"
+    channels = ["conda-forge"]
+    platforms = ["{platform}"]
+
+    [feature.test.dependencies]
+    bzip2 = "*"
+
+    [environments]
+    test = ["test"]
+    "#
+    ))
+    .unwrap();
+
+    pixi.install().with_environment("test").await.unwrap();
+
+    let project = pixi.project().unwrap();
+    let environment = project.environment("test").unwrap();
+    let conda_meta = pixi
+        .project_path()
+        .join(environment.dir())
+        .join("conda-meta");
+    let has_bzip2 = || {
+        fs_err::read_dir(&conda_meta).unwrap().any(|entry| {
+            entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with("bzip2-")
+        })
+    };
+    assert!(
+        has_bzip2(),
+        "bzip2 should be installed in the test environment"
+    );
+
+    pixi.remove("bzip2")
+        .with_feature("test")
+        .with_install(true)
+        .await
+        .unwrap();
+
+    assert!(
+        !has_bzip2(),
+        "bzip2 should be uninstalled from the test environment"
+    );
+}
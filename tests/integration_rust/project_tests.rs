@@ -4,8 +4,8 @@ use std::path::PathBuf;
 use crate::common::{package_database::PackageDatabase, PixiControl};
 use insta::assert_debug_snapshot;
 use pixi::Project;
-use pixi_config::Config;
-use pixi_manifest::FeaturesExt;
+use pixi_config::{Config, DetachedEnvironments};
+use pixi_manifest::{FeatureName, FeaturesExt};
 use rattler_conda_types::{NamedChannelOrUrl, Platform};
 use tempfile::TempDir;
 use url::Url;
@@ -148,3 +148,84 @@ fn parse_valid_docs_configs() {
         }
     }
 }
+
+/// Renaming a project moves its detached environments directory to the
+/// directory of the new name and updates the tasks that refer to the old name.
+#[tokio::test]
+async fn rename_project() {
+    let pixi = PixiControl::from_manifest(
+        r#"
+[project]
+name = "old-name"
+channels = []
+platforms = ["linux-64", "osx-64", "osx-arm64", "win-64"]
+
+[tasks]
+start = "python -m old-name"
+docs = "echo old-name-docs"
+"#,
+    )
+    .unwrap();
+
+    // Install the environments in a detached directory
+    let mut config = Config::default();
+    config.detached_environments = Some(DetachedEnvironments::Path(
+        pixi.project_path().join("target"),
+    ));
+    fs_err::write(
+        pixi.project().unwrap().pixi_dir().join("config.toml"),
+        toml_edit::ser::to_string(&config).unwrap(),
+    )
+    .unwrap();
+
+    // Pretend the default environment is installed
+    let project = pixi.project().unwrap();
+    let old_env_dir = project.default_environment().dir();
+    fs_err::create_dir_all(old_env_dir.join("conda-meta")).unwrap();
+    fs_err::create_dir_all(old_env_dir.join("bin")).unwrap();
+    fs_err::write(old_env_dir.join("bin").join("tool"), "").unwrap();
+
+    pixi::cli::project::name::set::execute(
+        project,
+        pixi::cli::project::name::set::Args {
+            name: "new-name".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let project = pixi.project().unwrap();
+    assert_eq!(project.name(), "new-name");
+
+    // The environments directory is moved, and the stale prefix is removed so
+    // it is recreated on the next install.
+    let new_env_dir = project.default_environment().dir();
+    assert_ne!(old_env_dir, new_env_dir);
+    assert!(!old_env_dir.exists());
+    assert!(!new_env_dir.exists());
+    // The detached directory of the project contains the `envs` directory.
+    let detached_dir =
+        |env_dir: &std::path::Path| env_dir.parent().unwrap().parent().unwrap().to_path_buf();
+    assert!(!detached_dir(&old_env_dir).exists());
+    assert!(detached_dir(&new_env_dir).is_dir());
+
+    // Only references to the project as a python module are renamed
+    let tasks = project
+        .manifest()
+        .tasks(None, &FeatureName::Default)
+        .unwrap();
+    assert_eq!(
+        tasks
+            .get(&"start".into())
+            .and_then(|task| task.as_single_command())
+            .as_deref(),
+        Some("python -m new-name")
+    );
+    assert_eq!(
+        tasks
+            .get(&"docs".into())
+            .and_then(|task| task.as_single_command())
+            .as_deref(),
+        Some("echo old-name-docs")
+    );
+}
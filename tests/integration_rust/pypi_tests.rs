@@ -287,3 +287,56 @@ async fn test_allow_insecure_host() {
     .unwrap();
     pixi.update_lock_file().await.unwrap();
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "slow_integration_tests"), ignore)]
+async fn test_no_build_and_no_binary() {
+    let pypi_indexes = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/pypi-indexes");
+    let flat_index = pypi_indexes.join("multiple-indexes-a/flat");
+    let manifest = |pypi_options: &str| {
+        format!(
+            r#"
+            [project]
+            name = "pypi-build-options"
+            platforms = ["{platform}"]
+            channels = ["https://prefix.dev/conda-forge"]
+
+            [dependencies]
+            python = "~=3.12.0"
+
+            [pypi-dependencies]
+            foo = "*"
+
+            [pypi-options]
+            find-links = [{{ path = "{flat_index}"}}]
+            {pypi_options}"#,
+            platform = Platform::current(),
+            flat_index = flat_index.display().to_string().replace("\\", "/"),
+        )
+    };
+
+    // Without binaries the source distribution is used
+    let pixi = PixiControl::from_manifest(&manifest(r#"no-binary = ["foo"]"#)).unwrap();
+    let lock_file = pixi.update_lock_file().await.unwrap();
+    assert_eq!(
+        lock_file
+            .get_pypi_package_url("default", Platform::current(), "foo")
+            .unwrap()
+            .as_path()
+            .unwrap(),
+        Utf8TypedPath::from(&*flat_index.as_os_str().to_string_lossy()).join("foo-1.0.0.tar.gz")
+    );
+
+    // Without building only the wheel is used
+    let pixi = PixiControl::from_manifest(&manifest("no-build = true")).unwrap();
+    let lock_file = pixi.update_lock_file().await.unwrap();
+    assert_eq!(
+        lock_file
+            .get_pypi_package_url("default", Platform::current(), "foo")
+            .unwrap()
+            .as_path()
+            .unwrap(),
+        Utf8TypedPath::from(&*flat_index.as_os_str().to_string_lossy())
+            .join("foo-1.0.0-py2.py3-none-any.whl")
+    );
+}